@@ -10,9 +10,12 @@ use crate::{
     doom::def::GameMode,
     doom::def::Key,
     doom::def::Language,
-    doom_exe_dir, misc,
+    doom_exe_dir,
+    map::Map,
+    misc,
     misc::args::{ArgMeta, Args},
-    wad::WadFileInfo,
+    tables::Angle,
+    wad::{LumpDirectory, WadFileInfo},
 };
 
 pub struct Configuration {
@@ -28,22 +31,40 @@ pub struct Configuration {
     // can also be 2
     pub deathmatch: usize,
     pub force_old_bsp: bool,
+    /// Always build the blockmap rather than reading BLOCKMAP.
+    pub force_blockmap: bool,
+    /// Build GL nodes, with minisegs, for maps saved without nodes.
+    pub build_gl_nodes: bool,
+    /// Keep nodes built for maps in a file next to their WAD.
+    pub cache_nodes: bool,
 
     pub game_mode: GameMode,
     pub game_mission: GameMission,
     pub language: Language,
 
     pub doom_ver_str: String,
+    /// The exact IWAD release, if its checksum is known.
+    pub iwad_release: Option<&'static str>,
     pub bfg_edition: bool,
     pub has_wolf_levels: bool,
 
     pub save_game_base: PathBuf,
     pub start_skill: SkillLevel,
+    /// The skill of the game being played.
+    pub game_skill: SkillLevel,
+    /// The episode and map being played.
+    pub game_episode: usize,
+    pub game_map: usize,
     pub start_episode: usize,
     pub start_map: usize,
     pub autostart: bool,
 
     pub wad_files: Vec<WadFileInfo>,
+    /// DEH and BEX patches to apply, in order.
+    pub deh_files: Vec<PathBuf>,
+    pub lumps: LumpDirectory,
+    /// The level being played, once one has been loaded.
+    pub level: Option<Map>,
 
     pub forward_move: [i32; 2],
     pub side_move: [i32; 2],
@@ -54,7 +75,7 @@ pub struct Configuration {
     pub no_draw: bool,
     pub no_blit: bool,
 
-    pub view_angle_offset: i32,
+    pub view_angle_offset: Angle,
 
     pub default_file: PathBuf,
     pub netgame: bool,
@@ -141,7 +162,10 @@ pub struct Configuration {
     pub dog_jumping: bool,
 
     pub rngseed: u32,
+    pub rng: misc::random::Rng,
     pub gametic: i32,
+    /// The gametic the current level started on, for demo insurance.
+    pub basetic: i32,
 
     pub sdl_window: Option<sdl2::video::Window>,
     pub screen_resolutions_list: Vec<String>,
@@ -165,22 +189,32 @@ impl Default for Configuration {
             devparm: false,
             deathmatch: 0,
             force_old_bsp: false,
+            force_blockmap: false,
+            build_gl_nodes: false,
+            cache_nodes: false,
 
             game_mode: GameMode::TBD,
             game_mission: GameMission::None,
             language: Language::English,
 
             doom_ver_str: String::new(),
+            iwad_release: None,
             bfg_edition: false,
             has_wolf_levels: false,
 
             save_game_base: PathBuf::new(),
             start_skill: SkillLevel::None,
+            game_skill: SkillLevel::None,
+            game_episode: 1,
+            game_map: 1,
             start_episode: 1,
             start_map: 1,
             autostart: false,
 
             wad_files: vec![],
+            deh_files: vec![],
+            lumps: LumpDirectory::default(),
+            level: None,
 
             forward_move: [0x19, 0x32],
             side_move: [0x18, 0x28],
@@ -191,7 +225,7 @@ impl Default for Configuration {
             no_draw: false,
             no_blit: false,
 
-            view_angle_offset: 0,
+            view_angle_offset: Angle(0),
 
             default_file: doom_exe_dir().join(misc::BOOM_CFG),
             netgame: false,
@@ -252,7 +286,9 @@ impl Default for Configuration {
             demo_insurance: defaults.demo_insurance,
             dog_jumping: defaults.dog_jumping,
             rngseed: 1993,
+            rng: Default::default(),
             gametic: 0,
+            basetic: 0,
 
             sdl_window: None,
             screen_resolutions_list: vec![],
@@ -2966,10 +3002,12 @@ impl Default for SkillLevel {
     }
 }
 
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MonsterInfightingLevel {
     None,
     OtherSpecies,
+    /// Even monsters of the same species fight, as with dehacked's
+    /// "Monsters Infight".
     All,
 }
 
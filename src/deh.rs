@@ -11,7 +11,7 @@ use strum::IntoEnumIterator;
 // TODO delete these dumb statics
 lazy_static! {
     pub static ref CODEPTR: RwLock<[ActionF; Statenum::NumStates as usize]> =
-        RwLock::new([|| (); Statenum::NumStates as usize]);
+        RwLock::new([ActionF::Null; Statenum::NumStates as usize]);
     pub static ref SPRITENAMES: RwLock<[&'static str; Spritenum::NumSprites as usize + 1]> =
         RwLock::new([""; Spritenum::NumSprites as usize + 1]);
     pub static ref MUSICNAMES: RwLock<[&'static str; Music::NUMMUSIC as usize + 1]> =
@@ -33,7 +33,7 @@ pub fn build_bex_tables() {
         state.sprite = Spritenum::TNT1;
         state.frame = 0;
         state.tics = -1;
-        state.action = || ();
+        state.action = ActionF::Null;
         state.nextstate = i;
         CODEPTR.write()[i] = state.action;
         i += 1;
@@ -3,7 +3,7 @@ use serde_derive::{Deserialize, Serialize};
 
 /// Handle IWAD-dependent animations, &c based
 /// on the value of this enum
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub enum GameMode {
     TBD,
     Shareware,
@@ -12,7 +12,7 @@ pub enum GameMode {
     Retail,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub enum GameMission {
     Doom,
     Doom2,
@@ -25,7 +25,7 @@ pub enum GameMission {
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Language {
     English,
     French,
@@ -33,6 +33,76 @@ pub enum Language {
     Unknown,
 }
 
+/// The weapons a player can have.
+#[allow(dead_code)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum WeaponType {
+    Fist,
+    #[default]
+    Pistol,
+    Shotgun,
+    Chaingun,
+    Missile,
+    Plasma,
+    Bfg,
+    Chainsaw,
+    SuperShotgun,
+}
+
+pub const NUMWEAPONS: usize = 9;
+
+/// The kinds of ammo weapons use.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AmmoType {
+    /// Pistol and chaingun.
+    Clip,
+    /// Shotgun and super shotgun.
+    Shell,
+    /// Plasma rifle and BFG.
+    Cell,
+    /// Rocket launcher.
+    Misl,
+    /// The fist and chainsaw use none.
+    NoAmmo,
+}
+
+pub const NUMAMMO: usize = 4;
+
+/// The keys, for locked doors.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CardType {
+    BlueCard,
+    YellowCard,
+    RedCard,
+    BlueSkull,
+    YellowSkull,
+    RedSkull,
+}
+
+pub const NUMCARDS: usize = 6;
+
+/// The power ups.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PowerType {
+    Invulnerability,
+    Strength,
+    Invisibility,
+    IronFeet,
+    AllMap,
+    Infrared,
+}
+
+pub const NUMPOWERS: usize = 6;
+
+/// Tics per second.
+pub const TICRATE: i32 = 35;
+
+/// How long the timed power ups last, in tics.
+pub const INVULNTICS: i32 = 30 * TICRATE;
+pub const INVISTICS: i32 = 60 * TICRATE;
+pub const INFRATICS: i32 = 120 * TICRATE;
+pub const IRONTICS: i32 = 60 * TICRATE;
+
 bitflags! {
     #[derive(Serialize, Deserialize)]
     pub struct Key: usize {
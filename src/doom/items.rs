@@ -0,0 +1,58 @@
+use super::def::{AmmoType, NUMWEAPONS};
+use crate::info::Statenum;
+
+/// The ammo a weapon uses and the states of its sprite, like vanilla's
+/// `weaponinfo_t`.
+#[derive(Debug, Copy, Clone)]
+pub struct WeaponInfo {
+    pub ammo: AmmoType,
+    pub upstate: usize,
+    pub downstate: usize,
+    pub readystate: usize,
+    pub atkstate: usize,
+    pub flashstate: usize,
+}
+
+const fn weapon(
+    ammo: AmmoType,
+    upstate: Statenum,
+    downstate: Statenum,
+    readystate: Statenum,
+    atkstate: Statenum,
+    flashstate: Statenum,
+) -> WeaponInfo {
+    WeaponInfo {
+        ammo,
+        upstate: upstate as usize,
+        downstate: downstate as usize,
+        readystate: readystate as usize,
+        atkstate: atkstate as usize,
+        flashstate: flashstate as usize,
+    }
+}
+
+use AmmoType as A;
+use Statenum as S;
+
+/// The weapons, indexed by `WeaponType`.
+#[rustfmt::skip]
+pub const WEAPONINFO: [WeaponInfo; NUMWEAPONS] = [
+    // fist
+    weapon(A::NoAmmo, S::PUNCHUP, S::PUNCHDOWN, S::PUNCH, S::PUNCH1, S::NULL),
+    // pistol
+    weapon(A::Clip, S::PISTOLUP, S::PISTOLDOWN, S::PISTOL, S::PISTOL1, S::PISTOLFLASH),
+    // shotgun
+    weapon(A::Shell, S::SGUNUP, S::SGUNDOWN, S::SGUN, S::SGUN1, S::SGUNFLASH1),
+    // chaingun
+    weapon(A::Clip, S::CHAINUP, S::CHAINDOWN, S::CHAIN, S::CHAIN1, S::CHAINFLASH1),
+    // missile launcher
+    weapon(A::Misl, S::MISSILEUP, S::MISSILEDOWN, S::MISSILE, S::MISSILE1, S::MISSILEFLASH1),
+    // plasma rifle
+    weapon(A::Cell, S::PLASMAUP, S::PLASMADOWN, S::PLASMA, S::PLASMA1, S::PLASMAFLASH1),
+    // bfg 9000
+    weapon(A::Cell, S::BFGUP, S::BFGDOWN, S::BFG, S::BFG1, S::BFGFLASH1),
+    // chainsaw
+    weapon(A::NoAmmo, S::SAWUP, S::SAWDOWN, S::SAW, S::SAW1, S::NULL),
+    // super shotgun
+    weapon(A::Shell, S::DSGUNUP, S::DSGUNDOWN, S::DSGUN, S::DSGUN1, S::DSGUNFLASH1),
+];
@@ -1,3 +1,4 @@
 pub mod def;
 pub mod english;
+pub mod items;
 pub mod stat;
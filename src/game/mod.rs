@@ -120,6 +120,7 @@ pub fn reload_defaults(configuration: &mut Configuration) {
         ((crate::system::get_random_time_seed() % (std::u32::MAX as u64 + 1)) as u32)
             .wrapping_add(configuration.gametic as u32),
     );
+    crate::misc::random::clear_random(configuration);
 }
 
 pub fn compatibility(configuration: &mut Configuration) {
@@ -1,7 +1,7 @@
 use crate::logic::mobj::MobjFlag;
-use crate::{misc, sounds::Sfx};
+use crate::{misc, sounds::Sfx, think::ActionF};
 use lazy_static::lazy_static;
-use misc::fixed::FRACUNIT;
+use misc::fixed::Fixed;
 use parking_lot::RwLock;
 use rustc_hash::FxHashMap;
 use strum_macros::EnumIter;
@@ -11,13 +11,20 @@ lazy_static! {
         RwLock::new(vec![State::default(); Statenum::NumStates as usize]);
 }
 
+/// Set in a state's frame to draw it at full brightness.
+pub const FF_FULLBRIGHT: i64 = 0x8000;
+
 #[derive(Copy, Clone)]
 pub struct State {
     pub sprite: Spritenum,
     pub frame: i64,
     pub tics: i64,
-    pub action: crate::think::ActionF,
+    pub action: ActionF,
     pub nextstate: usize,
+    /// Parameters for the action; for a player sprite, a non-zero `misc1`
+    /// moves the sprite to `(misc1, misc2)`.
+    pub misc1: i64,
+    pub misc2: i64,
 }
 
 impl Default for State {
@@ -26,8 +33,10 @@ impl Default for State {
             sprite: Spritenum::TNT1,
             frame: 0,
             tics: 0,
-            action: || (),
+            action: ActionF::Null,
             nextstate: Statenum::NULL as usize,
+            misc1: 0,
+            misc2: 0,
         }
     }
 }
@@ -50,9 +59,11 @@ pub struct MobjInfo {
     pub deathstate: usize,
     pub xdeathstate: usize,
     pub deathsound: usize,
+    /// Whole map units per step for monsters, but raw fixed point units
+    /// per tic for missiles, so this stays a plain int.
     pub speed: i32,
-    pub radius: i32,
-    pub height: i32,
+    pub radius: Fixed,
+    pub height: Fixed,
     pub mass: i32,
     pub damage: i32,
     pub activesound: usize,
@@ -80,8 +91,8 @@ impl MobjInfo {
         xdeathstate: U10,
         deathsound: U11,
         speed: i32,
-        radius: i32,
-        height: i32,
+        radius: Fixed,
+        height: Fixed,
         mass: i32,
         damage: i32,
         activesound: U12,
@@ -1756,6 +1767,1095 @@ pub const SPRNAMES: [&str; Spritenum::NumSprites as usize + 1] = [
     "SP96", "SP97", "SP98", "", "",
 ];
 
+/// Fill in `STATES` with vanilla's states, then Boom's and MBF's. The rest,
+/// up to `NumStates`, are left for DeHackEd to use.
+#[rustfmt::skip]
+#[allow(clippy::identity_op)]
+pub fn init_states() {
+    use crate::logic::{enemy::*, pspr::*};
+    use Spritenum as SPR;
+    use Statenum as S;
+
+    const NULL: ActionF = ActionF::Null;
+    let m = ActionF::Mobj;
+    let p = ActionF::PSprite;
+
+    let mut states = STATES.write();
+    let mut state = |state: S, sprite, frame, tics, action, nextstate: S, misc1, misc2| {
+        states[state as usize] = State {
+            sprite,
+            frame,
+            tics,
+            action,
+            nextstate: nextstate as usize,
+            misc1,
+            misc2,
+        };
+    };
+
+    state(S::NULL, SPR::TROO, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::LIGHTDONE, SPR::SHTG, 4, 0, p(a_light0), S::NULL, 0, 0);
+    state(S::PUNCH, SPR::PUNG, 0, 1, p(a_weapon_ready), S::PUNCH, 0, 0);
+    state(S::PUNCHDOWN, SPR::PUNG, 0, 1, p(a_lower), S::PUNCHDOWN, 0, 0);
+    state(S::PUNCHUP, SPR::PUNG, 0, 1, p(a_raise), S::PUNCHUP, 0, 0);
+    state(S::PUNCH1, SPR::PUNG, 1, 4, NULL, S::PUNCH2, 0, 0);
+    state(S::PUNCH2, SPR::PUNG, 2, 4, p(a_punch), S::PUNCH3, 0, 0);
+    state(S::PUNCH3, SPR::PUNG, 3, 5, NULL, S::PUNCH4, 0, 0);
+    state(S::PUNCH4, SPR::PUNG, 2, 4, NULL, S::PUNCH5, 0, 0);
+    state(S::PUNCH5, SPR::PUNG, 1, 5, p(a_refire), S::PUNCH, 0, 0);
+    state(S::PISTOL, SPR::PISG, 0, 1, p(a_weapon_ready), S::PISTOL, 0, 0);
+    state(S::PISTOLDOWN, SPR::PISG, 0, 1, p(a_lower), S::PISTOLDOWN, 0, 0);
+    state(S::PISTOLUP, SPR::PISG, 0, 1, p(a_raise), S::PISTOLUP, 0, 0);
+    state(S::PISTOL1, SPR::PISG, 0, 4, NULL, S::PISTOL2, 0, 0);
+    state(S::PISTOL2, SPR::PISG, 1, 6, p(a_fire_pistol), S::PISTOL3, 0, 0);
+    state(S::PISTOL3, SPR::PISG, 2, 4, NULL, S::PISTOL4, 0, 0);
+    state(S::PISTOL4, SPR::PISG, 1, 5, p(a_refire), S::PISTOL, 0, 0);
+    state(S::PISTOLFLASH, SPR::PISF, FF_FULLBRIGHT | 0, 7, p(a_light1), S::LIGHTDONE, 0, 0);
+    state(S::SGUN, SPR::SHTG, 0, 1, p(a_weapon_ready), S::SGUN, 0, 0);
+    state(S::SGUNDOWN, SPR::SHTG, 0, 1, p(a_lower), S::SGUNDOWN, 0, 0);
+    state(S::SGUNUP, SPR::SHTG, 0, 1, p(a_raise), S::SGUNUP, 0, 0);
+    state(S::SGUN1, SPR::SHTG, 0, 3, NULL, S::SGUN2, 0, 0);
+    state(S::SGUN2, SPR::SHTG, 0, 7, p(a_fire_shotgun), S::SGUN3, 0, 0);
+    state(S::SGUN3, SPR::SHTG, 1, 5, NULL, S::SGUN4, 0, 0);
+    state(S::SGUN4, SPR::SHTG, 2, 5, NULL, S::SGUN5, 0, 0);
+    state(S::SGUN5, SPR::SHTG, 3, 4, NULL, S::SGUN6, 0, 0);
+    state(S::SGUN6, SPR::SHTG, 2, 5, NULL, S::SGUN7, 0, 0);
+    state(S::SGUN7, SPR::SHTG, 1, 5, NULL, S::SGUN8, 0, 0);
+    state(S::SGUN8, SPR::SHTG, 0, 3, NULL, S::SGUN9, 0, 0);
+    state(S::SGUN9, SPR::SHTG, 0, 7, p(a_refire), S::SGUN, 0, 0);
+    state(S::SGUNFLASH1, SPR::SHTF, FF_FULLBRIGHT | 0, 4, p(a_light1), S::SGUNFLASH2, 0, 0);
+    state(S::SGUNFLASH2, SPR::SHTF, FF_FULLBRIGHT | 1, 3, p(a_light2), S::LIGHTDONE, 0, 0);
+    state(S::DSGUN, SPR::SHT2, 0, 1, p(a_weapon_ready), S::DSGUN, 0, 0);
+    state(S::DSGUNDOWN, SPR::SHT2, 0, 1, p(a_lower), S::DSGUNDOWN, 0, 0);
+    state(S::DSGUNUP, SPR::SHT2, 0, 1, p(a_raise), S::DSGUNUP, 0, 0);
+    state(S::DSGUN1, SPR::SHT2, 0, 3, NULL, S::DSGUN2, 0, 0);
+    state(S::DSGUN2, SPR::SHT2, 0, 7, p(a_fire_shotgun2), S::DSGUN3, 0, 0);
+    state(S::DSGUN3, SPR::SHT2, 1, 7, NULL, S::DSGUN4, 0, 0);
+    state(S::DSGUN4, SPR::SHT2, 2, 7, p(a_check_reload), S::DSGUN5, 0, 0);
+    state(S::DSGUN5, SPR::SHT2, 3, 7, p(a_open_shotgun2), S::DSGUN6, 0, 0);
+    state(S::DSGUN6, SPR::SHT2, 4, 7, NULL, S::DSGUN7, 0, 0);
+    state(S::DSGUN7, SPR::SHT2, 5, 7, p(a_load_shotgun2), S::DSGUN8, 0, 0);
+    state(S::DSGUN8, SPR::SHT2, 6, 6, NULL, S::DSGUN9, 0, 0);
+    state(S::DSGUN9, SPR::SHT2, 7, 6, p(a_close_shotgun2), S::DSGUN10, 0, 0);
+    state(S::DSGUN10, SPR::SHT2, 0, 5, p(a_refire), S::DSGUN, 0, 0);
+    state(S::DSNR1, SPR::SHT2, 1, 7, NULL, S::DSNR2, 0, 0);
+    state(S::DSNR2, SPR::SHT2, 0, 3, NULL, S::DSGUNDOWN, 0, 0);
+    state(S::DSGUNFLASH1, SPR::SHT2, FF_FULLBRIGHT | 8, 5, p(a_light1), S::DSGUNFLASH2, 0, 0);
+    state(S::DSGUNFLASH2, SPR::SHT2, FF_FULLBRIGHT | 9, 4, p(a_light2), S::LIGHTDONE, 0, 0);
+    state(S::CHAIN, SPR::CHGG, 0, 1, p(a_weapon_ready), S::CHAIN, 0, 0);
+    state(S::CHAINDOWN, SPR::CHGG, 0, 1, p(a_lower), S::CHAINDOWN, 0, 0);
+    state(S::CHAINUP, SPR::CHGG, 0, 1, p(a_raise), S::CHAINUP, 0, 0);
+    state(S::CHAIN1, SPR::CHGG, 0, 4, p(a_fire_cgun), S::CHAIN2, 0, 0);
+    state(S::CHAIN2, SPR::CHGG, 1, 4, p(a_fire_cgun), S::CHAIN3, 0, 0);
+    state(S::CHAIN3, SPR::CHGG, 1, 0, p(a_refire), S::CHAIN, 0, 0);
+    state(S::CHAINFLASH1, SPR::CHGF, FF_FULLBRIGHT | 0, 5, p(a_light1), S::LIGHTDONE, 0, 0);
+    state(S::CHAINFLASH2, SPR::CHGF, FF_FULLBRIGHT | 1, 5, p(a_light2), S::LIGHTDONE, 0, 0);
+    state(S::MISSILE, SPR::MISG, 0, 1, p(a_weapon_ready), S::MISSILE, 0, 0);
+    state(S::MISSILEDOWN, SPR::MISG, 0, 1, p(a_lower), S::MISSILEDOWN, 0, 0);
+    state(S::MISSILEUP, SPR::MISG, 0, 1, p(a_raise), S::MISSILEUP, 0, 0);
+    state(S::MISSILE1, SPR::MISG, 1, 8, p(a_gun_flash), S::MISSILE2, 0, 0);
+    state(S::MISSILE2, SPR::MISG, 1, 12, p(a_fire_missile), S::MISSILE3, 0, 0);
+    state(S::MISSILE3, SPR::MISG, 1, 0, p(a_refire), S::MISSILE, 0, 0);
+    state(S::MISSILEFLASH1, SPR::MISF, FF_FULLBRIGHT | 0, 3, p(a_light1), S::MISSILEFLASH2, 0, 0);
+    state(S::MISSILEFLASH2, SPR::MISF, FF_FULLBRIGHT | 1, 4, NULL, S::MISSILEFLASH3, 0, 0);
+    state(S::MISSILEFLASH3, SPR::MISF, FF_FULLBRIGHT | 2, 4, p(a_light2), S::MISSILEFLASH4, 0, 0);
+    state(S::MISSILEFLASH4, SPR::MISF, FF_FULLBRIGHT | 3, 4, p(a_light2), S::LIGHTDONE, 0, 0);
+    state(S::SAW, SPR::SAWG, 2, 4, p(a_weapon_ready), S::SAWB, 0, 0);
+    state(S::SAWB, SPR::SAWG, 3, 4, p(a_weapon_ready), S::SAW, 0, 0);
+    state(S::SAWDOWN, SPR::SAWG, 2, 1, p(a_lower), S::SAWDOWN, 0, 0);
+    state(S::SAWUP, SPR::SAWG, 2, 1, p(a_raise), S::SAWUP, 0, 0);
+    state(S::SAW1, SPR::SAWG, 0, 4, p(a_saw), S::SAW2, 0, 0);
+    state(S::SAW2, SPR::SAWG, 1, 4, p(a_saw), S::SAW3, 0, 0);
+    state(S::SAW3, SPR::SAWG, 1, 0, p(a_refire), S::SAW, 0, 0);
+    state(S::PLASMA, SPR::PLSG, 0, 1, p(a_weapon_ready), S::PLASMA, 0, 0);
+    state(S::PLASMADOWN, SPR::PLSG, 0, 1, p(a_lower), S::PLASMADOWN, 0, 0);
+    state(S::PLASMAUP, SPR::PLSG, 0, 1, p(a_raise), S::PLASMAUP, 0, 0);
+    state(S::PLASMA1, SPR::PLSG, 0, 3, p(a_fire_plasma), S::PLASMA2, 0, 0);
+    state(S::PLASMA2, SPR::PLSG, 1, 20, p(a_refire), S::PLASMA, 0, 0);
+    state(S::PLASMAFLASH1, SPR::PLSF, FF_FULLBRIGHT | 0, 4, p(a_light1), S::LIGHTDONE, 0, 0);
+    state(S::PLASMAFLASH2, SPR::PLSF, FF_FULLBRIGHT | 1, 4, p(a_light1), S::LIGHTDONE, 0, 0);
+    state(S::BFG, SPR::BFGG, 0, 1, p(a_weapon_ready), S::BFG, 0, 0);
+    state(S::BFGDOWN, SPR::BFGG, 0, 1, p(a_lower), S::BFGDOWN, 0, 0);
+    state(S::BFGUP, SPR::BFGG, 0, 1, p(a_raise), S::BFGUP, 0, 0);
+    state(S::BFG1, SPR::BFGG, 0, 20, p(a_bfg_sound), S::BFG2, 0, 0);
+    state(S::BFG2, SPR::BFGG, 1, 10, p(a_gun_flash), S::BFG3, 0, 0);
+    state(S::BFG3, SPR::BFGG, 1, 10, p(a_fire_bfg), S::BFG4, 0, 0);
+    state(S::BFG4, SPR::BFGG, 1, 20, p(a_refire), S::BFG, 0, 0);
+    state(S::BFGFLASH1, SPR::BFGF, FF_FULLBRIGHT | 0, 11, p(a_light1), S::BFGFLASH2, 0, 0);
+    state(S::BFGFLASH2, SPR::BFGF, FF_FULLBRIGHT | 1, 6, p(a_light2), S::LIGHTDONE, 0, 0);
+    state(S::BLOOD1, SPR::BLUD, 2, 8, NULL, S::BLOOD2, 0, 0);
+    state(S::BLOOD2, SPR::BLUD, 1, 8, NULL, S::BLOOD3, 0, 0);
+    state(S::BLOOD3, SPR::BLUD, 0, 8, NULL, S::NULL, 0, 0);
+    state(S::PUFF1, SPR::PUFF, FF_FULLBRIGHT | 0, 4, NULL, S::PUFF2, 0, 0);
+    state(S::PUFF2, SPR::PUFF, 1, 4, NULL, S::PUFF3, 0, 0);
+    state(S::PUFF3, SPR::PUFF, 2, 4, NULL, S::PUFF4, 0, 0);
+    state(S::PUFF4, SPR::PUFF, 3, 4, NULL, S::NULL, 0, 0);
+    state(S::TBALL1, SPR::BAL1, FF_FULLBRIGHT | 0, 4, NULL, S::TBALL2, 0, 0);
+    state(S::TBALL2, SPR::BAL1, FF_FULLBRIGHT | 1, 4, NULL, S::TBALL1, 0, 0);
+    state(S::TBALLX1, SPR::BAL1, FF_FULLBRIGHT | 2, 6, NULL, S::TBALLX2, 0, 0);
+    state(S::TBALLX2, SPR::BAL1, FF_FULLBRIGHT | 3, 6, NULL, S::TBALLX3, 0, 0);
+    state(S::TBALLX3, SPR::BAL1, FF_FULLBRIGHT | 4, 6, NULL, S::NULL, 0, 0);
+    state(S::RBALL1, SPR::BAL2, FF_FULLBRIGHT | 0, 4, NULL, S::RBALL2, 0, 0);
+    state(S::RBALL2, SPR::BAL2, FF_FULLBRIGHT | 1, 4, NULL, S::RBALL1, 0, 0);
+    state(S::RBALLX1, SPR::BAL2, FF_FULLBRIGHT | 2, 6, NULL, S::RBALLX2, 0, 0);
+    state(S::RBALLX2, SPR::BAL2, FF_FULLBRIGHT | 3, 6, NULL, S::RBALLX3, 0, 0);
+    state(S::RBALLX3, SPR::BAL2, FF_FULLBRIGHT | 4, 6, NULL, S::NULL, 0, 0);
+    state(S::PLASBALL, SPR::PLSS, FF_FULLBRIGHT | 0, 6, NULL, S::PLASBALL2, 0, 0);
+    state(S::PLASBALL2, SPR::PLSS, FF_FULLBRIGHT | 1, 6, NULL, S::PLASBALL, 0, 0);
+    state(S::PLASEXP, SPR::PLSE, FF_FULLBRIGHT | 0, 4, NULL, S::PLASEXP2, 0, 0);
+    state(S::PLASEXP2, SPR::PLSE, FF_FULLBRIGHT | 1, 4, NULL, S::PLASEXP3, 0, 0);
+    state(S::PLASEXP3, SPR::PLSE, FF_FULLBRIGHT | 2, 4, NULL, S::PLASEXP4, 0, 0);
+    state(S::PLASEXP4, SPR::PLSE, FF_FULLBRIGHT | 3, 4, NULL, S::PLASEXP5, 0, 0);
+    state(S::PLASEXP5, SPR::PLSE, FF_FULLBRIGHT | 4, 4, NULL, S::NULL, 0, 0);
+    state(S::ROCKET, SPR::MISL, FF_FULLBRIGHT | 0, 1, NULL, S::ROCKET, 0, 0);
+    state(S::BFGSHOT, SPR::BFS1, FF_FULLBRIGHT | 0, 4, NULL, S::BFGSHOT2, 0, 0);
+    state(S::BFGSHOT2, SPR::BFS1, FF_FULLBRIGHT | 1, 4, NULL, S::BFGSHOT, 0, 0);
+    state(S::BFGLAND, SPR::BFE1, FF_FULLBRIGHT | 0, 8, NULL, S::BFGLAND2, 0, 0);
+    state(S::BFGLAND2, SPR::BFE1, FF_FULLBRIGHT | 1, 8, NULL, S::BFGLAND3, 0, 0);
+    state(S::BFGLAND3, SPR::BFE1, FF_FULLBRIGHT | 2, 8, m(a_bfg_spray), S::BFGLAND4, 0, 0);
+    state(S::BFGLAND4, SPR::BFE1, FF_FULLBRIGHT | 3, 8, NULL, S::BFGLAND5, 0, 0);
+    state(S::BFGLAND5, SPR::BFE1, FF_FULLBRIGHT | 4, 8, NULL, S::BFGLAND6, 0, 0);
+    state(S::BFGLAND6, SPR::BFE1, FF_FULLBRIGHT | 5, 8, NULL, S::NULL, 0, 0);
+    state(S::BFGEXP, SPR::BFE2, FF_FULLBRIGHT | 0, 8, NULL, S::BFGEXP2, 0, 0);
+    state(S::BFGEXP2, SPR::BFE2, FF_FULLBRIGHT | 1, 8, NULL, S::BFGEXP3, 0, 0);
+    state(S::BFGEXP3, SPR::BFE2, FF_FULLBRIGHT | 2, 8, NULL, S::BFGEXP4, 0, 0);
+    state(S::BFGEXP4, SPR::BFE2, FF_FULLBRIGHT | 3, 8, NULL, S::NULL, 0, 0);
+    state(S::EXPLODE1, SPR::MISL, FF_FULLBRIGHT | 1, 8, m(a_explode), S::EXPLODE2, 0, 0);
+    state(S::EXPLODE2, SPR::MISL, FF_FULLBRIGHT | 2, 6, NULL, S::EXPLODE3, 0, 0);
+    state(S::EXPLODE3, SPR::MISL, FF_FULLBRIGHT | 3, 4, NULL, S::NULL, 0, 0);
+    state(S::TFOG, SPR::TFOG, FF_FULLBRIGHT | 0, 6, NULL, S::TFOG01, 0, 0);
+    state(S::TFOG01, SPR::TFOG, FF_FULLBRIGHT | 1, 6, NULL, S::TFOG02, 0, 0);
+    state(S::TFOG02, SPR::TFOG, FF_FULLBRIGHT | 0, 6, NULL, S::TFOG2, 0, 0);
+    state(S::TFOG2, SPR::TFOG, FF_FULLBRIGHT | 1, 6, NULL, S::TFOG3, 0, 0);
+    state(S::TFOG3, SPR::TFOG, FF_FULLBRIGHT | 2, 6, NULL, S::TFOG4, 0, 0);
+    state(S::TFOG4, SPR::TFOG, FF_FULLBRIGHT | 3, 6, NULL, S::TFOG5, 0, 0);
+    state(S::TFOG5, SPR::TFOG, FF_FULLBRIGHT | 4, 6, NULL, S::TFOG6, 0, 0);
+    state(S::TFOG6, SPR::TFOG, FF_FULLBRIGHT | 5, 6, NULL, S::TFOG7, 0, 0);
+    state(S::TFOG7, SPR::TFOG, FF_FULLBRIGHT | 6, 6, NULL, S::TFOG8, 0, 0);
+    state(S::TFOG8, SPR::TFOG, FF_FULLBRIGHT | 7, 6, NULL, S::TFOG9, 0, 0);
+    state(S::TFOG9, SPR::TFOG, FF_FULLBRIGHT | 8, 6, NULL, S::TFOG10, 0, 0);
+    state(S::TFOG10, SPR::TFOG, FF_FULLBRIGHT | 9, 6, NULL, S::NULL, 0, 0);
+    state(S::IFOG, SPR::IFOG, FF_FULLBRIGHT | 0, 6, NULL, S::IFOG01, 0, 0);
+    state(S::IFOG01, SPR::IFOG, FF_FULLBRIGHT | 1, 6, NULL, S::IFOG02, 0, 0);
+    state(S::IFOG02, SPR::IFOG, FF_FULLBRIGHT | 0, 6, NULL, S::IFOG2, 0, 0);
+    state(S::IFOG2, SPR::IFOG, FF_FULLBRIGHT | 1, 6, NULL, S::IFOG3, 0, 0);
+    state(S::IFOG3, SPR::IFOG, FF_FULLBRIGHT | 2, 6, NULL, S::IFOG4, 0, 0);
+    state(S::IFOG4, SPR::IFOG, FF_FULLBRIGHT | 3, 6, NULL, S::IFOG5, 0, 0);
+    state(S::IFOG5, SPR::IFOG, FF_FULLBRIGHT | 4, 6, NULL, S::NULL, 0, 0);
+    state(S::PLAY, SPR::PLAY, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::PLAY_RUN1, SPR::PLAY, 0, 4, NULL, S::PLAY_RUN2, 0, 0);
+    state(S::PLAY_RUN2, SPR::PLAY, 1, 4, NULL, S::PLAY_RUN3, 0, 0);
+    state(S::PLAY_RUN3, SPR::PLAY, 2, 4, NULL, S::PLAY_RUN4, 0, 0);
+    state(S::PLAY_RUN4, SPR::PLAY, 3, 4, NULL, S::PLAY_RUN1, 0, 0);
+    state(S::PLAY_ATK1, SPR::PLAY, 4, 12, NULL, S::PLAY, 0, 0);
+    state(S::PLAY_ATK2, SPR::PLAY, FF_FULLBRIGHT | 5, 6, NULL, S::PLAY_ATK1, 0, 0);
+    state(S::PLAY_PAIN, SPR::PLAY, 6, 4, NULL, S::PLAY_PAIN2, 0, 0);
+    state(S::PLAY_PAIN2, SPR::PLAY, 6, 4, m(a_pain), S::PLAY, 0, 0);
+    state(S::PLAY_DIE1, SPR::PLAY, 7, 10, NULL, S::PLAY_DIE2, 0, 0);
+    state(S::PLAY_DIE2, SPR::PLAY, 8, 10, m(a_player_scream), S::PLAY_DIE3, 0, 0);
+    state(S::PLAY_DIE3, SPR::PLAY, 9, 10, m(a_fall), S::PLAY_DIE4, 0, 0);
+    state(S::PLAY_DIE4, SPR::PLAY, 10, 10, NULL, S::PLAY_DIE5, 0, 0);
+    state(S::PLAY_DIE5, SPR::PLAY, 11, 10, NULL, S::PLAY_DIE6, 0, 0);
+    state(S::PLAY_DIE6, SPR::PLAY, 12, 10, NULL, S::PLAY_DIE7, 0, 0);
+    state(S::PLAY_DIE7, SPR::PLAY, 13, -1, NULL, S::NULL, 0, 0);
+    state(S::PLAY_XDIE1, SPR::PLAY, 14, 5, NULL, S::PLAY_XDIE2, 0, 0);
+    state(S::PLAY_XDIE2, SPR::PLAY, 15, 5, m(a_xscream), S::PLAY_XDIE3, 0, 0);
+    state(S::PLAY_XDIE3, SPR::PLAY, 16, 5, m(a_fall), S::PLAY_XDIE4, 0, 0);
+    state(S::PLAY_XDIE4, SPR::PLAY, 17, 5, NULL, S::PLAY_XDIE5, 0, 0);
+    state(S::PLAY_XDIE5, SPR::PLAY, 18, 5, NULL, S::PLAY_XDIE6, 0, 0);
+    state(S::PLAY_XDIE6, SPR::PLAY, 19, 5, NULL, S::PLAY_XDIE7, 0, 0);
+    state(S::PLAY_XDIE7, SPR::PLAY, 20, 5, NULL, S::PLAY_XDIE8, 0, 0);
+    state(S::PLAY_XDIE8, SPR::PLAY, 21, 5, NULL, S::PLAY_XDIE9, 0, 0);
+    state(S::PLAY_XDIE9, SPR::PLAY, 22, -1, NULL, S::NULL, 0, 0);
+    state(S::POSS_STND, SPR::POSS, 0, 10, m(a_look), S::POSS_STND2, 0, 0);
+    state(S::POSS_STND2, SPR::POSS, 1, 10, m(a_look), S::POSS_STND, 0, 0);
+    state(S::POSS_RUN1, SPR::POSS, 0, 4, m(a_chase), S::POSS_RUN2, 0, 0);
+    state(S::POSS_RUN2, SPR::POSS, 0, 4, m(a_chase), S::POSS_RUN3, 0, 0);
+    state(S::POSS_RUN3, SPR::POSS, 1, 4, m(a_chase), S::POSS_RUN4, 0, 0);
+    state(S::POSS_RUN4, SPR::POSS, 1, 4, m(a_chase), S::POSS_RUN5, 0, 0);
+    state(S::POSS_RUN5, SPR::POSS, 2, 4, m(a_chase), S::POSS_RUN6, 0, 0);
+    state(S::POSS_RUN6, SPR::POSS, 2, 4, m(a_chase), S::POSS_RUN7, 0, 0);
+    state(S::POSS_RUN7, SPR::POSS, 3, 4, m(a_chase), S::POSS_RUN8, 0, 0);
+    state(S::POSS_RUN8, SPR::POSS, 3, 4, m(a_chase), S::POSS_RUN1, 0, 0);
+    state(S::POSS_ATK1, SPR::POSS, 4, 10, m(a_face_target), S::POSS_ATK2, 0, 0);
+    state(S::POSS_ATK2, SPR::POSS, 5, 8, m(a_pos_attack), S::POSS_ATK3, 0, 0);
+    state(S::POSS_ATK3, SPR::POSS, 4, 8, NULL, S::POSS_RUN1, 0, 0);
+    state(S::POSS_PAIN, SPR::POSS, 6, 3, NULL, S::POSS_PAIN2, 0, 0);
+    state(S::POSS_PAIN2, SPR::POSS, 6, 3, m(a_pain), S::POSS_RUN1, 0, 0);
+    state(S::POSS_DIE1, SPR::POSS, 7, 5, NULL, S::POSS_DIE2, 0, 0);
+    state(S::POSS_DIE2, SPR::POSS, 8, 5, m(a_scream), S::POSS_DIE3, 0, 0);
+    state(S::POSS_DIE3, SPR::POSS, 9, 5, m(a_fall), S::POSS_DIE4, 0, 0);
+    state(S::POSS_DIE4, SPR::POSS, 10, 5, NULL, S::POSS_DIE5, 0, 0);
+    state(S::POSS_DIE5, SPR::POSS, 11, -1, NULL, S::NULL, 0, 0);
+    state(S::POSS_XDIE1, SPR::POSS, 12, 5, NULL, S::POSS_XDIE2, 0, 0);
+    state(S::POSS_XDIE2, SPR::POSS, 13, 5, m(a_xscream), S::POSS_XDIE3, 0, 0);
+    state(S::POSS_XDIE3, SPR::POSS, 14, 5, m(a_fall), S::POSS_XDIE4, 0, 0);
+    state(S::POSS_XDIE4, SPR::POSS, 15, 5, NULL, S::POSS_XDIE5, 0, 0);
+    state(S::POSS_XDIE5, SPR::POSS, 16, 5, NULL, S::POSS_XDIE6, 0, 0);
+    state(S::POSS_XDIE6, SPR::POSS, 17, 5, NULL, S::POSS_XDIE7, 0, 0);
+    state(S::POSS_XDIE7, SPR::POSS, 18, 5, NULL, S::POSS_XDIE8, 0, 0);
+    state(S::POSS_XDIE8, SPR::POSS, 19, 5, NULL, S::POSS_XDIE9, 0, 0);
+    state(S::POSS_XDIE9, SPR::POSS, 20, -1, NULL, S::NULL, 0, 0);
+    state(S::POSS_RAISE1, SPR::POSS, 10, 5, NULL, S::POSS_RAISE2, 0, 0);
+    state(S::POSS_RAISE2, SPR::POSS, 9, 5, NULL, S::POSS_RAISE3, 0, 0);
+    state(S::POSS_RAISE3, SPR::POSS, 8, 5, NULL, S::POSS_RAISE4, 0, 0);
+    state(S::POSS_RAISE4, SPR::POSS, 7, 5, NULL, S::POSS_RUN1, 0, 0);
+    state(S::SPOS_STND, SPR::SPOS, 0, 10, m(a_look), S::SPOS_STND2, 0, 0);
+    state(S::SPOS_STND2, SPR::SPOS, 1, 10, m(a_look), S::SPOS_STND, 0, 0);
+    state(S::SPOS_RUN1, SPR::SPOS, 0, 3, m(a_chase), S::SPOS_RUN2, 0, 0);
+    state(S::SPOS_RUN2, SPR::SPOS, 0, 3, m(a_chase), S::SPOS_RUN3, 0, 0);
+    state(S::SPOS_RUN3, SPR::SPOS, 1, 3, m(a_chase), S::SPOS_RUN4, 0, 0);
+    state(S::SPOS_RUN4, SPR::SPOS, 1, 3, m(a_chase), S::SPOS_RUN5, 0, 0);
+    state(S::SPOS_RUN5, SPR::SPOS, 2, 3, m(a_chase), S::SPOS_RUN6, 0, 0);
+    state(S::SPOS_RUN6, SPR::SPOS, 2, 3, m(a_chase), S::SPOS_RUN7, 0, 0);
+    state(S::SPOS_RUN7, SPR::SPOS, 3, 3, m(a_chase), S::SPOS_RUN8, 0, 0);
+    state(S::SPOS_RUN8, SPR::SPOS, 3, 3, m(a_chase), S::SPOS_RUN1, 0, 0);
+    state(S::SPOS_ATK1, SPR::SPOS, 4, 10, m(a_face_target), S::SPOS_ATK2, 0, 0);
+    state(S::SPOS_ATK2, SPR::SPOS, FF_FULLBRIGHT | 5, 10, m(a_spos_attack), S::SPOS_ATK3, 0, 0);
+    state(S::SPOS_ATK3, SPR::SPOS, 4, 10, NULL, S::SPOS_RUN1, 0, 0);
+    state(S::SPOS_PAIN, SPR::SPOS, 6, 3, NULL, S::SPOS_PAIN2, 0, 0);
+    state(S::SPOS_PAIN2, SPR::SPOS, 6, 3, m(a_pain), S::SPOS_RUN1, 0, 0);
+    state(S::SPOS_DIE1, SPR::SPOS, 7, 5, NULL, S::SPOS_DIE2, 0, 0);
+    state(S::SPOS_DIE2, SPR::SPOS, 8, 5, m(a_scream), S::SPOS_DIE3, 0, 0);
+    state(S::SPOS_DIE3, SPR::SPOS, 9, 5, m(a_fall), S::SPOS_DIE4, 0, 0);
+    state(S::SPOS_DIE4, SPR::SPOS, 10, 5, NULL, S::SPOS_DIE5, 0, 0);
+    state(S::SPOS_DIE5, SPR::SPOS, 11, -1, NULL, S::NULL, 0, 0);
+    state(S::SPOS_XDIE1, SPR::SPOS, 12, 5, NULL, S::SPOS_XDIE2, 0, 0);
+    state(S::SPOS_XDIE2, SPR::SPOS, 13, 5, m(a_xscream), S::SPOS_XDIE3, 0, 0);
+    state(S::SPOS_XDIE3, SPR::SPOS, 14, 5, m(a_fall), S::SPOS_XDIE4, 0, 0);
+    state(S::SPOS_XDIE4, SPR::SPOS, 15, 5, NULL, S::SPOS_XDIE5, 0, 0);
+    state(S::SPOS_XDIE5, SPR::SPOS, 16, 5, NULL, S::SPOS_XDIE6, 0, 0);
+    state(S::SPOS_XDIE6, SPR::SPOS, 17, 5, NULL, S::SPOS_XDIE7, 0, 0);
+    state(S::SPOS_XDIE7, SPR::SPOS, 18, 5, NULL, S::SPOS_XDIE8, 0, 0);
+    state(S::SPOS_XDIE8, SPR::SPOS, 19, 5, NULL, S::SPOS_XDIE9, 0, 0);
+    state(S::SPOS_XDIE9, SPR::SPOS, 20, -1, NULL, S::NULL, 0, 0);
+    state(S::SPOS_RAISE1, SPR::SPOS, 11, 5, NULL, S::SPOS_RAISE2, 0, 0);
+    state(S::SPOS_RAISE2, SPR::SPOS, 10, 5, NULL, S::SPOS_RAISE3, 0, 0);
+    state(S::SPOS_RAISE3, SPR::SPOS, 9, 5, NULL, S::SPOS_RAISE4, 0, 0);
+    state(S::SPOS_RAISE4, SPR::SPOS, 8, 5, NULL, S::SPOS_RAISE5, 0, 0);
+    state(S::SPOS_RAISE5, SPR::SPOS, 7, 5, NULL, S::SPOS_RUN1, 0, 0);
+    state(S::VILE_STND, SPR::VILE, 0, 10, m(a_look), S::VILE_STND2, 0, 0);
+    state(S::VILE_STND2, SPR::VILE, 1, 10, m(a_look), S::VILE_STND, 0, 0);
+    state(S::VILE_RUN1, SPR::VILE, 0, 2, m(a_vile_chase), S::VILE_RUN2, 0, 0);
+    state(S::VILE_RUN2, SPR::VILE, 0, 2, m(a_vile_chase), S::VILE_RUN3, 0, 0);
+    state(S::VILE_RUN3, SPR::VILE, 1, 2, m(a_vile_chase), S::VILE_RUN4, 0, 0);
+    state(S::VILE_RUN4, SPR::VILE, 1, 2, m(a_vile_chase), S::VILE_RUN5, 0, 0);
+    state(S::VILE_RUN5, SPR::VILE, 2, 2, m(a_vile_chase), S::VILE_RUN6, 0, 0);
+    state(S::VILE_RUN6, SPR::VILE, 2, 2, m(a_vile_chase), S::VILE_RUN7, 0, 0);
+    state(S::VILE_RUN7, SPR::VILE, 3, 2, m(a_vile_chase), S::VILE_RUN8, 0, 0);
+    state(S::VILE_RUN8, SPR::VILE, 3, 2, m(a_vile_chase), S::VILE_RUN9, 0, 0);
+    state(S::VILE_RUN9, SPR::VILE, 4, 2, m(a_vile_chase), S::VILE_RUN10, 0, 0);
+    state(S::VILE_RUN10, SPR::VILE, 4, 2, m(a_vile_chase), S::VILE_RUN11, 0, 0);
+    state(S::VILE_RUN11, SPR::VILE, 5, 2, m(a_vile_chase), S::VILE_RUN12, 0, 0);
+    state(S::VILE_RUN12, SPR::VILE, 5, 2, m(a_vile_chase), S::VILE_RUN1, 0, 0);
+    state(S::VILE_ATK1, SPR::VILE, FF_FULLBRIGHT | 6, 0, m(a_vile_start), S::VILE_ATK2, 0, 0);
+    state(S::VILE_ATK2, SPR::VILE, FF_FULLBRIGHT | 6, 10, m(a_face_target), S::VILE_ATK3, 0, 0);
+    state(S::VILE_ATK3, SPR::VILE, FF_FULLBRIGHT | 7, 8, m(a_vile_target), S::VILE_ATK4, 0, 0);
+    state(S::VILE_ATK4, SPR::VILE, FF_FULLBRIGHT | 8, 8, m(a_face_target), S::VILE_ATK5, 0, 0);
+    state(S::VILE_ATK5, SPR::VILE, FF_FULLBRIGHT | 9, 8, m(a_face_target), S::VILE_ATK6, 0, 0);
+    state(S::VILE_ATK6, SPR::VILE, FF_FULLBRIGHT | 10, 8, m(a_face_target), S::VILE_ATK7, 0, 0);
+    state(S::VILE_ATK7, SPR::VILE, FF_FULLBRIGHT | 11, 8, m(a_face_target), S::VILE_ATK8, 0, 0);
+    state(S::VILE_ATK8, SPR::VILE, FF_FULLBRIGHT | 12, 8, m(a_face_target), S::VILE_ATK9, 0, 0);
+    state(S::VILE_ATK9, SPR::VILE, FF_FULLBRIGHT | 13, 8, m(a_face_target), S::VILE_ATK10, 0, 0);
+    state(S::VILE_ATK10, SPR::VILE, FF_FULLBRIGHT | 14, 8, m(a_vile_attack), S::VILE_ATK11, 0, 0);
+    state(S::VILE_ATK11, SPR::VILE, FF_FULLBRIGHT | 15, 20, NULL, S::VILE_RUN1, 0, 0);
+    state(S::VILE_HEAL1, SPR::VILE, FF_FULLBRIGHT | 26, 10, NULL, S::VILE_HEAL2, 0, 0);
+    state(S::VILE_HEAL2, SPR::VILE, FF_FULLBRIGHT | 27, 10, NULL, S::VILE_HEAL3, 0, 0);
+    state(S::VILE_HEAL3, SPR::VILE, FF_FULLBRIGHT | 28, 10, NULL, S::VILE_RUN1, 0, 0);
+    state(S::VILE_PAIN, SPR::VILE, 16, 5, NULL, S::VILE_PAIN2, 0, 0);
+    state(S::VILE_PAIN2, SPR::VILE, 16, 5, m(a_pain), S::VILE_RUN1, 0, 0);
+    state(S::VILE_DIE1, SPR::VILE, 16, 7, NULL, S::VILE_DIE2, 0, 0);
+    state(S::VILE_DIE2, SPR::VILE, 17, 7, m(a_scream), S::VILE_DIE3, 0, 0);
+    state(S::VILE_DIE3, SPR::VILE, 18, 7, m(a_fall), S::VILE_DIE4, 0, 0);
+    state(S::VILE_DIE4, SPR::VILE, 19, 7, NULL, S::VILE_DIE5, 0, 0);
+    state(S::VILE_DIE5, SPR::VILE, 20, 7, NULL, S::VILE_DIE6, 0, 0);
+    state(S::VILE_DIE6, SPR::VILE, 21, 7, NULL, S::VILE_DIE7, 0, 0);
+    state(S::VILE_DIE7, SPR::VILE, 22, 7, NULL, S::VILE_DIE8, 0, 0);
+    state(S::VILE_DIE8, SPR::VILE, 23, 5, NULL, S::VILE_DIE9, 0, 0);
+    state(S::VILE_DIE9, SPR::VILE, 24, 5, NULL, S::VILE_DIE10, 0, 0);
+    state(S::VILE_DIE10, SPR::VILE, 25, -1, NULL, S::NULL, 0, 0);
+    state(S::FIRE1, SPR::FIRE, FF_FULLBRIGHT | 0, 2, m(a_start_fire), S::FIRE2, 0, 0);
+    state(S::FIRE2, SPR::FIRE, FF_FULLBRIGHT | 1, 2, m(a_fire), S::FIRE3, 0, 0);
+    state(S::FIRE3, SPR::FIRE, FF_FULLBRIGHT | 0, 2, m(a_fire), S::FIRE4, 0, 0);
+    state(S::FIRE4, SPR::FIRE, FF_FULLBRIGHT | 1, 2, m(a_fire), S::FIRE5, 0, 0);
+    state(S::FIRE5, SPR::FIRE, FF_FULLBRIGHT | 2, 2, m(a_fire_crackle), S::FIRE6, 0, 0);
+    state(S::FIRE6, SPR::FIRE, FF_FULLBRIGHT | 1, 2, m(a_fire), S::FIRE7, 0, 0);
+    state(S::FIRE7, SPR::FIRE, FF_FULLBRIGHT | 2, 2, m(a_fire), S::FIRE8, 0, 0);
+    state(S::FIRE8, SPR::FIRE, FF_FULLBRIGHT | 1, 2, m(a_fire), S::FIRE9, 0, 0);
+    state(S::FIRE9, SPR::FIRE, FF_FULLBRIGHT | 2, 2, m(a_fire), S::FIRE10, 0, 0);
+    state(S::FIRE10, SPR::FIRE, FF_FULLBRIGHT | 3, 2, m(a_fire), S::FIRE11, 0, 0);
+    state(S::FIRE11, SPR::FIRE, FF_FULLBRIGHT | 2, 2, m(a_fire), S::FIRE12, 0, 0);
+    state(S::FIRE12, SPR::FIRE, FF_FULLBRIGHT | 3, 2, m(a_fire), S::FIRE13, 0, 0);
+    state(S::FIRE13, SPR::FIRE, FF_FULLBRIGHT | 2, 2, m(a_fire), S::FIRE14, 0, 0);
+    state(S::FIRE14, SPR::FIRE, FF_FULLBRIGHT | 3, 2, m(a_fire), S::FIRE15, 0, 0);
+    state(S::FIRE15, SPR::FIRE, FF_FULLBRIGHT | 4, 2, m(a_fire), S::FIRE16, 0, 0);
+    state(S::FIRE16, SPR::FIRE, FF_FULLBRIGHT | 3, 2, m(a_fire), S::FIRE17, 0, 0);
+    state(S::FIRE17, SPR::FIRE, FF_FULLBRIGHT | 4, 2, m(a_fire), S::FIRE18, 0, 0);
+    state(S::FIRE18, SPR::FIRE, FF_FULLBRIGHT | 3, 2, m(a_fire), S::FIRE19, 0, 0);
+    state(S::FIRE19, SPR::FIRE, FF_FULLBRIGHT | 4, 2, m(a_fire_crackle), S::FIRE20, 0, 0);
+    state(S::FIRE20, SPR::FIRE, FF_FULLBRIGHT | 5, 2, m(a_fire), S::FIRE21, 0, 0);
+    state(S::FIRE21, SPR::FIRE, FF_FULLBRIGHT | 4, 2, m(a_fire), S::FIRE22, 0, 0);
+    state(S::FIRE22, SPR::FIRE, FF_FULLBRIGHT | 5, 2, m(a_fire), S::FIRE23, 0, 0);
+    state(S::FIRE23, SPR::FIRE, FF_FULLBRIGHT | 4, 2, m(a_fire), S::FIRE24, 0, 0);
+    state(S::FIRE24, SPR::FIRE, FF_FULLBRIGHT | 5, 2, m(a_fire), S::FIRE25, 0, 0);
+    state(S::FIRE25, SPR::FIRE, FF_FULLBRIGHT | 6, 2, m(a_fire), S::FIRE26, 0, 0);
+    state(S::FIRE26, SPR::FIRE, FF_FULLBRIGHT | 7, 2, m(a_fire), S::FIRE27, 0, 0);
+    state(S::FIRE27, SPR::FIRE, FF_FULLBRIGHT | 6, 2, m(a_fire), S::FIRE28, 0, 0);
+    state(S::FIRE28, SPR::FIRE, FF_FULLBRIGHT | 7, 2, m(a_fire), S::FIRE29, 0, 0);
+    state(S::FIRE29, SPR::FIRE, FF_FULLBRIGHT | 6, 2, m(a_fire), S::FIRE30, 0, 0);
+    state(S::FIRE30, SPR::FIRE, FF_FULLBRIGHT | 7, 2, m(a_fire), S::NULL, 0, 0);
+    state(S::SMOKE1, SPR::PUFF, 1, 4, NULL, S::SMOKE2, 0, 0);
+    state(S::SMOKE2, SPR::PUFF, 2, 4, NULL, S::SMOKE3, 0, 0);
+    state(S::SMOKE3, SPR::PUFF, 1, 4, NULL, S::SMOKE4, 0, 0);
+    state(S::SMOKE4, SPR::PUFF, 2, 4, NULL, S::SMOKE5, 0, 0);
+    state(S::SMOKE5, SPR::PUFF, 3, 4, NULL, S::NULL, 0, 0);
+    state(S::TRACER, SPR::FATB, FF_FULLBRIGHT | 0, 2, m(a_tracer), S::TRACER2, 0, 0);
+    state(S::TRACER2, SPR::FATB, FF_FULLBRIGHT | 1, 2, m(a_tracer), S::TRACER, 0, 0);
+    state(S::TRACEEXP1, SPR::FBXP, FF_FULLBRIGHT | 0, 8, NULL, S::TRACEEXP2, 0, 0);
+    state(S::TRACEEXP2, SPR::FBXP, FF_FULLBRIGHT | 1, 6, NULL, S::TRACEEXP3, 0, 0);
+    state(S::TRACEEXP3, SPR::FBXP, FF_FULLBRIGHT | 2, 4, NULL, S::NULL, 0, 0);
+    state(S::SKEL_STND, SPR::SKEL, 0, 10, m(a_look), S::SKEL_STND2, 0, 0);
+    state(S::SKEL_STND2, SPR::SKEL, 1, 10, m(a_look), S::SKEL_STND, 0, 0);
+    state(S::SKEL_RUN1, SPR::SKEL, 0, 2, m(a_chase), S::SKEL_RUN2, 0, 0);
+    state(S::SKEL_RUN2, SPR::SKEL, 0, 2, m(a_chase), S::SKEL_RUN3, 0, 0);
+    state(S::SKEL_RUN3, SPR::SKEL, 1, 2, m(a_chase), S::SKEL_RUN4, 0, 0);
+    state(S::SKEL_RUN4, SPR::SKEL, 1, 2, m(a_chase), S::SKEL_RUN5, 0, 0);
+    state(S::SKEL_RUN5, SPR::SKEL, 2, 2, m(a_chase), S::SKEL_RUN6, 0, 0);
+    state(S::SKEL_RUN6, SPR::SKEL, 2, 2, m(a_chase), S::SKEL_RUN7, 0, 0);
+    state(S::SKEL_RUN7, SPR::SKEL, 3, 2, m(a_chase), S::SKEL_RUN8, 0, 0);
+    state(S::SKEL_RUN8, SPR::SKEL, 3, 2, m(a_chase), S::SKEL_RUN9, 0, 0);
+    state(S::SKEL_RUN9, SPR::SKEL, 4, 2, m(a_chase), S::SKEL_RUN10, 0, 0);
+    state(S::SKEL_RUN10, SPR::SKEL, 4, 2, m(a_chase), S::SKEL_RUN11, 0, 0);
+    state(S::SKEL_RUN11, SPR::SKEL, 5, 2, m(a_chase), S::SKEL_RUN12, 0, 0);
+    state(S::SKEL_RUN12, SPR::SKEL, 5, 2, m(a_chase), S::SKEL_RUN1, 0, 0);
+    state(S::SKEL_FIST1, SPR::SKEL, 6, 0, m(a_face_target), S::SKEL_FIST2, 0, 0);
+    state(S::SKEL_FIST2, SPR::SKEL, 6, 6, m(a_skel_whoosh), S::SKEL_FIST3, 0, 0);
+    state(S::SKEL_FIST3, SPR::SKEL, 7, 6, m(a_face_target), S::SKEL_FIST4, 0, 0);
+    state(S::SKEL_FIST4, SPR::SKEL, 8, 6, m(a_skel_fist), S::SKEL_RUN1, 0, 0);
+    state(S::SKEL_MISS1, SPR::SKEL, FF_FULLBRIGHT | 9, 0, m(a_face_target), S::SKEL_MISS2, 0, 0);
+    state(S::SKEL_MISS2, SPR::SKEL, FF_FULLBRIGHT | 9, 10, m(a_face_target), S::SKEL_MISS3, 0, 0);
+    state(S::SKEL_MISS3, SPR::SKEL, 10, 10, m(a_skel_missile), S::SKEL_MISS4, 0, 0);
+    state(S::SKEL_MISS4, SPR::SKEL, 10, 10, m(a_face_target), S::SKEL_RUN1, 0, 0);
+    state(S::SKEL_PAIN, SPR::SKEL, 11, 5, NULL, S::SKEL_PAIN2, 0, 0);
+    state(S::SKEL_PAIN2, SPR::SKEL, 11, 5, m(a_pain), S::SKEL_RUN1, 0, 0);
+    state(S::SKEL_DIE1, SPR::SKEL, 11, 7, NULL, S::SKEL_DIE2, 0, 0);
+    state(S::SKEL_DIE2, SPR::SKEL, 12, 7, NULL, S::SKEL_DIE3, 0, 0);
+    state(S::SKEL_DIE3, SPR::SKEL, 13, 7, m(a_scream), S::SKEL_DIE4, 0, 0);
+    state(S::SKEL_DIE4, SPR::SKEL, 14, 7, m(a_fall), S::SKEL_DIE5, 0, 0);
+    state(S::SKEL_DIE5, SPR::SKEL, 15, 7, NULL, S::SKEL_DIE6, 0, 0);
+    state(S::SKEL_DIE6, SPR::SKEL, 16, -1, NULL, S::NULL, 0, 0);
+    state(S::SKEL_RAISE1, SPR::SKEL, 16, 5, NULL, S::SKEL_RAISE2, 0, 0);
+    state(S::SKEL_RAISE2, SPR::SKEL, 15, 5, NULL, S::SKEL_RAISE3, 0, 0);
+    state(S::SKEL_RAISE3, SPR::SKEL, 14, 5, NULL, S::SKEL_RAISE4, 0, 0);
+    state(S::SKEL_RAISE4, SPR::SKEL, 13, 5, NULL, S::SKEL_RAISE5, 0, 0);
+    state(S::SKEL_RAISE5, SPR::SKEL, 12, 5, NULL, S::SKEL_RAISE6, 0, 0);
+    state(S::SKEL_RAISE6, SPR::SKEL, 11, 5, NULL, S::SKEL_RUN1, 0, 0);
+    state(S::FATSHOT1, SPR::MANF, FF_FULLBRIGHT | 0, 4, NULL, S::FATSHOT2, 0, 0);
+    state(S::FATSHOT2, SPR::MANF, FF_FULLBRIGHT | 1, 4, NULL, S::FATSHOT1, 0, 0);
+    state(S::FATSHOTX1, SPR::MISL, FF_FULLBRIGHT | 1, 8, NULL, S::FATSHOTX2, 0, 0);
+    state(S::FATSHOTX2, SPR::MISL, FF_FULLBRIGHT | 2, 6, NULL, S::FATSHOTX3, 0, 0);
+    state(S::FATSHOTX3, SPR::MISL, FF_FULLBRIGHT | 3, 4, NULL, S::NULL, 0, 0);
+    state(S::FATT_STND, SPR::FATT, 0, 15, m(a_look), S::FATT_STND2, 0, 0);
+    state(S::FATT_STND2, SPR::FATT, 1, 15, m(a_look), S::FATT_STND, 0, 0);
+    state(S::FATT_RUN1, SPR::FATT, 0, 4, m(a_chase), S::FATT_RUN2, 0, 0);
+    state(S::FATT_RUN2, SPR::FATT, 0, 4, m(a_chase), S::FATT_RUN3, 0, 0);
+    state(S::FATT_RUN3, SPR::FATT, 1, 4, m(a_chase), S::FATT_RUN4, 0, 0);
+    state(S::FATT_RUN4, SPR::FATT, 1, 4, m(a_chase), S::FATT_RUN5, 0, 0);
+    state(S::FATT_RUN5, SPR::FATT, 2, 4, m(a_chase), S::FATT_RUN6, 0, 0);
+    state(S::FATT_RUN6, SPR::FATT, 2, 4, m(a_chase), S::FATT_RUN7, 0, 0);
+    state(S::FATT_RUN7, SPR::FATT, 3, 4, m(a_chase), S::FATT_RUN8, 0, 0);
+    state(S::FATT_RUN8, SPR::FATT, 3, 4, m(a_chase), S::FATT_RUN9, 0, 0);
+    state(S::FATT_RUN9, SPR::FATT, 4, 4, m(a_chase), S::FATT_RUN10, 0, 0);
+    state(S::FATT_RUN10, SPR::FATT, 4, 4, m(a_chase), S::FATT_RUN11, 0, 0);
+    state(S::FATT_RUN11, SPR::FATT, 5, 4, m(a_chase), S::FATT_RUN12, 0, 0);
+    state(S::FATT_RUN12, SPR::FATT, 5, 4, m(a_chase), S::FATT_RUN1, 0, 0);
+    state(S::FATT_ATK1, SPR::FATT, 6, 20, m(a_fat_raise), S::FATT_ATK2, 0, 0);
+    state(S::FATT_ATK2, SPR::FATT, FF_FULLBRIGHT | 7, 10, m(a_fat_attack1), S::FATT_ATK3, 0, 0);
+    state(S::FATT_ATK3, SPR::FATT, 8, 5, m(a_face_target), S::FATT_ATK4, 0, 0);
+    state(S::FATT_ATK4, SPR::FATT, 6, 5, m(a_face_target), S::FATT_ATK5, 0, 0);
+    state(S::FATT_ATK5, SPR::FATT, FF_FULLBRIGHT | 7, 10, m(a_fat_attack2), S::FATT_ATK6, 0, 0);
+    state(S::FATT_ATK6, SPR::FATT, 8, 5, m(a_face_target), S::FATT_ATK7, 0, 0);
+    state(S::FATT_ATK7, SPR::FATT, 6, 5, m(a_face_target), S::FATT_ATK8, 0, 0);
+    state(S::FATT_ATK8, SPR::FATT, FF_FULLBRIGHT | 7, 10, m(a_fat_attack3), S::FATT_ATK9, 0, 0);
+    state(S::FATT_ATK9, SPR::FATT, 8, 5, m(a_face_target), S::FATT_ATK10, 0, 0);
+    state(S::FATT_ATK10, SPR::FATT, 6, 5, m(a_face_target), S::FATT_RUN1, 0, 0);
+    state(S::FATT_PAIN, SPR::FATT, 9, 3, NULL, S::FATT_PAIN2, 0, 0);
+    state(S::FATT_PAIN2, SPR::FATT, 9, 3, m(a_pain), S::FATT_RUN1, 0, 0);
+    state(S::FATT_DIE1, SPR::FATT, 10, 6, NULL, S::FATT_DIE2, 0, 0);
+    state(S::FATT_DIE2, SPR::FATT, 11, 6, m(a_scream), S::FATT_DIE3, 0, 0);
+    state(S::FATT_DIE3, SPR::FATT, 12, 6, m(a_fall), S::FATT_DIE4, 0, 0);
+    state(S::FATT_DIE4, SPR::FATT, 13, 6, NULL, S::FATT_DIE5, 0, 0);
+    state(S::FATT_DIE5, SPR::FATT, 14, 6, NULL, S::FATT_DIE6, 0, 0);
+    state(S::FATT_DIE6, SPR::FATT, 15, 6, NULL, S::FATT_DIE7, 0, 0);
+    state(S::FATT_DIE7, SPR::FATT, 16, 6, NULL, S::FATT_DIE8, 0, 0);
+    state(S::FATT_DIE8, SPR::FATT, 17, 6, NULL, S::FATT_DIE9, 0, 0);
+    state(S::FATT_DIE9, SPR::FATT, 18, 6, NULL, S::FATT_DIE10, 0, 0);
+    state(S::FATT_DIE10, SPR::FATT, 19, -1, m(a_boss_death), S::NULL, 0, 0);
+    state(S::FATT_RAISE1, SPR::FATT, 17, 5, NULL, S::FATT_RAISE2, 0, 0);
+    state(S::FATT_RAISE2, SPR::FATT, 16, 5, NULL, S::FATT_RAISE3, 0, 0);
+    state(S::FATT_RAISE3, SPR::FATT, 15, 5, NULL, S::FATT_RAISE4, 0, 0);
+    state(S::FATT_RAISE4, SPR::FATT, 14, 5, NULL, S::FATT_RAISE5, 0, 0);
+    state(S::FATT_RAISE5, SPR::FATT, 13, 5, NULL, S::FATT_RAISE6, 0, 0);
+    state(S::FATT_RAISE6, SPR::FATT, 12, 5, NULL, S::FATT_RAISE7, 0, 0);
+    state(S::FATT_RAISE7, SPR::FATT, 11, 5, NULL, S::FATT_RAISE8, 0, 0);
+    state(S::FATT_RAISE8, SPR::FATT, 10, 5, NULL, S::FATT_RUN1, 0, 0);
+    state(S::CPOS_STND, SPR::CPOS, 0, 10, m(a_look), S::CPOS_STND2, 0, 0);
+    state(S::CPOS_STND2, SPR::CPOS, 1, 10, m(a_look), S::CPOS_STND, 0, 0);
+    state(S::CPOS_RUN1, SPR::CPOS, 0, 3, m(a_chase), S::CPOS_RUN2, 0, 0);
+    state(S::CPOS_RUN2, SPR::CPOS, 0, 3, m(a_chase), S::CPOS_RUN3, 0, 0);
+    state(S::CPOS_RUN3, SPR::CPOS, 1, 3, m(a_chase), S::CPOS_RUN4, 0, 0);
+    state(S::CPOS_RUN4, SPR::CPOS, 1, 3, m(a_chase), S::CPOS_RUN5, 0, 0);
+    state(S::CPOS_RUN5, SPR::CPOS, 2, 3, m(a_chase), S::CPOS_RUN6, 0, 0);
+    state(S::CPOS_RUN6, SPR::CPOS, 2, 3, m(a_chase), S::CPOS_RUN7, 0, 0);
+    state(S::CPOS_RUN7, SPR::CPOS, 3, 3, m(a_chase), S::CPOS_RUN8, 0, 0);
+    state(S::CPOS_RUN8, SPR::CPOS, 3, 3, m(a_chase), S::CPOS_RUN1, 0, 0);
+    state(S::CPOS_ATK1, SPR::CPOS, 4, 10, m(a_face_target), S::CPOS_ATK2, 0, 0);
+    state(S::CPOS_ATK2, SPR::CPOS, FF_FULLBRIGHT | 5, 4, m(a_cpos_attack), S::CPOS_ATK3, 0, 0);
+    state(S::CPOS_ATK3, SPR::CPOS, FF_FULLBRIGHT | 4, 4, m(a_cpos_attack), S::CPOS_ATK4, 0, 0);
+    state(S::CPOS_ATK4, SPR::CPOS, 5, 1, m(a_cpos_refire), S::CPOS_ATK2, 0, 0);
+    state(S::CPOS_PAIN, SPR::CPOS, 6, 3, NULL, S::CPOS_PAIN2, 0, 0);
+    state(S::CPOS_PAIN2, SPR::CPOS, 6, 3, m(a_pain), S::CPOS_RUN1, 0, 0);
+    state(S::CPOS_DIE1, SPR::CPOS, 7, 5, NULL, S::CPOS_DIE2, 0, 0);
+    state(S::CPOS_DIE2, SPR::CPOS, 8, 5, m(a_scream), S::CPOS_DIE3, 0, 0);
+    state(S::CPOS_DIE3, SPR::CPOS, 9, 5, m(a_fall), S::CPOS_DIE4, 0, 0);
+    state(S::CPOS_DIE4, SPR::CPOS, 10, 5, NULL, S::CPOS_DIE5, 0, 0);
+    state(S::CPOS_DIE5, SPR::CPOS, 11, 5, NULL, S::CPOS_DIE6, 0, 0);
+    state(S::CPOS_DIE6, SPR::CPOS, 12, 5, NULL, S::CPOS_DIE7, 0, 0);
+    state(S::CPOS_DIE7, SPR::CPOS, 13, -1, NULL, S::NULL, 0, 0);
+    state(S::CPOS_XDIE1, SPR::CPOS, 14, 5, NULL, S::CPOS_XDIE2, 0, 0);
+    state(S::CPOS_XDIE2, SPR::CPOS, 15, 5, m(a_xscream), S::CPOS_XDIE3, 0, 0);
+    state(S::CPOS_XDIE3, SPR::CPOS, 16, 5, m(a_fall), S::CPOS_XDIE4, 0, 0);
+    state(S::CPOS_XDIE4, SPR::CPOS, 17, 5, NULL, S::CPOS_XDIE5, 0, 0);
+    state(S::CPOS_XDIE5, SPR::CPOS, 18, 5, NULL, S::CPOS_XDIE6, 0, 0);
+    state(S::CPOS_XDIE6, SPR::CPOS, 19, -1, NULL, S::NULL, 0, 0);
+    state(S::CPOS_RAISE1, SPR::CPOS, 13, 5, NULL, S::CPOS_RAISE2, 0, 0);
+    state(S::CPOS_RAISE2, SPR::CPOS, 12, 5, NULL, S::CPOS_RAISE3, 0, 0);
+    state(S::CPOS_RAISE3, SPR::CPOS, 11, 5, NULL, S::CPOS_RAISE4, 0, 0);
+    state(S::CPOS_RAISE4, SPR::CPOS, 10, 5, NULL, S::CPOS_RAISE5, 0, 0);
+    state(S::CPOS_RAISE5, SPR::CPOS, 9, 5, NULL, S::CPOS_RAISE6, 0, 0);
+    state(S::CPOS_RAISE6, SPR::CPOS, 8, 5, NULL, S::CPOS_RAISE7, 0, 0);
+    state(S::CPOS_RAISE7, SPR::CPOS, 7, 5, NULL, S::CPOS_RUN1, 0, 0);
+    state(S::TROO_STND, SPR::TROO, 0, 10, m(a_look), S::TROO_STND2, 0, 0);
+    state(S::TROO_STND2, SPR::TROO, 1, 10, m(a_look), S::TROO_STND, 0, 0);
+    state(S::TROO_RUN1, SPR::TROO, 0, 3, m(a_chase), S::TROO_RUN2, 0, 0);
+    state(S::TROO_RUN2, SPR::TROO, 0, 3, m(a_chase), S::TROO_RUN3, 0, 0);
+    state(S::TROO_RUN3, SPR::TROO, 1, 3, m(a_chase), S::TROO_RUN4, 0, 0);
+    state(S::TROO_RUN4, SPR::TROO, 1, 3, m(a_chase), S::TROO_RUN5, 0, 0);
+    state(S::TROO_RUN5, SPR::TROO, 2, 3, m(a_chase), S::TROO_RUN6, 0, 0);
+    state(S::TROO_RUN6, SPR::TROO, 2, 3, m(a_chase), S::TROO_RUN7, 0, 0);
+    state(S::TROO_RUN7, SPR::TROO, 3, 3, m(a_chase), S::TROO_RUN8, 0, 0);
+    state(S::TROO_RUN8, SPR::TROO, 3, 3, m(a_chase), S::TROO_RUN1, 0, 0);
+    state(S::TROO_ATK1, SPR::TROO, 4, 8, m(a_face_target), S::TROO_ATK2, 0, 0);
+    state(S::TROO_ATK2, SPR::TROO, 5, 8, m(a_face_target), S::TROO_ATK3, 0, 0);
+    state(S::TROO_ATK3, SPR::TROO, 6, 6, m(a_troop_attack), S::TROO_RUN1, 0, 0);
+    state(S::TROO_PAIN, SPR::TROO, 7, 2, NULL, S::TROO_PAIN2, 0, 0);
+    state(S::TROO_PAIN2, SPR::TROO, 7, 2, m(a_pain), S::TROO_RUN1, 0, 0);
+    state(S::TROO_DIE1, SPR::TROO, 8, 8, NULL, S::TROO_DIE2, 0, 0);
+    state(S::TROO_DIE2, SPR::TROO, 9, 8, m(a_scream), S::TROO_DIE3, 0, 0);
+    state(S::TROO_DIE3, SPR::TROO, 10, 6, NULL, S::TROO_DIE4, 0, 0);
+    state(S::TROO_DIE4, SPR::TROO, 11, 6, m(a_fall), S::TROO_DIE5, 0, 0);
+    state(S::TROO_DIE5, SPR::TROO, 12, -1, NULL, S::NULL, 0, 0);
+    state(S::TROO_XDIE1, SPR::TROO, 13, 5, NULL, S::TROO_XDIE2, 0, 0);
+    state(S::TROO_XDIE2, SPR::TROO, 14, 5, m(a_xscream), S::TROO_XDIE3, 0, 0);
+    state(S::TROO_XDIE3, SPR::TROO, 15, 5, NULL, S::TROO_XDIE4, 0, 0);
+    state(S::TROO_XDIE4, SPR::TROO, 16, 5, m(a_fall), S::TROO_XDIE5, 0, 0);
+    state(S::TROO_XDIE5, SPR::TROO, 17, 5, NULL, S::TROO_XDIE6, 0, 0);
+    state(S::TROO_XDIE6, SPR::TROO, 18, 5, NULL, S::TROO_XDIE7, 0, 0);
+    state(S::TROO_XDIE7, SPR::TROO, 19, 5, NULL, S::TROO_XDIE8, 0, 0);
+    state(S::TROO_XDIE8, SPR::TROO, 20, -1, NULL, S::NULL, 0, 0);
+    state(S::TROO_RAISE1, SPR::TROO, 12, 8, NULL, S::TROO_RAISE2, 0, 0);
+    state(S::TROO_RAISE2, SPR::TROO, 11, 8, NULL, S::TROO_RAISE3, 0, 0);
+    state(S::TROO_RAISE3, SPR::TROO, 10, 6, NULL, S::TROO_RAISE4, 0, 0);
+    state(S::TROO_RAISE4, SPR::TROO, 9, 6, NULL, S::TROO_RAISE5, 0, 0);
+    state(S::TROO_RAISE5, SPR::TROO, 8, 6, NULL, S::TROO_RUN1, 0, 0);
+    state(S::SARG_STND, SPR::SARG, 0, 10, m(a_look), S::SARG_STND2, 0, 0);
+    state(S::SARG_STND2, SPR::SARG, 1, 10, m(a_look), S::SARG_STND, 0, 0);
+    state(S::SARG_RUN1, SPR::SARG, 0, 2, m(a_chase), S::SARG_RUN2, 0, 0);
+    state(S::SARG_RUN2, SPR::SARG, 0, 2, m(a_chase), S::SARG_RUN3, 0, 0);
+    state(S::SARG_RUN3, SPR::SARG, 1, 2, m(a_chase), S::SARG_RUN4, 0, 0);
+    state(S::SARG_RUN4, SPR::SARG, 1, 2, m(a_chase), S::SARG_RUN5, 0, 0);
+    state(S::SARG_RUN5, SPR::SARG, 2, 2, m(a_chase), S::SARG_RUN6, 0, 0);
+    state(S::SARG_RUN6, SPR::SARG, 2, 2, m(a_chase), S::SARG_RUN7, 0, 0);
+    state(S::SARG_RUN7, SPR::SARG, 3, 2, m(a_chase), S::SARG_RUN8, 0, 0);
+    state(S::SARG_RUN8, SPR::SARG, 3, 2, m(a_chase), S::SARG_RUN1, 0, 0);
+    state(S::SARG_ATK1, SPR::SARG, 4, 8, m(a_face_target), S::SARG_ATK2, 0, 0);
+    state(S::SARG_ATK2, SPR::SARG, 5, 8, m(a_face_target), S::SARG_ATK3, 0, 0);
+    state(S::SARG_ATK3, SPR::SARG, 6, 8, m(a_sarg_attack), S::SARG_RUN1, 0, 0);
+    state(S::SARG_PAIN, SPR::SARG, 7, 2, NULL, S::SARG_PAIN2, 0, 0);
+    state(S::SARG_PAIN2, SPR::SARG, 7, 2, m(a_pain), S::SARG_RUN1, 0, 0);
+    state(S::SARG_DIE1, SPR::SARG, 8, 8, NULL, S::SARG_DIE2, 0, 0);
+    state(S::SARG_DIE2, SPR::SARG, 9, 8, m(a_scream), S::SARG_DIE3, 0, 0);
+    state(S::SARG_DIE3, SPR::SARG, 10, 4, NULL, S::SARG_DIE4, 0, 0);
+    state(S::SARG_DIE4, SPR::SARG, 11, 4, m(a_fall), S::SARG_DIE5, 0, 0);
+    state(S::SARG_DIE5, SPR::SARG, 12, 4, NULL, S::SARG_DIE6, 0, 0);
+    state(S::SARG_DIE6, SPR::SARG, 13, -1, NULL, S::NULL, 0, 0);
+    state(S::SARG_RAISE1, SPR::SARG, 13, 5, NULL, S::SARG_RAISE2, 0, 0);
+    state(S::SARG_RAISE2, SPR::SARG, 12, 5, NULL, S::SARG_RAISE3, 0, 0);
+    state(S::SARG_RAISE3, SPR::SARG, 11, 5, NULL, S::SARG_RAISE4, 0, 0);
+    state(S::SARG_RAISE4, SPR::SARG, 10, 5, NULL, S::SARG_RAISE5, 0, 0);
+    state(S::SARG_RAISE5, SPR::SARG, 9, 5, NULL, S::SARG_RAISE6, 0, 0);
+    state(S::SARG_RAISE6, SPR::SARG, 8, 5, NULL, S::SARG_RUN1, 0, 0);
+    state(S::HEAD_STND, SPR::HEAD, 0, 10, m(a_look), S::HEAD_STND, 0, 0);
+    state(S::HEAD_RUN1, SPR::HEAD, 0, 3, m(a_chase), S::HEAD_RUN1, 0, 0);
+    state(S::HEAD_ATK1, SPR::HEAD, 1, 5, m(a_face_target), S::HEAD_ATK2, 0, 0);
+    state(S::HEAD_ATK2, SPR::HEAD, 2, 5, m(a_face_target), S::HEAD_ATK3, 0, 0);
+    state(S::HEAD_ATK3, SPR::HEAD, FF_FULLBRIGHT | 3, 5, m(a_head_attack), S::HEAD_RUN1, 0, 0);
+    state(S::HEAD_PAIN, SPR::HEAD, 4, 3, NULL, S::HEAD_PAIN2, 0, 0);
+    state(S::HEAD_PAIN2, SPR::HEAD, 4, 3, m(a_pain), S::HEAD_PAIN3, 0, 0);
+    state(S::HEAD_PAIN3, SPR::HEAD, 5, 6, NULL, S::HEAD_RUN1, 0, 0);
+    state(S::HEAD_DIE1, SPR::HEAD, 6, 8, NULL, S::HEAD_DIE2, 0, 0);
+    state(S::HEAD_DIE2, SPR::HEAD, 7, 8, m(a_scream), S::HEAD_DIE3, 0, 0);
+    state(S::HEAD_DIE3, SPR::HEAD, 8, 8, NULL, S::HEAD_DIE4, 0, 0);
+    state(S::HEAD_DIE4, SPR::HEAD, 9, 8, NULL, S::HEAD_DIE5, 0, 0);
+    state(S::HEAD_DIE5, SPR::HEAD, 10, 8, m(a_fall), S::HEAD_DIE6, 0, 0);
+    state(S::HEAD_DIE6, SPR::HEAD, 11, -1, NULL, S::NULL, 0, 0);
+    state(S::HEAD_RAISE1, SPR::HEAD, 11, 8, NULL, S::HEAD_RAISE2, 0, 0);
+    state(S::HEAD_RAISE2, SPR::HEAD, 10, 8, NULL, S::HEAD_RAISE3, 0, 0);
+    state(S::HEAD_RAISE3, SPR::HEAD, 9, 8, NULL, S::HEAD_RAISE4, 0, 0);
+    state(S::HEAD_RAISE4, SPR::HEAD, 8, 8, NULL, S::HEAD_RAISE5, 0, 0);
+    state(S::HEAD_RAISE5, SPR::HEAD, 7, 8, NULL, S::HEAD_RAISE6, 0, 0);
+    state(S::HEAD_RAISE6, SPR::HEAD, 6, 8, NULL, S::HEAD_RUN1, 0, 0);
+    state(S::BRBALL1, SPR::BAL7, FF_FULLBRIGHT | 0, 4, NULL, S::BRBALL2, 0, 0);
+    state(S::BRBALL2, SPR::BAL7, FF_FULLBRIGHT | 1, 4, NULL, S::BRBALL1, 0, 0);
+    state(S::BRBALLX1, SPR::BAL7, FF_FULLBRIGHT | 2, 6, NULL, S::BRBALLX2, 0, 0);
+    state(S::BRBALLX2, SPR::BAL7, FF_FULLBRIGHT | 3, 6, NULL, S::BRBALLX3, 0, 0);
+    state(S::BRBALLX3, SPR::BAL7, FF_FULLBRIGHT | 4, 6, NULL, S::NULL, 0, 0);
+    state(S::BOSS_STND, SPR::BOSS, 0, 10, m(a_look), S::BOSS_STND2, 0, 0);
+    state(S::BOSS_STND2, SPR::BOSS, 1, 10, m(a_look), S::BOSS_STND, 0, 0);
+    state(S::BOSS_RUN1, SPR::BOSS, 0, 3, m(a_chase), S::BOSS_RUN2, 0, 0);
+    state(S::BOSS_RUN2, SPR::BOSS, 0, 3, m(a_chase), S::BOSS_RUN3, 0, 0);
+    state(S::BOSS_RUN3, SPR::BOSS, 1, 3, m(a_chase), S::BOSS_RUN4, 0, 0);
+    state(S::BOSS_RUN4, SPR::BOSS, 1, 3, m(a_chase), S::BOSS_RUN5, 0, 0);
+    state(S::BOSS_RUN5, SPR::BOSS, 2, 3, m(a_chase), S::BOSS_RUN6, 0, 0);
+    state(S::BOSS_RUN6, SPR::BOSS, 2, 3, m(a_chase), S::BOSS_RUN7, 0, 0);
+    state(S::BOSS_RUN7, SPR::BOSS, 3, 3, m(a_chase), S::BOSS_RUN8, 0, 0);
+    state(S::BOSS_RUN8, SPR::BOSS, 3, 3, m(a_chase), S::BOSS_RUN1, 0, 0);
+    state(S::BOSS_ATK1, SPR::BOSS, 4, 8, m(a_face_target), S::BOSS_ATK2, 0, 0);
+    state(S::BOSS_ATK2, SPR::BOSS, 5, 8, m(a_face_target), S::BOSS_ATK3, 0, 0);
+    state(S::BOSS_ATK3, SPR::BOSS, 6, 8, m(a_bruis_attack), S::BOSS_RUN1, 0, 0);
+    state(S::BOSS_PAIN, SPR::BOSS, 7, 2, NULL, S::BOSS_PAIN2, 0, 0);
+    state(S::BOSS_PAIN2, SPR::BOSS, 7, 2, m(a_pain), S::BOSS_RUN1, 0, 0);
+    state(S::BOSS_DIE1, SPR::BOSS, 8, 8, NULL, S::BOSS_DIE2, 0, 0);
+    state(S::BOSS_DIE2, SPR::BOSS, 9, 8, m(a_scream), S::BOSS_DIE3, 0, 0);
+    state(S::BOSS_DIE3, SPR::BOSS, 10, 8, NULL, S::BOSS_DIE4, 0, 0);
+    state(S::BOSS_DIE4, SPR::BOSS, 11, 8, m(a_fall), S::BOSS_DIE5, 0, 0);
+    state(S::BOSS_DIE5, SPR::BOSS, 12, 8, NULL, S::BOSS_DIE6, 0, 0);
+    state(S::BOSS_DIE6, SPR::BOSS, 13, 8, NULL, S::BOSS_DIE7, 0, 0);
+    state(S::BOSS_DIE7, SPR::BOSS, 14, -1, m(a_boss_death), S::NULL, 0, 0);
+    state(S::BOSS_RAISE1, SPR::BOSS, 14, 8, NULL, S::BOSS_RAISE2, 0, 0);
+    state(S::BOSS_RAISE2, SPR::BOSS, 13, 8, NULL, S::BOSS_RAISE3, 0, 0);
+    state(S::BOSS_RAISE3, SPR::BOSS, 12, 8, NULL, S::BOSS_RAISE4, 0, 0);
+    state(S::BOSS_RAISE4, SPR::BOSS, 11, 8, NULL, S::BOSS_RAISE5, 0, 0);
+    state(S::BOSS_RAISE5, SPR::BOSS, 10, 8, NULL, S::BOSS_RAISE6, 0, 0);
+    state(S::BOSS_RAISE6, SPR::BOSS, 9, 8, NULL, S::BOSS_RAISE7, 0, 0);
+    state(S::BOSS_RAISE7, SPR::BOSS, 8, 8, NULL, S::BOSS_RUN1, 0, 0);
+    state(S::BOS2_STND, SPR::BOS2, 0, 10, m(a_look), S::BOS2_STND2, 0, 0);
+    state(S::BOS2_STND2, SPR::BOS2, 1, 10, m(a_look), S::BOS2_STND, 0, 0);
+    state(S::BOS2_RUN1, SPR::BOS2, 0, 3, m(a_chase), S::BOS2_RUN2, 0, 0);
+    state(S::BOS2_RUN2, SPR::BOS2, 0, 3, m(a_chase), S::BOS2_RUN3, 0, 0);
+    state(S::BOS2_RUN3, SPR::BOS2, 1, 3, m(a_chase), S::BOS2_RUN4, 0, 0);
+    state(S::BOS2_RUN4, SPR::BOS2, 1, 3, m(a_chase), S::BOS2_RUN5, 0, 0);
+    state(S::BOS2_RUN5, SPR::BOS2, 2, 3, m(a_chase), S::BOS2_RUN6, 0, 0);
+    state(S::BOS2_RUN6, SPR::BOS2, 2, 3, m(a_chase), S::BOS2_RUN7, 0, 0);
+    state(S::BOS2_RUN7, SPR::BOS2, 3, 3, m(a_chase), S::BOS2_RUN8, 0, 0);
+    state(S::BOS2_RUN8, SPR::BOS2, 3, 3, m(a_chase), S::BOS2_RUN1, 0, 0);
+    state(S::BOS2_ATK1, SPR::BOS2, 4, 8, m(a_face_target), S::BOS2_ATK2, 0, 0);
+    state(S::BOS2_ATK2, SPR::BOS2, 5, 8, m(a_face_target), S::BOS2_ATK3, 0, 0);
+    state(S::BOS2_ATK3, SPR::BOS2, 6, 8, m(a_bruis_attack), S::BOS2_RUN1, 0, 0);
+    state(S::BOS2_PAIN, SPR::BOS2, 7, 2, NULL, S::BOS2_PAIN2, 0, 0);
+    state(S::BOS2_PAIN2, SPR::BOS2, 7, 2, m(a_pain), S::BOS2_RUN1, 0, 0);
+    state(S::BOS2_DIE1, SPR::BOS2, 8, 8, NULL, S::BOS2_DIE2, 0, 0);
+    state(S::BOS2_DIE2, SPR::BOS2, 9, 8, m(a_scream), S::BOS2_DIE3, 0, 0);
+    state(S::BOS2_DIE3, SPR::BOS2, 10, 8, NULL, S::BOS2_DIE4, 0, 0);
+    state(S::BOS2_DIE4, SPR::BOS2, 11, 8, m(a_fall), S::BOS2_DIE5, 0, 0);
+    state(S::BOS2_DIE5, SPR::BOS2, 12, 8, NULL, S::BOS2_DIE6, 0, 0);
+    state(S::BOS2_DIE6, SPR::BOS2, 13, 8, NULL, S::BOS2_DIE7, 0, 0);
+    state(S::BOS2_DIE7, SPR::BOS2, 14, -1, NULL, S::NULL, 0, 0);
+    state(S::BOS2_RAISE1, SPR::BOS2, 14, 8, NULL, S::BOS2_RAISE2, 0, 0);
+    state(S::BOS2_RAISE2, SPR::BOS2, 13, 8, NULL, S::BOS2_RAISE3, 0, 0);
+    state(S::BOS2_RAISE3, SPR::BOS2, 12, 8, NULL, S::BOS2_RAISE4, 0, 0);
+    state(S::BOS2_RAISE4, SPR::BOS2, 11, 8, NULL, S::BOS2_RAISE5, 0, 0);
+    state(S::BOS2_RAISE5, SPR::BOS2, 10, 8, NULL, S::BOS2_RAISE6, 0, 0);
+    state(S::BOS2_RAISE6, SPR::BOS2, 9, 8, NULL, S::BOS2_RAISE7, 0, 0);
+    state(S::BOS2_RAISE7, SPR::BOS2, 8, 8, NULL, S::BOS2_RUN1, 0, 0);
+    state(S::SKULL_STND, SPR::SKUL, FF_FULLBRIGHT | 0, 10, m(a_look), S::SKULL_STND2, 0, 0);
+    state(S::SKULL_STND2, SPR::SKUL, FF_FULLBRIGHT | 1, 10, m(a_look), S::SKULL_STND, 0, 0);
+    state(S::SKULL_RUN1, SPR::SKUL, FF_FULLBRIGHT | 0, 6, m(a_chase), S::SKULL_RUN2, 0, 0);
+    state(S::SKULL_RUN2, SPR::SKUL, FF_FULLBRIGHT | 1, 6, m(a_chase), S::SKULL_RUN1, 0, 0);
+    state(S::SKULL_ATK1, SPR::SKUL, FF_FULLBRIGHT | 2, 10, m(a_face_target), S::SKULL_ATK2, 0, 0);
+    state(S::SKULL_ATK2, SPR::SKUL, FF_FULLBRIGHT | 3, 4, m(a_skull_attack), S::SKULL_ATK3, 0, 0);
+    state(S::SKULL_ATK3, SPR::SKUL, FF_FULLBRIGHT | 2, 4, NULL, S::SKULL_ATK4, 0, 0);
+    state(S::SKULL_ATK4, SPR::SKUL, FF_FULLBRIGHT | 3, 4, NULL, S::SKULL_ATK3, 0, 0);
+    state(S::SKULL_PAIN, SPR::SKUL, FF_FULLBRIGHT | 4, 3, NULL, S::SKULL_PAIN2, 0, 0);
+    state(S::SKULL_PAIN2, SPR::SKUL, FF_FULLBRIGHT | 4, 3, m(a_pain), S::SKULL_RUN1, 0, 0);
+    state(S::SKULL_DIE1, SPR::SKUL, FF_FULLBRIGHT | 5, 6, NULL, S::SKULL_DIE2, 0, 0);
+    state(S::SKULL_DIE2, SPR::SKUL, FF_FULLBRIGHT | 6, 6, m(a_scream), S::SKULL_DIE3, 0, 0);
+    state(S::SKULL_DIE3, SPR::SKUL, FF_FULLBRIGHT | 7, 6, NULL, S::SKULL_DIE4, 0, 0);
+    state(S::SKULL_DIE4, SPR::SKUL, FF_FULLBRIGHT | 8, 6, m(a_fall), S::SKULL_DIE5, 0, 0);
+    state(S::SKULL_DIE5, SPR::SKUL, 9, 6, NULL, S::SKULL_DIE6, 0, 0);
+    state(S::SKULL_DIE6, SPR::SKUL, 10, 6, NULL, S::NULL, 0, 0);
+    state(S::SPID_STND, SPR::SPID, 0, 10, m(a_look), S::SPID_STND2, 0, 0);
+    state(S::SPID_STND2, SPR::SPID, 1, 10, m(a_look), S::SPID_STND, 0, 0);
+    state(S::SPID_RUN1, SPR::SPID, 0, 3, m(a_metal), S::SPID_RUN2, 0, 0);
+    state(S::SPID_RUN2, SPR::SPID, 0, 3, m(a_chase), S::SPID_RUN3, 0, 0);
+    state(S::SPID_RUN3, SPR::SPID, 1, 3, m(a_chase), S::SPID_RUN4, 0, 0);
+    state(S::SPID_RUN4, SPR::SPID, 1, 3, m(a_chase), S::SPID_RUN5, 0, 0);
+    state(S::SPID_RUN5, SPR::SPID, 2, 3, m(a_metal), S::SPID_RUN6, 0, 0);
+    state(S::SPID_RUN6, SPR::SPID, 2, 3, m(a_chase), S::SPID_RUN7, 0, 0);
+    state(S::SPID_RUN7, SPR::SPID, 3, 3, m(a_chase), S::SPID_RUN8, 0, 0);
+    state(S::SPID_RUN8, SPR::SPID, 3, 3, m(a_chase), S::SPID_RUN9, 0, 0);
+    state(S::SPID_RUN9, SPR::SPID, 4, 3, m(a_metal), S::SPID_RUN10, 0, 0);
+    state(S::SPID_RUN10, SPR::SPID, 4, 3, m(a_chase), S::SPID_RUN11, 0, 0);
+    state(S::SPID_RUN11, SPR::SPID, 5, 3, m(a_chase), S::SPID_RUN12, 0, 0);
+    state(S::SPID_RUN12, SPR::SPID, 5, 3, m(a_chase), S::SPID_RUN1, 0, 0);
+    state(S::SPID_ATK1, SPR::SPID, FF_FULLBRIGHT | 0, 20, m(a_face_target), S::SPID_ATK2, 0, 0);
+    state(S::SPID_ATK2, SPR::SPID, FF_FULLBRIGHT | 6, 4, m(a_spos_attack), S::SPID_ATK3, 0, 0);
+    state(S::SPID_ATK3, SPR::SPID, FF_FULLBRIGHT | 7, 4, m(a_spos_attack), S::SPID_ATK4, 0, 0);
+    state(S::SPID_ATK4, SPR::SPID, FF_FULLBRIGHT | 7, 1, m(a_spid_refire), S::SPID_ATK2, 0, 0);
+    state(S::SPID_PAIN, SPR::SPID, 8, 3, NULL, S::SPID_PAIN2, 0, 0);
+    state(S::SPID_PAIN2, SPR::SPID, 8, 3, m(a_pain), S::SPID_RUN1, 0, 0);
+    state(S::SPID_DIE1, SPR::SPID, 9, 20, m(a_scream), S::SPID_DIE2, 0, 0);
+    state(S::SPID_DIE2, SPR::SPID, 10, 10, m(a_fall), S::SPID_DIE3, 0, 0);
+    state(S::SPID_DIE3, SPR::SPID, 11, 10, NULL, S::SPID_DIE4, 0, 0);
+    state(S::SPID_DIE4, SPR::SPID, 12, 10, NULL, S::SPID_DIE5, 0, 0);
+    state(S::SPID_DIE5, SPR::SPID, 13, 10, NULL, S::SPID_DIE6, 0, 0);
+    state(S::SPID_DIE6, SPR::SPID, 14, 10, NULL, S::SPID_DIE7, 0, 0);
+    state(S::SPID_DIE7, SPR::SPID, 15, 10, NULL, S::SPID_DIE8, 0, 0);
+    state(S::SPID_DIE8, SPR::SPID, 16, 10, NULL, S::SPID_DIE9, 0, 0);
+    state(S::SPID_DIE9, SPR::SPID, 17, 10, NULL, S::SPID_DIE10, 0, 0);
+    state(S::SPID_DIE10, SPR::SPID, 18, 30, NULL, S::SPID_DIE11, 0, 0);
+    state(S::SPID_DIE11, SPR::SPID, 18, -1, m(a_boss_death), S::NULL, 0, 0);
+    state(S::BSPI_STND, SPR::BSPI, 0, 10, m(a_look), S::BSPI_STND2, 0, 0);
+    state(S::BSPI_STND2, SPR::BSPI, 1, 10, m(a_look), S::BSPI_STND, 0, 0);
+    state(S::BSPI_SIGHT, SPR::BSPI, 0, 20, NULL, S::BSPI_RUN1, 0, 0);
+    state(S::BSPI_RUN1, SPR::BSPI, 0, 3, m(a_baby_metal), S::BSPI_RUN2, 0, 0);
+    state(S::BSPI_RUN2, SPR::BSPI, 0, 3, m(a_chase), S::BSPI_RUN3, 0, 0);
+    state(S::BSPI_RUN3, SPR::BSPI, 1, 3, m(a_chase), S::BSPI_RUN4, 0, 0);
+    state(S::BSPI_RUN4, SPR::BSPI, 1, 3, m(a_chase), S::BSPI_RUN5, 0, 0);
+    state(S::BSPI_RUN5, SPR::BSPI, 2, 3, m(a_chase), S::BSPI_RUN6, 0, 0);
+    state(S::BSPI_RUN6, SPR::BSPI, 2, 3, m(a_chase), S::BSPI_RUN7, 0, 0);
+    state(S::BSPI_RUN7, SPR::BSPI, 3, 3, m(a_baby_metal), S::BSPI_RUN8, 0, 0);
+    state(S::BSPI_RUN8, SPR::BSPI, 3, 3, m(a_chase), S::BSPI_RUN9, 0, 0);
+    state(S::BSPI_RUN9, SPR::BSPI, 4, 3, m(a_chase), S::BSPI_RUN10, 0, 0);
+    state(S::BSPI_RUN10, SPR::BSPI, 4, 3, m(a_chase), S::BSPI_RUN11, 0, 0);
+    state(S::BSPI_RUN11, SPR::BSPI, 5, 3, m(a_chase), S::BSPI_RUN12, 0, 0);
+    state(S::BSPI_RUN12, SPR::BSPI, 5, 3, m(a_chase), S::BSPI_RUN1, 0, 0);
+    state(S::BSPI_ATK1, SPR::BSPI, FF_FULLBRIGHT | 0, 20, m(a_face_target), S::BSPI_ATK2, 0, 0);
+    state(S::BSPI_ATK2, SPR::BSPI, FF_FULLBRIGHT | 6, 4, m(a_bspi_attack), S::BSPI_ATK3, 0, 0);
+    state(S::BSPI_ATK3, SPR::BSPI, FF_FULLBRIGHT | 7, 4, NULL, S::BSPI_ATK4, 0, 0);
+    state(S::BSPI_ATK4, SPR::BSPI, FF_FULLBRIGHT | 7, 1, m(a_spid_refire), S::BSPI_ATK2, 0, 0);
+    state(S::BSPI_PAIN, SPR::BSPI, 8, 3, NULL, S::BSPI_PAIN2, 0, 0);
+    state(S::BSPI_PAIN2, SPR::BSPI, 8, 3, m(a_pain), S::BSPI_RUN1, 0, 0);
+    state(S::BSPI_DIE1, SPR::BSPI, 9, 20, m(a_scream), S::BSPI_DIE2, 0, 0);
+    state(S::BSPI_DIE2, SPR::BSPI, 10, 7, m(a_fall), S::BSPI_DIE3, 0, 0);
+    state(S::BSPI_DIE3, SPR::BSPI, 11, 7, NULL, S::BSPI_DIE4, 0, 0);
+    state(S::BSPI_DIE4, SPR::BSPI, 12, 7, NULL, S::BSPI_DIE5, 0, 0);
+    state(S::BSPI_DIE5, SPR::BSPI, 13, 7, NULL, S::BSPI_DIE6, 0, 0);
+    state(S::BSPI_DIE6, SPR::BSPI, 14, 7, NULL, S::BSPI_DIE7, 0, 0);
+    state(S::BSPI_DIE7, SPR::BSPI, 15, -1, m(a_boss_death), S::NULL, 0, 0);
+    state(S::BSPI_RAISE1, SPR::BSPI, 15, 5, NULL, S::BSPI_RAISE2, 0, 0);
+    state(S::BSPI_RAISE2, SPR::BSPI, 14, 5, NULL, S::BSPI_RAISE3, 0, 0);
+    state(S::BSPI_RAISE3, SPR::BSPI, 13, 5, NULL, S::BSPI_RAISE4, 0, 0);
+    state(S::BSPI_RAISE4, SPR::BSPI, 12, 5, NULL, S::BSPI_RAISE5, 0, 0);
+    state(S::BSPI_RAISE5, SPR::BSPI, 11, 5, NULL, S::BSPI_RAISE6, 0, 0);
+    state(S::BSPI_RAISE6, SPR::BSPI, 10, 5, NULL, S::BSPI_RAISE7, 0, 0);
+    state(S::BSPI_RAISE7, SPR::BSPI, 9, 5, NULL, S::BSPI_RUN1, 0, 0);
+    state(S::ARACH_PLAZ, SPR::APLS, FF_FULLBRIGHT | 0, 5, NULL, S::ARACH_PLAZ2, 0, 0);
+    state(S::ARACH_PLAZ2, SPR::APLS, FF_FULLBRIGHT | 1, 5, NULL, S::ARACH_PLAZ, 0, 0);
+    state(S::ARACH_PLEX, SPR::APBX, FF_FULLBRIGHT | 0, 5, NULL, S::ARACH_PLEX2, 0, 0);
+    state(S::ARACH_PLEX2, SPR::APBX, FF_FULLBRIGHT | 1, 5, NULL, S::ARACH_PLEX3, 0, 0);
+    state(S::ARACH_PLEX3, SPR::APBX, FF_FULLBRIGHT | 2, 5, NULL, S::ARACH_PLEX4, 0, 0);
+    state(S::ARACH_PLEX4, SPR::APBX, FF_FULLBRIGHT | 3, 5, NULL, S::ARACH_PLEX5, 0, 0);
+    state(S::ARACH_PLEX5, SPR::APBX, FF_FULLBRIGHT | 4, 5, NULL, S::NULL, 0, 0);
+    state(S::CYBER_STND, SPR::CYBR, 0, 10, m(a_look), S::CYBER_STND2, 0, 0);
+    state(S::CYBER_STND2, SPR::CYBR, 1, 10, m(a_look), S::CYBER_STND, 0, 0);
+    state(S::CYBER_RUN1, SPR::CYBR, 0, 3, m(a_hoof), S::CYBER_RUN2, 0, 0);
+    state(S::CYBER_RUN2, SPR::CYBR, 0, 3, m(a_chase), S::CYBER_RUN3, 0, 0);
+    state(S::CYBER_RUN3, SPR::CYBR, 1, 3, m(a_chase), S::CYBER_RUN4, 0, 0);
+    state(S::CYBER_RUN4, SPR::CYBR, 1, 3, m(a_chase), S::CYBER_RUN5, 0, 0);
+    state(S::CYBER_RUN5, SPR::CYBR, 2, 3, m(a_chase), S::CYBER_RUN6, 0, 0);
+    state(S::CYBER_RUN6, SPR::CYBR, 2, 3, m(a_chase), S::CYBER_RUN7, 0, 0);
+    state(S::CYBER_RUN7, SPR::CYBR, 3, 3, m(a_metal), S::CYBER_RUN8, 0, 0);
+    state(S::CYBER_RUN8, SPR::CYBR, 3, 3, m(a_chase), S::CYBER_RUN1, 0, 0);
+    state(S::CYBER_ATK1, SPR::CYBR, 4, 6, m(a_face_target), S::CYBER_ATK2, 0, 0);
+    state(S::CYBER_ATK2, SPR::CYBR, 5, 12, m(a_cyber_attack), S::CYBER_ATK3, 0, 0);
+    state(S::CYBER_ATK3, SPR::CYBR, 4, 12, m(a_face_target), S::CYBER_ATK4, 0, 0);
+    state(S::CYBER_ATK4, SPR::CYBR, 5, 12, m(a_cyber_attack), S::CYBER_ATK5, 0, 0);
+    state(S::CYBER_ATK5, SPR::CYBR, 4, 12, m(a_face_target), S::CYBER_ATK6, 0, 0);
+    state(S::CYBER_ATK6, SPR::CYBR, 5, 12, m(a_cyber_attack), S::CYBER_RUN1, 0, 0);
+    state(S::CYBER_PAIN, SPR::CYBR, 6, 10, m(a_pain), S::CYBER_RUN1, 0, 0);
+    state(S::CYBER_DIE1, SPR::CYBR, 7, 10, NULL, S::CYBER_DIE2, 0, 0);
+    state(S::CYBER_DIE2, SPR::CYBR, 8, 10, m(a_scream), S::CYBER_DIE3, 0, 0);
+    state(S::CYBER_DIE3, SPR::CYBR, 9, 10, NULL, S::CYBER_DIE4, 0, 0);
+    state(S::CYBER_DIE4, SPR::CYBR, 10, 10, NULL, S::CYBER_DIE5, 0, 0);
+    state(S::CYBER_DIE5, SPR::CYBR, 11, 10, NULL, S::CYBER_DIE6, 0, 0);
+    state(S::CYBER_DIE6, SPR::CYBR, 12, 10, m(a_fall), S::CYBER_DIE7, 0, 0);
+    state(S::CYBER_DIE7, SPR::CYBR, 13, 10, NULL, S::CYBER_DIE8, 0, 0);
+    state(S::CYBER_DIE8, SPR::CYBR, 14, 10, NULL, S::CYBER_DIE9, 0, 0);
+    state(S::CYBER_DIE9, SPR::CYBR, 15, 30, NULL, S::CYBER_DIE10, 0, 0);
+    state(S::CYBER_DIE10, SPR::CYBR, 15, -1, m(a_boss_death), S::NULL, 0, 0);
+    state(S::PAIN_STND, SPR::PAIN, 0, 10, m(a_look), S::PAIN_STND, 0, 0);
+    state(S::PAIN_RUN1, SPR::PAIN, 0, 3, m(a_chase), S::PAIN_RUN2, 0, 0);
+    state(S::PAIN_RUN2, SPR::PAIN, 0, 3, m(a_chase), S::PAIN_RUN3, 0, 0);
+    state(S::PAIN_RUN3, SPR::PAIN, 1, 3, m(a_chase), S::PAIN_RUN4, 0, 0);
+    state(S::PAIN_RUN4, SPR::PAIN, 1, 3, m(a_chase), S::PAIN_RUN5, 0, 0);
+    state(S::PAIN_RUN5, SPR::PAIN, 2, 3, m(a_chase), S::PAIN_RUN6, 0, 0);
+    state(S::PAIN_RUN6, SPR::PAIN, 2, 3, m(a_chase), S::PAIN_RUN1, 0, 0);
+    state(S::PAIN_ATK1, SPR::PAIN, 3, 5, m(a_face_target), S::PAIN_ATK2, 0, 0);
+    state(S::PAIN_ATK2, SPR::PAIN, 4, 5, m(a_face_target), S::PAIN_ATK3, 0, 0);
+    state(S::PAIN_ATK3, SPR::PAIN, FF_FULLBRIGHT | 5, 5, m(a_face_target), S::PAIN_ATK4, 0, 0);
+    state(S::PAIN_ATK4, SPR::PAIN, FF_FULLBRIGHT | 5, 0, m(a_pain_attack), S::PAIN_RUN1, 0, 0);
+    state(S::PAIN_PAIN, SPR::PAIN, 6, 6, NULL, S::PAIN_PAIN2, 0, 0);
+    state(S::PAIN_PAIN2, SPR::PAIN, 6, 6, m(a_pain), S::PAIN_RUN1, 0, 0);
+    state(S::PAIN_DIE1, SPR::PAIN, FF_FULLBRIGHT | 7, 8, NULL, S::PAIN_DIE2, 0, 0);
+    state(S::PAIN_DIE2, SPR::PAIN, FF_FULLBRIGHT | 8, 8, m(a_scream), S::PAIN_DIE3, 0, 0);
+    state(S::PAIN_DIE3, SPR::PAIN, FF_FULLBRIGHT | 9, 8, NULL, S::PAIN_DIE4, 0, 0);
+    state(S::PAIN_DIE4, SPR::PAIN, FF_FULLBRIGHT | 10, 8, NULL, S::PAIN_DIE5, 0, 0);
+    state(S::PAIN_DIE5, SPR::PAIN, FF_FULLBRIGHT | 11, 8, m(a_pain_die), S::PAIN_DIE6, 0, 0);
+    state(S::PAIN_DIE6, SPR::PAIN, FF_FULLBRIGHT | 12, 8, NULL, S::NULL, 0, 0);
+    state(S::PAIN_RAISE1, SPR::PAIN, 12, 8, NULL, S::PAIN_RAISE2, 0, 0);
+    state(S::PAIN_RAISE2, SPR::PAIN, 11, 8, NULL, S::PAIN_RAISE3, 0, 0);
+    state(S::PAIN_RAISE3, SPR::PAIN, 10, 8, NULL, S::PAIN_RAISE4, 0, 0);
+    state(S::PAIN_RAISE4, SPR::PAIN, 9, 8, NULL, S::PAIN_RAISE5, 0, 0);
+    state(S::PAIN_RAISE5, SPR::PAIN, 8, 8, NULL, S::PAIN_RAISE6, 0, 0);
+    state(S::PAIN_RAISE6, SPR::PAIN, 7, 8, NULL, S::PAIN_RUN1, 0, 0);
+    state(S::SSWV_STND, SPR::SSWV, 0, 10, m(a_look), S::SSWV_STND2, 0, 0);
+    state(S::SSWV_STND2, SPR::SSWV, 1, 10, m(a_look), S::SSWV_STND, 0, 0);
+    state(S::SSWV_RUN1, SPR::SSWV, 0, 3, m(a_chase), S::SSWV_RUN2, 0, 0);
+    state(S::SSWV_RUN2, SPR::SSWV, 0, 3, m(a_chase), S::SSWV_RUN3, 0, 0);
+    state(S::SSWV_RUN3, SPR::SSWV, 1, 3, m(a_chase), S::SSWV_RUN4, 0, 0);
+    state(S::SSWV_RUN4, SPR::SSWV, 1, 3, m(a_chase), S::SSWV_RUN5, 0, 0);
+    state(S::SSWV_RUN5, SPR::SSWV, 2, 3, m(a_chase), S::SSWV_RUN6, 0, 0);
+    state(S::SSWV_RUN6, SPR::SSWV, 2, 3, m(a_chase), S::SSWV_RUN7, 0, 0);
+    state(S::SSWV_RUN7, SPR::SSWV, 3, 3, m(a_chase), S::SSWV_RUN8, 0, 0);
+    state(S::SSWV_RUN8, SPR::SSWV, 3, 3, m(a_chase), S::SSWV_RUN1, 0, 0);
+    state(S::SSWV_ATK1, SPR::SSWV, 4, 10, m(a_face_target), S::SSWV_ATK2, 0, 0);
+    state(S::SSWV_ATK2, SPR::SSWV, 5, 10, m(a_face_target), S::SSWV_ATK3, 0, 0);
+    state(S::SSWV_ATK3, SPR::SSWV, FF_FULLBRIGHT | 6, 4, m(a_cpos_attack), S::SSWV_ATK4, 0, 0);
+    state(S::SSWV_ATK4, SPR::SSWV, 5, 6, m(a_face_target), S::SSWV_ATK5, 0, 0);
+    state(S::SSWV_ATK5, SPR::SSWV, FF_FULLBRIGHT | 6, 4, m(a_cpos_attack), S::SSWV_ATK6, 0, 0);
+    state(S::SSWV_ATK6, SPR::SSWV, 5, 1, m(a_cpos_refire), S::SSWV_ATK2, 0, 0);
+    state(S::SSWV_PAIN, SPR::SSWV, 7, 3, NULL, S::SSWV_PAIN2, 0, 0);
+    state(S::SSWV_PAIN2, SPR::SSWV, 7, 3, m(a_pain), S::SSWV_RUN1, 0, 0);
+    state(S::SSWV_DIE1, SPR::SSWV, 8, 5, NULL, S::SSWV_DIE2, 0, 0);
+    state(S::SSWV_DIE2, SPR::SSWV, 9, 5, m(a_scream), S::SSWV_DIE3, 0, 0);
+    state(S::SSWV_DIE3, SPR::SSWV, 10, 5, m(a_fall), S::SSWV_DIE4, 0, 0);
+    state(S::SSWV_DIE4, SPR::SSWV, 11, 5, NULL, S::SSWV_DIE5, 0, 0);
+    state(S::SSWV_DIE5, SPR::SSWV, 12, -1, NULL, S::NULL, 0, 0);
+    state(S::SSWV_XDIE1, SPR::SSWV, 13, 5, NULL, S::SSWV_XDIE2, 0, 0);
+    state(S::SSWV_XDIE2, SPR::SSWV, 14, 5, m(a_xscream), S::SSWV_XDIE3, 0, 0);
+    state(S::SSWV_XDIE3, SPR::SSWV, 15, 5, m(a_fall), S::SSWV_XDIE4, 0, 0);
+    state(S::SSWV_XDIE4, SPR::SSWV, 16, 5, NULL, S::SSWV_XDIE5, 0, 0);
+    state(S::SSWV_XDIE5, SPR::SSWV, 17, 5, NULL, S::SSWV_XDIE6, 0, 0);
+    state(S::SSWV_XDIE6, SPR::SSWV, 18, 5, NULL, S::SSWV_XDIE7, 0, 0);
+    state(S::SSWV_XDIE7, SPR::SSWV, 19, 5, NULL, S::SSWV_XDIE8, 0, 0);
+    state(S::SSWV_XDIE8, SPR::SSWV, 20, 5, NULL, S::SSWV_XDIE9, 0, 0);
+    state(S::SSWV_XDIE9, SPR::SSWV, 21, -1, NULL, S::NULL, 0, 0);
+    state(S::SSWV_RAISE1, SPR::SSWV, 12, 5, NULL, S::SSWV_RAISE2, 0, 0);
+    state(S::SSWV_RAISE2, SPR::SSWV, 11, 5, NULL, S::SSWV_RAISE3, 0, 0);
+    state(S::SSWV_RAISE3, SPR::SSWV, 10, 5, NULL, S::SSWV_RAISE4, 0, 0);
+    state(S::SSWV_RAISE4, SPR::SSWV, 9, 5, NULL, S::SSWV_RAISE5, 0, 0);
+    state(S::SSWV_RAISE5, SPR::SSWV, 8, 5, NULL, S::SSWV_RUN1, 0, 0);
+    state(S::KEENSTND, SPR::KEEN, 0, -1, NULL, S::KEENSTND, 0, 0);
+    state(S::COMMKEEN, SPR::KEEN, 0, 6, NULL, S::COMMKEEN2, 0, 0);
+    state(S::COMMKEEN2, SPR::KEEN, 1, 6, NULL, S::COMMKEEN3, 0, 0);
+    state(S::COMMKEEN3, SPR::KEEN, 2, 6, m(a_scream), S::COMMKEEN4, 0, 0);
+    state(S::COMMKEEN4, SPR::KEEN, 3, 6, NULL, S::COMMKEEN5, 0, 0);
+    state(S::COMMKEEN5, SPR::KEEN, 4, 6, NULL, S::COMMKEEN6, 0, 0);
+    state(S::COMMKEEN6, SPR::KEEN, 5, 6, NULL, S::COMMKEEN7, 0, 0);
+    state(S::COMMKEEN7, SPR::KEEN, 6, 6, NULL, S::COMMKEEN8, 0, 0);
+    state(S::COMMKEEN8, SPR::KEEN, 7, 6, NULL, S::COMMKEEN9, 0, 0);
+    state(S::COMMKEEN9, SPR::KEEN, 8, 6, NULL, S::COMMKEEN10, 0, 0);
+    state(S::COMMKEEN10, SPR::KEEN, 9, 6, NULL, S::COMMKEEN11, 0, 0);
+    state(S::COMMKEEN11, SPR::KEEN, 10, 6, m(a_keen_die), S::COMMKEEN12, 0, 0);
+    state(S::COMMKEEN12, SPR::KEEN, 11, -1, NULL, S::NULL, 0, 0);
+    state(S::KEENPAIN, SPR::KEEN, 12, 4, NULL, S::KEENPAIN2, 0, 0);
+    state(S::KEENPAIN2, SPR::KEEN, 12, 8, m(a_pain), S::KEENSTND, 0, 0);
+    state(S::BRAIN, SPR::BBRN, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::BRAIN_PAIN, SPR::BBRN, 1, 36, m(a_brain_pain), S::BRAIN, 0, 0);
+    state(S::BRAIN_DIE1, SPR::BBRN, 0, 100, m(a_brain_scream), S::BRAIN_DIE2, 0, 0);
+    state(S::BRAIN_DIE2, SPR::BBRN, 0, 10, NULL, S::BRAIN_DIE3, 0, 0);
+    state(S::BRAIN_DIE3, SPR::BBRN, 0, 10, NULL, S::BRAIN_DIE4, 0, 0);
+    state(S::BRAIN_DIE4, SPR::BBRN, 0, -1, m(a_brain_die), S::NULL, 0, 0);
+    state(S::BRAINEYE, SPR::SSWV, 0, 10, m(a_look), S::BRAINEYE, 0, 0);
+    state(S::BRAINEYESEE, SPR::SSWV, 0, 181, m(a_brain_awake), S::BRAINEYE1, 0, 0);
+    state(S::BRAINEYE1, SPR::SSWV, 0, 150, m(a_brain_spit), S::BRAINEYE1, 0, 0);
+    state(S::SPAWN1, SPR::BOSF, FF_FULLBRIGHT | 0, 3, m(a_spawn_sound), S::SPAWN2, 0, 0);
+    state(S::SPAWN2, SPR::BOSF, FF_FULLBRIGHT | 1, 3, m(a_spawn_fly), S::SPAWN3, 0, 0);
+    state(S::SPAWN3, SPR::BOSF, FF_FULLBRIGHT | 2, 3, m(a_spawn_fly), S::SPAWN4, 0, 0);
+    state(S::SPAWN4, SPR::BOSF, FF_FULLBRIGHT | 3, 3, m(a_spawn_fly), S::SPAWN1, 0, 0);
+    state(S::SPAWNFIRE1, SPR::FIRE, FF_FULLBRIGHT | 0, 4, m(a_fire), S::SPAWNFIRE2, 0, 0);
+    state(S::SPAWNFIRE2, SPR::FIRE, FF_FULLBRIGHT | 1, 4, m(a_fire), S::SPAWNFIRE3, 0, 0);
+    state(S::SPAWNFIRE3, SPR::FIRE, FF_FULLBRIGHT | 2, 4, m(a_fire), S::SPAWNFIRE4, 0, 0);
+    state(S::SPAWNFIRE4, SPR::FIRE, FF_FULLBRIGHT | 3, 4, m(a_fire), S::SPAWNFIRE5, 0, 0);
+    state(S::SPAWNFIRE5, SPR::FIRE, FF_FULLBRIGHT | 4, 4, m(a_fire), S::SPAWNFIRE6, 0, 0);
+    state(S::SPAWNFIRE6, SPR::FIRE, FF_FULLBRIGHT | 5, 4, m(a_fire), S::SPAWNFIRE7, 0, 0);
+    state(S::SPAWNFIRE7, SPR::FIRE, FF_FULLBRIGHT | 6, 4, m(a_fire), S::SPAWNFIRE8, 0, 0);
+    state(S::SPAWNFIRE8, SPR::FIRE, FF_FULLBRIGHT | 7, 4, m(a_fire), S::NULL, 0, 0);
+    state(S::BRAINEXPLODE1, SPR::MISL, FF_FULLBRIGHT | 1, 10, NULL, S::BRAINEXPLODE2, 0, 0);
+    state(S::BRAINEXPLODE2, SPR::MISL, FF_FULLBRIGHT | 2, 10, NULL, S::BRAINEXPLODE3, 0, 0);
+    state(S::BRAINEXPLODE3, SPR::MISL, FF_FULLBRIGHT | 3, 10, m(a_brain_explode), S::NULL, 0, 0);
+    state(S::ARM1, SPR::ARM1, 0, 6, NULL, S::ARM1A, 0, 0);
+    state(S::ARM1A, SPR::ARM1, FF_FULLBRIGHT | 1, 7, NULL, S::ARM1, 0, 0);
+    state(S::ARM2, SPR::ARM2, 0, 6, NULL, S::ARM2A, 0, 0);
+    state(S::ARM2A, SPR::ARM2, FF_FULLBRIGHT | 1, 6, NULL, S::ARM2, 0, 0);
+    state(S::BAR1, SPR::BAR1, 0, 6, NULL, S::BAR2, 0, 0);
+    state(S::BAR2, SPR::BAR1, 1, 6, NULL, S::BAR1, 0, 0);
+    state(S::BEXP, SPR::BEXP, FF_FULLBRIGHT | 0, 5, NULL, S::BEXP2, 0, 0);
+    state(S::BEXP2, SPR::BEXP, FF_FULLBRIGHT | 1, 5, m(a_scream), S::BEXP3, 0, 0);
+    state(S::BEXP3, SPR::BEXP, FF_FULLBRIGHT | 2, 5, NULL, S::BEXP4, 0, 0);
+    state(S::BEXP4, SPR::BEXP, FF_FULLBRIGHT | 3, 10, m(a_explode), S::BEXP5, 0, 0);
+    state(S::BEXP5, SPR::BEXP, FF_FULLBRIGHT | 4, 10, NULL, S::NULL, 0, 0);
+    state(S::BBAR1, SPR::FCAN, FF_FULLBRIGHT | 0, 4, NULL, S::BBAR2, 0, 0);
+    state(S::BBAR2, SPR::FCAN, FF_FULLBRIGHT | 1, 4, NULL, S::BBAR3, 0, 0);
+    state(S::BBAR3, SPR::FCAN, FF_FULLBRIGHT | 2, 4, NULL, S::BBAR1, 0, 0);
+    state(S::BON1, SPR::BON1, 0, 6, NULL, S::BON1A, 0, 0);
+    state(S::BON1A, SPR::BON1, 1, 6, NULL, S::BON1B, 0, 0);
+    state(S::BON1B, SPR::BON1, 2, 6, NULL, S::BON1C, 0, 0);
+    state(S::BON1C, SPR::BON1, 3, 6, NULL, S::BON1D, 0, 0);
+    state(S::BON1D, SPR::BON1, 2, 6, NULL, S::BON1E, 0, 0);
+    state(S::BON1E, SPR::BON1, 1, 6, NULL, S::BON1, 0, 0);
+    state(S::BON2, SPR::BON2, 0, 6, NULL, S::BON2A, 0, 0);
+    state(S::BON2A, SPR::BON2, 1, 6, NULL, S::BON2B, 0, 0);
+    state(S::BON2B, SPR::BON2, 2, 6, NULL, S::BON2C, 0, 0);
+    state(S::BON2C, SPR::BON2, 3, 6, NULL, S::BON2D, 0, 0);
+    state(S::BON2D, SPR::BON2, 2, 6, NULL, S::BON2E, 0, 0);
+    state(S::BON2E, SPR::BON2, 1, 6, NULL, S::BON2, 0, 0);
+    state(S::BKEY, SPR::BKEY, 0, 10, NULL, S::BKEY2, 0, 0);
+    state(S::BKEY2, SPR::BKEY, FF_FULLBRIGHT | 1, 10, NULL, S::BKEY, 0, 0);
+    state(S::RKEY, SPR::RKEY, 0, 10, NULL, S::RKEY2, 0, 0);
+    state(S::RKEY2, SPR::RKEY, FF_FULLBRIGHT | 1, 10, NULL, S::RKEY, 0, 0);
+    state(S::YKEY, SPR::YKEY, 0, 10, NULL, S::YKEY2, 0, 0);
+    state(S::YKEY2, SPR::YKEY, FF_FULLBRIGHT | 1, 10, NULL, S::YKEY, 0, 0);
+    state(S::BSKULL, SPR::BSKU, 0, 10, NULL, S::BSKULL2, 0, 0);
+    state(S::BSKULL2, SPR::BSKU, FF_FULLBRIGHT | 1, 10, NULL, S::BSKULL, 0, 0);
+    state(S::RSKULL, SPR::RSKU, 0, 10, NULL, S::RSKULL2, 0, 0);
+    state(S::RSKULL2, SPR::RSKU, FF_FULLBRIGHT | 1, 10, NULL, S::RSKULL, 0, 0);
+    state(S::YSKULL, SPR::YSKU, 0, 10, NULL, S::YSKULL2, 0, 0);
+    state(S::YSKULL2, SPR::YSKU, FF_FULLBRIGHT | 1, 10, NULL, S::YSKULL, 0, 0);
+    state(S::STIM, SPR::STIM, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::MEDI, SPR::MEDI, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::SOUL, SPR::SOUL, FF_FULLBRIGHT | 0, 6, NULL, S::SOUL2, 0, 0);
+    state(S::SOUL2, SPR::SOUL, FF_FULLBRIGHT | 1, 6, NULL, S::SOUL3, 0, 0);
+    state(S::SOUL3, SPR::SOUL, FF_FULLBRIGHT | 2, 6, NULL, S::SOUL4, 0, 0);
+    state(S::SOUL4, SPR::SOUL, FF_FULLBRIGHT | 3, 6, NULL, S::SOUL5, 0, 0);
+    state(S::SOUL5, SPR::SOUL, FF_FULLBRIGHT | 2, 6, NULL, S::SOUL6, 0, 0);
+    state(S::SOUL6, SPR::SOUL, FF_FULLBRIGHT | 1, 6, NULL, S::SOUL, 0, 0);
+    state(S::PINV, SPR::PINV, FF_FULLBRIGHT | 0, 6, NULL, S::PINV2, 0, 0);
+    state(S::PINV2, SPR::PINV, FF_FULLBRIGHT | 1, 6, NULL, S::PINV3, 0, 0);
+    state(S::PINV3, SPR::PINV, FF_FULLBRIGHT | 2, 6, NULL, S::PINV4, 0, 0);
+    state(S::PINV4, SPR::PINV, FF_FULLBRIGHT | 3, 6, NULL, S::PINV, 0, 0);
+    state(S::PSTR, SPR::PSTR, FF_FULLBRIGHT | 0, -1, NULL, S::NULL, 0, 0);
+    state(S::PINS, SPR::PINS, FF_FULLBRIGHT | 0, 6, NULL, S::PINS2, 0, 0);
+    state(S::PINS2, SPR::PINS, FF_FULLBRIGHT | 1, 6, NULL, S::PINS3, 0, 0);
+    state(S::PINS3, SPR::PINS, FF_FULLBRIGHT | 2, 6, NULL, S::PINS4, 0, 0);
+    state(S::PINS4, SPR::PINS, FF_FULLBRIGHT | 3, 6, NULL, S::PINS, 0, 0);
+    state(S::MEGA, SPR::MEGA, FF_FULLBRIGHT | 0, 6, NULL, S::MEGA2, 0, 0);
+    state(S::MEGA2, SPR::MEGA, FF_FULLBRIGHT | 1, 6, NULL, S::MEGA3, 0, 0);
+    state(S::MEGA3, SPR::MEGA, FF_FULLBRIGHT | 2, 6, NULL, S::MEGA4, 0, 0);
+    state(S::MEGA4, SPR::MEGA, FF_FULLBRIGHT | 3, 6, NULL, S::MEGA, 0, 0);
+    state(S::SUIT, SPR::SUIT, FF_FULLBRIGHT | 0, -1, NULL, S::NULL, 0, 0);
+    state(S::PMAP, SPR::PMAP, FF_FULLBRIGHT | 0, 6, NULL, S::PMAP2, 0, 0);
+    state(S::PMAP2, SPR::PMAP, FF_FULLBRIGHT | 1, 6, NULL, S::PMAP3, 0, 0);
+    state(S::PMAP3, SPR::PMAP, FF_FULLBRIGHT | 2, 6, NULL, S::PMAP4, 0, 0);
+    state(S::PMAP4, SPR::PMAP, FF_FULLBRIGHT | 3, 6, NULL, S::PMAP5, 0, 0);
+    state(S::PMAP5, SPR::PMAP, FF_FULLBRIGHT | 2, 6, NULL, S::PMAP6, 0, 0);
+    state(S::PMAP6, SPR::PMAP, FF_FULLBRIGHT | 1, 6, NULL, S::PMAP, 0, 0);
+    state(S::PVIS, SPR::PVIS, FF_FULLBRIGHT | 0, 6, NULL, S::PVIS2, 0, 0);
+    state(S::PVIS2, SPR::PVIS, 1, 6, NULL, S::PVIS, 0, 0);
+    state(S::CLIP, SPR::CLIP, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::AMMO, SPR::AMMO, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::ROCK, SPR::ROCK, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::BROK, SPR::BROK, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::CELL, SPR::CELL, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::CELP, SPR::CELP, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::SHEL, SPR::SHEL, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::SBOX, SPR::SBOX, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::BPAK, SPR::BPAK, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::BFUG, SPR::BFUG, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::MGUN, SPR::MGUN, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::CSAW, SPR::CSAW, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::LAUN, SPR::LAUN, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::PLAS, SPR::PLAS, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::SHOT, SPR::SHOT, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::SHOT2, SPR::SGN2, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::COLU, SPR::COLU, FF_FULLBRIGHT | 0, -1, NULL, S::NULL, 0, 0);
+    state(S::STALAG, SPR::SMT2, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::BLOODYTWITCH, SPR::GOR1, 0, 10, NULL, S::BLOODYTWITCH2, 0, 0);
+    state(S::BLOODYTWITCH2, SPR::GOR1, 1, 15, NULL, S::BLOODYTWITCH3, 0, 0);
+    state(S::BLOODYTWITCH3, SPR::GOR1, 2, 8, NULL, S::BLOODYTWITCH4, 0, 0);
+    state(S::BLOODYTWITCH4, SPR::GOR1, 1, 6, NULL, S::BLOODYTWITCH, 0, 0);
+    state(S::DEADTORSO, SPR::PLAY, 13, -1, NULL, S::NULL, 0, 0);
+    state(S::DEADBOTTOM, SPR::PLAY, 18, -1, NULL, S::NULL, 0, 0);
+    state(S::HEADSONSTICK, SPR::POL2, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::GIBS, SPR::POL5, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::HEADONASTICK, SPR::POL4, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::HEADCANDLES, SPR::POL3, FF_FULLBRIGHT | 0, 6, NULL, S::HEADCANDLES2, 0, 0);
+    state(S::HEADCANDLES2, SPR::POL3, FF_FULLBRIGHT | 1, 6, NULL, S::HEADCANDLES, 0, 0);
+    state(S::DEADSTICK, SPR::POL1, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::LIVESTICK, SPR::POL6, 0, 6, NULL, S::LIVESTICK2, 0, 0);
+    state(S::LIVESTICK2, SPR::POL6, 1, 8, NULL, S::LIVESTICK, 0, 0);
+    state(S::MEAT2, SPR::GOR2, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::MEAT3, SPR::GOR3, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::MEAT4, SPR::GOR4, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::MEAT5, SPR::GOR5, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::STALAGTITE, SPR::SMIT, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::TALLGRNCOL, SPR::COL1, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::SHRTGRNCOL, SPR::COL2, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::TALLREDCOL, SPR::COL3, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::SHRTREDCOL, SPR::COL4, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::CANDLESTIK, SPR::CAND, FF_FULLBRIGHT | 0, -1, NULL, S::NULL, 0, 0);
+    state(S::CANDELABRA, SPR::CBRA, FF_FULLBRIGHT | 0, -1, NULL, S::NULL, 0, 0);
+    state(S::SKULLCOL, SPR::COL6, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::TORCHTREE, SPR::TRE1, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::BIGTREE, SPR::TRE2, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::TECHPILLAR, SPR::ELEC, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::EVILEYE, SPR::CEYE, FF_FULLBRIGHT | 0, 6, NULL, S::EVILEYE2, 0, 0);
+    state(S::EVILEYE2, SPR::CEYE, FF_FULLBRIGHT | 1, 6, NULL, S::EVILEYE3, 0, 0);
+    state(S::EVILEYE3, SPR::CEYE, FF_FULLBRIGHT | 2, 6, NULL, S::EVILEYE4, 0, 0);
+    state(S::EVILEYE4, SPR::CEYE, FF_FULLBRIGHT | 1, 6, NULL, S::EVILEYE, 0, 0);
+    state(S::FLOATSKULL, SPR::FSKU, FF_FULLBRIGHT | 0, 6, NULL, S::FLOATSKULL2, 0, 0);
+    state(S::FLOATSKULL2, SPR::FSKU, FF_FULLBRIGHT | 1, 6, NULL, S::FLOATSKULL3, 0, 0);
+    state(S::FLOATSKULL3, SPR::FSKU, FF_FULLBRIGHT | 2, 6, NULL, S::FLOATSKULL, 0, 0);
+    state(S::HEARTCOL, SPR::COL5, 0, 14, NULL, S::HEARTCOL2, 0, 0);
+    state(S::HEARTCOL2, SPR::COL5, 1, 14, NULL, S::HEARTCOL, 0, 0);
+    state(S::BLUETORCH, SPR::TBLU, FF_FULLBRIGHT | 0, 4, NULL, S::BLUETORCH2, 0, 0);
+    state(S::BLUETORCH2, SPR::TBLU, FF_FULLBRIGHT | 1, 4, NULL, S::BLUETORCH3, 0, 0);
+    state(S::BLUETORCH3, SPR::TBLU, FF_FULLBRIGHT | 2, 4, NULL, S::BLUETORCH4, 0, 0);
+    state(S::BLUETORCH4, SPR::TBLU, FF_FULLBRIGHT | 3, 4, NULL, S::BLUETORCH, 0, 0);
+    state(S::GREENTORCH, SPR::TGRN, FF_FULLBRIGHT | 0, 4, NULL, S::GREENTORCH2, 0, 0);
+    state(S::GREENTORCH2, SPR::TGRN, FF_FULLBRIGHT | 1, 4, NULL, S::GREENTORCH3, 0, 0);
+    state(S::GREENTORCH3, SPR::TGRN, FF_FULLBRIGHT | 2, 4, NULL, S::GREENTORCH4, 0, 0);
+    state(S::GREENTORCH4, SPR::TGRN, FF_FULLBRIGHT | 3, 4, NULL, S::GREENTORCH, 0, 0);
+    state(S::REDTORCH, SPR::TRED, FF_FULLBRIGHT | 0, 4, NULL, S::REDTORCH2, 0, 0);
+    state(S::REDTORCH2, SPR::TRED, FF_FULLBRIGHT | 1, 4, NULL, S::REDTORCH3, 0, 0);
+    state(S::REDTORCH3, SPR::TRED, FF_FULLBRIGHT | 2, 4, NULL, S::REDTORCH4, 0, 0);
+    state(S::REDTORCH4, SPR::TRED, FF_FULLBRIGHT | 3, 4, NULL, S::REDTORCH, 0, 0);
+    state(S::BTORCHSHRT, SPR::SMBT, FF_FULLBRIGHT | 0, 4, NULL, S::BTORCHSHRT2, 0, 0);
+    state(S::BTORCHSHRT2, SPR::SMBT, FF_FULLBRIGHT | 1, 4, NULL, S::BTORCHSHRT3, 0, 0);
+    state(S::BTORCHSHRT3, SPR::SMBT, FF_FULLBRIGHT | 2, 4, NULL, S::BTORCHSHRT4, 0, 0);
+    state(S::BTORCHSHRT4, SPR::SMBT, FF_FULLBRIGHT | 3, 4, NULL, S::BTORCHSHRT, 0, 0);
+    state(S::GTORCHSHRT, SPR::SMGT, FF_FULLBRIGHT | 0, 4, NULL, S::GTORCHSHRT2, 0, 0);
+    state(S::GTORCHSHRT2, SPR::SMGT, FF_FULLBRIGHT | 1, 4, NULL, S::GTORCHSHRT3, 0, 0);
+    state(S::GTORCHSHRT3, SPR::SMGT, FF_FULLBRIGHT | 2, 4, NULL, S::GTORCHSHRT4, 0, 0);
+    state(S::GTORCHSHRT4, SPR::SMGT, FF_FULLBRIGHT | 3, 4, NULL, S::GTORCHSHRT, 0, 0);
+    state(S::RTORCHSHRT, SPR::SMRT, FF_FULLBRIGHT | 0, 4, NULL, S::RTORCHSHRT2, 0, 0);
+    state(S::RTORCHSHRT2, SPR::SMRT, FF_FULLBRIGHT | 1, 4, NULL, S::RTORCHSHRT3, 0, 0);
+    state(S::RTORCHSHRT3, SPR::SMRT, FF_FULLBRIGHT | 2, 4, NULL, S::RTORCHSHRT4, 0, 0);
+    state(S::RTORCHSHRT4, SPR::SMRT, FF_FULLBRIGHT | 3, 4, NULL, S::RTORCHSHRT, 0, 0);
+    state(S::HANGNOGUTS, SPR::HDB1, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::HANGBNOBRAIN, SPR::HDB2, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::HANGTLOOKDN, SPR::HDB3, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::HANGTSKULL, SPR::HDB4, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::HANGTLOOKUP, SPR::HDB5, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::HANGTNOBRAIN, SPR::HDB6, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::COLONGIBS, SPR::POB1, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::SMALLPOOL, SPR::POB2, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::BRAINSTEM, SPR::BRS1, 0, -1, NULL, S::NULL, 0, 0);
+    state(S::TECHLAMP, SPR::TLMP, FF_FULLBRIGHT | 0, 4, NULL, S::TECHLAMP2, 0, 0);
+    state(S::TECHLAMP2, SPR::TLMP, FF_FULLBRIGHT | 1, 4, NULL, S::TECHLAMP3, 0, 0);
+    state(S::TECHLAMP3, SPR::TLMP, FF_FULLBRIGHT | 2, 4, NULL, S::TECHLAMP4, 0, 0);
+    state(S::TECHLAMP4, SPR::TLMP, FF_FULLBRIGHT | 3, 4, NULL, S::TECHLAMP, 0, 0);
+    state(S::TECH2LAMP, SPR::TLP2, FF_FULLBRIGHT | 0, 4, NULL, S::TECH2LAMP2, 0, 0);
+    state(S::TECH2LAMP2, SPR::TLP2, FF_FULLBRIGHT | 1, 4, NULL, S::TECH2LAMP3, 0, 0);
+    state(S::TECH2LAMP3, SPR::TLP2, FF_FULLBRIGHT | 2, 4, NULL, S::TECH2LAMP4, 0, 0);
+    state(S::TECH2LAMP4, SPR::TLP2, FF_FULLBRIGHT | 3, 4, NULL, S::TECH2LAMP, 0, 0);
+    state(S::TNT1, SPR::TNT1, 0, -1, NULL, S::TNT1, 0, 0);
+    state(S::GRENADE, SPR::MISL, FF_FULLBRIGHT | 0, 1000, m(a_die), S::GRENADE, 0, 0);
+    state(S::DETONATE, SPR::MISL, FF_FULLBRIGHT | 1, 4, m(a_scream), S::DETONATE2, 0, 0);
+    state(S::DETONATE2, SPR::MISL, FF_FULLBRIGHT | 2, 6, m(a_detonate), S::DETONATE3, 0, 0);
+    state(S::DETONATE3, SPR::MISL, FF_FULLBRIGHT | 3, 10, NULL, S::NULL, 0, 0);
+    state(S::DOGS_STND, SPR::DOGS, 0, 10, m(a_look), S::DOGS_STND2, 0, 0);
+    state(S::DOGS_STND2, SPR::DOGS, 1, 10, m(a_look), S::DOGS_STND, 0, 0);
+    state(S::DOGS_RUN1, SPR::DOGS, 0, 2, m(a_chase), S::DOGS_RUN2, 0, 0);
+    state(S::DOGS_RUN2, SPR::DOGS, 0, 2, m(a_chase), S::DOGS_RUN3, 0, 0);
+    state(S::DOGS_RUN3, SPR::DOGS, 1, 2, m(a_chase), S::DOGS_RUN4, 0, 0);
+    state(S::DOGS_RUN4, SPR::DOGS, 1, 2, m(a_chase), S::DOGS_RUN5, 0, 0);
+    state(S::DOGS_RUN5, SPR::DOGS, 2, 2, m(a_chase), S::DOGS_RUN6, 0, 0);
+    state(S::DOGS_RUN6, SPR::DOGS, 2, 2, m(a_chase), S::DOGS_RUN7, 0, 0);
+    state(S::DOGS_RUN7, SPR::DOGS, 3, 2, m(a_chase), S::DOGS_RUN8, 0, 0);
+    state(S::DOGS_RUN8, SPR::DOGS, 3, 2, m(a_chase), S::DOGS_RUN1, 0, 0);
+    state(S::DOGS_ATK1, SPR::DOGS, 4, 8, m(a_face_target), S::DOGS_ATK2, 0, 0);
+    state(S::DOGS_ATK2, SPR::DOGS, 5, 8, m(a_face_target), S::DOGS_ATK3, 0, 0);
+    state(S::DOGS_ATK3, SPR::DOGS, 6, 8, m(a_sarg_attack), S::DOGS_RUN1, 0, 0);
+    state(S::DOGS_PAIN, SPR::DOGS, 7, 2, NULL, S::DOGS_PAIN2, 0, 0);
+    state(S::DOGS_PAIN2, SPR::DOGS, 7, 2, m(a_pain), S::DOGS_RUN1, 0, 0);
+    state(S::DOGS_DIE1, SPR::DOGS, 8, 8, NULL, S::DOGS_DIE2, 0, 0);
+    state(S::DOGS_DIE2, SPR::DOGS, 9, 8, m(a_scream), S::DOGS_DIE3, 0, 0);
+    state(S::DOGS_DIE3, SPR::DOGS, 10, 4, NULL, S::DOGS_DIE4, 0, 0);
+    state(S::DOGS_DIE4, SPR::DOGS, 11, 4, m(a_fall), S::DOGS_DIE5, 0, 0);
+    state(S::DOGS_DIE5, SPR::DOGS, 12, 4, NULL, S::DOGS_DIE6, 0, 0);
+    state(S::DOGS_DIE6, SPR::DOGS, 13, -1, NULL, S::NULL, 0, 0);
+    state(S::DOGS_RAISE1, SPR::DOGS, 13, 5, NULL, S::DOGS_RAISE2, 0, 0);
+    state(S::DOGS_RAISE2, SPR::DOGS, 12, 5, NULL, S::DOGS_RAISE3, 0, 0);
+    state(S::DOGS_RAISE3, SPR::DOGS, 11, 5, NULL, S::DOGS_RAISE4, 0, 0);
+    state(S::DOGS_RAISE4, SPR::DOGS, 10, 5, NULL, S::DOGS_RAISE5, 0, 0);
+    state(S::DOGS_RAISE5, SPR::DOGS, 9, 5, NULL, S::DOGS_RAISE6, 0, 0);
+    state(S::DOGS_RAISE6, SPR::DOGS, 8, 5, NULL, S::DOGS_RUN1, 0, 0);
+    state(S::OLDBFG1, SPR::BFGG, 0, 10, p(a_bfg_sound), S::OLDBFG1, 0, 0); // nextstate set below
+    state(S::OLDBFG42, SPR::BFGG, 1, 0, p(a_light0), S::OLDBFG43, 0, 0);
+    state(S::OLDBFG43, SPR::BFGG, 1, 20, p(a_refire), S::BFG, 0, 0);
+    state(S::PLS1BALL, SPR::PLS1, FF_FULLBRIGHT | 0, 6, NULL, S::PLS1BALL2, 0, 0);
+    state(S::PLS1BALL2, SPR::PLS1, FF_FULLBRIGHT | 1, 6, NULL, S::PLS1BALL, 0, 0);
+    state(S::PLS1EXP, SPR::PLS1, FF_FULLBRIGHT | 2, 4, NULL, S::PLS1EXP2, 0, 0);
+    state(S::PLS1EXP2, SPR::PLS1, FF_FULLBRIGHT | 3, 4, NULL, S::PLS1EXP3, 0, 0);
+    state(S::PLS1EXP3, SPR::PLS1, FF_FULLBRIGHT | 4, 4, NULL, S::PLS1EXP4, 0, 0);
+    state(S::PLS1EXP4, SPR::PLS1, FF_FULLBRIGHT | 5, 4, NULL, S::PLS1EXP5, 0, 0);
+    state(S::PLS1EXP5, SPR::PLS1, FF_FULLBRIGHT | 6, 4, NULL, S::NULL, 0, 0);
+    state(S::PLS2BALL, SPR::PLS2, FF_FULLBRIGHT | 0, 4, NULL, S::PLS2BALL2, 0, 0);
+    state(S::PLS2BALL2, SPR::PLS2, FF_FULLBRIGHT | 1, 4, NULL, S::PLS2BALL, 0, 0);
+    state(S::PLS2BALLX1, SPR::PLS2, FF_FULLBRIGHT | 2, 6, NULL, S::PLS2BALLX2, 0, 0);
+    state(S::PLS2BALLX2, SPR::PLS2, FF_FULLBRIGHT | 3, 6, NULL, S::PLS2BALLX3, 0, 0);
+    state(S::PLS2BALLX3, SPR::PLS2, FF_FULLBRIGHT | 4, 6, NULL, S::NULL, 0, 0);
+    state(S::BON3, SPR::BON3, 0, 6, NULL, S::BON3, 0, 0);
+    state(S::BON4, SPR::BON4, 0, 6, NULL, S::BON4, 0, 0);
+    state(S::BSKUL_STND, SPR::SKUL, 0, 10, m(a_look), S::BSKUL_STND, 0, 0);
+    state(S::BSKUL_RUN1, SPR::SKUL, 1, 5, m(a_chase), S::BSKUL_RUN2, 0, 0);
+    state(S::BSKUL_RUN2, SPR::SKUL, 2, 5, m(a_chase), S::BSKUL_RUN3, 0, 0);
+    state(S::BSKUL_RUN3, SPR::SKUL, 3, 5, m(a_chase), S::BSKUL_RUN4, 0, 0);
+    state(S::BSKUL_RUN4, SPR::SKUL, 0, 5, m(a_chase), S::BSKUL_RUN1, 0, 0);
+    state(S::BSKUL_ATK1, SPR::SKUL, 4, 4, m(a_face_target), S::BSKUL_ATK2, 0, 0);
+    state(S::BSKUL_ATK2, SPR::SKUL, 5, 5, m(a_beta_skull_attack), S::BSKUL_ATK3, 0, 0);
+    state(S::BSKUL_ATK3, SPR::SKUL, 5, 4, NULL, S::BSKUL_RUN1, 0, 0);
+    state(S::BSKUL_PAIN1, SPR::SKUL, 6, 4, NULL, S::BSKUL_PAIN2, 0, 0);
+    state(S::BSKUL_PAIN2, SPR::SKUL, 7, 2, m(a_pain), S::BSKUL_RUN1, 0, 0);
+    state(S::BSKUL_PAIN3, SPR::SKUL, 8, 4, NULL, S::BSKUL_RUN1, 0, 0);
+    state(S::BSKUL_DIE1, SPR::SKUL, 9, 5, NULL, S::BSKUL_DIE2, 0, 0);
+    state(S::BSKUL_DIE2, SPR::SKUL, 10, 5, NULL, S::BSKUL_DIE3, 0, 0);
+    state(S::BSKUL_DIE3, SPR::SKUL, 11, 5, NULL, S::BSKUL_DIE4, 0, 0);
+    state(S::BSKUL_DIE4, SPR::SKUL, 12, 5, NULL, S::BSKUL_DIE5, 0, 0);
+    state(S::BSKUL_DIE5, SPR::SKUL, 13, 5, m(a_scream), S::BSKUL_DIE6, 0, 0);
+    state(S::BSKUL_DIE6, SPR::SKUL, 14, 5, NULL, S::BSKUL_DIE7, 0, 0);
+    state(S::BSKUL_DIE7, SPR::SKUL, 15, 5, m(a_fall), S::BSKUL_DIE8, 0, 0);
+    state(S::BSKUL_DIE8, SPR::SKUL, 16, 5, m(a_stop), S::BSKUL_DIE8, 0, 0);
+    state(S::MUSHROOM, SPR::MISL, FF_FULLBRIGHT | 1, 8, m(a_mushroom), S::EXPLODE2, 0, 0);
+    state(S::PLAY_GDIE1, SPR::PLAY, 14, 5, NULL, S::PLAY_GDIE2, 0, 0);
+    state(S::PLAY_GDIE2, SPR::PLAY, 15, 5, m(a_xscream), S::PLAY_GDIE3, 0, 0);
+    state(S::PLAY_GDIE3, SPR::PLAY, 16, 5, m(a_fall), S::PLAY_GDIE4, 0, 0);
+    state(S::PLAY_GDIE4, SPR::PLAY, 17, 5, NULL, S::PLAY_GDIE5, 0, 0);
+    state(S::PLAY_GDIE5, SPR::PLAY, 18, 5, NULL, S::PLAY_GDIE6, 0, 0);
+    state(S::PLAY_GDIE6, SPR::PLAY, 19, 5, NULL, S::PLAY_GDIE7, 0, 0);
+    state(S::PLAY_GDIE7, SPR::PLAY, 20, 5, NULL, S::PLAY_GDIE8, 0, 0);
+    state(S::PLAY_GDIE8, SPR::PLAY, 21, 5, NULL, S::PLAY_GDIE9, 0, 0);
+    state(S::PLAY_GDIE9, SPR::PLAY, 22, -1, NULL, S::NULL, 0, 0);
+
+    // MBF's classic BFG fires once a tic for 40 tics, through states that
+    // have no names of their own
+    let oldbfg = S::OLDBFG1 as usize;
+    states[oldbfg].nextstate = oldbfg + 1;
+    for i in oldbfg + 1..S::OLDBFG42 as usize {
+        states[i] = State {
+            sprite: SPR::BFGG,
+            frame: 1,
+            tics: 1,
+            action: p(a_fire_old_bfg),
+            nextstate: i + 1,
+            misc1: 0,
+            misc2: 0,
+        };
+    }
+}
+
 lazy_static! {
     pub static ref MOBJINFO: RwLock<FxHashMap<MobjType, MobjInfo>> =
         RwLock::new(FxHashMap::default());
@@ -1769,7 +2869,6 @@ pub fn init_mobjinfo() {
     use MobjFlag as MF;
     use MobjType as MT;
     use Statenum as S;
-    let fracunit = FRACUNIT as i32;
 
     info.insert(
         MT::PLAYER,
@@ -1790,8 +2889,8 @@ pub fn init_mobjinfo() {
             S::PLAY_XDIE1,
             Sfx::PLDETH,
             0,
-            16 * fracunit,
-            56 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(56),
             100,
             0,
             Sfx::None,
@@ -1819,8 +2918,8 @@ pub fn init_mobjinfo() {
             S::POSS_XDIE1,
             Sfx::PODTH1,
             8,
-            20 * fracunit,
-            56 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(56),
             100,
             0,
             Sfx::POSACT,
@@ -1848,8 +2947,8 @@ pub fn init_mobjinfo() {
             S::SPOS_XDIE1,
             Sfx::PODTH2,
             8,
-            20 * fracunit,
-            56 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(56),
             100,
             0,
             Sfx::POSACT,
@@ -1877,8 +2976,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::VILDTH,
             15,
-            20 * fracunit,
-            56 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(56),
             500,
             0,
             Sfx::VILACT,
@@ -1906,8 +3005,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -1935,8 +3034,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::SKEDTH,
             10,
-            20 * fracunit,
-            56 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(56),
             500,
             0,
             Sfx::SKEACT,
@@ -1963,9 +3062,9 @@ pub fn init_mobjinfo() {
             S::TRACEEXP1,
             S::NULL,
             Sfx::BAREXP,
-            10 * fracunit,
-            11 * fracunit,
-            8 * fracunit,
+            Fixed::from_int(10).0,
+            Fixed::from_int(11),
+            Fixed::from_int(8),
             100,
             10,
             Sfx::None,
@@ -1993,8 +3092,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -2022,8 +3121,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::MANDTH,
             8,
-            48 * fracunit,
-            64 * fracunit,
+            Fixed::from_int(48),
+            Fixed::from_int(64),
             1000,
             0,
             Sfx::POSACT,
@@ -2050,9 +3149,9 @@ pub fn init_mobjinfo() {
             S::FATSHOTX1,
             S::NULL,
             Sfx::FIRXPL,
-            20 * fracunit,
-            6 * fracunit,
-            8 * fracunit,
+            Fixed::from_int(20).0,
+            Fixed::from_int(6),
+            Fixed::from_int(8),
             100,
             8,
             Sfx::None,
@@ -2081,8 +3180,8 @@ pub fn init_mobjinfo() {
             S::CPOS_XDIE1,
             Sfx::PODTH2,
             8,
-            20 * fracunit,
-            56 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(56),
             100,
             0,
             Sfx::POSACT,
@@ -2111,8 +3210,8 @@ pub fn init_mobjinfo() {
             S::TROO_XDIE1,
             Sfx::BGDTH1,
             8,
-            20 * fracunit,
-            56 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(56),
             100,
             0,
             Sfx::BGACT,
@@ -2141,8 +3240,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::SGTDTH,
             10,
-            30 * fracunit,
-            56 * fracunit,
+            Fixed::from_int(30),
+            Fixed::from_int(56),
             400,
             0,
             Sfx::DMACT,
@@ -2171,8 +3270,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::SGTDTH,
             10,
-            30 * fracunit,
-            56 * fracunit,
+            Fixed::from_int(30),
+            Fixed::from_int(56),
             400,
             0,
             Sfx::DMACT,
@@ -2201,8 +3300,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::CACDTH,
             8,
-            31 * fracunit,
-            56 * fracunit,
+            Fixed::from_int(31),
+            Fixed::from_int(56),
             400,
             0,
             Sfx::DMACT,
@@ -2231,8 +3330,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::BRSDTH,
             8,
-            24 * fracunit,
-            64 * fracunit,
+            Fixed::from_int(24),
+            Fixed::from_int(64),
             1000,
             0,
             Sfx::DMACT,
@@ -2260,9 +3359,9 @@ pub fn init_mobjinfo() {
             S::BRBALLX1,
             S::NULL,
             Sfx::FIRXPL,
-            15 * fracunit,
-            6 * fracunit,
-            8 * fracunit,
+            Fixed::from_int(15).0,
+            Fixed::from_int(6),
+            Fixed::from_int(8),
             100,
             8,
             Sfx::None,
@@ -2291,8 +3390,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::KNTDTH,
             8,
-            24 * fracunit,
-            64 * fracunit,
+            Fixed::from_int(24),
+            Fixed::from_int(64),
             1000,
             0,
             Sfx::DMACT,
@@ -2321,8 +3420,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::FIRXPL,
             8,
-            16 * fracunit,
-            56 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(56),
             50,
             3,
             Sfx::DMACT,
@@ -2351,8 +3450,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::SPIDTH,
             12,
-            128 * fracunit,
-            100 * fracunit,
+            Fixed::from_int(128),
+            Fixed::from_int(100),
             1000,
             0,
             Sfx::DMACT,
@@ -2381,8 +3480,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::BSPDTH,
             12,
-            64 * fracunit,
-            64 * fracunit,
+            Fixed::from_int(64),
+            Fixed::from_int(64),
             600,
             0,
             Sfx::BSPACT,
@@ -2411,8 +3510,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::CYBDTH,
             16,
-            40 * fracunit,
-            110 * fracunit,
+            Fixed::from_int(40),
+            Fixed::from_int(110),
             1000,
             0,
             Sfx::DMACT,
@@ -2441,8 +3540,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::PEDTH,
             8,
-            31 * fracunit,
-            56 * fracunit,
+            Fixed::from_int(31),
+            Fixed::from_int(56),
             400,
             0,
             Sfx::DMACT,
@@ -2471,8 +3570,8 @@ pub fn init_mobjinfo() {
             S::SSWV_XDIE1,
             Sfx::SSDTH,
             8,
-            20 * fracunit,
-            56 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(56),
             100,
             0,
             Sfx::POSACT,
@@ -2501,8 +3600,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::KEENDT,
             0,
-            16 * fracunit,
-            72 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(72),
             10000000,
             0,
             Sfx::None,
@@ -2531,8 +3630,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::BOSDTH,
             0,
-            16 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             10000000,
             0,
             Sfx::None,
@@ -2561,8 +3660,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            32 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(32),
             100,
             0,
             Sfx::None,
@@ -2591,8 +3690,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            32 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(32),
             100,
             0,
             Sfx::None,
@@ -2620,9 +3719,9 @@ pub fn init_mobjinfo() {
             S::NULL,
             S::NULL,
             Sfx::FIRXPL,
-            10 * fracunit,
-            6 * fracunit,
-            32 * fracunit,
+            Fixed::from_int(10).0,
+            Fixed::from_int(6),
+            Fixed::from_int(32),
             100,
             3,
             Sfx::None,
@@ -2651,8 +3750,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -2681,8 +3780,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::BAREXP,
             0,
-            10 * fracunit,
-            42 * fracunit,
+            Fixed::from_int(10),
+            Fixed::from_int(42),
             100,
             0,
             Sfx::None,
@@ -2710,9 +3809,9 @@ pub fn init_mobjinfo() {
             S::TBALLX1,
             S::NULL,
             Sfx::FIRXPL,
-            10 * fracunit,
-            6 * fracunit,
-            8 * fracunit,
+            Fixed::from_int(10).0,
+            Fixed::from_int(6),
+            Fixed::from_int(8),
             100,
             3,
             Sfx::None,
@@ -2740,9 +3839,9 @@ pub fn init_mobjinfo() {
             S::RBALLX1,
             S::NULL,
             Sfx::FIRXPL,
-            10 * fracunit,
-            6 * fracunit,
-            8 * fracunit,
+            Fixed::from_int(10).0,
+            Fixed::from_int(6),
+            Fixed::from_int(8),
             100,
             5,
             Sfx::None,
@@ -2770,9 +3869,9 @@ pub fn init_mobjinfo() {
             S::EXPLODE1,
             S::NULL,
             Sfx::BAREXP,
-            20 * fracunit,
-            11 * fracunit,
-            8 * fracunit,
+            Fixed::from_int(20).0,
+            Fixed::from_int(11),
+            Fixed::from_int(8),
             100,
             20,
             Sfx::None,
@@ -2800,9 +3899,9 @@ pub fn init_mobjinfo() {
             S::PLASEXP,
             S::NULL,
             Sfx::FIRXPL,
-            25 * fracunit,
-            13 * fracunit,
-            8 * fracunit,
+            Fixed::from_int(25).0,
+            Fixed::from_int(13),
+            Fixed::from_int(8),
             100,
             5,
             Sfx::None,
@@ -2830,9 +3929,9 @@ pub fn init_mobjinfo() {
             S::BFGLAND,
             S::NULL,
             Sfx::RXPLOD,
-            25 * fracunit,
-            13 * fracunit,
-            8 * fracunit,
+            Fixed::from_int(25).0,
+            Fixed::from_int(13),
+            Fixed::from_int(8),
             100,
             100,
             Sfx::None,
@@ -2860,9 +3959,9 @@ pub fn init_mobjinfo() {
             S::ARACH_PLEX,
             S::NULL,
             Sfx::FIRXPL,
-            25 * fracunit,
-            13 * fracunit,
-            8 * fracunit,
+            Fixed::from_int(25).0,
+            Fixed::from_int(13),
+            Fixed::from_int(8),
             100,
             5,
             Sfx::None,
@@ -2891,8 +3990,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -2921,8 +4020,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -2951,8 +4050,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -2981,8 +4080,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3011,8 +4110,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3041,8 +4140,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3071,8 +4170,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3101,8 +4200,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3131,8 +4230,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3161,8 +4260,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3191,8 +4290,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3221,8 +4320,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3251,8 +4350,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3281,8 +4380,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3311,8 +4410,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3341,8 +4440,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3371,8 +4470,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3401,8 +4500,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3431,8 +4530,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3461,8 +4560,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3491,8 +4590,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3521,8 +4620,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3551,8 +4650,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3581,8 +4680,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3611,8 +4710,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3641,8 +4740,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3671,8 +4770,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3701,8 +4800,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3731,8 +4830,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3761,8 +4860,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3791,8 +4890,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3821,8 +4920,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3851,8 +4950,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3881,8 +4980,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3911,8 +5010,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3941,8 +5040,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -3971,8 +5070,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -4001,8 +5100,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -4031,8 +5130,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -4061,8 +5160,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -4091,8 +5190,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -4121,8 +5220,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -4151,8 +5250,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -4181,8 +5280,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -4211,8 +5310,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -4241,8 +5340,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -4271,8 +5370,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -4301,8 +5400,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -4331,8 +5430,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -4361,8 +5460,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -4391,8 +5490,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -4421,8 +5520,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -4451,8 +5550,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -4481,8 +5580,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -4511,8 +5610,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -4541,8 +5640,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -4571,8 +5670,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -4601,8 +5700,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -4631,8 +5730,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -4661,8 +5760,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -4691,8 +5790,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -4721,8 +5820,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -4751,8 +5850,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -4781,8 +5880,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -4811,8 +5910,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            68 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(68),
             100,
             0,
             Sfx::None,
@@ -4841,8 +5940,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            84 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(84),
             100,
             0,
             Sfx::None,
@@ -4871,8 +5970,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            84 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(84),
             100,
             0,
             Sfx::None,
@@ -4901,8 +6000,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            68 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(68),
             100,
             0,
             Sfx::None,
@@ -4931,8 +6030,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            52 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(52),
             100,
             0,
             Sfx::None,
@@ -4961,8 +6060,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            84 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(84),
             100,
             0,
             Sfx::None,
@@ -4991,8 +6090,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            68 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(68),
             100,
             0,
             Sfx::None,
@@ -5021,8 +6120,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            52 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(52),
             100,
             0,
             Sfx::None,
@@ -5051,8 +6150,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            52 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(52),
             100,
             0,
             Sfx::None,
@@ -5081,8 +6180,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            68 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(68),
             100,
             0,
             Sfx::None,
@@ -5111,8 +6210,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -5141,8 +6240,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -5171,8 +6270,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -5201,8 +6300,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -5231,8 +6330,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -5261,8 +6360,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -5291,8 +6390,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -5321,8 +6420,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -5351,8 +6450,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -5381,8 +6480,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -5411,8 +6510,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -5441,8 +6540,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -5471,8 +6570,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -5501,8 +6600,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -5531,8 +6630,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -5561,8 +6660,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            32 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(32),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -5591,8 +6690,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -5621,8 +6720,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            88 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(88),
             100,
             0,
             Sfx::None,
@@ -5651,8 +6750,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            88 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(88),
             100,
             0,
             Sfx::None,
@@ -5681,8 +6780,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            64 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(64),
             100,
             0,
             Sfx::None,
@@ -5711,8 +6810,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            64 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(64),
             100,
             0,
             Sfx::None,
@@ -5741,8 +6840,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            64 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(64),
             100,
             0,
             Sfx::None,
@@ -5771,8 +6870,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16 * fracunit,
-            64 * fracunit,
+            Fixed::from_int(16),
+            Fixed::from_int(64),
             100,
             0,
             Sfx::None,
@@ -5801,8 +6900,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -5831,8 +6930,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -5861,8 +6960,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -5891,8 +6990,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            8,
-            8,
+            Fixed::from_int(8),
+            Fixed::from_int(8),
             10,
             0,
             Sfx::None,
@@ -5921,8 +7020,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            8,
-            8,
+            Fixed::from_int(8),
+            Fixed::from_int(8),
             10,
             0,
             Sfx::None,
@@ -5951,8 +7050,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::DGDTH,
             10,
-            12 * fracunit,
-            28 * fracunit,
+            Fixed::from_int(12),
+            Fixed::from_int(28),
             100,
             0,
             Sfx::DGACT,
@@ -5980,9 +7079,9 @@ pub fn init_mobjinfo() {
             S::PLS1EXP,
             S::NULL,
             Sfx::FIRXPL,
-            25 * fracunit,
-            13 * fracunit,
-            8 * fracunit,
+            Fixed::from_int(25).0,
+            Fixed::from_int(13),
+            Fixed::from_int(8),
             100,
             4,
             Sfx::None,
@@ -6010,9 +7109,9 @@ pub fn init_mobjinfo() {
             S::PLS2BALLX1,
             S::NULL,
             Sfx::FIRXPL,
-            25 * fracunit,
-            6 * fracunit,
-            8 * fracunit,
+            Fixed::from_int(25).0,
+            Fixed::from_int(6),
+            Fixed::from_int(8),
             100,
             4,
             Sfx::None,
@@ -6041,8 +7140,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            10 * fracunit,
-            16 * fracunit,
+            Fixed::from_int(10),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -6071,8 +7170,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            20 * fracunit,
-            10 * fracunit,
+            Fixed::from_int(20),
+            Fixed::from_int(10),
             100,
             0,
             Sfx::None,
@@ -6101,8 +7200,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            16,
-            16,
+            Fixed::from_int(16),
+            Fixed::from_int(16),
             100,
             0,
             Sfx::None,
@@ -6131,8 +7230,8 @@ pub fn init_mobjinfo() {
             S::NULL,
             Sfx::None,
             0,
-            4 * fracunit,
-            4 * fracunit,
+            Fixed::from_int(4),
+            Fixed::from_int(4),
             100,
             0,
             Sfx::None,
@@ -0,0 +1,2681 @@
+use super::{
+    inter::damage_mobj,
+    map,
+    maputl::{
+        approx_distance, block_lines_iterator, block_things_iterator, block_x, block_y,
+        box_on_line_side, boxes_overlap, line_bbox, line_opening, point_on_line_side,
+        set_thing_position, unset_thing_position, BOXBOTTOM, BOXLEFT, BOXRIGHT, BOXTOP, MAXRADIUS,
+    },
+    mobj::{
+        remove_mobj, set_mobj_state, spawn_missile, spawn_missile_at, spawn_mobj, spawn_puff,
+        MobjFlag, FLOATSPEED,
+    },
+    movement::{
+        aim_line_attack, check_position, get_move_factor, line_attack, radius_attack,
+        teleport_move, touching_sectors, try_move, DropOff, MELEERANGE, MISSILERANGE,
+        ORIG_FRICTION, ORIG_FRICTION_FACTOR,
+    },
+    player::{PlayerState, MAXPLAYERS},
+    sight::check_sight,
+    spec::{exit_level, use_special_line},
+    Context, Level, Thinker,
+};
+use crate::{
+    configuration::{CompatibilityLevel, Configuration, MonsterInfightingLevel, SkillLevel},
+    doom::def::{GameMode, WeaponType},
+    info::{MobjType, Statenum, MOBJINFO, STATES},
+    map::{LineFlags, Map},
+    misc::{
+        fixed::{fixed_mul, Fixed, FRACUNIT},
+        random::{p_random, p_sub_random, PrClass},
+    },
+    sounds::Sfx,
+    tables::{point_to_angle2, Angle, ANG180, ANG270, ANG90},
+    think::ThinkerId,
+};
+
+/// How long a monster sticks with what hurt it before it'll switch
+/// targets again.
+pub const BASETHRESHOLD: i32 = 100;
+
+/// The directions monsters walk in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DirType {
+    East,
+    NorthEast,
+    North,
+    NorthWest,
+    West,
+    SouthWest,
+    South,
+    SouthEast,
+    NoDir,
+}
+
+/// The directions in order, so they can be turned and reversed by index.
+const DIRS: [DirType; 9] = [
+    DirType::East,
+    DirType::NorthEast,
+    DirType::North,
+    DirType::NorthWest,
+    DirType::West,
+    DirType::SouthWest,
+    DirType::South,
+    DirType::SouthEast,
+    DirType::NoDir,
+];
+
+/// What a sector last heard, for waking up the monsters in it.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct SectorSound {
+    pub validcount: i32,
+    /// 0 if the sound hasn't reached the sector yet, 1 if it came straight
+    /// through, 2 if it came through one sound blocking line.
+    pub soundtraversed: i32,
+    /// The mobj that made the noise.
+    pub soundtarget: Option<ThinkerId>,
+}
+
+/// The boss brain's spawn spots, the `MT_BOSSTARGET`s in thinker order,
+/// and which it spits a cube at next.
+#[derive(Debug, Clone, Default)]
+pub struct Brain {
+    pub targets: Vec<ThinkerId>,
+    pub targeton: usize,
+    /// killough 3/26/98: toggles each spit, so it only spits every other
+    /// time on the easy skills.
+    pub easy: bool,
+}
+
+/// How far a step in each direction goes, per unit of speed.
+#[rustfmt::skip]
+const XSPEED: [Fixed; 8] = [
+    Fixed(FRACUNIT as i32), Fixed(47000), Fixed(0), Fixed(-47000),
+    Fixed(-(FRACUNIT as i32)), Fixed(-47000), Fixed(0), Fixed(47000),
+];
+#[rustfmt::skip]
+const YSPEED: [Fixed; 8] = [
+    Fixed(0), Fixed(47000), Fixed(FRACUNIT as i32), Fixed(47000),
+    Fixed(0), Fixed(-47000), Fixed(-(FRACUNIT as i32)), Fixed(-47000),
+];
+
+/// A dying thing stops blocking others, so its corpse can be walked over.
+pub fn a_fall(context: &mut Context, actor: ThinkerId) {
+    if let Some(actor) = context.level.mobj_mut(actor) {
+        actor.flags.remove(MobjFlag::SOLID);
+    }
+}
+
+/// Take a monster one step in its `movedir`, like vanilla's `P_Move`.
+/// Returns false if it was blocked.
+pub fn move_monster(context: &mut Context, actor: ThinkerId, dropoff: DropOff) -> bool {
+    let (movedir, mobj_type) = match context.level.mobj(actor) {
+        Some(mo) if mo.movedir != DirType::NoDir => (mo.movedir as usize, mo.mobj_type),
+        _ => return false,
+    };
+
+    // killough 10/98: make monsters get affected by ice and sludge too
+    let (mut movefactor, friction) = if context.configuration.monster_friction {
+        get_move_factor(context, actor)
+    } else {
+        (ORIG_FRICTION_FACTOR, ORIG_FRICTION)
+    };
+
+    let mut speed = MOBJINFO.read()[&mobj_type].speed;
+    if friction < ORIG_FRICTION {
+        // sludge
+        speed = ((ORIG_FRICTION_FACTOR - (ORIG_FRICTION_FACTOR - movefactor) / 2) * speed)
+            / ORIG_FRICTION_FACTOR;
+        if speed == 0 {
+            // always give the monster a little bit of speed
+            speed = 1;
+        }
+    }
+
+    let mo = context.level.mobj(actor).unwrap();
+    let (origx, origy) = (mo.x, mo.y);
+    let deltax = XSPEED[movedir] * speed;
+    let deltay = YSPEED[movedir] * speed;
+    let try_ok = try_move(context, actor, origx + deltax, origy + deltay, dropoff);
+
+    // killough 10/98: let normal momentum carry them, instead of
+    // steptoeing them across ice
+    if try_ok && friction > ORIG_FRICTION {
+        // the position is put back without relinking, as in MBF
+        let mo = context.level.mobj_mut(actor).unwrap();
+        mo.x = origx;
+        mo.y = origy;
+        movefactor *= FRACUNIT as i32 / ORIG_FRICTION_FACTOR / 4;
+        mo.momx += fixed_mul(deltax, Fixed(movefactor));
+        mo.momy += fixed_mul(deltay, Fixed(movefactor));
+    }
+
+    let mbf = context.configuration.compatibility_level >= CompatibilityLevel::Mbf;
+    let check = &mut context.level.check;
+    let (floatok, floorz, felldown) = (check.floatok, check.floorz, check.felldown);
+    let (blockline, spechit) = (check.blockline, !check.spechit.is_empty());
+    let mo = context.level.mobj_mut(actor).unwrap();
+
+    if !try_ok {
+        // open any specials
+        if mo.flags.contains(MobjFlag::FLOAT) && floatok {
+            // must adjust height
+            if mo.z < floorz {
+                mo.z += FLOATSPEED;
+            } else {
+                mo.z -= FLOATSPEED;
+            }
+            mo.flags |= MobjFlag::INFLOAT;
+            return true;
+        }
+
+        if !spechit {
+            return false;
+        }
+        mo.movedir = DirType::NoDir;
+
+        // if the special is not a door that can be opened, return false
+        //
+        // killough 8/9/98: this is what caused monsters to get stuck in
+        // doortracks, because it thought that the monster freed itself by
+        // opening a door, even if it was moving towards the doortrack,
+        // and not the door itself.
+        //
+        // killough 9/9/98: If a line blocking the monster is activated,
+        // return true 90% of the time. If a line blocking the monster is
+        // not activated, but some other line is, return false 90% of the
+        // time. A bit of randomness is needed to ensure it's free from
+        // lockups, but for most cases, it returns the correct result.
+        //
+        // Do NOT simply return false 1/4th of the time (causes monsters to
+        // back out when they shouldn't, and creates secondary stickiness).
+        let mut good = 0;
+        // a teleporter switch empties the list, as vanilla's
+        // P_TeleportMove resets numspechit
+        while let Some(line) = context.level.check.spechit.pop() {
+            if use_special_line(context, actor, line, 0) {
+                good |= if Some(line) == blockline { 1 } else { 2 };
+            }
+        }
+
+        // cph - compatibility maze here
+        // Boom v2.01 and orig. Doom return "good"
+        // Boom v2.02 and LxDoom return good && (P_Random(pr_trywalk)&3)
+        // MBF plays even more games
+        if good == 0 || context.configuration.comp_doorstuck {
+            return good != 0;
+        }
+        if !mbf {
+            // jff 8/13/98
+            return p_random(context.configuration, PrClass::TryWalk) & 3 != 0;
+        }
+        // finally, MBF code
+        return (p_random(context.configuration, PrClass::OpenDoor) >= 230) ^ (good & 1 != 0);
+    }
+    mo.flags.remove(MobjFlag::INFLOAT);
+
+    // killough 11/98: fall more slowly, under gravity, if felldown
+    if !mo.flags.contains(MobjFlag::FLOAT) && (!felldown || !mbf) {
+        mo.z = mo.floorz;
+    }
+    true
+}
+
+fn player_in_game(configuration: &Configuration, n: usize) -> bool {
+    configuration
+        .player_in_game
+        .get(n)
+        .copied()
+        .unwrap_or(false)
+}
+
+/// Flood a sound through the sectors it can reach, so monsters there wake
+/// up, like vanilla's `P_RecursiveSound`. Sound gets through at most one
+/// sound blocking line.
+fn recursive_sound(
+    map: &Map,
+    level: &mut Level,
+    sector: usize,
+    soundblocks: i32,
+    soundtarget: Option<ThinkerId>,
+) {
+    // wake up all monsters in this sector
+    let sound = &mut level.sector_sound[sector];
+    if sound.validcount == level.validcount && sound.soundtraversed <= soundblocks + 1 {
+        // already flooded
+        return;
+    }
+    sound.validcount = level.validcount;
+    sound.soundtraversed = soundblocks + 1;
+    sound.soundtarget = soundtarget;
+
+    for &line in &map.sectors[sector].lines {
+        let check = &map.lines[line];
+        if !check.flags.contains(LineFlags::TWO_SIDED) {
+            continue;
+        }
+        if line_opening(map, line).range <= Fixed::ZERO {
+            // closed door
+            continue;
+        }
+        let other = if check.front_sector == Some(sector) {
+            check.back_sector
+        } else {
+            check.front_sector
+        };
+        let other = match other {
+            Some(other) => other,
+            None => continue,
+        };
+        if !check.flags.contains(LineFlags::SOUND_BLOCK) {
+            recursive_sound(map, level, other, soundblocks, soundtarget);
+        } else if soundblocks == 0 {
+            recursive_sound(map, level, other, 1, soundtarget);
+        }
+    }
+}
+
+/// Let the monsters that can hear `emitter` know `target` is around, like
+/// vanilla's `P_NoiseAlert`. Used when a player shoots.
+pub fn noise_alert(context: &mut Context, target: ThinkerId, emitter: ThinkerId) {
+    let map = map(context.configuration);
+    let sector = match context.level.mobj(emitter) {
+        Some(emitter) => map.subsectors[emitter.subsector].sector,
+        None => return,
+    };
+    context.level.validcount += 1;
+    recursive_sound(map, context.level, sector, 0, Some(target));
+}
+
+/// Whether the actor's target is close enough to hit in melee, like
+/// vanilla's `P_CheckMeleeRange`.
+fn check_melee_range(context: &mut Context, actor: ThinkerId) -> bool {
+    let cl = context.configuration.compatibility_level;
+    let mo = match context.level.mobj(actor) {
+        Some(mo) => mo,
+        None => return false,
+    };
+    let (target, pl) = match mo.target.and_then(|t| Some((t, context.level.mobj(t)?))) {
+        Some(target) => target,
+        None => return false,
+    };
+    // killough 7/18/98: friendly monsters don't attack other friends
+    if (mo.flags & pl.flags).contains(MobjFlag::FRIEND) {
+        return false;
+    }
+    let range = if cl == CompatibilityLevel::DoomV12 {
+        MELEERANGE
+    } else {
+        MELEERANGE - Fixed::from_int(20) + MOBJINFO.read()[&pl.mobj_type].radius
+    };
+    if approx_distance(pl.x - mo.x, pl.y - mo.y) >= range {
+        return false;
+    }
+    check_sight(context, actor, target)
+}
+
+/// Whether a friendly actor would hit one of its own shooting at its
+/// target, like MBF's `P_HitFriend`.
+fn hit_friend(context: &mut Context, actor: ThinkerId) -> bool {
+    let mo = match context.level.mobj(actor) {
+        Some(mo) if mo.flags.contains(MobjFlag::FRIEND) => mo,
+        _ => return false,
+    };
+    let flags = mo.flags;
+    let (target, t) = match mo.target.and_then(|t| Some((t, context.level.mobj(t)?))) {
+        Some(target) => target,
+        None => return false,
+    };
+    let angle = point_to_angle2(mo.x, mo.y, t.x, t.y);
+    let distance = approx_distance(mo.x - t.x, mo.y - t.y);
+    let aim = aim_line_attack(context, actor, angle, distance, MobjFlag::empty());
+    match aim.linetarget.filter(|&lt| lt != target) {
+        Some(lt) => context
+            .level
+            .mobj(lt)
+            .is_some_and(|lt| !(lt.flags ^ flags).contains(MobjFlag::FRIEND)),
+        None => false,
+    }
+}
+
+/// Whether the actor should fire at its target, like vanilla's
+/// `P_CheckMissileRange`. The closer the target, the likelier.
+fn check_missile_range(context: &mut Context, actor: ThinkerId) -> bool {
+    let monster_infighting =
+        context.configuration.monster_infighting != MonsterInfightingLevel::None;
+    let target = match context.level.mobj(actor).and_then(|mo| mo.target) {
+        Some(target) if context.level.mobj(target).is_some() => target,
+        _ => return false,
+    };
+    if !check_sight(context, actor, target) {
+        return false;
+    }
+
+    let mo = context.level.mobj_mut(actor).unwrap();
+    let flags = mo.flags;
+    if flags.contains(MobjFlag::JUSTHIT) {
+        // the target just hit the enemy, so fight back!
+        mo.flags.remove(MobjFlag::JUSTHIT);
+        if !flags.contains(MobjFlag::FRIEND) {
+            return true;
+        }
+
+        // killough 7/18/98: no friendly fire at corpses
+        // killough 11/98: prevent too much infighting among friends
+        let t = context.level.mobj(target).unwrap();
+        if t.health <= 0 {
+            return false;
+        }
+        if !t.flags.contains(MobjFlag::FRIEND) {
+            return true;
+        }
+        return if t.player.is_some() {
+            monster_infighting || p_random(context.configuration, PrClass::Defect) > 128
+        } else {
+            !t.flags.contains(MobjFlag::JUSTHIT)
+                && p_random(context.configuration, PrClass::Defect) > 128
+        };
+    }
+
+    let mo = context.level.mobj(actor).unwrap();
+    let t = context.level.mobj(target).unwrap();
+    // killough 7/18/98: friendly monsters don't attack other friendly
+    // monsters or players (except when attacked, and then only once)
+    if (mo.flags & t.flags).contains(MobjFlag::FRIEND) {
+        return false;
+    }
+    if mo.reactiontime != 0 {
+        // do not attack yet
+        return false;
+    }
+
+    let mut dist = approx_distance(mo.x - t.x, mo.y - t.y) - Fixed::from_int(64);
+    if MOBJINFO.read()[&mo.mobj_type].meleestate == Statenum::NULL as usize {
+        // no melee attack, so fire more
+        dist -= Fixed::from_int(128);
+    }
+    let mut dist = dist.0 >> 16;
+
+    if mo.mobj_type == MobjType::VILE && dist > 14 * 64 {
+        // too far away
+        return false;
+    }
+    if mo.mobj_type == MobjType::UNDEAD {
+        if dist < 196 {
+            // close for fist attack
+            return false;
+        }
+        dist >>= 1;
+    }
+    if matches!(
+        mo.mobj_type,
+        MobjType::CYBORG | MobjType::SPIDER | MobjType::SKULL
+    ) {
+        dist >>= 1;
+    }
+    dist = dist.min(200);
+    if mo.mobj_type == MobjType::CYBORG && dist > 160 {
+        dist = 160;
+    }
+
+    if p_random(context.configuration, PrClass::MissRange) < dist {
+        return false;
+    }
+    !hit_friend(context, actor)
+}
+
+/// killough 9/12/98: whether the actor is on a lift, or a sector that can
+/// be lowered as one, like MBF's `P_IsOnLift`.
+fn is_on_lift(context: &Context, actor: ThinkerId) -> bool {
+    let map = map(context.configuration);
+    let mo = match context.level.mobj(actor) {
+        Some(mo) => mo,
+        None => return false,
+    };
+    let sector_index = map.subsectors[mo.subsector].sector;
+    let sector = &map.sectors[sector_index];
+
+    // short-circuit: look for active lifts
+    if context.level.sector_lift[sector_index] {
+        return true;
+    }
+
+    // Check to see if it's in a sector which can be activated as a lift.
+    sector.tag != 0
+        && map.lines.iter().filter(|l| l.tag == sector.tag).any(|l| {
+            matches!(
+                l.special,
+                10 | 14
+                    | 15
+                    | 20
+                    | 21
+                    | 22
+                    | 47
+                    | 53
+                    | 62
+                    | 66
+                    | 67
+                    | 68
+                    | 87
+                    | 88
+                    | 95
+                    | 120
+                    | 121
+                    | 122
+                    | 123
+                    | 143
+                    | 162
+                    | 163
+                    | 181
+                    | 182
+                    | 144
+                    | 148
+                    | 149
+                    | 211
+                    | 227
+                    | 228
+                    | 231
+                    | 232
+                    | 235
+                    | 236
+            )
+        })
+}
+
+/// killough 9/9/98: whether the actor is under a crushing ceiling, like
+/// MBF's `P_IsUnderDamage`: 1 if one is going up, -1 if one is coming
+/// down, 0 if neither.
+fn is_under_damage(context: &Context, actor: ThinkerId) -> i32 {
+    let configuration = &*context.configuration;
+    let mo = match context.level.mobj(actor) {
+        Some(mo) => mo,
+        None => return 0,
+    };
+    touching_sectors(map(configuration), configuration.compatibility_level, mo)
+        .into_iter()
+        .fold(0, |dir, sector| {
+            dir | context.level.sector_ceiling_direction[sector]
+        })
+}
+
+/// Step the actor towards its target, avoiding hazards when it can, like
+/// MBF's `P_SmartMove`.
+fn smart_move(context: &mut Context, actor: ThinkerId) -> bool {
+    let configuration = &*context.configuration;
+    let map = map(configuration);
+    let avoid_hazards = configuration.monster_avoid_hazards;
+    let mo = match context.level.mobj(actor) {
+        Some(mo) => mo,
+        None => return false,
+    };
+    let target = mo.target.and_then(|t| context.level.mobj(t));
+
+    // killough 9/12/98: Stay on a lift if target is on one
+    let on_lift = !configuration.comp_staylift
+        && target.is_some_and(|t| {
+            t.health > 0
+                && map.sectors[map.subsectors[t.subsector].sector].tag
+                    == map.sectors[map.subsectors[mo.subsector].sector].tag
+        })
+        && is_on_lift(context, actor);
+
+    let mut under_damage = if avoid_hazards {
+        is_under_damage(context, actor)
+    } else {
+        0
+    };
+
+    // killough 10/98: allow dogs to drop off of taller ledges sometimes,
+    // but only up to 128 high, and only if the target is immediately on
+    // the other side of the line.
+    let mut dropoff = DropOff::No;
+    if mo.mobj_type == MobjType::DOGS
+        && configuration.dog_jumping
+        && target.is_some_and(|t| {
+            !(t.flags ^ mo.flags).contains(MobjFlag::FRIEND)
+                && approx_distance(mo.x - t.x, mo.y - t.y) < Fixed::from_int(144)
+        })
+        && p_random(context.configuration, PrClass::DropOff) < 235
+    {
+        dropoff = DropOff::Jump;
+    }
+
+    if !move_monster(context, actor, dropoff) {
+        return false;
+    }
+
+    // killough 9/9/98: avoid crushing ceilings or other damaging areas
+    let avoid = (on_lift
+        && p_random(context.configuration, PrClass::StayOnLift) < 230
+        && !is_on_lift(context, actor))
+        || (avoid_hazards && under_damage == 0 && {
+            under_damage = is_under_damage(context, actor);
+            under_damage != 0
+                && (under_damage < 0 || p_random(context.configuration, PrClass::AvoidCrush) < 200)
+        });
+    if avoid {
+        // avoid the area (most of the time anyway)
+        context.level.mobj_mut(actor).unwrap().movedir = DirType::NoDir;
+    }
+    true
+}
+
+/// Try a step in the actor's `movedir`, and if it works, decide how many
+/// more to take, like vanilla's `P_TryWalk`.
+fn try_walk(context: &mut Context, actor: ThinkerId) -> bool {
+    if !smart_move(context, actor) {
+        return false;
+    }
+    let movecount = p_random(context.configuration, PrClass::TryWalk) & 15;
+    context.level.mobj_mut(actor).unwrap().movecount = movecount;
+    true
+}
+
+/// Try walking with the actor facing `dir`.
+fn try_walk_dir(context: &mut Context, actor: ThinkerId, dir: DirType) -> bool {
+    context.level.mobj_mut(actor).unwrap().movedir = dir;
+    try_walk(context, actor)
+}
+
+/// Pick a direction to walk towards `(deltax, deltay)`, trying the direct
+/// route first and never turning right around unless there's no other
+/// way, like MBF's `P_DoNewChaseDir`.
+fn do_new_chase_dir(context: &mut Context, actor: ThinkerId, deltax: Fixed, deltay: Fixed) {
+    let olddir = context.level.mobj(actor).unwrap().movedir;
+    // find reverse direction
+    let turnaround = if olddir == DirType::NoDir {
+        olddir
+    } else {
+        DIRS[olddir as usize ^ 4]
+    };
+
+    let mut xdir = if deltax > Fixed::from_int(10) {
+        DirType::East
+    } else if deltax < Fixed::from_int(-10) {
+        DirType::West
+    } else {
+        DirType::NoDir
+    };
+    let mut ydir = if deltay < Fixed::from_int(-10) {
+        DirType::South
+    } else if deltay > Fixed::from_int(10) {
+        DirType::North
+    } else {
+        DirType::NoDir
+    };
+
+    // try direct route
+    if xdir != DirType::NoDir && ydir != DirType::NoDir {
+        let dir = match (deltay < Fixed::ZERO, deltax > Fixed::ZERO) {
+            (true, true) => DirType::SouthEast,
+            (true, false) => DirType::SouthWest,
+            (false, true) => DirType::NorthEast,
+            (false, false) => DirType::NorthWest,
+        };
+        context.level.mobj_mut(actor).unwrap().movedir = dir;
+        if turnaround != dir && try_walk(context, actor) {
+            return;
+        }
+    }
+
+    // try other directions
+    if p_random(context.configuration, PrClass::NewChase) > 200 || deltay.abs() > deltax.abs() {
+        std::mem::swap(&mut xdir, &mut ydir);
+    }
+
+    if xdir == turnaround {
+        xdir = DirType::NoDir;
+    }
+    if xdir != DirType::NoDir && try_walk_dir(context, actor, xdir) {
+        // either moved forward or attacked
+        return;
+    }
+    if ydir == turnaround {
+        ydir = DirType::NoDir;
+    }
+    if ydir != DirType::NoDir && try_walk_dir(context, actor, ydir) {
+        return;
+    }
+
+    // there is no direct path to the player, so pick another direction
+    if olddir != DirType::NoDir && try_walk_dir(context, actor, olddir) {
+        return;
+    }
+
+    // randomly determine direction of search
+    if p_random(context.configuration, PrClass::NewChaseDir) & 1 != 0 {
+        for &tdir in &DIRS[..8] {
+            if tdir != turnaround && try_walk_dir(context, actor, tdir) {
+                return;
+            }
+        }
+    } else {
+        for &tdir in DIRS[..8].iter().rev() {
+            if tdir != turnaround && try_walk_dir(context, actor, tdir) {
+                return;
+            }
+        }
+    }
+
+    context.level.mobj_mut(actor).unwrap().movedir = turnaround;
+    if turnaround != DirType::NoDir && !try_walk(context, actor) {
+        context.level.mobj_mut(actor).unwrap().movedir = DirType::NoDir;
+    }
+}
+
+/// killough 11/98: which way to move to get the actor off a tall ledge,
+/// if it's hanging over one, like MBF's `P_AvoidDropoff`. Multiple
+/// contacted lines add up, e.g. when hanging over a corner.
+fn avoid_dropoff(context: &mut Context, actor: ThinkerId) -> (Fixed, Fixed) {
+    let map = map(context.configuration);
+    let mo = context.level.mobj(actor).unwrap();
+    let mut bbox = [Fixed::ZERO; 4];
+    bbox[BOXTOP] = mo.y + mo.radius;
+    bbox[BOXBOTTOM] = mo.y - mo.radius;
+    bbox[BOXRIGHT] = mo.x + mo.radius;
+    bbox[BOXLEFT] = mo.x - mo.radius;
+    // remember floor height
+    let floorz = mo.z;
+
+    let xl = block_x(map, bbox[BOXLEFT]);
+    let xh = block_x(map, bbox[BOXRIGHT]);
+    let yl = block_y(map, bbox[BOXBOTTOM]);
+    let yh = block_y(map, bbox[BOXTOP]);
+
+    let mut deltax = Fixed::ZERO;
+    let mut deltay = Fixed::ZERO;
+    // check lines
+    context.level.validcount += 1;
+    for bx in xl..=xh {
+        for by in yl..=yh {
+            // all contacted lines
+            block_lines_iterator(context, bx, by, |context, line| {
+                let map = super::map(context.configuration);
+                let ld = &map.lines[line];
+                // ignore one-sided linedefs; the linedef must be contacted
+                let (front, back) = match (ld.front_sector, ld.back_sector) {
+                    (Some(front), Some(back)) => (&map.sectors[front], &map.sectors[back]),
+                    _ => return true,
+                };
+                if !boxes_overlap(&bbox, &line_bbox(map, line))
+                    || box_on_line_side(map, &bbox, line).is_some()
+                {
+                    return true;
+                }
+
+                // The monster must contact one of the two floors, and the
+                // other must be a tall dropoff (more than 24).
+                let (front, back) = (front.floor_height, back.floor_height);
+                let angle = if back == floorz && front < floorz - Fixed::from_int(24) {
+                    // front side dropoff
+                    point_to_angle2(Fixed::ZERO, Fixed::ZERO, ld.dx, ld.dy)
+                } else if front == floorz && back < floorz - Fixed::from_int(24) {
+                    // back side dropoff
+                    point_to_angle2(ld.dx, ld.dy, Fixed::ZERO, Fixed::ZERO)
+                } else {
+                    return true;
+                };
+
+                // Move away from dropoff at a standard speed.
+                deltax -= angle.sin() * 32;
+                deltay += angle.cos() * 32;
+                true
+            });
+        }
+    }
+    (deltax, deltay)
+}
+
+/// Pick a new direction for the actor to chase its target in, like
+/// vanilla's `P_NewChaseDir`. In MBF, monsters also back away from
+/// ledges, crowded friends and melee attackers.
+fn new_chase_dir(context: &mut Context, actor: ThinkerId) {
+    let configuration = &*context.configuration;
+    let mbf = configuration.compatibility_level >= CompatibilityLevel::Mbf;
+    let comp_dropoff = configuration.comp_dropoff;
+    let monster_backing = configuration.monster_backing;
+    let friend_distance = configuration.friend_distance.get();
+
+    let mo = match context.level.mobj_mut(actor) {
+        Some(mo) => mo,
+        None => return,
+    };
+    mo.strafecount = 0;
+    let (target, t) = match mo.target.and_then(|t| Some((t, context.level.mobj(t)?))) {
+        Some(target) => target,
+        None => return,
+    };
+    let mo = context.level.mobj(actor).unwrap();
+    let mut deltax = t.x - mo.x;
+    let mut deltay = t.y - mo.y;
+
+    // killough 8/8/98: sometimes move away from target, keeping distance
+    //
+    // 1) Stay a certain distance away from a friend, to avoid being in
+    //    their way
+    // 2) Take advantage over an enemy without missiles, by keeping
+    //    distance
+    if mbf {
+        let hanging = mo.floorz - mo.dropoffz > Fixed::from_int(24)
+            && mo.z <= mo.floorz
+            && !mo.flags.intersects(MobjFlag::DROPOFF | MobjFlag::FLOAT)
+            && !comp_dropoff;
+        let away = if hanging {
+            avoid_dropoff(context, actor)
+        } else {
+            (Fixed::ZERO, Fixed::ZERO)
+        };
+        if away.0 .0 | away.1 .0 != 0 {
+            // Move away from dropoff
+            do_new_chase_dir(context, actor, away.0, away.1);
+
+            // If moving away from dropoff, set movecount to 1 so that small
+            // steps are taken to get monster away from dropoff.
+            context.level.mobj_mut(actor).unwrap().movecount = 1;
+            return;
+        }
+
+        let mo = context.level.mobj(actor).unwrap();
+        let t = context.level.mobj(target).unwrap();
+        let dist = approx_distance(deltax, deltay);
+
+        // Move away from friends when too close, except in certain
+        // situations (e.g. a crowded lift)
+        if (mo.flags & t.flags).contains(MobjFlag::FRIEND)
+            && Fixed::from_int(friend_distance) > dist
+            && !is_on_lift(context, target)
+            && is_under_damage(context, actor) == 0
+        {
+            deltax = -deltax;
+            deltay = -deltay;
+        } else if t.health > 0 && (mo.flags ^ t.flags).contains(MobjFlag::FRIEND) {
+            // Live enemy target
+            let backing = monster_backing && {
+                let mobjinfo = MOBJINFO.read();
+                let readyweapon = t.player.map(|n| context.level.players[n].readyweapon);
+                mobjinfo[&mo.mobj_type].missilestate != Statenum::NULL as usize
+                    && mo.mobj_type != MobjType::SKULL
+                    && ((mobjinfo[&t.mobj_type].missilestate == Statenum::NULL as usize
+                        && dist < MELEERANGE * 2)
+                        || (dist < MELEERANGE * 3
+                            && matches!(
+                                readyweapon,
+                                Some(WeaponType::Fist) | Some(WeaponType::Chainsaw)
+                            )))
+            };
+            if backing {
+                // Back away from melee attacker
+                let strafecount = p_random(context.configuration, PrClass::EnemyStrafe) & 15;
+                context.level.mobj_mut(actor).unwrap().strafecount = strafecount;
+                deltax = -deltax;
+                deltay = -deltay;
+            }
+        }
+    }
+
+    do_new_chase_dir(context, actor, deltax, deltay);
+
+    // If strafing, set movecount to strafecount so that old Doom logic
+    // still works the same, except in the strafing part
+    let mo = context.level.mobj_mut(actor).unwrap();
+    if mo.strafecount != 0 {
+        mo.movecount = mo.strafecount;
+    }
+}
+
+/// Whether the actor can see `mo`: in front of it, or close by, unless it
+/// looks all around, like MBF's `P_IsVisible`.
+fn is_visible(context: &mut Context, actor: ThinkerId, mo: ThinkerId, allaround: bool) -> bool {
+    if !allaround {
+        let (a, m) = match (context.level.mobj(actor), context.level.mobj(mo)) {
+            (Some(a), Some(m)) => (a, m),
+            _ => return false,
+        };
+        let an = point_to_angle2(a.x, a.y, m.x, m.y) - a.angle;
+        if an > ANG90 && an < ANG270 && approx_distance(m.x - a.x, m.y - a.y) > MELEERANGE {
+            return false;
+        }
+    }
+    check_sight(context, actor, mo)
+}
+
+/// Look for a player to go after, like vanilla's `P_LookForPlayers`. If
+/// `allaround` is false, only players in front are seen. Friends go back
+/// to a player whether they can see one or not.
+fn look_for_players(context: &mut Context, actor: ThinkerId, allaround: bool) -> bool {
+    let configuration = &*context.configuration;
+    let cl = configuration.compatibility_level;
+    let mbf = cl >= CompatibilityLevel::Mbf;
+    let demo_compatibility = cl < CompatibilityLevel::Boom;
+    let monsters_remember = configuration.monsters_remember;
+    let comp_pursuit = configuration.comp_pursuit;
+
+    let mo = match context.level.mobj(actor) {
+        Some(mo) => mo,
+        None => return false,
+    };
+
+    if mo.flags.contains(MobjFlag::FRIEND) {
+        // killough 9/9/98: friendly monsters go about players differently
+
+        // Go back to a player, no matter whether it's visible or not
+        for anyone in [false, true] {
+            for c in 0..MAXPLAYERS {
+                let player = &context.level.players[c];
+                let player_mo = match player.mo {
+                    Some(player_mo)
+                        if player_in_game(context.configuration, c)
+                            && player.playerstate == PlayerState::Live =>
+                    {
+                        player_mo
+                    }
+                    _ => continue,
+                };
+                if !anyone && !is_visible(context, actor, player_mo, allaround) {
+                    continue;
+                }
+                context.level.mobj_mut(actor).unwrap().target = Some(player_mo);
+
+                // killough 12/98: get out of refiring loop, to avoid
+                // hitting player accidentally
+                let mobj_type = context.level.mobj(actor).unwrap().mobj_type;
+                let (missilestate, seestate) = {
+                    let mobjinfo = MOBJINFO.read();
+                    (
+                        mobjinfo[&mobj_type].missilestate,
+                        mobjinfo[&mobj_type].seestate,
+                    )
+                };
+                if missilestate != Statenum::NULL as usize {
+                    set_mobj_state(context, actor, seestate);
+                    if let Some(mo) = context.level.mobj_mut(actor) {
+                        mo.flags.remove(MobjFlag::JUSTHIT);
+                    }
+                }
+                return true;
+            }
+        }
+        return false;
+    }
+
+    // killough 2/15/98: change mask of 3 to (MAXPLAYERS-1)
+    let stop = (mo.lastlook - 1) & (MAXPLAYERS as i32 - 1);
+    // killough 9/9/98
+    let boom_remember = !mbf && !demo_compatibility && monsters_remember;
+    let stopc = if boom_remember { MAXPLAYERS } else { 2 };
+    let mut c = 0;
+
+    let mut first = true;
+    loop {
+        let lastlook = {
+            let mo = context.level.mobj_mut(actor).unwrap();
+            if !first {
+                mo.lastlook = (mo.lastlook + 1) & (MAXPLAYERS as i32 - 1);
+            }
+            first = false;
+            mo.lastlook
+        };
+        if !player_in_game(context.configuration, lastlook as usize) {
+            continue;
+        }
+
+        // killough 2/15/98, 9/9/98
+        let done = c == stopc || lastlook == stop;
+        c += 1;
+        if done {
+            // done looking
+            // e6y: Boom went back to the last enemy here, which was
+            // missed
+            if boom_remember {
+                let mo = context.level.mobj(actor).unwrap();
+                if let Some(lastenemy) = mo
+                    .lastenemy
+                    .filter(|&e| context.level.mobj(e).is_some_and(|e| e.health > 0))
+                {
+                    let mo = context.level.mobj_mut(actor).unwrap();
+                    mo.target = Some(lastenemy);
+                    mo.lastenemy = None;
+                    return true;
+                }
+            }
+            return false;
+        }
+
+        let player = &context.level.players[lastlook as usize];
+        if player.health <= 0 {
+            // dead
+            continue;
+        }
+        let player_mo = match player.mo {
+            Some(player_mo) => player_mo,
+            None => continue,
+        };
+        if !is_visible(context, actor, player_mo, allaround) {
+            continue;
+        }
+
+        let mo = context.level.mobj_mut(actor).unwrap();
+        mo.target = Some(player_mo);
+        // killough 9/9/98: give monsters a threshold towards getting
+        // players (we don't want it to be too easy for a player with dogs
+        // :)
+        if !comp_pursuit {
+            mo.threshold = 60;
+        }
+        return true;
+    }
+}
+
+/// Whether `mo` is a monster on the other side that the actor should go
+/// after; if so it becomes the actor's target, like MBF's
+/// `PIT_FindTarget`. Returns false when a target was found, to stop the
+/// search.
+fn find_target(context: &mut Context, actor: ThinkerId, mo: ThinkerId, allaround: bool) -> bool {
+    let (a, m) = match (context.level.mobj(actor), context.level.mobj(mo)) {
+        (Some(a), Some(m)) => (a, m),
+        _ => return true,
+    };
+    // invalid target
+    if !((a.flags ^ m.flags).contains(MobjFlag::FRIEND)
+        && m.health > 0
+        && (m.flags.contains(MobjFlag::COUNTKILL) || m.mobj_type == MobjType::SKULL))
+    {
+        return true;
+    }
+
+    // If the monster is already engaged in a one-on-one attack with a
+    // healthy friend, don't attack around 60% the time
+    if let Some(targ) = m.target.and_then(|t| context.level.mobj(t)) {
+        let (targ_target, targ_flags, targ_health, targ_type) =
+            (targ.target, targ.flags, targ.health, targ.mobj_type);
+        let m_flags = m.flags;
+        if targ_target == Some(mo)
+            && p_random(context.configuration, PrClass::SkipTarget) > 100
+            && (targ_flags ^ m_flags).contains(MobjFlag::FRIEND)
+            && targ_health * 2 >= MOBJINFO.read()[&targ_type].spawnhealth
+        {
+            return true;
+        }
+    }
+
+    if !is_visible(context, actor, mo, allaround) {
+        return true;
+    }
+
+    // Remember previous target
+    let a = context.level.mobj_mut(actor).unwrap();
+    a.lastenemy = a.target;
+    // Found target
+    a.target = Some(mo);
+
+    // Move the selected monster to the end of its associated list, so that
+    // it gets searched last next time.
+    let friend = context
+        .level
+        .mobj(mo)
+        .unwrap()
+        .flags
+        .contains(MobjFlag::FRIEND);
+    let level = &mut *context.level;
+    level.friends.retain(|&th| th != mo);
+    level.enemies.retain(|&th| th != mo);
+    level.class_list(friend).push(mo);
+    false
+}
+
+/// Look for a monster on the other side to go after, nearby ones first,
+/// like MBF's `P_LookForMonsters`. Monsters remembering an enemy go back
+/// to it.
+fn look_for_monsters(context: &mut Context, actor: ThinkerId, allaround: bool) -> bool {
+    let configuration = &*context.configuration;
+    let cl = configuration.compatibility_level;
+    if cl < CompatibilityLevel::Boom {
+        return false;
+    }
+    let monsters_remember = configuration.monsters_remember;
+
+    let mo = match context.level.mobj(actor) {
+        Some(mo) => mo,
+        None => return false,
+    };
+    let lastenemy = mo.lastenemy.filter(|&e| {
+        context
+            .level
+            .mobj(e)
+            .is_some_and(|e| e.health > 0 && !(e.flags & mo.flags).contains(MobjFlag::FRIEND))
+    });
+    if let Some(lastenemy) = lastenemy.filter(|_| monsters_remember) {
+        // not friends
+        let mo = context.level.mobj_mut(actor).unwrap();
+        mo.target = Some(lastenemy);
+        mo.lastenemy = None;
+        return true;
+    }
+
+    // Old demos do not support monster-seeking bots
+    if cl < CompatibilityLevel::Mbf {
+        return false;
+    }
+
+    // Search the threaded list corresponding to this object's potential
+    // targets
+    let (friend, x, y) = (mo.flags.contains(MobjFlag::FRIEND), mo.x, mo.y);
+    if context.level.class_list(!friend).is_empty() {
+        // Empty list? bail out early
+        return false;
+    }
+
+    let map = map(context.configuration);
+    let x = block_x(map, x);
+    let y = block_y(map, y);
+    let search = |context: &mut Context, bx: i32, by: i32| {
+        !block_things_iterator(context, bx, by, |context, th| {
+            find_target(context, actor, th, allaround)
+        })
+    };
+
+    // Search first in the immediate vicinity.
+    if search(context, x, y) {
+        return true;
+    }
+    for d in 1..5 {
+        let mut i = 1 - d;
+        loop {
+            if search(context, x + i, y - d) || search(context, x + i, y + d) {
+                return true;
+            }
+            i += 1;
+            if i >= d {
+                break;
+            }
+        }
+        loop {
+            if search(context, x - d, y + i) || search(context, x + d, y + i) {
+                return true;
+            }
+            i -= 1;
+            if i + d < 0 {
+                break;
+            }
+        }
+    }
+
+    // Random number of monsters, to prevent patterns from forming
+    let mut n = (p_random(context.configuration, PrClass::Friends) & 31) + 15;
+    let list = context.level.class_list(!friend).clone();
+    for (searched, &th) in list.iter().enumerate() {
+        n -= 1;
+        if n < 0 {
+            // Only a subset of the monsters were searched. Move all of the
+            // ones which were searched so far, to the end of the list.
+            context.level.class_list(!friend).rotate_left(searched);
+            break;
+        }
+        if !find_target(context, actor, th, allaround) {
+            // If target sighted
+            return true;
+        }
+    }
+
+    // No monster found
+    false
+}
+
+type LookFn = fn(&mut Context, ThinkerId, bool) -> bool;
+
+/// killough 9/5/98: look for a player or monster to go after, like MBF's
+/// `P_LookForTargets`. Friends look for monsters first, and go back to a
+/// player if there are none.
+fn look_for_targets(context: &mut Context, actor: ThinkerId, allaround: bool) -> bool {
+    let friend = context
+        .level
+        .mobj(actor)
+        .is_some_and(|mo| mo.flags.contains(MobjFlag::FRIEND));
+    let (first, second): (LookFn, LookFn) = if friend {
+        (look_for_monsters, look_for_players)
+    } else {
+        (look_for_players, look_for_monsters)
+    };
+    first(context, actor, allaround) || second(context, actor, allaround)
+}
+
+/// killough 9/8/98: go after what's attacking a badly hurt friend, unless
+/// the actor is badly hurt itself, like MBF's `P_HelpFriend`.
+fn help_friend(context: &mut Context, actor: ThinkerId) -> bool {
+    let mo = match context.level.mobj(actor) {
+        Some(mo) => mo,
+        None => return false,
+    };
+
+    // If less than 33% health, self-preservation rules
+    if mo.health * 3 < MOBJINFO.read()[&mo.mobj_type].spawnhealth {
+        return false;
+    }
+
+    // Possibly help a friend under 50% health
+    let friend = mo.flags.contains(MobjFlag::FRIEND);
+    let list = context.level.class_list(friend).clone();
+    for th in list {
+        let (health, spawnhealth, target) = match context.level.mobj(th) {
+            Some(th) => (
+                th.health,
+                MOBJINFO.read()[&th.mobj_type].spawnhealth,
+                th.target,
+            ),
+            None => continue,
+        };
+        if health * 2 >= spawnhealth {
+            if p_random(context.configuration, PrClass::HelpFriend) < 180 {
+                break;
+            }
+        } else if let Some(target) = target.filter(|&t| t != actor) {
+            if !find_target(context, actor, target, true) {
+                // Ignore any attacking monsters, while searching for friend
+                context.level.mobj_mut(actor).unwrap().threshold = BASETHRESHOLD;
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Stay in the spawn state until a player is seen or heard, then go into
+/// the chase state.
+pub fn a_look(context: &mut Context, actor: ThinkerId) {
+    let map = map(context.configuration);
+    let mo = match context.level.mobj_mut(actor) {
+        Some(mo) => mo,
+        None => return,
+    };
+    // any shot will wake up
+    mo.threshold = 0;
+    // killough 7/18/98: friendly monsters go after other monsters first,
+    // but also return to player, without attacking them, if they cannot
+    // find any targets
+    mo.pursuecount = 0;
+    let friend = mo.flags.contains(MobjFlag::FRIEND);
+    let ambush = mo.flags.contains(MobjFlag::AMBUSH);
+    let sector = map.subsectors[mo.subsector].sector;
+    let targ = context.level.sector_sound[sector].soundtarget;
+
+    // killough 7/18/98: Friendly monsters go after other monsters first,
+    // but also return to owner if they cannot find any targets. A marine's
+    // best friend :)
+    let seen = (friend && look_for_targets(context, actor, false))
+        || match targ.filter(|&t| {
+            context
+                .level
+                .mobj(t)
+                .is_some_and(|t| t.flags.contains(MobjFlag::SHOOTABLE))
+        }) {
+            Some(targ) => {
+                context.level.mobj_mut(actor).unwrap().target = Some(targ);
+                !ambush || check_sight(context, actor, targ)
+            }
+            None => false,
+        }
+        || (!friend && look_for_targets(context, actor, false));
+    if !seen {
+        return;
+    }
+
+    // go into chase state
+    let (seesound, seestate) = {
+        let mobjinfo = MOBJINFO.read();
+        let info = &mobjinfo[&context.level.mobj(actor).unwrap().mobj_type];
+        (info.seesound, info.seestate)
+    };
+    if seesound != Sfx::None as usize {
+        let posit = [Sfx::POSIT1, Sfx::POSIT2, Sfx::POSIT3].map(|s| s as usize);
+        let bgsit = [Sfx::BGSIT1, Sfx::BGSIT2].map(|s| s as usize);
+        let sound = if posit.contains(&seesound) {
+            posit[0] + (p_random(context.configuration, PrClass::See) % 3) as usize
+        } else if bgsit.contains(&seesound) {
+            bgsit[0] + (p_random(context.configuration, PrClass::See) % 2) as usize
+        } else {
+            seesound
+        };
+        start_boss_sound(context, actor, sound);
+    }
+    set_mobj_state(context, actor, seestate);
+}
+
+/// Walk towards the target, attacking when in range, and look for a
+/// better target every so often.
+pub fn a_chase(context: &mut Context, actor: ThinkerId) {
+    let configuration = &*context.configuration;
+    let mbf = configuration.compatibility_level >= CompatibilityLevel::Mbf;
+    let fast = configuration.game_skill == SkillLevel::Nm || configuration.fastparm;
+    let netgame = configuration.netgame;
+    let help_friends = configuration.help_friends;
+    let comp_pursuit = configuration.comp_pursuit;
+    let monster_infighting = configuration.monster_infighting != MonsterInfightingLevel::None;
+
+    let target_alive = context
+        .level
+        .mobj(actor)
+        .and_then(|mo| mo.target)
+        .and_then(|t| context.level.mobj(t))
+        .is_some_and(|t| t.health > 0);
+    let mo = match context.level.mobj_mut(actor) {
+        Some(mo) => mo,
+        None => return,
+    };
+    if mo.reactiontime != 0 {
+        mo.reactiontime -= 1;
+    }
+
+    // modify target threshold
+    if mo.threshold != 0 {
+        if !target_alive {
+            mo.threshold = 0;
+        } else {
+            mo.threshold -= 1;
+        }
+    }
+
+    // turn towards movement direction if not there yet
+    // killough 9/7/98: keep facing towards target if strafing or backing
+    // out
+    if mo.strafecount != 0 {
+        a_face_target(context, actor);
+    } else if mo.movedir != DirType::NoDir {
+        mo.angle = Angle(mo.angle.0 & (7 << 29));
+        let delta = mo.angle.0.wrapping_sub((mo.movedir as u32) << 29) as i32;
+        if delta > 0 {
+            mo.angle -= ANG90 / 2;
+        } else if delta < 0 {
+            mo.angle += ANG90 / 2;
+        }
+    }
+
+    let mo = context.level.mobj(actor).unwrap();
+    let (spawnstate, meleestate, missilestate, attacksound, activesound) = {
+        let mobjinfo = MOBJINFO.read();
+        let info = &mobjinfo[&mo.mobj_type];
+        (
+            info.spawnstate,
+            info.meleestate,
+            info.missilestate,
+            info.attacksound,
+            info.activesound,
+        )
+    };
+    let target = match mo.target.filter(|&t| {
+        context
+            .level
+            .mobj(t)
+            .is_some_and(|t| t.flags.contains(MobjFlag::SHOOTABLE))
+    }) {
+        Some(target) => target,
+        None => {
+            // look for a new target
+            if !look_for_targets(context, actor, true) {
+                // no new target
+                set_mobj_state(context, actor, spawnstate);
+            }
+            return;
+        }
+    };
+
+    // do not attack twice in a row
+    let mo = context.level.mobj_mut(actor).unwrap();
+    if mo.flags.contains(MobjFlag::JUSTATTACKED) {
+        mo.flags.remove(MobjFlag::JUSTATTACKED);
+        if !fast {
+            new_chase_dir(context, actor);
+        }
+        return;
+    }
+
+    // check for melee attack
+    if meleestate != Statenum::NULL as usize && check_melee_range(context, actor) {
+        context.level.start_sound(Some(actor), attacksound);
+        set_mobj_state(context, actor, meleestate);
+        // killough 8/98: remember an attack
+        if missilestate == Statenum::NULL as usize {
+            if let Some(mo) = context.level.mobj_mut(actor) {
+                mo.flags |= MobjFlag::JUSTHIT;
+            }
+        }
+        return;
+    }
+
+    // check for missile attack
+    if missilestate != Statenum::NULL as usize
+        && (fast || context.level.mobj(actor).unwrap().movecount == 0)
+        && check_missile_range(context, actor)
+    {
+        set_mobj_state(context, actor, missilestate);
+        if let Some(mo) = context.level.mobj_mut(actor) {
+            mo.flags |= MobjFlag::JUSTATTACKED;
+        }
+        return;
+    }
+
+    if context.level.mobj(actor).unwrap().threshold == 0 {
+        if !mbf {
+            // killough 9/9/98: for backward demo compatibility
+            if netgame
+                && !check_sight(context, actor, target)
+                && look_for_players(context, actor, true)
+            {
+                return;
+            }
+        } else if help_friends && help_friend(context, actor) {
+            // killough 9/8/98: Help friends in need
+            return;
+        } else if context.level.mobj(actor).unwrap().pursuecount != 0 {
+            // Look for new targets if current one is bad or is out of view
+            context.level.mobj_mut(actor).unwrap().pursuecount -= 1;
+        } else {
+            // Our pursuit time has expired. We're going to think about
+            // changing targets
+            context.level.mobj_mut(actor).unwrap().pursuecount = BASETHRESHOLD;
+
+            // Unless (we have a live target and it's not friendly and we
+            // can see it) try to find a new one; return if successful
+            let mo = context.level.mobj(actor).unwrap();
+            let flags = mo.flags;
+            let good_target = match mo.target.and_then(|t| context.level.mobj(t)) {
+                Some(t) if t.health > 0 => {
+                    (comp_pursuit && !netgame)
+                        || (((t.flags ^ flags).contains(MobjFlag::FRIEND)
+                            || (!flags.contains(MobjFlag::FRIEND) && monster_infighting))
+                            && check_sight(context, actor, target))
+                }
+                _ => false,
+            };
+            if !good_target && look_for_targets(context, actor, true) {
+                return;
+            }
+
+            // (Current target was good, or no new target was found.)
+            //
+            // If monster is a missile-less friend, give up pursuit and
+            // return to player, if no attacks have occurred recently.
+            let mo = context.level.mobj_mut(actor).unwrap();
+            if missilestate == Statenum::NULL as usize && mo.flags.contains(MobjFlag::FRIEND) {
+                if mo.flags.contains(MobjFlag::JUSTHIT) {
+                    // if recent action, keep fighting
+                    mo.flags.remove(MobjFlag::JUSTHIT);
+                } else if look_for_players(context, actor, true) {
+                    // else return to player
+                    return;
+                }
+            }
+        }
+    }
+
+    let mo = context.level.mobj_mut(actor).unwrap();
+    if mo.strafecount != 0 {
+        mo.strafecount -= 1;
+    }
+
+    // chase towards player
+    mo.movecount -= 1;
+    if mo.movecount < 0 || !smart_move(context, actor) {
+        new_chase_dir(context, actor);
+    }
+
+    // make active sound
+    if activesound != Sfx::None as usize && p_random(context.configuration, PrClass::See) < 3 {
+        context.level.start_sound(Some(actor), activesound);
+    }
+}
+
+/// Turn to face the target, wobbling a bit if it's partially invisible.
+pub fn a_face_target(context: &mut Context, actor: ThinkerId) {
+    let mo = match context.level.mobj(actor) {
+        Some(mo) => mo,
+        None => return,
+    };
+    let t = match mo.target.and_then(|t| context.level.mobj(t)) {
+        Some(t) => t,
+        None => return,
+    };
+    let angle = point_to_angle2(mo.x, mo.y, t.x, t.y);
+    let shadow = t.flags.contains(MobjFlag::SHADOW);
+    let mo = context.level.mobj_mut(actor).unwrap();
+    mo.flags.remove(MobjFlag::AMBUSH);
+    mo.angle = angle;
+    if shadow {
+        // killough 5/5/98: remove dependence on order of evaluation
+        let t = p_random(context.configuration, PrClass::FaceTarget);
+        let r = p_random(context.configuration, PrClass::FaceTarget);
+        context.level.mobj_mut(actor).unwrap().angle += Angle(((t - r) << 21) as u32);
+    }
+}
+
+/// Start a sound from `actor`, or at full volume everywhere if it's one of
+/// the bosses, as their sight and death sounds are.
+fn start_boss_sound(context: &mut Context, actor: ThinkerId, sound: usize) {
+    let boss = context
+        .level
+        .mobj(actor)
+        .is_some_and(|mo| matches!(mo.mobj_type, MobjType::SPIDER | MobjType::CYBORG));
+    let origin = if boss { None } else { Some(actor) };
+    context.level.start_sound(origin, sound);
+}
+
+/// The actor's target, if it has one that's still on the level.
+fn target(context: &Context, actor: ThinkerId) -> Option<ThinkerId> {
+    context
+        .level
+        .mobj(actor)?
+        .target
+        .filter(|&t| context.level.mobj(t).is_some())
+}
+
+/// Whether the actor's target is gone, dead or out of sight, so it should
+/// stop firing.
+fn lost_target(context: &mut Context, actor: ThinkerId) -> bool {
+    match target(context, actor) {
+        Some(t) if context.level.mobj(t).unwrap().health > 0 => !check_sight(context, actor, t),
+        _ => true,
+    }
+}
+
+/// Whether the actor and its target are both friends.
+fn friendly_target(context: &Context, actor: ThinkerId) -> bool {
+    let flags = context.level.mobj(actor).unwrap().flags;
+    target(context, actor)
+        .is_some_and(|t| (flags & context.level.mobj(t).unwrap().flags).contains(MobjFlag::FRIEND))
+}
+
+/// Hit the actor's target for `damage` if it's in melee range, with
+/// `sound`. Returns whether it was.
+fn melee_attack(
+    context: &mut Context,
+    actor: ThinkerId,
+    target: ThinkerId,
+    sound: Option<Sfx>,
+    damage: impl FnOnce(&mut Context) -> i32,
+) -> bool {
+    if !check_melee_range(context, actor) {
+        return false;
+    }
+    if let Some(sound) = sound {
+        context.level.start_sound(Some(actor), sound as usize);
+    }
+    let damage = damage(context);
+    damage_mobj(context, target, Some(actor), Some(actor), damage);
+    true
+}
+
+/// Go back to chasing, from a refire check that decided to stop.
+fn stop_firing(context: &mut Context, actor: ThinkerId) {
+    let seestate = match context.level.mobj(actor) {
+        Some(mo) => MOBJINFO.read()[&mo.mobj_type].seestate,
+        None => return,
+    };
+    set_mobj_state(context, actor, seestate);
+}
+
+/// A rocket or whatever else explodes, hurting everything around it.
+pub fn a_explode(context: &mut Context, thingy: ThinkerId) {
+    let source = target(context, thingy);
+    radius_attack(context, thingy, source, 128);
+}
+
+pub fn a_pain(context: &mut Context, actor: ThinkerId) {
+    if let Some(mo) = context.level.mobj(actor) {
+        let painsound = MOBJINFO.read()[&mo.mobj_type].painsound;
+        context.level.start_sound(Some(actor), painsound);
+    }
+}
+
+/// A player dies, screaming louder in Doom II if they died hard.
+pub fn a_player_scream(context: &mut Context, mo: ThinkerId) {
+    let health = match context.level.mobj(mo) {
+        Some(mo) => mo.health,
+        None => return,
+    };
+    // if the player dies less than -50% without gibbing
+    let sound = if context.configuration.game_mode == GameMode::Commercial && health < -50 {
+        Sfx::PDIEHI
+    } else {
+        // default death sound
+        Sfx::PLDETH
+    };
+    context.level.start_sound(Some(mo), sound as usize);
+}
+
+pub fn a_xscream(context: &mut Context, actor: ThinkerId) {
+    context.level.start_sound(Some(actor), Sfx::SLOP as usize);
+}
+
+/// A monster dies, with one of the death sounds it has.
+pub fn a_scream(context: &mut Context, actor: ThinkerId) {
+    let deathsound = match context.level.mobj(actor) {
+        Some(mo) => MOBJINFO.read()[&mo.mobj_type].deathsound,
+        None => return,
+    };
+    let podth = [Sfx::PODTH1, Sfx::PODTH2, Sfx::PODTH3].map(|s| s as usize);
+    let bgdth = [Sfx::BGDTH1, Sfx::BGDTH2].map(|s| s as usize);
+    let sound = if deathsound == Sfx::None as usize {
+        return;
+    } else if podth.contains(&deathsound) {
+        podth[0] + (p_random(context.configuration, PrClass::Scream) % 3) as usize
+    } else if bgdth.contains(&deathsound) {
+        bgdth[0] + (p_random(context.configuration, PrClass::Scream) % 2) as usize
+    } else {
+        deathsound
+    };
+    start_boss_sound(context, actor, sound);
+}
+
+/// A zombieman fires its pistol.
+pub fn a_pos_attack(context: &mut Context, actor: ThinkerId) {
+    if target(context, actor).is_none() {
+        return;
+    }
+    a_face_target(context, actor);
+    let angle = context.level.mobj(actor).unwrap().angle;
+    // killough 8/2/98
+    let slope = aim_line_attack(context, actor, angle, MISSILERANGE, MobjFlag::empty()).slope;
+    context.level.start_sound(Some(actor), Sfx::PISTOL as usize);
+    // killough 5/5/98: remove dependence on order of evaluation
+    let angle =
+        angle + Angle((p_sub_random(context.configuration, PrClass::PosAttack) << 20) as u32);
+    let damage = (p_random(context.configuration, PrClass::PosAttack) % 5 + 1) * 3;
+    line_attack(context, actor, angle, MISSILERANGE, slope, damage);
+}
+
+/// A shotgun guy fires three pellets.
+pub fn a_spos_attack(context: &mut Context, actor: ThinkerId) {
+    if target(context, actor).is_none() {
+        return;
+    }
+    context.level.start_sound(Some(actor), Sfx::SHOTGN as usize);
+    a_face_target(context, actor);
+    let bangle = context.level.mobj(actor).unwrap().angle;
+    // killough 8/2/98
+    let slope = aim_line_attack(context, actor, bangle, MISSILERANGE, MobjFlag::empty()).slope;
+    for _ in 0..3 {
+        // killough 5/5/98: remove dependence on order of evaluation
+        let angle =
+            bangle + Angle((p_sub_random(context.configuration, PrClass::SPosAttack) << 20) as u32);
+        let damage = (p_random(context.configuration, PrClass::SPosAttack) % 5 + 1) * 3;
+        line_attack(context, actor, angle, MISSILERANGE, slope, damage);
+    }
+}
+
+/// A chaingunner fires a bullet.
+pub fn a_cpos_attack(context: &mut Context, actor: ThinkerId) {
+    if target(context, actor).is_none() {
+        return;
+    }
+    context.level.start_sound(Some(actor), Sfx::SHOTGN as usize);
+    a_face_target(context, actor);
+    let bangle = context.level.mobj(actor).unwrap().angle;
+    // killough 8/2/98
+    let slope = aim_line_attack(context, actor, bangle, MISSILERANGE, MobjFlag::empty()).slope;
+    // killough 5/5/98: remove dependence on order of evaluation
+    let angle =
+        bangle + Angle((p_sub_random(context.configuration, PrClass::CPosAttack) << 20) as u32);
+    let damage = (p_random(context.configuration, PrClass::CPosAttack) % 5 + 1) * 3;
+    line_attack(context, actor, angle, MISSILERANGE, slope, damage);
+}
+
+/// A chaingunner keeps firing unless its target got out of sight.
+pub fn a_cpos_refire(context: &mut Context, actor: ThinkerId) {
+    a_face_target(context, actor);
+    // killough 12/98: stop firing if a friend has gotten in the way
+    if !hit_friend(context, actor) {
+        // killough 11/98: prevent refiring on friends continuously
+        if p_random(context.configuration, PrClass::CPosReFire) < 40 {
+            if !friendly_target(context, actor) {
+                return;
+            }
+        } else if !lost_target(context, actor) {
+            return;
+        }
+    }
+    stop_firing(context, actor);
+}
+
+/// A spider keeps firing unless its target got out of sight.
+pub fn a_spid_refire(context: &mut Context, actor: ThinkerId) {
+    a_face_target(context, actor);
+    // killough 12/98: stop firing if a friend has gotten in the way
+    if !hit_friend(context, actor) {
+        if p_random(context.configuration, PrClass::SpidReFire) < 10 {
+            return;
+        }
+        if !friendly_target(context, actor) && !lost_target(context, actor) {
+            return;
+        }
+    }
+    stop_firing(context, actor);
+}
+
+/// An arachnotron fires a plasma ball.
+pub fn a_bspi_attack(context: &mut Context, actor: ThinkerId) {
+    let target = match target(context, actor) {
+        Some(target) => target,
+        None => return,
+    };
+    a_face_target(context, actor);
+    // launch a missile
+    spawn_missile(context, actor, target, MobjType::ARACHPLAZ);
+}
+
+/// An imp claws at its target, or throws a fireball from further away.
+pub fn a_troop_attack(context: &mut Context, actor: ThinkerId) {
+    let target = match target(context, actor) {
+        Some(target) => target,
+        None => return,
+    };
+    a_face_target(context, actor);
+    if melee_attack(context, actor, target, Some(Sfx::CLAW), |context| {
+        (p_random(context.configuration, PrClass::TroopAttack) % 8 + 1) * 3
+    }) {
+        return;
+    }
+    // launch a missile
+    spawn_missile(context, actor, target, MobjType::TROOPSHOT);
+}
+
+/// A demon bites.
+pub fn a_sarg_attack(context: &mut Context, actor: ThinkerId) {
+    let target = match target(context, actor) {
+        Some(target) => target,
+        None => return,
+    };
+    a_face_target(context, actor);
+    if context.configuration.compatibility_level == CompatibilityLevel::DoomV12 {
+        // Doom 1.2 bit at whatever was in front instead
+        let damage = (p_random(context.configuration, PrClass::SargAttack) % 10 + 1) * 4;
+        let angle = context.level.mobj(actor).unwrap().angle;
+        line_attack(context, actor, angle, MELEERANGE, Fixed::ZERO, damage);
+    } else {
+        melee_attack(context, actor, target, None, |context| {
+            (p_random(context.configuration, PrClass::SargAttack) % 10 + 1) * 4
+        });
+    }
+}
+
+/// A cacodemon bites, or spits a fireball from further away.
+pub fn a_head_attack(context: &mut Context, actor: ThinkerId) {
+    let target = match target(context, actor) {
+        Some(target) => target,
+        None => return,
+    };
+    a_face_target(context, actor);
+    if melee_attack(context, actor, target, None, |context| {
+        (p_random(context.configuration, PrClass::HeadAttack) % 6 + 1) * 10
+    }) {
+        return;
+    }
+    // launch a missile
+    spawn_missile(context, actor, target, MobjType::HEADSHOT);
+}
+
+/// A cyberdemon fires a rocket.
+pub fn a_cyber_attack(context: &mut Context, actor: ThinkerId) {
+    let target = match target(context, actor) {
+        Some(target) => target,
+        None => return,
+    };
+    a_face_target(context, actor);
+    spawn_missile(context, actor, target, MobjType::ROCKET);
+}
+
+/// A baron or knight claws, or throws a fireball from further away.
+pub fn a_bruis_attack(context: &mut Context, actor: ThinkerId) {
+    let target = match target(context, actor) {
+        Some(target) => target,
+        None => return,
+    };
+    if melee_attack(context, actor, target, Some(Sfx::CLAW), |context| {
+        (p_random(context.configuration, PrClass::BruisAttack) % 8 + 1) * 10
+    }) {
+        return;
+    }
+    // launch a missile
+    spawn_missile(context, actor, target, MobjType::BRUISERSHOT);
+}
+
+/// A revenant fires a homing missile.
+pub fn a_skel_missile(context: &mut Context, actor: ThinkerId) {
+    let target = match target(context, actor) {
+        Some(target) => target,
+        None => return,
+    };
+    a_face_target(context, actor);
+    // so missile spawns higher
+    context.level.mobj_mut(actor).unwrap().z += Fixed::from_int(16);
+    let missile = spawn_missile(context, actor, target, MobjType::TRACER);
+    // back to normal
+    context.level.mobj_mut(actor).unwrap().z -= Fixed::from_int(16);
+
+    if let Some(mo) = missile.and_then(|m| context.level.mobj_mut(m)) {
+        mo.x += mo.momx;
+        mo.y += mo.momy;
+        mo.tracer = Some(target);
+    }
+}
+
+/// How far a homing missile turns each time it steers.
+const TRACEANGLE: Angle = Angle(0xc000000);
+
+/// A revenant's missile leaves smoke, and every fourth tic turns towards
+/// its target.
+pub fn a_tracer(context: &mut Context, actor: ThinkerId) {
+    let configuration = &*context.configuration;
+    if configuration.gametic.wrapping_sub(configuration.basetic) & 3 != 0 {
+        return;
+    }
+
+    // spawn a puff of smoke behind the rocket; vanilla's puff goes by the
+    // range of the last line attack, which only decides if it sparks
+    let (x, y, z, momx, momy) = match context.level.mobj(actor) {
+        Some(mo) => (mo.x, mo.y, mo.z, mo.momx, mo.momy),
+        None => return,
+    };
+    spawn_puff(context, x, y, z, MISSILERANGE);
+    let th = spawn_mobj(context, x - momx, y - momy, z, MobjType::SMOKE);
+    let random = p_random(context.configuration, PrClass::Tracer) as i64;
+    let smoke = context.level.mobj_mut(th).unwrap();
+    smoke.momz = Fixed::UNIT;
+    smoke.tics = (smoke.tics - (random & 3)).max(1);
+
+    // adjust direction
+    let dest = match context.level.mobj(actor).unwrap().tracer {
+        Some(dest) => dest,
+        None => return,
+    };
+    let (dx, dy, dz) = match context.level.mobj(dest) {
+        Some(dest) if dest.health > 0 => (dest.x, dest.y, dest.z),
+        _ => return,
+    };
+
+    // change angle
+    let mo = context.level.mobj_mut(actor).unwrap();
+    let speed = MOBJINFO.read()[&mo.mobj_type].speed;
+    let exact = point_to_angle2(mo.x, mo.y, dx, dy);
+    if exact != mo.angle {
+        if exact - mo.angle > ANG180 {
+            mo.angle -= TRACEANGLE;
+            if exact - mo.angle < ANG180 {
+                mo.angle = exact;
+            }
+        } else {
+            mo.angle += TRACEANGLE;
+            if exact - mo.angle > ANG180 {
+                mo.angle = exact;
+            }
+        }
+    }
+    mo.momx = fixed_mul(Fixed(speed), mo.angle.cos());
+    mo.momy = fixed_mul(Fixed(speed), mo.angle.sin());
+
+    // change slope
+    let dist = (approx_distance(dx - mo.x, dy - mo.y).0 / speed).max(1);
+    let slope = (dz + Fixed::from_int(40) - mo.z) / dist;
+    if slope < mo.momz {
+        mo.momz -= Fixed::UNIT / 8;
+    } else {
+        mo.momz += Fixed::UNIT / 8;
+    }
+}
+
+/// A revenant swings its fist.
+pub fn a_skel_whoosh(context: &mut Context, actor: ThinkerId) {
+    if target(context, actor).is_none() {
+        return;
+    }
+    a_face_target(context, actor);
+    context.level.start_sound(Some(actor), Sfx::SKESWG as usize);
+}
+
+/// A revenant punches its target, if it's close enough.
+pub fn a_skel_fist(context: &mut Context, actor: ThinkerId) {
+    let target = match target(context, actor) {
+        Some(target) => target,
+        None => return,
+    };
+    a_face_target(context, actor);
+    melee_attack(context, actor, target, Some(Sfx::SKEPCH), |context| {
+        (p_random(context.configuration, PrClass::SkelFist) % 10 + 1) * 6
+    });
+}
+
+/// Whether `thing` is a corpse an arch-vile about to step to
+/// `(tryx, tryy)` could raise, like vanilla's `PIT_VileCheck`.
+fn vile_check(context: &mut Context, thing: ThinkerId, tryx: Fixed, tryy: Fixed) -> bool {
+    let comp_vile = context.configuration.comp_vile;
+    let mo = context.level.mobj(thing).unwrap();
+    let (raisestate, radius, height) = {
+        let mobjinfo = MOBJINFO.read();
+        let info = &mobjinfo[&mo.mobj_type];
+        (info.raisestate, info.radius, info.height)
+    };
+    if !mo.flags.contains(MobjFlag::CORPSE)
+        // not lying still yet
+        || mo.tics != -1
+        // monster doesn't have a raise state
+        || raisestate == Statenum::NULL as usize
+    {
+        return false;
+    }
+    let maxdist = radius + MOBJINFO.read()[&MobjType::VILE].radius;
+    if (mo.x - tryx).abs() > maxdist || (mo.y - tryy).abs() > maxdist {
+        // not actually touching
+        return false;
+    }
+
+    // phares: a corpse crushed to a gib has no height or radius. Doom
+    // raised it as a ghost; otherwise it's raised at its proper size.
+    let mo = context.level.mobj_mut(thing).unwrap();
+    mo.momx = Fixed::ZERO;
+    mo.momy = Fixed::ZERO;
+    let (x, y) = (mo.x, mo.y);
+    if comp_vile {
+        mo.height = mo.height << 2;
+        let check = check_position(context, thing, x, y);
+        let mo = context.level.mobj_mut(thing).unwrap();
+        mo.height = mo.height >> 2;
+        check
+    } else {
+        let saved = (mo.height, mo.radius);
+        mo.height = height;
+        mo.radius = radius;
+        mo.flags |= MobjFlag::SOLID;
+        let check = check_position(context, thing, x, y);
+        let mo = context.level.mobj_mut(thing).unwrap();
+        (mo.height, mo.radius) = saved;
+        mo.flags.remove(MobjFlag::SOLID);
+        check
+    }
+}
+
+/// An arch-vile chases its target, raising any corpse it walks up to.
+pub fn a_vile_chase(context: &mut Context, actor: ThinkerId) {
+    let mbf = context.configuration.compatibility_level >= CompatibilityLevel::Mbf;
+    let comp_vile = context.configuration.comp_vile;
+    let mo = match context.level.mobj(actor) {
+        Some(mo) => mo,
+        None => return,
+    };
+    if mo.movedir != DirType::NoDir {
+        // check for corpses to raise
+        let speed = MOBJINFO.read()[&mo.mobj_type].speed;
+        let tryx = mo.x + XSPEED[mo.movedir as usize] * speed;
+        let tryy = mo.y + YSPEED[mo.movedir as usize] * speed;
+        let map = map(context.configuration);
+        let xl = block_x(map, tryx - MAXRADIUS * 2);
+        let xh = block_x(map, tryx + MAXRADIUS * 2);
+        let yl = block_y(map, tryy - MAXRADIUS * 2);
+        let yh = block_y(map, tryy + MAXRADIUS * 2);
+        let mut corpsehit = None;
+        'search: for bx in xl..=xh {
+            for by in yl..=yh {
+                if !block_things_iterator(context, bx, by, |context, thing| {
+                    if vile_check(context, thing, tryx, tryy) {
+                        corpsehit = Some(thing);
+                        return false;
+                    }
+                    true
+                }) {
+                    break 'search;
+                }
+            }
+        }
+
+        if let Some(corpsehit) = corpsehit {
+            // got one!
+            let temp = context.level.mobj(actor).unwrap().target;
+            context.level.mobj_mut(actor).unwrap().target = Some(corpsehit);
+            a_face_target(context, actor);
+            context.level.mobj_mut(actor).unwrap().target = temp;
+            set_mobj_state(context, actor, Statenum::VILE_HEAL1 as usize);
+            context
+                .level
+                .start_sound(Some(corpsehit), Sfx::SLOP as usize);
+
+            let mobj_type = context.level.mobj(corpsehit).unwrap().mobj_type;
+            let (raisestate, height, radius, flags, spawnhealth) = {
+                let mobjinfo = MOBJINFO.read();
+                let info = &mobjinfo[&mobj_type];
+                (
+                    info.raisestate,
+                    info.height,
+                    info.radius,
+                    MobjFlag::from_bits_truncate(info.flags),
+                    info.spawnhealth,
+                )
+            };
+            set_mobj_state(context, corpsehit, raisestate);
+            let friend = context.level.mobj(actor).unwrap().flags & MobjFlag::FRIEND;
+            let mo = context.level.mobj_mut(corpsehit).unwrap();
+            if comp_vile {
+                // phares
+                mo.height = mo.height << 2;
+            } else {
+                // fix Ghost bug
+                mo.height = height;
+                mo.radius = radius;
+            }
+            // killough 7/18/98: friendliness is transferred from the
+            // arch-vile to the raised corpse
+            mo.flags = (flags - MobjFlag::FRIEND) | friend;
+            mo.health = spawnhealth;
+            // killough 11/98
+            mo.target = None;
+            if mbf {
+                mo.lastenemy = None;
+                mo.flags.remove(MobjFlag::JUSTHIT);
+            }
+            // killough 8/29/98: add to appropriate thread
+            context.level.update_thinker_class(corpsehit);
+            return;
+        }
+    }
+
+    // return to normal attack
+    a_chase(context, actor);
+}
+
+/// An arch-vile starts its attack.
+pub fn a_vile_start(context: &mut Context, actor: ThinkerId) {
+    context.level.start_sound(Some(actor), Sfx::VILATK as usize);
+}
+
+/// The arch-vile's fire keeps in front of its victim, as long as the
+/// arch-vile can see it.
+pub fn a_fire(context: &mut Context, actor: ThinkerId) {
+    let (dest, target) = match context.level.mobj(actor) {
+        Some(mo) => (mo.tracer, mo.target),
+        None => return,
+    };
+    let (dest, target) = match (dest, target) {
+        (Some(dest), Some(target))
+            if context.level.mobj(dest).is_some() && context.level.mobj(target).is_some() =>
+        {
+            (dest, target)
+        }
+        _ => return,
+    };
+    // don't move it if the vile lost sight
+    if !check_sight(context, target, dest) {
+        return;
+    }
+
+    let dest = context.level.mobj(dest).unwrap();
+    let (x, y, z, an) = (dest.x, dest.y, dest.z, dest.angle);
+    unset_thing_position(context, actor);
+    let mo = context.level.mobj_mut(actor).unwrap();
+    mo.x = x + fixed_mul(Fixed::from_int(24), an.cos());
+    mo.y = y + fixed_mul(Fixed::from_int(24), an.sin());
+    mo.z = z;
+    set_thing_position(context, actor);
+}
+
+pub fn a_start_fire(context: &mut Context, actor: ThinkerId) {
+    context.level.start_sound(Some(actor), Sfx::FLAMST as usize);
+    a_fire(context, actor);
+}
+
+pub fn a_fire_crackle(context: &mut Context, actor: ThinkerId) {
+    context.level.start_sound(Some(actor), Sfx::FLAME as usize);
+    a_fire(context, actor);
+}
+
+/// An arch-vile lights its fire on its target.
+pub fn a_vile_target(context: &mut Context, actor: ThinkerId) {
+    let target = match target(context, actor) {
+        Some(target) => target,
+        None => return,
+    };
+    a_face_target(context, actor);
+
+    // killough 12/98: fix Vile fog coordinates
+    let t = context.level.mobj(target).unwrap();
+    let y = if context.configuration.compatibility_level < CompatibilityLevel::LxDoomV1 {
+        t.x
+    } else {
+        t.y
+    };
+    let fog = spawn_mobj(context, t.x, y, t.z, MobjType::FIRE);
+    context.level.mobj_mut(actor).unwrap().tracer = Some(fog);
+    let fire = context.level.mobj_mut(fog).unwrap();
+    fire.target = Some(actor);
+    fire.tracer = Some(target);
+    a_fire(context, fog);
+}
+
+/// An arch-vile blows up its fire, throwing its target in the air.
+pub fn a_vile_attack(context: &mut Context, actor: ThinkerId) {
+    let target = match target(context, actor) {
+        Some(target) => target,
+        None => return,
+    };
+    a_face_target(context, actor);
+    if !check_sight(context, actor, target) {
+        return;
+    }
+    context.level.start_sound(Some(actor), Sfx::BAREXP as usize);
+    damage_mobj(context, target, Some(actor), Some(actor), 20);
+    let (tx, ty) = match context.level.mobj_mut(target) {
+        Some(t) => {
+            t.momz = Fixed(1000 * FRACUNIT as i32 / MOBJINFO.read()[&t.mobj_type].mass);
+            (t.x, t.y)
+        }
+        None => return,
+    };
+
+    let mo = context.level.mobj(actor).unwrap();
+    let an = mo.angle;
+    let fire = match mo.tracer.filter(|&f| context.level.mobj(f).is_some()) {
+        Some(fire) => fire,
+        None => return,
+    };
+    // move the fire between the vile and the player
+    let fog = context.level.mobj_mut(fire).unwrap();
+    fog.x = tx - fixed_mul(Fixed::from_int(24), an.cos());
+    fog.y = ty - fixed_mul(Fixed::from_int(24), an.sin());
+    radius_attack(context, fire, Some(actor), 70);
+}
+
+/// How far apart a mancubus spreads its fireballs.
+const FATSPREAD: Angle = Angle(ANG90.0 / 8);
+
+/// A mancubus gets ready to fire.
+pub fn a_fat_raise(context: &mut Context, actor: ThinkerId) {
+    a_face_target(context, actor);
+    context.level.start_sound(Some(actor), Sfx::MANATK as usize);
+}
+
+/// Turn a missile by `delta`, keeping its speed.
+fn turn_missile(context: &mut Context, missile: Option<ThinkerId>, delta: Angle) {
+    if let Some(mo) = missile.and_then(|m| context.level.mobj_mut(m)) {
+        let speed = Fixed(MOBJINFO.read()[&mo.mobj_type].speed);
+        mo.angle += delta;
+        mo.momx = fixed_mul(speed, mo.angle.cos());
+        mo.momy = fixed_mul(speed, mo.angle.sin());
+    }
+}
+
+/// A mancubus fires a pair of fireballs, one aimed and one to the left.
+pub fn a_fat_attack1(context: &mut Context, actor: ThinkerId) {
+    let target = match target(context, actor) {
+        Some(target) => target,
+        None => return,
+    };
+    a_face_target(context, actor);
+    // change direction to ...
+    context.level.mobj_mut(actor).unwrap().angle += FATSPREAD;
+    spawn_missile(context, actor, target, MobjType::FATSHOT);
+    let mo = spawn_missile(context, actor, target, MobjType::FATSHOT);
+    turn_missile(context, mo, FATSPREAD);
+}
+
+/// A mancubus fires a pair of fireballs, one aimed and one to the right.
+pub fn a_fat_attack2(context: &mut Context, actor: ThinkerId) {
+    let target = match target(context, actor) {
+        Some(target) => target,
+        None => return,
+    };
+    a_face_target(context, actor);
+    // now here choose opposite deviation
+    context.level.mobj_mut(actor).unwrap().angle -= FATSPREAD;
+    spawn_missile(context, actor, target, MobjType::FATSHOT);
+    let mo = spawn_missile(context, actor, target, MobjType::FATSHOT);
+    turn_missile(context, mo, -(FATSPREAD * 2));
+}
+
+/// A mancubus fires a pair of fireballs either side of its target.
+pub fn a_fat_attack3(context: &mut Context, actor: ThinkerId) {
+    let target = match target(context, actor) {
+        Some(target) => target,
+        None => return,
+    };
+    a_face_target(context, actor);
+    let mo = spawn_missile(context, actor, target, MobjType::FATSHOT);
+    turn_missile(context, mo, -(FATSPREAD / 2));
+    let mo = spawn_missile(context, actor, target, MobjType::FATSHOT);
+    turn_missile(context, mo, FATSPREAD / 2);
+}
+
+/// How fast a lost soul flies at its target.
+const SKULLSPEED: Fixed = Fixed::from_int(20);
+
+/// A lost soul flies at its target.
+pub fn a_skull_attack(context: &mut Context, actor: ThinkerId) {
+    let target = match target(context, actor) {
+        Some(target) => target,
+        None => return,
+    };
+    let mo = context.level.mobj_mut(actor).unwrap();
+    mo.flags |= MobjFlag::SKULLFLY;
+    let attacksound = MOBJINFO.read()[&mo.mobj_type].attacksound;
+    context.level.start_sound(Some(actor), attacksound);
+    a_face_target(context, actor);
+
+    let dest = context.level.mobj(target).unwrap();
+    let (dx, dy, dz) = (dest.x, dest.y, dest.z + (dest.height >> 1));
+    let mo = context.level.mobj_mut(actor).unwrap();
+    mo.momx = fixed_mul(SKULLSPEED, mo.angle.cos());
+    mo.momy = fixed_mul(SKULLSPEED, mo.angle.sin());
+    let dist = (approx_distance(dx - mo.x, dy - mo.y).0 / SKULLSPEED.0).max(1);
+    mo.momz = (dz - mo.z) / dist;
+}
+
+/// killough 10/98: the beta lost soul bites instead of flying at its
+/// target.
+pub fn a_beta_skull_attack(context: &mut Context, actor: ThinkerId) {
+    let target = match target(context, actor) {
+        Some(target) if context.level.mobj(target).unwrap().mobj_type != MobjType::SKULL => target,
+        _ => return,
+    };
+    let (attacksound, damage) = {
+        let mobjinfo = MOBJINFO.read();
+        let info = &mobjinfo[&context.level.mobj(actor).unwrap().mobj_type];
+        (info.attacksound, info.damage)
+    };
+    context.level.start_sound(Some(actor), attacksound);
+    a_face_target(context, actor);
+    let damage = (p_random(context.configuration, PrClass::SkullFly) % 8 + 1) * damage;
+    damage_mobj(context, target, Some(actor), Some(actor), damage);
+}
+
+/// Stop dead.
+pub fn a_stop(context: &mut Context, actor: ThinkerId) {
+    if let Some(mo) = context.level.mobj_mut(actor) {
+        mo.momx = Fixed::ZERO;
+        mo.momy = Fixed::ZERO;
+        mo.momz = Fixed::ZERO;
+    }
+}
+
+/// A cyberdemon's or spider's footstep, and a step of the chase.
+pub fn a_metal(context: &mut Context, mo: ThinkerId) {
+    context.level.start_sound(Some(mo), Sfx::METAL as usize);
+    a_chase(context, mo);
+}
+
+/// An arachnotron's footstep, and a step of the chase.
+pub fn a_baby_metal(context: &mut Context, mo: ThinkerId) {
+    context.level.start_sound(Some(mo), Sfx::BSPWLK as usize);
+    a_chase(context, mo);
+}
+
+/// A cyberdemon's hoof, and a step of the chase.
+pub fn a_hoof(context: &mut Context, mo: ThinkerId) {
+    context.level.start_sound(Some(mo), Sfx::HOOF as usize);
+    a_chase(context, mo);
+}
+
+/// killough 7/20/98: whether a wall or a line monsters can't cross is
+/// between `actor` and `(x, y)`, so a pain elemental can't spit lost souls
+/// through it, like MBF's `Check_Sides`.
+fn check_sides(context: &mut Context, actor: ThinkerId, x: Fixed, y: Fixed) -> bool {
+    let (pe_x, pe_y) = match context.level.mobj(actor) {
+        Some(mo) => (mo.x, mo.y),
+        None => return false,
+    };
+    // here is the bounding box of the trajectory
+    let mut bbox = [Fixed::ZERO; 4];
+    bbox[BOXLEFT] = pe_x.min(x);
+    bbox[BOXRIGHT] = pe_x.max(x);
+    bbox[BOXTOP] = pe_y.max(y);
+    bbox[BOXBOTTOM] = pe_y.min(y);
+
+    // determine which blocks to look in for blocking lines
+    let map = map(context.configuration);
+    let xl = block_x(map, bbox[BOXLEFT]);
+    let xh = block_x(map, bbox[BOXRIGHT]);
+    let yl = block_y(map, bbox[BOXBOTTOM]);
+    let yh = block_y(map, bbox[BOXTOP]);
+    context.level.validcount += 1;
+    for bx in xl..=xh {
+        for by in yl..=yh {
+            if !block_lines_iterator(context, bx, by, |context, line| {
+                let map = super::map(context.configuration);
+                let ld = &map.lines[line];
+                let lbox = line_bbox(map, line);
+                let blocking =
+                    LineFlags::BLOCKING | LineFlags::TWO_SIDED | LineFlags::BLOCK_MONSTERS;
+                ((ld.flags ^ LineFlags::TWO_SIDED) & blocking).is_empty()
+                    || bbox[BOXLEFT] > lbox[BOXRIGHT]
+                    || bbox[BOXRIGHT] < lbox[BOXLEFT]
+                    || bbox[BOXTOP] < lbox[BOXBOTTOM]
+                    || bbox[BOXBOTTOM] > lbox[BOXTOP]
+                    || point_on_line_side(map, pe_x, pe_y, line)
+                        == point_on_line_side(map, x, y, line)
+            }) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// A pain elemental spits a lost soul out at `angle`, like vanilla's
+/// `A_PainShootSkull`.
+fn pain_shoot_skull(context: &mut Context, actor: ThinkerId, angle: Angle) {
+    let configuration = &*context.configuration;
+    let (comp_pain, comp_skull) = (configuration.comp_pain, configuration.comp_skull);
+    // killough 10/98: compatibility-optioned
+    if comp_pain {
+        // count total number of skulls currently on the level
+        let count = context
+            .level
+            .thinkers
+            .iter()
+            .filter(|(_, Thinker::Mobj(mo))| mo.mobj_type == MobjType::SKULL)
+            .count();
+        // if there are already 20 skulls on the level, don't spit another
+        // one
+        if count > 20 {
+            return;
+        }
+    }
+
+    // okay, there's room for another one
+    let mo = match context.level.mobj(actor) {
+        Some(mo) => mo,
+        None => return,
+    };
+    let (friend, target) = (mo.flags & MobjFlag::FRIEND, mo.target);
+    let prestep = {
+        let mobjinfo = MOBJINFO.read();
+        Fixed::from_int(4)
+            + (mobjinfo[&mo.mobj_type].radius + mobjinfo[&MobjType::SKULL].radius) * 3 / 2
+    };
+    let x = mo.x + fixed_mul(prestep, angle.cos());
+    let y = mo.y + fixed_mul(prestep, angle.sin());
+    let z = mo.z + Fixed::from_int(8);
+
+    let newmobj = if comp_skull {
+        spawn_mobj(context, x, y, z, MobjType::SKULL)
+    } else {
+        // check whether the lost soul is being fired through a 1-sided
+        // wall or an impassible line, or a "monsters can't cross" line
+        if check_sides(context, actor, x, y) {
+            return;
+        }
+        let newmobj = spawn_mobj(context, x, y, z, MobjType::SKULL);
+
+        // check to see if the new lost soul's z value is above the ceiling
+        // of its new sector, or below the floor; if so, kill it
+        let map = map(context.configuration);
+        let mo = context.level.mobj(newmobj).unwrap();
+        let sector = &map.sectors[map.subsectors[mo.subsector].sector];
+        if mo.z > sector.ceiling_height - mo.height || mo.z < sector.floor_height {
+            // kill it immediately
+            damage_mobj(context, newmobj, Some(actor), Some(actor), 10000);
+            return;
+        }
+        newmobj
+    };
+
+    // killough 7/20/98: PEs shoot lost souls with the same friendliness
+    let mo = context.level.mobj_mut(newmobj).unwrap();
+    mo.flags = (mo.flags - MobjFlag::FRIEND) | friend;
+    let (nx, ny) = (mo.x, mo.y);
+    // killough 8/29/98: add to appropriate thread
+    context.level.update_thinker_class(newmobj);
+
+    // check for movements; killough 3/15/98: don't jump over dropoffs
+    if !try_move(context, newmobj, nx, ny, DropOff::No) {
+        // kill it immediately
+        damage_mobj(context, newmobj, Some(actor), Some(actor), 10000);
+        return;
+    }
+    if let Some(mo) = context.level.mobj_mut(newmobj) {
+        mo.target = target;
+    }
+    a_skull_attack(context, newmobj);
+}
+
+/// A pain elemental spits a lost soul at its target.
+pub fn a_pain_attack(context: &mut Context, actor: ThinkerId) {
+    if target(context, actor).is_none() {
+        return;
+    }
+    a_face_target(context, actor);
+    let angle = context.level.mobj(actor).unwrap().angle;
+    pain_shoot_skull(context, actor, angle);
+}
+
+/// A pain elemental dies, spitting out three lost souls.
+pub fn a_pain_die(context: &mut Context, actor: ThinkerId) {
+    a_fall(context, actor);
+    let angle = match context.level.mobj(actor) {
+        Some(mo) => mo.angle,
+        None => return,
+    };
+    pain_shoot_skull(context, actor, angle + ANG90);
+    pain_shoot_skull(context, actor, angle + ANG180);
+    pain_shoot_skull(context, actor, angle + ANG270);
+}
+
+/// Whether any mobj of `mobj_type` but `mo` is still alive.
+fn others_alive(level: &Level, mo: ThinkerId, mobj_type: MobjType) -> bool {
+    level
+        .thinkers
+        .iter()
+        .any(|(id, Thinker::Mobj(mo2))| id != mo && mo2.mobj_type == mobj_type && mo2.health > 0)
+}
+
+/// A commander keen dies, and once they all have, the doors tagged 666
+/// open.
+pub fn a_keen_die(context: &mut Context, mo: ThinkerId) {
+    a_fall(context, mo);
+    let mobj_type = match context.level.mobj(mo) {
+        Some(mo) => mo.mobj_type,
+        None => return,
+    };
+    // scan the remaining thinkers to see if all Keens are dead
+    if !others_alive(context.level, mo, mobj_type) {
+        // TODO: open the doors tagged 666, like vanilla's EV_DoDoor with
+        // `open`, once there are sector movers
+    }
+}
+
+/// A boss dies, and if it was the last of the bosses that end the level,
+/// the level's finale happens: a floor lowers or rises or a door opens,
+/// or the level ends.
+pub fn a_boss_death(context: &mut Context, mo: ThinkerId) {
+    use MobjType::*;
+    let configuration = &*context.configuration;
+    let (episode, map) = (configuration.game_episode, configuration.game_map);
+    let mobj_type = match context.level.mobj(mo) {
+        Some(mo) => mo.mobj_type,
+        None => return,
+    };
+
+    if configuration.game_mode == GameMode::Commercial {
+        if map != 7 || !matches!(mobj_type, FATSO | BABY) {
+            return;
+        }
+    } else if configuration.compatibility_level < CompatibilityLevel::UltimateDoom {
+        // e6y: Doom 1.9's rules, where any boss on an episode's last map
+        // counts but the barons only count in episode 1
+        if map != 8 || (mobj_type == BRUISER && episode != 1) {
+            return;
+        }
+    } else {
+        let boss = match (episode, map) {
+            (1, 8) => Some(BRUISER),
+            (2, 8) | (4, 6) => Some(CYBORG),
+            (3, 8) | (4, 8) => Some(SPIDER),
+            (4, _) => return,
+            (_, 8) => None,
+            _ => return,
+        };
+        if boss.is_some_and(|boss| boss != mobj_type) {
+            return;
+        }
+    }
+
+    // make sure there is a player alive for victory
+    if !(0..MAXPLAYERS)
+        .any(|i| player_in_game(configuration, i) && context.level.players[i].health > 0)
+    {
+        // no one left alive, so do not end game
+        return;
+    }
+
+    // scan the remaining thinkers to see if all bosses are dead
+    if others_alive(context.level, mo, mobj_type) {
+        // other boss not dead
+        return;
+    }
+
+    // victory!
+    // TODO: move the tagged sectors once there are sector movers: floors
+    // tagged 666 lower to the lowest neighbour (EV_DoFloor with
+    // `lowerFloorToLowest`), floors tagged 667 rise by their shortest lower
+    // texture (`raiseToTexture`), and doors tagged 666 open fast
+    // (EV_DoDoor with `blazeOpen`).
+    let moves_sectors = if configuration.game_mode == GameMode::Commercial {
+        matches!(mobj_type, FATSO | BABY)
+    } else {
+        matches!((episode, map), (1, _) | (4, 8) | (4, 6))
+    };
+    if !moves_sectors {
+        exit_level(context);
+    }
+}
+
+/// The boss brain wakes up.
+///
+/// killough 3/26/98: only generates sound now; the spawn spots are found
+/// when the level starts.
+pub fn a_brain_awake(context: &mut Context, _: ThinkerId) {
+    context.level.start_sound(None, Sfx::BOSSIT as usize);
+}
+
+pub fn a_brain_pain(context: &mut Context, _: ThinkerId) {
+    context.level.start_sound(None, Sfx::BOSPN as usize);
+}
+
+/// Spawn one of the rockets the boss brain blows up in when it dies.
+fn brain_explosion(context: &mut Context, x: Fixed, y: Fixed, class: PrClass) {
+    let z = Fixed(128 + p_random(context.configuration, class) * 2 * FRACUNIT as i32);
+    let th = spawn_mobj(context, x, y, z, MobjType::ROCKET);
+    let momz = Fixed(p_random(context.configuration, class) * 512);
+    context.level.mobj_mut(th).unwrap().momz = momz;
+    set_mobj_state(context, th, Statenum::BRAINEXPLODE1 as usize);
+    let random = p_random(context.configuration, class) as i64;
+    if let Some(mo) = context.level.mobj_mut(th) {
+        mo.tics = (mo.tics - (random & 7)).max(1);
+    }
+}
+
+/// The boss brain dies in a row of explosions.
+pub fn a_brain_scream(context: &mut Context, mo: ThinkerId) {
+    let (mx, my) = match context.level.mobj(mo) {
+        Some(mo) => (mo.x, mo.y),
+        None => return,
+    };
+    let mut x = mx - Fixed::from_int(196);
+    while x < mx + Fixed::from_int(320) {
+        brain_explosion(context, x, my - Fixed::from_int(320), PrClass::BrainScream);
+        x += Fixed::from_int(8);
+    }
+    context.level.start_sound(None, Sfx::BOSDTH as usize);
+}
+
+/// One more explosion of the dying boss brain.
+pub fn a_brain_explode(context: &mut Context, mo: ThinkerId) {
+    let (mx, my) = match context.level.mobj(mo) {
+        Some(mo) => (mo.x, mo.y),
+        None => return,
+    };
+    // killough 5/5/98: remove dependence on order of evaluation
+    let x = mx + Fixed(p_sub_random(context.configuration, PrClass::BrainExp) * 2048);
+    brain_explosion(context, x, my, PrClass::BrainExp);
+}
+
+/// The boss brain's death ends the level.
+pub fn a_brain_die(context: &mut Context, _: ThinkerId) {
+    exit_level(context);
+}
+
+/// The boss spitter shoots a cube at the next spawn spot, only every other
+/// time on the easy skills.
+pub fn a_brain_spit(context: &mut Context, mo: ThinkerId) {
+    let skill = context.configuration.game_skill;
+    let brain = &mut context.level.brain;
+    // killough 4/1/98: ignore if no targets
+    if brain.targets.is_empty() {
+        return;
+    }
+    brain.easy = !brain.easy;
+    if matches!(
+        skill,
+        SkillLevel::None | SkillLevel::Itytd | SkillLevel::Hntr
+    ) && !brain.easy
+    {
+        return;
+    }
+
+    // shoot a cube at current target
+    let targ = brain.targets[brain.targeton];
+    // killough 3/26/98
+    brain.targeton = (brain.targeton + 1) % brain.targets.len();
+
+    // spawn brain missile
+    let newmobj = match spawn_missile(context, mo, targ, MobjType::SPAWNSHOT) {
+        Some(newmobj) => newmobj,
+        None => return,
+    };
+    let ty = context.level.mobj(targ).unwrap().y;
+    let (my, friend) = {
+        let mo = context.level.mobj(mo).unwrap();
+        (mo.y, mo.flags & MobjFlag::FRIEND)
+    };
+    let cube = context.level.mobj_mut(newmobj).unwrap();
+    cube.target = Some(targ);
+    // vanilla divides by zero for a spot straight across from the spitter
+    let tics = STATES.read()[cube.state].tics as i32;
+    cube.reactiontime = (ty - my)
+        .0
+        .checked_div(cube.momy.0)
+        .and_then(|t| t.checked_div(tics))
+        .unwrap_or(0);
+    // killough 7/18/98: brain friendliness is transferred
+    cube.flags = (cube.flags - MobjFlag::FRIEND) | friend;
+    // killough 8/29/98: add to appropriate thread
+    context.level.update_thinker_class(newmobj);
+    context.level.start_sound(None, Sfx::BOSPIT as usize);
+}
+
+/// A cube flies towards its spawn spot.
+pub fn a_spawn_sound(context: &mut Context, mo: ThinkerId) {
+    context.level.start_sound(Some(mo), Sfx::BOSCUB as usize);
+    a_spawn_fly(context, mo);
+}
+
+/// Once a cube reaches its spawn spot, it turns into a random monster,
+/// telefragging whatever is there.
+pub fn a_spawn_fly(context: &mut Context, mo: ThinkerId) {
+    let cube = match context.level.mobj_mut(mo) {
+        Some(cube) => cube,
+        None => return,
+    };
+    cube.reactiontime -= 1;
+    if cube.reactiontime != 0 {
+        // still flying
+        return;
+    }
+    let friend = cube.flags & MobjFlag::FRIEND;
+    let (x, y, z) = match target(context, mo).and_then(|t| context.level.mobj(t)) {
+        Some(targ) => (targ.x, targ.y, targ.z),
+        None => return,
+    };
+
+    // first spawn teleport fog
+    let fog = spawn_mobj(context, x, y, z, MobjType::SPAWNFIRE);
+    context.level.start_sound(Some(fog), Sfx::TELEPT as usize);
+
+    // randomly select monster to spawn, with decreasing likelihood
+    let mobj_type = match p_random(context.configuration, PrClass::SpawnFly) {
+        0..=49 => MobjType::TROOP,
+        50..=89 => MobjType::SERGEANT,
+        90..=119 => MobjType::SHADOWS,
+        120..=129 => MobjType::PAIN,
+        130..=159 => MobjType::HEAD,
+        160..=161 => MobjType::VILE,
+        162..=171 => MobjType::UNDEAD,
+        172..=191 => MobjType::BABY,
+        192..=221 => MobjType::FATSO,
+        222..=245 => MobjType::KNIGHT,
+        _ => MobjType::BRUISER,
+    };
+    let newmobj = spawn_mobj(context, x, y, z, mobj_type);
+    // killough 7/18/98: brain friendliness is transferred
+    let new = context.level.mobj_mut(newmobj).unwrap();
+    new.flags = (new.flags - MobjFlag::FRIEND) | friend;
+    // killough 8/29/98: add to appropriate thread
+    context.level.update_thinker_class(newmobj);
+
+    // killough 9/4/98
+    if look_for_targets(context, newmobj, true) {
+        let seestate = MOBJINFO.read()[&mobj_type].seestate;
+        set_mobj_state(context, newmobj, seestate);
+    }
+
+    // telefrag anything in this spot; killough 8/9/98
+    if let Some(new) = context.level.mobj(newmobj) {
+        let (nx, ny) = (new.x, new.y);
+        teleport_move(context, newmobj, nx, ny, true);
+    }
+
+    // remove self (i.e., cube)
+    remove_mobj(context, mo);
+}
+
+/// Die, as a DeHackEd action.
+pub fn a_die(context: &mut Context, actor: ThinkerId) {
+    if let Some(health) = context.level.mobj(actor).map(|mo| mo.health) {
+        damage_mobj(context, actor, None, None, health);
+    }
+}
+
+/// killough 8/9/98: explode with the damage in the mobj's info, for
+/// grenades and the like.
+pub fn a_detonate(context: &mut Context, mo: ThinkerId) {
+    let damage = match context.level.mobj(mo) {
+        Some(mo) => MOBJINFO.read()[&mo.mobj_type].damage,
+        None => return,
+    };
+    let source = target(context, mo);
+    radius_attack(context, mo, source, damage);
+}
+
+/// killough 9/98: explode, then throw fireballs in all directions, as many
+/// as the mobj's info damage says. The state's `misc1` says how high to
+/// aim and `misc2` how fast they go.
+pub fn a_mushroom(context: &mut Context, actor: ThinkerId) {
+    let (n, state, x, y, z, shadow) = match context.level.mobj(actor) {
+        Some(mo) => (
+            MOBJINFO.read()[&mo.mobj_type].damage,
+            mo.state,
+            mo.x,
+            mo.y,
+            mo.z,
+            mo.flags.contains(MobjFlag::SHADOW),
+        ),
+        None => return,
+    };
+    // mushroom parameters are part of code pointer's state
+    let st = STATES.read()[state];
+    let misc1 = if st.misc1 != 0 {
+        Fixed(st.misc1 as i32)
+    } else {
+        Fixed::from_int(4)
+    };
+    let misc2 = if st.misc2 != 0 {
+        Fixed(st.misc2 as i32)
+    } else {
+        Fixed::UNIT / 2
+    };
+
+    // first make normal explosion
+    a_explode(context, actor);
+
+    // now launch mushroom cloud; killough 10/98: fix for ports
+    for i in (-n..=n).step_by(8) {
+        for j in (-n..=n).step_by(8) {
+            // aim in many directions from source, fairly high
+            let tx = x + Fixed::from_int(i);
+            let ty = y + Fixed::from_int(j);
+            let tz = z + misc1 * approx_distance(Fixed(i), Fixed(j)).0;
+            let mo = spawn_missile_at(context, actor, tx, ty, tz, shadow, MobjType::FATSHOT);
+            if let Some(mo) = mo.and_then(|mo| context.level.mobj_mut(mo)) {
+                // slow down a bit
+                mo.momx = fixed_mul(mo.momx, misc2);
+                mo.momy = fixed_mul(mo.momy, misc2);
+                mo.momz = fixed_mul(mo.momz, misc2);
+                // make debris fall under gravity
+                mo.flags.remove(MobjFlag::NOGRAVITY);
+            }
+        }
+    }
+}
@@ -0,0 +1,882 @@
+use super::{
+    enemy::BASETHRESHOLD,
+    mobj::{remove_mobj, set_mobj_state, spawn_mobj, MobjFlag, MobjIntFlag, ONFLOORZ},
+    movement::MAXGEAR,
+    player::{PlayerState, MAXPLAYERS},
+    Context,
+};
+use crate::{
+    configuration::{CompatibilityLevel, MonsterInfightingLevel, SkillLevel},
+    doom::{
+        def::{
+            AmmoType, CardType, GameMode, PowerType, WeaponType, INFRATICS, INVISTICS, INVULNTICS,
+            IRONTICS, NUMAMMO, NUMPOWERS,
+        },
+        english::*,
+        items::WEAPONINFO,
+    },
+    info::{MobjType, Spritenum, Statenum, MOBJINFO},
+    misc::{
+        fixed::{fixed_mul, Fixed, FRACUNIT},
+        random::{p_random, PrClass},
+    },
+    sounds::Sfx,
+    tables::{point_to_angle2, ANG180},
+    think::ThinkerId,
+};
+
+/// The most of each kind of ammo a player can carry without a backpack.
+pub const MAXAMMO: [i32; NUMAMMO] = [200, 50, 300, 50];
+/// The ammo in a clip of each kind.
+const CLIPAMMO: [i32; NUMAMMO] = [10, 4, 20, 1];
+
+/// The most health from stimpacks and medikits.
+pub const MAXHEALTH: i32 = 100;
+/// The most armor from armor bonuses.
+const MAX_ARMOR: i32 = 200;
+/// The armor classes of green and blue armor.
+const GREEN_ARMOR_CLASS: i32 = 1;
+const BLUE_ARMOR_CLASS: i32 = 2;
+/// What a soulsphere gives, and the most health it gives it up to.
+const SOUL_HEALTH: i32 = 100;
+const MAX_SOUL: i32 = 200;
+/// The health a megasphere sets.
+const MEGA_HEALTH: i32 = 200;
+
+/// How much gold the screen flashes with for a pickup.
+const BONUSADD: i32 = 6;
+
+/// Give a player `num` clips of ammo, or half a clip if `num` is 0, like
+/// vanilla's `P_GiveAmmo`. Returns false if the player couldn't carry
+/// any more. Someone out of ammo switches to a weapon that uses it.
+fn give_ammo(context: &mut Context, n: usize, ammo: AmmoType, num: i32) -> bool {
+    if ammo == AmmoType::NoAmmo {
+        return false;
+    }
+    let skill = context.configuration.game_skill;
+    let player = &mut context.level.players[n];
+    let a = ammo as usize;
+    if player.ammo[a] == player.maxammo[a] {
+        return false;
+    }
+
+    let mut num = if num != 0 {
+        num * CLIPAMMO[a]
+    } else {
+        CLIPAMMO[a] / 2
+    };
+    // give double ammo in trainer mode, you'll need in nightmare
+    if skill == SkillLevel::Itytd || skill == SkillLevel::Nm {
+        num <<= 1;
+    }
+
+    let oldammo = player.ammo[a];
+    player.ammo[a] = (player.ammo[a] + num).min(player.maxammo[a]);
+
+    // If non zero ammo, don't change up weapons, player was lower on
+    // purpose.
+    if oldammo != 0 {
+        return true;
+    }
+
+    // We were down to zero, so select a new weapon. Preferences are not
+    // user selectable.
+    let ready = player.readyweapon;
+    let owned = |weapon: WeaponType| player.weaponowned[weapon as usize];
+    let pending = match ammo {
+        AmmoType::Clip if ready == WeaponType::Fist => {
+            if owned(WeaponType::Chaingun) {
+                Some(WeaponType::Chaingun)
+            } else {
+                Some(WeaponType::Pistol)
+            }
+        }
+        AmmoType::Shell
+            if (ready == WeaponType::Fist || ready == WeaponType::Pistol)
+                && owned(WeaponType::Shotgun) =>
+        {
+            Some(WeaponType::Shotgun)
+        }
+        AmmoType::Cell
+            if (ready == WeaponType::Fist || ready == WeaponType::Pistol)
+                && owned(WeaponType::Plasma) =>
+        {
+            Some(WeaponType::Plasma)
+        }
+        AmmoType::Misl if ready == WeaponType::Fist && owned(WeaponType::Missile) => {
+            Some(WeaponType::Missile)
+        }
+        _ => None,
+    };
+    if pending.is_some() {
+        player.pendingweapon = pending;
+    }
+    true
+}
+
+/// Whether a pickup sound for player `n` should be heard.
+///
+/// cph 2002/08/10: for old-school DM addicts, allow old behavior where
+/// only the console player's pickup sounds are heard.
+fn pickup_sound_heard(context: &Context, n: usize) -> bool {
+    !context.configuration.comp_sound || n == context.configuration.console_player
+}
+
+/// Give a player a weapon and the ammo that comes with it, like vanilla's
+/// `P_GiveWeapon`. A dropped weapon has half the ammo of a placed one.
+/// Returns false if the player got nothing from it.
+fn give_weapon(context: &mut Context, n: usize, weapon: WeaponType, dropped: bool) -> bool {
+    let netgame = context.configuration.netgame;
+    let deathmatch = context.configuration.deathmatch;
+    let ammo = WEAPONINFO[weapon as usize].ammo;
+
+    if netgame && deathmatch != 2 && !dropped {
+        // leave placed weapons forever on net games
+        let player = &mut context.level.players[n];
+        if player.weaponowned[weapon as usize] {
+            return false;
+        }
+        player.bonuscount += BONUSADD;
+        player.weaponowned[weapon as usize] = true;
+        give_ammo(context, n, ammo, if deathmatch != 0 { 5 } else { 2 });
+        context.level.players[n].pendingweapon = Some(weapon);
+        if pickup_sound_heard(context, n) {
+            let mo = context.level.players[n].mo;
+            context.level.start_sound(mo, Sfx::WPNUP as usize);
+        }
+        return false;
+    }
+
+    // give one clip with a dropped weapon, two clips with a found weapon
+    let gaveammo =
+        ammo != AmmoType::NoAmmo && give_ammo(context, n, ammo, if dropped { 1 } else { 2 });
+
+    let player = &mut context.level.players[n];
+    let gaveweapon = !player.weaponowned[weapon as usize];
+    if gaveweapon {
+        player.weaponowned[weapon as usize] = true;
+        player.pendingweapon = Some(weapon);
+    }
+    gaveweapon || gaveammo
+}
+
+/// Give a player `num` health, up to `MAXHEALTH`, like vanilla's
+/// `P_GiveBody`. Returns false if the player was already that healthy.
+pub fn give_body(context: &mut Context, n: usize, num: i32) -> bool {
+    let player = &mut context.level.players[n];
+    if player.health >= MAXHEALTH {
+        return false;
+    }
+    player.health = (player.health + num).min(MAXHEALTH);
+    let (health, mo) = (player.health, player.mo);
+    if let Some(mo) = mo.and_then(|mo| context.level.mobj_mut(mo)) {
+        mo.health = health;
+    }
+    true
+}
+
+/// Give a player armor of class `armortype`, worth 100 points a class,
+/// like vanilla's `P_GiveArmor`. Returns false if the player already had
+/// as much.
+fn give_armor(context: &mut Context, n: usize, armortype: i32) -> bool {
+    let player = &mut context.level.players[n];
+    let hits = armortype * 100;
+    if player.armorpoints >= hits {
+        // don't pick up
+        return false;
+    }
+    player.armortype = armortype;
+    player.armorpoints = hits;
+    true
+}
+
+/// Give a player a key, like vanilla's `P_GiveCard`.
+fn give_card(context: &mut Context, n: usize, card: CardType) {
+    let player = &mut context.level.players[n];
+    if player.cards[card as usize] {
+        return;
+    }
+    player.bonuscount = BONUSADD;
+    player.cards[card as usize] = true;
+}
+
+/// Give a player a power up, like vanilla's `P_GivePower`. Returns false
+/// if the player already had a computer map.
+pub fn give_power(context: &mut Context, n: usize, power: PowerType) -> bool {
+    const TICS: [i32; NUMPOWERS] = [INVULNTICS, 1, INVISTICS, IRONTICS, 1, INFRATICS];
+    match power {
+        PowerType::Invisibility => {
+            let mo = context.level.players[n].mo;
+            if let Some(mo) = mo.and_then(|mo| context.level.mobj_mut(mo)) {
+                mo.flags |= MobjFlag::SHADOW;
+            }
+        }
+        PowerType::AllMap if context.level.players[n].powers[power as usize] != 0 => {
+            return false;
+        }
+        PowerType::Strength => {
+            give_body(context, n, 100);
+        }
+        _ => {}
+    }
+
+    // Unless player has infinite duration cheat, set duration (killough)
+    let player = &mut context.level.players[n];
+    if player.powers[power as usize] >= 0 {
+        player.powers[power as usize] = TICS[power as usize];
+    }
+    true
+}
+
+/// Let `toucher` pick up `special`, if it can use it, like vanilla's
+/// `P_TouchSpecialThing`. Items are told apart by their sprite. What's
+/// picked up is removed, except for keys in netgames.
+pub fn touch_special_thing(context: &mut Context, special: ThinkerId, toucher: ThinkerId) {
+    let configuration = &*context.configuration;
+    let cl = configuration.compatibility_level;
+    let netgame = configuration.netgame;
+    let commercial = configuration.game_mode == GameMode::Commercial;
+
+    let (sprite, special_flags, special_z) = match context.level.mobj(special) {
+        Some(mo) => (mo.sprite, mo.flags, mo.z),
+        None => return,
+    };
+    let (n, toucher_height, toucher_z, toucher_health) = match context.level.mobj(toucher) {
+        Some(mo) => match mo.player {
+            Some(n) => (n, mo.height, mo.z, mo.health),
+            None => return,
+        },
+        None => return,
+    };
+
+    let delta = special_z - toucher_z;
+    if delta > toucher_height || delta < Fixed::from_int(-8) {
+        // out of reach
+        return;
+    }
+
+    // Dead thing touching. Can happen with a sliding player corpse.
+    if toucher_health <= 0 {
+        return;
+    }
+
+    let dropped = special_flags.contains(MobjFlag::DROPPED);
+    let mut sound = Sfx::ITEMUP;
+    let message = match sprite {
+        // armor
+        Spritenum::ARM1 => {
+            if !give_armor(context, n, GREEN_ARMOR_CLASS) {
+                return;
+            }
+            GOTARMOR
+        }
+        Spritenum::ARM2 => {
+            if !give_armor(context, n, BLUE_ARMOR_CLASS) {
+                return;
+            }
+            GOTMEGA
+        }
+
+        // bonus items
+        Spritenum::BON1 => {
+            let player = &mut context.level.players[n];
+            // can go over 100%
+            player.health = (player.health + 1).min(MAXHEALTH * 2);
+            set_player_mobj_health(context, n);
+            GOTHTHBONUS
+        }
+        Spritenum::BON2 => {
+            let player = &mut context.level.players[n];
+            // can go over 100%
+            player.armorpoints += 1;
+            // e6y: Doom 1.2 doesn't check for armor points overflowing
+            if player.armorpoints > MAX_ARMOR && cl != CompatibilityLevel::DoomV12 {
+                player.armorpoints = MAX_ARMOR;
+            }
+            // e6y: armor bonuses always give green armor
+            if player.armortype == 0 {
+                player.armortype = GREEN_ARMOR_CLASS;
+            }
+            GOTARMBONUS
+        }
+        Spritenum::SOUL => {
+            let player = &mut context.level.players[n];
+            player.health = (player.health + SOUL_HEALTH).min(MAX_SOUL);
+            set_player_mobj_health(context, n);
+            sound = Sfx::GETPOW;
+            GOTSUPER
+        }
+        Spritenum::MEGA => {
+            if !commercial {
+                return;
+            }
+            context.level.players[n].health = MEGA_HEALTH;
+            set_player_mobj_health(context, n);
+            // e6y: the megasphere always gives blue armor
+            give_armor(context, n, BLUE_ARMOR_CLASS);
+            sound = Sfx::GETPOW;
+            GOTMSPHERE
+        }
+
+        // cards, which are left for everyone in netgames
+        Spritenum::BKEY
+        | Spritenum::YKEY
+        | Spritenum::RKEY
+        | Spritenum::BSKU
+        | Spritenum::YSKU
+        | Spritenum::RSKU => {
+            let (card, message) = match sprite {
+                Spritenum::BKEY => (CardType::BlueCard, GOTBLUECARD),
+                Spritenum::YKEY => (CardType::YellowCard, GOTYELWCARD),
+                Spritenum::RKEY => (CardType::RedCard, GOTREDCARD),
+                Spritenum::BSKU => (CardType::BlueSkull, GOTBLUESKUL),
+                Spritenum::YSKU => (CardType::YellowSkull, GOTYELWSKUL),
+                _ => (CardType::RedSkull, GOTREDSKULL),
+            };
+            if !context.level.players[n].cards[card as usize] {
+                context.level.players[n].message = Some(message);
+            }
+            give_card(context, n, card);
+            if netgame {
+                return;
+            }
+            context.level.players[n].message.unwrap_or(message)
+        }
+
+        // medikits, heals
+        Spritenum::STIM => {
+            if !give_body(context, n, 10) {
+                return;
+            }
+            GOTSTIM
+        }
+        Spritenum::MEDI => {
+            if !give_body(context, n, 25) {
+                return;
+            }
+            // cph: 25 plus the 25 just added
+            if context.level.players[n].health < 50 {
+                GOTMEDINEED
+            } else {
+                GOTMEDIKIT
+            }
+        }
+
+        // power ups
+        Spritenum::PINV
+        | Spritenum::PSTR
+        | Spritenum::PINS
+        | Spritenum::SUIT
+        | Spritenum::PMAP
+        | Spritenum::PVIS => {
+            let (power, message) = match sprite {
+                Spritenum::PINV => (PowerType::Invulnerability, GOTINVUL),
+                Spritenum::PSTR => (PowerType::Strength, GOTBERSERK),
+                Spritenum::PINS => (PowerType::Invisibility, GOTINVIS),
+                Spritenum::SUIT => (PowerType::IronFeet, GOTSUIT),
+                Spritenum::PMAP => (PowerType::AllMap, GOTMAP),
+                _ => (PowerType::Infrared, GOTVISOR),
+            };
+            if !give_power(context, n, power) {
+                return;
+            }
+            let player = &mut context.level.players[n];
+            if power == PowerType::Strength && player.readyweapon != WeaponType::Fist {
+                player.pendingweapon = Some(WeaponType::Fist);
+            }
+            sound = Sfx::GETPOW;
+            message
+        }
+
+        // ammo
+        Spritenum::CLIP => {
+            if !give_ammo(context, n, AmmoType::Clip, if dropped { 0 } else { 1 }) {
+                return;
+            }
+            GOTCLIP
+        }
+        Spritenum::AMMO => {
+            if !give_ammo(context, n, AmmoType::Clip, 5) {
+                return;
+            }
+            GOTCLIPBOX
+        }
+        Spritenum::ROCK => {
+            if !give_ammo(context, n, AmmoType::Misl, 1) {
+                return;
+            }
+            GOTROCKET
+        }
+        Spritenum::BROK => {
+            if !give_ammo(context, n, AmmoType::Misl, 5) {
+                return;
+            }
+            GOTROCKBOX
+        }
+        Spritenum::CELL => {
+            if !give_ammo(context, n, AmmoType::Cell, 1) {
+                return;
+            }
+            GOTCELL
+        }
+        Spritenum::CELP => {
+            if !give_ammo(context, n, AmmoType::Cell, 5) {
+                return;
+            }
+            GOTCELLBOX
+        }
+        Spritenum::SHEL => {
+            if !give_ammo(context, n, AmmoType::Shell, 1) {
+                return;
+            }
+            GOTSHELLS
+        }
+        Spritenum::SBOX => {
+            if !give_ammo(context, n, AmmoType::Shell, 5) {
+                return;
+            }
+            GOTSHELLBOX
+        }
+        Spritenum::BPAK => {
+            let player = &mut context.level.players[n];
+            if !player.backpack {
+                for maxammo in &mut player.maxammo {
+                    *maxammo *= 2;
+                }
+                player.backpack = true;
+            }
+            for ammo in [
+                AmmoType::Clip,
+                AmmoType::Shell,
+                AmmoType::Cell,
+                AmmoType::Misl,
+            ] {
+                give_ammo(context, n, ammo, 1);
+            }
+            GOTBACKPACK
+        }
+
+        // weapons
+        Spritenum::BFUG
+        | Spritenum::MGUN
+        | Spritenum::CSAW
+        | Spritenum::LAUN
+        | Spritenum::PLAS
+        | Spritenum::SHOT
+        | Spritenum::SGN2 => {
+            // only the chaingun and shotguns can be dropped
+            let (weapon, droppable, message) = match sprite {
+                Spritenum::BFUG => (WeaponType::Bfg, false, GOTBFG9000),
+                Spritenum::MGUN => (WeaponType::Chaingun, true, GOTCHAINGUN),
+                Spritenum::CSAW => (WeaponType::Chainsaw, false, GOTCHAINSAW),
+                Spritenum::LAUN => (WeaponType::Missile, false, GOTLAUNCHER),
+                Spritenum::PLAS => (WeaponType::Plasma, false, GOTPLASMA),
+                Spritenum::SHOT => (WeaponType::Shotgun, true, GOTSHOTGUN),
+                _ => (WeaponType::SuperShotgun, true, GOTSHOTGUN2),
+            };
+            if !give_weapon(context, n, weapon, droppable && dropped) {
+                return;
+            }
+            sound = Sfx::WPNUP;
+            message
+        }
+
+        _ => crate::error("touch_special_thing: Unknown gettable thing"),
+    };
+
+    let player = &mut context.level.players[n];
+    player.message = Some(message);
+    if special_flags.contains(MobjFlag::COUNTITEM) {
+        player.itemcount += 1;
+    }
+    remove_mobj(context, special);
+    context.level.players[n].bonuscount += BONUSADD;
+
+    if pickup_sound_heard(context, n) {
+        let mo = context.level.players[n].mo;
+        context.level.start_sound(mo, sound as usize);
+    }
+}
+
+/// Mirror a player's health in their mobj.
+fn set_player_mobj_health(context: &mut Context, n: usize) {
+    let player = &context.level.players[n];
+    let (health, mo) = (player.health, player.mo);
+    if let Some(mo) = mo.and_then(|mo| context.level.mobj_mut(mo)) {
+        mo.health = health;
+    }
+}
+
+/// Hurt `target`, pushing it away from `inflictor` and killing it if its
+/// health runs out, like vanilla's `P_DamageMobj`. `inflictor` is what
+/// did the damage, e.g. a missile, and `source` is who's to blame, e.g.
+/// whoever fired it. Damage from the world, like slime, has neither.
+pub fn damage_mobj(
+    context: &mut Context,
+    target: ThinkerId,
+    inflictor: Option<ThinkerId>,
+    source: Option<ThinkerId>,
+    damage: i32,
+) {
+    let configuration = &*context.configuration;
+    let mbf = configuration.compatibility_level >= CompatibilityLevel::Mbf;
+    let monster_infighting = configuration.monster_infighting != MonsterInfightingLevel::None;
+    let baby = configuration.game_skill == SkillLevel::Itytd;
+    let mut damage = damage;
+
+    let inflictor = inflictor
+        .and_then(|i| context.level.mobj(i))
+        .map(|i| (i.x, i.y, i.z));
+    let source_player = source
+        .and_then(|s| context.level.mobj(s))
+        .and_then(|s| s.player);
+    let chainsaw =
+        source_player.is_some_and(|n| context.level.players[n].readyweapon == WeaponType::Chainsaw);
+
+    let mo = match context.level.mobj_mut(target) {
+        Some(mo) => mo,
+        None => return,
+    };
+    // killough 8/31/98: allow bouncers to take damage
+    if !mo.flags.intersects(MobjFlag::SHOOTABLE | MobjFlag::BOUNCES) {
+        // shouldn't happen...
+        return;
+    }
+    if mo.health <= 0 {
+        return;
+    }
+    if mo.flags.contains(MobjFlag::SKULLFLY) {
+        mo.momx = Fixed::ZERO;
+        mo.momy = Fixed::ZERO;
+        mo.momz = Fixed::ZERO;
+    }
+
+    let player = mo.player;
+    if player.is_some() && baby {
+        // take half damage in trainer mode
+        damage >>= 1;
+    }
+
+    let (mass, painchance, painstate, spawnhealth, spawnstate, seestate) = {
+        let mobjinfo = MOBJINFO.read();
+        let info = &mobjinfo[&mo.mobj_type];
+        (
+            info.mass,
+            info.painchance,
+            info.painstate,
+            info.spawnhealth,
+            info.spawnstate,
+            info.seestate,
+        )
+    };
+
+    // Some close combat weapons should not inflict thrust and push the
+    // victim out of reach, thus kick away unless using the chainsaw.
+    if let Some((ix, iy, iz)) = inflictor {
+        if !mo.flags.contains(MobjFlag::NOCLIP) && !chainsaw {
+            let mut angle = point_to_angle2(ix, iy, mo.x, mo.y);
+            // DEHACKED can give a thing a mass of 0, which vanilla divides
+            // by and crashes on. It's taken as 1 here, the lightest a
+            // thing can be, so it's thrown as far as any thing would be.
+            let mass = if mass == 0 { 1 } else { mass };
+            let mut thrust =
+                Fixed(damage.wrapping_mul(FRACUNIT as i32 >> 3).wrapping_mul(100) / mass);
+
+            // make fall forwards sometimes
+            if damage < 40
+                && damage > mo.health
+                && mo.z - iz > Fixed::from_int(64)
+                && p_random(context.configuration, PrClass::DamageMobj) & 1 != 0
+            {
+                angle += ANG180;
+                thrust = thrust * 4;
+            }
+            let mo = context.level.mobj_mut(target).unwrap();
+            mo.momx += fixed_mul(thrust, angle.cos());
+            mo.momy += fixed_mul(thrust, angle.sin());
+
+            // killough 11/98: thrust objects hanging off ledges
+            if mo.intflags.contains(MobjIntFlag::FALLING) && mo.gear >= MAXGEAR {
+                mo.gear = 0;
+            }
+        }
+    }
+
+    let mo = context.level.mobj_mut(target).unwrap();
+    // player specific
+    if let Some(n) = player {
+        // end of game hell hack
+        let map = super::map(context.configuration);
+        if map.sectors[map.subsectors[mo.subsector].sector].special == 11 && damage >= mo.health {
+            damage = mo.health - 1;
+        }
+
+        // TODO god mode and invulnerability, once players have cheats and
+        // powers
+
+        let player = &mut context.level.players[n];
+        if player.armortype != 0 {
+            let mut saved = if player.armortype == 1 {
+                damage / 3
+            } else {
+                damage / 2
+            };
+            if player.armorpoints <= saved {
+                // armor is used up
+                saved = player.armorpoints;
+                player.armortype = 0;
+            }
+            player.armorpoints -= saved;
+            damage -= saved;
+        }
+
+        // mirror mobj health here for Dave
+        player.health = (player.health - damage).max(0);
+        player.attacker = source;
+        // add damage after armor / invuln
+        // teleport stomp does 10k points...
+        player.damagecount = (player.damagecount + damage).min(100);
+    }
+
+    // do the damage
+    let mo = context.level.mobj_mut(target).unwrap();
+    mo.health -= damage;
+    if mo.health <= 0 {
+        kill_mobj(context, source, target);
+        return;
+    }
+
+    // killough 9/7/98: keep track of targets so that friends can help
+    // friends
+    if mbf {
+        // If target is a player, set player's target to source, so that a
+        // friend can tell who's hurting a player.
+        if player.is_some() {
+            mo.target = source;
+        }
+
+        // killough 9/8/98: If target's health is less than 50%, move it to
+        // the front of its list. This will slightly increase the chances
+        // that enemies will choose to "finish it off", but its main purpose
+        // is to alert friends of danger.
+        if mo.health * 2 < spawnhealth {
+            let friend = mo.flags.contains(MobjFlag::FRIEND);
+            let level = &mut *context.level;
+            level.friends.retain(|&th| th != target);
+            level.enemies.retain(|&th| th != target);
+            level.class_list(friend).insert(0, target);
+        }
+    }
+
+    let mut justhit = false;
+    if p_random(context.configuration, PrClass::PainChance) < painchance
+        && !context
+            .level
+            .mobj(target)
+            .unwrap()
+            .flags
+            .contains(MobjFlag::SKULLFLY)
+    {
+        // killough 11/98: see below
+        if mbf {
+            justhit = true;
+        } else {
+            // fight back!
+            context.level.mobj_mut(target).unwrap().flags |= MobjFlag::JUSTHIT;
+        }
+        set_mobj_state(context, target, painstate);
+    }
+
+    let mo = match context.level.mobj_mut(target) {
+        Some(mo) => mo,
+        None => return,
+    };
+    // we're awake now...
+    mo.reactiontime = 0;
+
+    // killough 9/9/98: cleaned up, made more consistent
+    let source_mo = source
+        .filter(|&s| s != target)
+        .and_then(|s| context.level.mobj(s))
+        .map(|s| (s.mobj_type, s.flags));
+    if let Some((source_type, source_flags)) = source_mo {
+        let mo = context.level.mobj(target).unwrap();
+        if source_type != MobjType::VILE
+            && (mo.threshold == 0 || mo.mobj_type == MobjType::VILE)
+            && ((source_flags ^ mo.flags).contains(MobjFlag::FRIEND) || monster_infighting || !mbf)
+        {
+            // if not intent on another player, chase after this one
+            //
+            // killough 2/15/98: remember last enemy, to prevent sleeping
+            // early; 2/21/98: Place priority on players
+            let remember = match mo.lastenemy.and_then(|e| context.level.mobj(e)) {
+                Some(lastenemy) if lastenemy.health > 0 => {
+                    if mbf {
+                        !(mo.flags ^ lastenemy.flags).contains(MobjFlag::FRIEND)
+                            && mo.target != source
+                    } else {
+                        lastenemy.player.is_none()
+                    }
+                }
+                _ => true,
+            };
+            let in_spawnstate = mo.state == spawnstate;
+            let mo = context.level.mobj_mut(target).unwrap();
+            if remember {
+                mo.lastenemy = mo.target;
+            }
+            mo.target = source;
+            mo.threshold = BASETHRESHOLD;
+            if in_spawnstate && seestate != Statenum::NULL as usize {
+                set_mobj_state(context, target, seestate);
+            }
+        }
+    }
+
+    let mo = match context.level.mobj_mut(target) {
+        Some(mo) => mo,
+        None => return,
+    };
+
+    // killough 11/98: Don't attack a friend, unless hit by that friend.
+    if justhit {
+        let friend_target = mo
+            .target
+            .and_then(|t| context.level.mobj(t))
+            .is_some_and(|t| t.flags.contains(MobjFlag::FRIEND));
+        let mo = context.level.mobj_mut(target).unwrap();
+        if mo.target == source
+            || mo.target.is_none()
+            || !(mo.flags.contains(MobjFlag::FRIEND) && friend_target)
+        {
+            // fight back!
+            mo.flags |= MobjFlag::JUSTHIT;
+        }
+    }
+}
+
+/// Kill `target`, putting it into its death state and dropping what it
+/// carried, like vanilla's `P_KillMobj`. `source` gets the credit.
+pub fn kill_mobj(context: &mut Context, source: Option<ThinkerId>, target: ThinkerId) {
+    let cl = context.configuration.compatibility_level;
+    let netgame = context.configuration.netgame;
+    let deathmatch = context.configuration.deathmatch != 0;
+
+    let mo = match context.level.mobj_mut(target) {
+        Some(mo) => mo,
+        None => return,
+    };
+    mo.flags
+        .remove(MobjFlag::SHOOTABLE | MobjFlag::FLOAT | MobjFlag::SKULLFLY);
+    if mo.mobj_type != MobjType::SKULL {
+        mo.flags.remove(MobjFlag::NOGRAVITY);
+    }
+    mo.flags |= MobjFlag::CORPSE | MobjFlag::DROPOFF;
+    mo.height = mo.height >> 2;
+    let (flags, victim, lastenemy) = (mo.flags, mo.player, mo.lastenemy);
+
+    // killough 8/29/98: remove from threaded list
+    context.level.update_thinker_class(target);
+
+    let source_player = source
+        .and_then(|s| context.level.mobj(s))
+        .and_then(|s| s.player);
+    if let Some(n) = source_player {
+        // count for intermission
+        let player = &mut context.level.players[n];
+        if flags.contains(MobjFlag::COUNTKILL) {
+            player.killcount += 1;
+        }
+        if let Some(victim) = victim {
+            player.frags[victim] += 1;
+        }
+    } else if flags.contains(MobjFlag::COUNTKILL) {
+        // add to kills tally
+        if cl < CompatibilityLevel::LxDoomV1 || !netgame {
+            if !netgame {
+                // count all monster deaths, even those caused by other
+                // monsters
+                context.level.players[0].killcount += 1;
+            }
+        } else if !deathmatch {
+            // try and find a player to give the kill to, otherwise give
+            // the kill to a random player. this fixes the missing monsters
+            // bug in coop - rain
+            let fighting = lastenemy
+                .and_then(|e| context.level.mobj(e))
+                .filter(|e| e.health > 0)
+                .and_then(|e| e.player);
+            if let Some(n) = fighting {
+                context.level.players[n].killcount += 1;
+            } else {
+                // cph - randomly choose a player in the game to be
+                // credited, uniformly between the active players
+                let active: Vec<usize> = (0..MAXPLAYERS)
+                    .filter(|&n| {
+                        context
+                            .configuration
+                            .player_in_game
+                            .get(n)
+                            .copied()
+                            .unwrap_or(false)
+                    })
+                    .collect();
+                if !active.is_empty() {
+                    let n =
+                        p_random(context.configuration, PrClass::Friends) as usize % active.len();
+                    context.level.players[active[n]].killcount += 1;
+                }
+            }
+        }
+    }
+
+    if let Some(n) = victim {
+        // count environment kills against you
+        if source.is_none() {
+            context.level.players[n].frags[n] += 1;
+        }
+        context
+            .level
+            .mobj_mut(target)
+            .unwrap()
+            .flags
+            .remove(MobjFlag::SOLID);
+        context.level.players[n].playerstate = PlayerState::Dead;
+        super::pspr::drop_weapon(context, n);
+    }
+
+    let (health, spawnhealth, deathstate, xdeathstate, droppeditem) = {
+        let mo = context.level.mobj(target).unwrap();
+        let mobjinfo = MOBJINFO.read();
+        let info = &mobjinfo[&mo.mobj_type];
+        (
+            mo.health,
+            info.spawnhealth,
+            info.deathstate,
+            info.xdeathstate,
+            info.droppeditem,
+        )
+    };
+    if health < -spawnhealth && xdeathstate != Statenum::NULL as usize {
+        set_mobj_state(context, target, xdeathstate);
+    } else {
+        set_mobj_state(context, target, deathstate);
+    }
+
+    let random = p_random(context.configuration, PrClass::KillTics) as i64;
+    let (x, y) = match context.level.mobj_mut(target) {
+        Some(mo) => {
+            mo.tics = (mo.tics - (random & 3)).max(1);
+            (mo.x, mo.y)
+        }
+        None => return,
+    };
+
+    // Drop stuff. This determines the kind of object spawned during the
+    // death frame of a thing.
+    if droppeditem != MobjType::NULL {
+        let item = spawn_mobj(context, x, y, ONFLOORZ, droppeditem);
+        // special versions of items
+        context.level.mobj_mut(item).unwrap().flags |= MobjFlag::DROPPED;
+    }
+}
@@ -0,0 +1,712 @@
+use super::{map, mobj::MobjFlag, Context};
+use crate::{
+    configuration::CompatibilityLevel,
+    map::{Map, Node, NodeChild},
+    misc::fixed::{fixed_div, fixed_mul, Fixed, FRACBITS, FRACUNIT},
+    think::ThinkerId,
+};
+use bitflags::bitflags;
+
+/// Blockmap blocks are 128 map units on a side.
+pub const MAPBLOCKUNITS: i32 = 128;
+pub const MAPBLOCKSHIFT: u32 = FRACBITS as u32 + 7;
+pub const MAPBLOCKSIZE: i32 = MAPBLOCKUNITS << FRACBITS;
+/// Shift a blockmap coordinate right by this to get a fraction of a block.
+const MAPBTOFRAC: u32 = MAPBLOCKSHIFT - FRACBITS as u32;
+
+/// The biggest radius of any mobj. Mobjs are linked into the block their
+/// center is in, so they can stick out of it by up to this much.
+pub const MAXRADIUS: Fixed = Fixed::from_int(32);
+
+/// Indexes into a bounding box, like vanilla's `m_bbox.h`.
+pub const BOXTOP: usize = 0;
+pub const BOXBOTTOM: usize = 1;
+pub const BOXLEFT: usize = 2;
+pub const BOXRIGHT: usize = 3;
+
+/// Which side of a node's partition line a point is on: 0 for the front,
+/// 1 for the back, like vanilla's `R_PointOnSide`.
+pub fn point_on_side(x: Fixed, y: Fixed, node: &Node) -> usize {
+    if node.dx == Fixed::ZERO {
+        return (if x <= node.x {
+            node.dy > Fixed::ZERO
+        } else {
+            node.dy < Fixed::ZERO
+        }) as usize;
+    }
+    if node.dy == Fixed::ZERO {
+        return (if y <= node.y {
+            node.dx < Fixed::ZERO
+        } else {
+            node.dx > Fixed::ZERO
+        }) as usize;
+    }
+    let x = x - node.x;
+    let y = y - node.y;
+
+    // try to decide by the signs alone
+    if (node.dy.0 ^ node.dx.0 ^ x.0 ^ y.0) < 0 {
+        return ((node.dy.0 ^ x.0) < 0) as usize;
+    }
+    let left = fixed_mul(node.dy >> FRACBITS as u32, x);
+    let right = fixed_mul(y, node.dx >> FRACBITS as u32);
+    (right >= left) as usize
+}
+
+/// The subsector containing a point, found by walking the BSP tree.
+pub fn point_in_subsector(map: &Map, x: Fixed, y: Fixed) -> usize {
+    if map.nodes.is_empty() {
+        return 0;
+    }
+    let mut node = map.nodes.len() - 1;
+    loop {
+        let node_ref = &map.nodes[node];
+        match node_ref.children[point_on_side(x, y, node_ref)] {
+            NodeChild::Node(child) => node = child,
+            NodeChild::Subsector(subsector) => return subsector,
+        }
+    }
+}
+
+/// The blockmap block a point is in, if it's on the blockmap at all.
+pub fn block_at(map: &Map, x: Fixed, y: Fixed) -> Option<usize> {
+    let blockx = (x - map.blockmap.origin_x).0 >> MAPBLOCKSHIFT;
+    let blocky = (y - map.blockmap.origin_y).0 >> MAPBLOCKSHIFT;
+    if blockx < 0
+        || blocky < 0
+        || blockx as usize >= map.blockmap.width
+        || blocky as usize >= map.blockmap.height
+    {
+        return None;
+    }
+    Some(blocky as usize * map.blockmap.width + blockx as usize)
+}
+
+/// Link a mobj into its subsector, its sector's thing list and its
+/// blockmap block, from its position, like vanilla's `P_SetThingPosition`.
+/// Call it after moving the mobj; mobjs off the blockmap aren't in any
+/// block.
+pub fn set_thing_position(context: &mut Context, id: ThinkerId) {
+    let map = map(context.configuration);
+    let mobj = match context.level.mobj_mut(id) {
+        Some(mobj) => mobj,
+        None => return,
+    };
+    mobj.subsector = point_in_subsector(map, mobj.x, mobj.y);
+    let sector = map.subsectors[mobj.subsector].sector;
+    let flags = mobj.flags;
+    mobj.block = if flags.contains(MobjFlag::NOBLOCKMAP) {
+        None
+    } else {
+        block_at(map, mobj.x, mobj.y)
+    };
+    let block = mobj.block;
+
+    if !flags.contains(MobjFlag::NOSECTOR) {
+        context.level.sector_things[sector].push(id);
+    }
+    if let Some(block) = block {
+        context.level.blocklinks[block].push(id);
+    }
+}
+
+/// Unlink a mobj from its sector and block, before moving it or taking it
+/// off the map, like vanilla's `P_UnsetThingPosition`.
+pub fn unset_thing_position(context: &mut Context, id: ThinkerId) {
+    let map = map(context.configuration);
+    let mobj = match context.level.mobj_mut(id) {
+        Some(mobj) => mobj,
+        None => return,
+    };
+    let sector = map.subsectors[mobj.subsector].sector;
+    let flags = mobj.flags;
+    let block = mobj.block.take();
+
+    if !flags.contains(MobjFlag::NOSECTOR) {
+        context.level.sector_things[sector].retain(|&thing| thing != id);
+    }
+    if let Some(block) = block {
+        context.level.blocklinks[block].retain(|&thing| thing != id);
+    }
+}
+
+/// A cheap approximation of the length of `(dx, dy)`, like vanilla's
+/// `P_AproxDistance`.
+pub fn approx_distance(dx: Fixed, dy: Fixed) -> Fixed {
+    let dx = dx.abs();
+    let dy = dy.abs();
+    if dx < dy {
+        dx + dy - (dx >> 1)
+    } else {
+        dx + dy - (dy >> 1)
+    }
+}
+
+/// Which side of a linedef a point is on: 0 for the front, 1 for the
+/// back, like vanilla's `P_PointOnLineSide`.
+pub fn point_on_line_side(map: &Map, x: Fixed, y: Fixed, line: usize) -> usize {
+    let line = &map.lines[line];
+    let v1 = &map.vertices[line.v1];
+    (if line.dx == Fixed::ZERO {
+        if x <= v1.x {
+            line.dy > Fixed::ZERO
+        } else {
+            line.dy < Fixed::ZERO
+        }
+    } else if line.dy == Fixed::ZERO {
+        if y <= v1.y {
+            line.dx < Fixed::ZERO
+        } else {
+            line.dx > Fixed::ZERO
+        }
+    } else {
+        fixed_mul(y - v1.y, line.dx >> FRACBITS as u32)
+            >= fixed_mul(line.dy >> FRACBITS as u32, x - v1.x)
+    }) as usize
+}
+
+/// The bounding box of a linedef, top, bottom, left, right.
+pub fn line_bbox(map: &Map, line: usize) -> [Fixed; 4] {
+    let line = &map.lines[line];
+    let v1 = &map.vertices[line.v1];
+    let v2 = &map.vertices[line.v2];
+    let mut bbox = [Fixed::ZERO; 4];
+    if v1.x < v2.x {
+        bbox[BOXLEFT] = v1.x;
+        bbox[BOXRIGHT] = v2.x;
+    } else {
+        bbox[BOXLEFT] = v2.x;
+        bbox[BOXRIGHT] = v1.x;
+    }
+    if v1.y < v2.y {
+        bbox[BOXBOTTOM] = v1.y;
+        bbox[BOXTOP] = v2.y;
+    } else {
+        bbox[BOXBOTTOM] = v2.y;
+        bbox[BOXTOP] = v1.y;
+    }
+    bbox
+}
+
+/// Whether two bounding boxes overlap, not counting touching edges.
+pub fn boxes_overlap(a: &[Fixed; 4], b: &[Fixed; 4]) -> bool {
+    a[BOXRIGHT] > b[BOXLEFT]
+        && a[BOXLEFT] < b[BOXRIGHT]
+        && a[BOXTOP] > b[BOXBOTTOM]
+        && a[BOXBOTTOM] < b[BOXTOP]
+}
+
+/// Which way a linedef slopes, for `box_on_line_side`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SlopeType {
+    Horizontal,
+    Vertical,
+    Positive,
+    Negative,
+}
+
+pub fn slope_type(map: &Map, line: usize) -> SlopeType {
+    let line = &map.lines[line];
+    if line.dx == Fixed::ZERO {
+        SlopeType::Vertical
+    } else if line.dy == Fixed::ZERO {
+        SlopeType::Horizontal
+    } else if fixed_div(line.dy, line.dx) > Fixed::ZERO {
+        SlopeType::Positive
+    } else {
+        SlopeType::Negative
+    }
+}
+
+/// Which side of a linedef a bounding box is on, or `None` if the line
+/// crosses it, like vanilla's `P_BoxOnLineSide`.
+pub fn box_on_line_side(map: &Map, bbox: &[Fixed; 4], line: usize) -> Option<usize> {
+    let ld = &map.lines[line];
+    let v1 = &map.vertices[ld.v1];
+    let (a, b) = match slope_type(map, line) {
+        SlopeType::Horizontal => {
+            let p = bbox[BOXTOP] > v1.y;
+            let q = bbox[BOXBOTTOM] > v1.y;
+            return if p == q {
+                Some((p ^ (ld.dx < Fixed::ZERO)) as usize)
+            } else {
+                None
+            };
+        }
+        SlopeType::Vertical => {
+            let p = bbox[BOXRIGHT] < v1.x;
+            let q = bbox[BOXLEFT] < v1.x;
+            return if p == q {
+                Some((p ^ (ld.dy < Fixed::ZERO)) as usize)
+            } else {
+                None
+            };
+        }
+        SlopeType::Positive => (
+            point_on_line_side(map, bbox[BOXRIGHT], bbox[BOXBOTTOM], line),
+            point_on_line_side(map, bbox[BOXLEFT], bbox[BOXTOP], line),
+        ),
+        SlopeType::Negative => (
+            point_on_line_side(map, bbox[BOXLEFT], bbox[BOXBOTTOM], line),
+            point_on_line_side(map, bbox[BOXRIGHT], bbox[BOXTOP], line),
+        ),
+    };
+    if a == b {
+        Some(b)
+    } else {
+        None
+    }
+}
+
+/// The gap between the sectors on either side of a linedef.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Opening {
+    /// The lower ceiling.
+    pub top: Fixed,
+    /// The higher floor.
+    pub bottom: Fixed,
+    /// How tall the gap is; 0 for one-sided lines, which have nothing
+    /// else set.
+    pub range: Fixed,
+    /// The lower floor.
+    pub lowfloor: Fixed,
+}
+
+/// The window from one side of a linedef to the other, like vanilla's
+/// `P_LineOpening`.
+pub fn line_opening(map: &Map, line: usize) -> Opening {
+    let line = &map.lines[line];
+    let (front, back) = match (line.sides[1], line.front_sector, line.back_sector) {
+        (Some(_), Some(front), Some(back)) => (&map.sectors[front], &map.sectors[back]),
+        _ => return Opening::default(),
+    };
+    let top = front.ceiling_height.min(back.ceiling_height);
+    let (bottom, lowfloor) = if front.floor_height > back.floor_height {
+        (front.floor_height, back.floor_height)
+    } else {
+        (back.floor_height, front.floor_height)
+    };
+    Opening {
+        top,
+        bottom,
+        range: top - bottom,
+        lowfloor,
+    }
+}
+
+/// The blockmap column or row a map coordinate is in, which may be off the
+/// blockmap.
+pub fn block_x(map: &Map, x: Fixed) -> i32 {
+    (x - map.blockmap.origin_x).0 >> MAPBLOCKSHIFT
+}
+
+pub fn block_y(map: &Map, y: Fixed) -> i32 {
+    (y - map.blockmap.origin_y).0 >> MAPBLOCKSHIFT
+}
+
+fn block_index(map: &Map, x: i32, y: i32) -> Option<usize> {
+    if x < 0 || y < 0 || x as usize >= map.blockmap.width || y as usize >= map.blockmap.height {
+        return None;
+    }
+    Some(y as usize * map.blockmap.width + x as usize)
+}
+
+/// The lines in a blockmap block. Every list starts with a 0, which
+/// vanilla took for line 0 and Boom skips.
+pub fn block_lines(map: &Map, cl: CompatibilityLevel, block: usize) -> &[usize] {
+    let list = &map.blockmap.blocks[block];
+    // killough 1/31/98: for compatibility we need to use the old method.
+    // Most demos go out of sync, and maybe other problems happen, if we
+    // don't consider linedef 0.
+    if cl >= CompatibilityLevel::Boom {
+        list.get(1..).unwrap_or(&[])
+    } else {
+        list
+    }
+}
+
+/// Call `func` on each line in a blockmap block that hasn't been checked
+/// since the last `validcount` bump, stopping if it returns false, like
+/// vanilla's `P_BlockLinesIterator`. Returns false if it was stopped.
+/// Blocks off the blockmap are empty.
+pub fn block_lines_iterator(
+    context: &mut Context,
+    x: i32,
+    y: i32,
+    mut func: impl FnMut(&mut Context, usize) -> bool,
+) -> bool {
+    let block = match block_index(map(context.configuration), x, y) {
+        Some(block) => block,
+        None => return true,
+    };
+    let cl = context.configuration.compatibility_level;
+    let mut i = 0;
+    while let Some(&line) = block_lines(map(context.configuration), cl, block).get(i) {
+        i += 1;
+        let level = &mut *context.level;
+        if level.line_validcount[line] == level.validcount {
+            // line has already been checked
+            continue;
+        }
+        level.line_validcount[line] = level.validcount;
+        if !func(context, line) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Call `func` on each mobj in a blockmap block, newest first, stopping if
+/// it returns false, like vanilla's `P_BlockThingsIterator`. Returns false
+/// if it was stopped. Mobjs removed along the way are skipped.
+pub fn block_things_iterator(
+    context: &mut Context,
+    x: i32,
+    y: i32,
+    mut func: impl FnMut(&mut Context, ThinkerId) -> bool,
+) -> bool {
+    let block = match block_index(map(context.configuration), x, y) {
+        Some(block) => block,
+        None => return true,
+    };
+    let things = context.level.blocklinks[block].clone();
+    for &thing in things.iter().rev() {
+        if context.level.mobj(thing).is_some() && !func(context, thing) {
+            return false;
+        }
+    }
+    true
+}
+
+/// A line through a point, given as the point and a direction.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct DivLine {
+    pub x: Fixed,
+    pub y: Fixed,
+    pub dx: Fixed,
+    pub dy: Fixed,
+}
+
+impl DivLine {
+    pub fn from_line(map: &Map, line: usize) -> Self {
+        let line = &map.lines[line];
+        let v1 = &map.vertices[line.v1];
+        DivLine {
+            x: v1.x,
+            y: v1.y,
+            dx: line.dx,
+            dy: line.dy,
+        }
+    }
+
+    /// Which side of the line a point is on: 0 for the front, 1 for the
+    /// back, like vanilla's `P_PointOnDivlineSide`.
+    pub fn point_on_side(&self, x: Fixed, y: Fixed) -> usize {
+        if self.dx == Fixed::ZERO {
+            return (if x <= self.x {
+                self.dy > Fixed::ZERO
+            } else {
+                self.dy < Fixed::ZERO
+            }) as usize;
+        }
+        if self.dy == Fixed::ZERO {
+            return (if y <= self.y {
+                self.dx < Fixed::ZERO
+            } else {
+                self.dx > Fixed::ZERO
+            }) as usize;
+        }
+        let x = x - self.x;
+        let y = y - self.y;
+
+        // try to decide by the signs alone
+        if (self.dy.0 ^ self.dx.0 ^ x.0 ^ y.0) < 0 {
+            return ((self.dy.0 ^ x.0) < 0) as usize;
+        }
+        (fixed_mul(y >> 8, self.dx >> 8) >= fixed_mul(self.dy >> 8, x >> 8)) as usize
+    }
+
+    /// How far along this line it crosses `other`, as a fraction of its
+    /// length, like vanilla's `P_InterceptVector`. 0 if they're parallel.
+    pub fn intercept(&self, other: &DivLine) -> Fixed {
+        let den = fixed_mul(other.dy >> 8, self.dx) - fixed_mul(other.dx >> 8, self.dy);
+        if den == Fixed::ZERO {
+            return Fixed::ZERO;
+        }
+        let num = fixed_mul((other.x - self.x) >> 8, other.dy)
+            + fixed_mul((self.y - other.y) >> 8, other.dx);
+        fixed_div(num, den)
+    }
+}
+
+bitflags! {
+    /// What `path_traverse` looks for along the trace.
+    pub struct PathFlags: u32 {
+        const ADDLINES = 1;
+        const ADDTHINGS = 2;
+        /// Stop at the first one-sided line.
+        const EARLYOUT = 4;
+    }
+}
+
+/// Something a trace crosses.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum InterceptTarget {
+    Line(usize),
+    Thing(ThinkerId),
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Intercept {
+    /// How far along the trace, as a fraction of its length.
+    pub frac: Fixed,
+    pub target: InterceptTarget,
+}
+
+/// Trace a line from `(x1, y1)` to `(x2, y2)` through the blockmap, and
+/// call `trav` on the lines and things it crosses, nearest first, like
+/// vanilla's `P_PathTraverse`. `trav` gets the trace too. Returns false
+/// if `trav` stopped the traversal.
+#[allow(clippy::too_many_arguments)]
+pub fn path_traverse(
+    context: &mut Context,
+    x1: Fixed,
+    y1: Fixed,
+    x2: Fixed,
+    y2: Fixed,
+    flags: PathFlags,
+    mut trav: impl FnMut(&mut Context, &DivLine, &Intercept) -> bool,
+) -> bool {
+    let (mut x1, mut y1, mut x2, mut y2) = (x1, y1, x2, y2);
+    let (origin_x, origin_y) = {
+        let blockmap = &map(context.configuration).blockmap;
+        (blockmap.origin_x, blockmap.origin_y)
+    };
+    context.level.validcount += 1;
+
+    // don't side exactly on a line
+    if (x1 - origin_x).0 & (MAPBLOCKSIZE - 1) == 0 {
+        x1 += Fixed::UNIT;
+    }
+    if (y1 - origin_y).0 & (MAPBLOCKSIZE - 1) == 0 {
+        y1 += Fixed::UNIT;
+    }
+    let trace = DivLine {
+        x: x1,
+        y: y1,
+        dx: x2 - x1,
+        dy: y2 - y1,
+    };
+
+    x1 -= origin_x;
+    y1 -= origin_y;
+    x2 -= origin_x;
+    y2 -= origin_y;
+    let xt1 = x1.0 >> MAPBLOCKSHIFT;
+    let yt1 = y1.0 >> MAPBLOCKSHIFT;
+    let xt2 = x2.0 >> MAPBLOCKSHIFT;
+    let yt2 = y2.0 >> MAPBLOCKSHIFT;
+    let fracmask = FRACUNIT as i32 - 1;
+
+    let (mapxstep, partial, ystep) = if xt2 > xt1 {
+        (
+            1,
+            Fixed::UNIT - Fixed((x1.0 >> MAPBTOFRAC) & fracmask),
+            fixed_div(y2 - y1, (x2 - x1).abs()),
+        )
+    } else if xt2 < xt1 {
+        (
+            -1,
+            Fixed((x1.0 >> MAPBTOFRAC) & fracmask),
+            fixed_div(y2 - y1, (x2 - x1).abs()),
+        )
+    } else {
+        (0, Fixed::UNIT, Fixed::from_int(256))
+    };
+    let mut yintercept = Fixed(y1.0 >> MAPBTOFRAC) + fixed_mul(partial, ystep);
+
+    let (mapystep, partial, xstep) = if yt2 > yt1 {
+        (
+            1,
+            Fixed::UNIT - Fixed((y1.0 >> MAPBTOFRAC) & fracmask),
+            fixed_div(x2 - x1, (y2 - y1).abs()),
+        )
+    } else if yt2 < yt1 {
+        (
+            -1,
+            Fixed((y1.0 >> MAPBTOFRAC) & fracmask),
+            fixed_div(x2 - x1, (y2 - y1).abs()),
+        )
+    } else {
+        (0, Fixed::UNIT, Fixed::from_int(256))
+    };
+    let mut xintercept = Fixed(x1.0 >> MAPBTOFRAC) + fixed_mul(partial, xstep);
+
+    // Step through map blocks. Count is present to prevent a round off
+    // error from skipping the break.
+    let mut intercepts = vec![];
+    let earlyout = flags.contains(PathFlags::EARLYOUT);
+    let mut mapx = xt1;
+    let mut mapy = yt1;
+    for _ in 0..64 {
+        if flags.contains(PathFlags::ADDLINES)
+            && !block_lines_iterator(context, mapx, mapy, |context, line| {
+                add_line_intercept(context, &trace, earlyout, line, &mut intercepts)
+            })
+        {
+            // early out
+            return false;
+        }
+        if flags.contains(PathFlags::ADDTHINGS) {
+            block_things_iterator(context, mapx, mapy, |context, thing| {
+                add_thing_intercept(context, &trace, thing, &mut intercepts);
+                true
+            });
+        }
+        if mapx == xt2 && mapy == yt2 {
+            break;
+        }
+        if yintercept.to_int() == mapy {
+            yintercept += ystep;
+            mapx += mapxstep;
+        } else if xintercept.to_int() == mapx {
+            xintercept += xstep;
+            mapy += mapystep;
+        }
+    }
+
+    traverse_intercepts(context, &trace, &mut intercepts, Fixed::UNIT, &mut trav)
+}
+
+/// Note a line the trace crosses, like vanilla's `PIT_AddLineIntercepts`.
+/// Returns false to stop at a one-sided line when `earlyout` is set.
+fn add_line_intercept(
+    context: &mut Context,
+    trace: &DivLine,
+    earlyout: bool,
+    line: usize,
+    intercepts: &mut Vec<Intercept>,
+) -> bool {
+    let map = map(context.configuration);
+    let ld = &map.lines[line];
+    let sixteen = Fixed::from_int(16);
+
+    // avoid precision problems with two routines
+    let (s1, s2) =
+        if trace.dx > sixteen || trace.dy > sixteen || trace.dx < -sixteen || trace.dy < -sixteen {
+            let v1 = &map.vertices[ld.v1];
+            let v2 = &map.vertices[ld.v2];
+            (
+                trace.point_on_side(v1.x, v1.y),
+                trace.point_on_side(v2.x, v2.y),
+            )
+        } else {
+            (
+                point_on_line_side(map, trace.x, trace.y, line),
+                point_on_line_side(map, trace.x + trace.dx, trace.y + trace.dy, line),
+            )
+        };
+    if s1 == s2 {
+        // line isn't crossed
+        return true;
+    }
+
+    let frac = trace.intercept(&DivLine::from_line(map, line));
+    if frac < Fixed::ZERO {
+        // behind source
+        return true;
+    }
+    if earlyout && frac < Fixed::UNIT && ld.back_sector.is_none() {
+        // stop checking
+        return false;
+    }
+    intercepts.push(Intercept {
+        frac,
+        target: InterceptTarget::Line(line),
+    });
+    true
+}
+
+/// Note a mobj the trace crosses, by checking it against the diagonal of
+/// the mobj's box that's most across the trace, like vanilla's
+/// `PIT_AddThingIntercepts`.
+fn add_thing_intercept(
+    context: &mut Context,
+    trace: &DivLine,
+    thing: ThinkerId,
+    intercepts: &mut Vec<Intercept>,
+) {
+    let mobj = match context.level.mobj(thing) {
+        Some(mobj) => mobj,
+        None => return,
+    };
+    let tracepositive = (trace.dx.0 ^ trace.dy.0) > 0;
+
+    // check a corner to corner crossection for hit
+    let (x1, y1, x2, y2) = if tracepositive {
+        (
+            mobj.x - mobj.radius,
+            mobj.y + mobj.radius,
+            mobj.x + mobj.radius,
+            mobj.y - mobj.radius,
+        )
+    } else {
+        (
+            mobj.x - mobj.radius,
+            mobj.y - mobj.radius,
+            mobj.x + mobj.radius,
+            mobj.y + mobj.radius,
+        )
+    };
+    if trace.point_on_side(x1, y1) == trace.point_on_side(x2, y2) {
+        // line isn't crossed
+        return;
+    }
+    let frac = trace.intercept(&DivLine {
+        x: x1,
+        y: y1,
+        dx: x2 - x1,
+        dy: y2 - y1,
+    });
+    if frac < Fixed::ZERO {
+        // behind source
+        return;
+    }
+    intercepts.push(Intercept {
+        frac,
+        target: InterceptTarget::Thing(thing),
+    });
+}
+
+/// Call `trav` on the intercepts nearest first, up to `maxfrac` along the
+/// trace, like vanilla's `P_TraverseIntercepts`. Intercepts the same
+/// distance away go in the order they were found.
+fn traverse_intercepts(
+    context: &mut Context,
+    trace: &DivLine,
+    intercepts: &mut [Intercept],
+    maxfrac: Fixed,
+    trav: &mut impl FnMut(&mut Context, &DivLine, &Intercept) -> bool,
+) -> bool {
+    for _ in 0..intercepts.len() {
+        let mut dist = Fixed::MAX;
+        let mut nearest = 0;
+        for (i, intercept) in intercepts.iter().enumerate() {
+            if intercept.frac < dist {
+                dist = intercept.frac;
+                nearest = i;
+            }
+        }
+        if dist > maxfrac {
+            // checked everything in range
+            return true;
+        }
+        if !trav(context, trace, &intercepts[nearest]) {
+            // don't bother going farther
+            return false;
+        }
+        intercepts[nearest].frac = Fixed::MAX;
+    }
+    // everything was traversed
+    true
+}
@@ -1,3 +1,30 @@
+use super::{
+    enemy::DirType,
+    inter::damage_mobj,
+    map,
+    maputl::{approx_distance, point_in_subsector, set_thing_position, unset_thing_position},
+    movement::{
+        aim_line_attack, apply_torque, check_position, get_friction, is_sky, slide_move, try_move,
+        DropOff, MELEERANGE, ORIG_FRICTION, ORIG_FRICTION_FACTOR,
+    },
+    player::{PlayerState, MAXPLAYERS, VIEWHEIGHT},
+    Context, Thinker,
+};
+use crate::{
+    configuration::{CompatibilityLevel, SkillLevel},
+    doom::def::NUMCARDS,
+    info::{MobjType, Spritenum, Statenum, MOBJINFO, STATES},
+    lprint,
+    map::{Thing, ThingFlags},
+    misc::{
+        fixed::{fixed_mul, Fixed},
+        lprint::OutputLevel,
+        random::{p_random, p_sub_random, PrClass},
+    },
+    sounds::Sfx,
+    tables::{point_to_angle2, Angle, ANG45},
+    think::{ActionF, ThinkerId},
+};
 use bitflags::bitflags;
 
 bitflags! {
@@ -95,8 +122,1153 @@ bitflags! {
     }
 }
 
+bitflags! {
+    /// killough 9/15/98: flags the engine keeps for itself, which never
+    /// come from info or dehacked.
+    pub struct MobjIntFlag: u32 {
+        /// Falling off a ledge, pushed by torque.
+        const FALLING = 1;
+        /// A touchy thing that has come to rest, and now goes off.
+        const ARMED = 2;
+    }
+}
+
 impl Into<u64> for MobjFlag {
     fn into(self) -> u64 {
         self.bits
     }
 }
+
+/// A thing in the level: a monster, a player, a missile, a decoration and
+/// so on. It lives on the thinker list, so it's referred to by its
+/// `ThinkerId`.
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct Mobj {
+    pub mobj_type: MobjType,
+    pub x: Fixed,
+    pub y: Fixed,
+    pub z: Fixed,
+    pub angle: Angle,
+    pub sprite: Spritenum,
+    pub frame: i64,
+
+    /// The subsector the mobj's center is in, set by `set_thing_position`.
+    pub subsector: usize,
+    /// The blockmap block the mobj is linked into, if any.
+    pub block: Option<usize>,
+    /// The highest floor and lowest ceiling the mobj touches.
+    pub floorz: Fixed,
+    pub ceilingz: Fixed,
+    /// killough 11/98: the lowest floor the mobj touches, for dropoffs.
+    pub dropoffz: Fixed,
+
+    pub radius: Fixed,
+    pub height: Fixed,
+    pub momx: Fixed,
+    pub momy: Fixed,
+    pub momz: Fixed,
+
+    pub flags: MobjFlag,
+    pub intflags: MobjIntFlag,
+    /// The current state, an index into `info::STATES`.
+    pub state: usize,
+    /// Tics left in the current state, or -1 to stay in it forever.
+    pub tics: i64,
+    pub health: i32,
+    /// Tics before a monster can attack again; also how long a player
+    /// can't move after teleporting.
+    pub reactiontime: i32,
+    /// The player to look at first when looking for one to chase.
+    pub lastlook: i32,
+    /// The direction a monster is walking in.
+    pub movedir: DirType,
+    /// How many steps a monster takes before picking a new direction.
+    pub movecount: i32,
+    /// killough 9/8/98: how many steps a monster backs away or strafes
+    /// for.
+    pub strafecount: i32,
+    /// Tics a monster keeps chasing its target before it will switch, even
+    /// if something else hurts it.
+    pub threshold: i32,
+    /// killough 9/9/98: how long a monster pursues its target before
+    /// looking for a better one.
+    pub pursuecount: i32,
+
+    /// Boom: friction and movefactor left by friction thinkers, used and
+    /// reset each tic in Boom demos.
+    pub friction: Fixed,
+    pub movefactor: i32,
+    /// killough 9/12/98: the torque gear of a mobj falling off a ledge.
+    pub gear: i32,
+
+    /// What a monster is after, or who fired a missile.
+    pub target: Option<ThinkerId>,
+    /// What a homing missile is after.
+    pub tracer: Option<ThinkerId>,
+    /// killough 10/98: what a monster was after before its current target.
+    pub lastenemy: Option<ThinkerId>,
+    /// The player number, for player mobjs.
+    pub player: Option<usize>,
+    /// The map thing this was spawned from, for respawning.
+    pub spawnpoint: Option<Thing>,
+}
+
+impl Mobj {
+    /// killough 11/98: whether the mobj is alive and can see, i.e. a
+    /// living monster or player rather than an object.
+    pub fn sentient(&self) -> bool {
+        self.health > 0 && MOBJINFO.read()[&self.mobj_type].seestate != Statenum::NULL as usize
+    }
+}
+
+/// Spawn a mobj with its feet on the floor.
+pub const ONFLOORZ: Fixed = Fixed::MIN;
+/// Spawn a mobj with its head against the ceiling.
+pub const ONCEILINGZ: Fixed = Fixed::MAX;
+
+/// Spawn a mobj in its spawn state and put it on the map, like vanilla's
+/// `P_SpawnMobj`. The spawn state's action isn't run.
+pub fn spawn_mobj(
+    context: &mut Context,
+    x: Fixed,
+    y: Fixed,
+    z: Fixed,
+    mobj_type: MobjType,
+) -> ThinkerId {
+    let configuration = &mut *context.configuration;
+    let (mut mobj, spawnstate) = {
+        let mobjinfo = MOBJINFO.read();
+        let info = &mobjinfo[&mobj_type];
+        let mut flags = MobjFlag::from_bits_truncate(info.flags);
+        // killough 8/23/98: no friends, bouncers, or touchy things in old
+        // demos, and from MBF on players are always friends
+        if configuration.compatibility_level < CompatibilityLevel::Mbf {
+            flags.remove(MobjFlag::BOUNCES | MobjFlag::FRIEND | MobjFlag::TOUCHY);
+        } else if mobj_type == MobjType::PLAYER {
+            flags.insert(MobjFlag::FRIEND);
+        }
+        let mobj = Mobj {
+            mobj_type,
+            x,
+            y,
+            z,
+            angle: Angle(0),
+            sprite: Spritenum::TNT1,
+            frame: 0,
+            subsector: 0,
+            block: None,
+            floorz: Fixed::ZERO,
+            ceilingz: Fixed::ZERO,
+            dropoffz: Fixed::ZERO,
+            radius: info.radius,
+            height: info.height,
+            momx: Fixed::ZERO,
+            momy: Fixed::ZERO,
+            momz: Fixed::ZERO,
+            flags,
+            intflags: MobjIntFlag::empty(),
+            state: info.spawnstate,
+            tics: 0,
+            health: info.spawnhealth,
+            reactiontime: if configuration.game_skill != SkillLevel::Nm {
+                info.reactiontime
+            } else {
+                0
+            },
+            lastlook: 0,
+            // zeroed like vanilla's, which is east
+            movedir: DirType::East,
+            movecount: 0,
+            strafecount: 0,
+            threshold: 0,
+            pursuecount: 0,
+            friction: ORIG_FRICTION,
+            movefactor: ORIG_FRICTION_FACTOR,
+            gear: 0,
+            target: None,
+            tracer: None,
+            lastenemy: None,
+            player: None,
+            spawnpoint: None,
+        };
+        (mobj, info.spawnstate)
+    };
+    mobj.lastlook = p_random(configuration, PrClass::LastLook) % MAXPLAYERS as i32;
+
+    // not set_mobj_state, since action functions can't be called yet
+    let st = STATES.read()[spawnstate];
+    mobj.tics = st.tics;
+    mobj.sprite = st.sprite;
+    mobj.frame = st.frame;
+
+    let id = context.level.thinkers.add(Thinker::Mobj(mobj));
+    context.level.update_thinker_class(id);
+    set_thing_position(context, id);
+
+    let map = map(context.configuration);
+    let mobj = context.level.mobj_mut(id).unwrap();
+    let sector = &map.sectors[map.subsectors[mobj.subsector].sector];
+    mobj.floorz = sector.floor_height;
+    mobj.dropoffz = sector.floor_height;
+    mobj.ceilingz = sector.ceiling_height;
+    mobj.z = match z {
+        ONFLOORZ => mobj.floorz,
+        ONCEILINGZ => mobj.ceilingz - mobj.height,
+        z => z,
+    };
+    id
+}
+
+/// Take a mobj off the map and the thinker list, like vanilla's
+/// `P_RemoveMobj`. Its handle stops working, so anything still targeting
+/// it lets go.
+pub fn remove_mobj(context: &mut Context, id: ThinkerId) {
+    unset_thing_position(context, id);
+    context.level.thinkers.remove(id);
+    context.level.update_thinker_class(id);
+}
+
+/// Spawn player `n` at a player start, like vanilla's `P_SpawnPlayer`.
+/// Players who aren't in the game are left out.
+pub fn spawn_player(context: &mut Context, n: usize, start: &Thing) {
+    if !context
+        .configuration
+        .player_in_game
+        .get(n)
+        .copied()
+        .unwrap_or(false)
+    {
+        return;
+    }
+    if context.level.players[n].playerstate == PlayerState::Reborn {
+        context.level.players[n].reborn();
+    }
+
+    let x = Fixed::from_int(start.x as i32);
+    let y = Fixed::from_int(start.y as i32);
+    let id = spawn_mobj(context, x, y, ONFLOORZ, MobjType::PLAYER);
+    let health = context.level.players[n].health;
+    let mobj = context.level.mobj_mut(id).unwrap();
+    // set the color translation for the player's sprites
+    if n > 0 {
+        mobj.flags |= MobjFlag::from_bits_truncate((n as u64) << MobjFlag::TRANSSHIFT.bits);
+    }
+    mobj.angle = ANG45 * (start.angle as i32 / 45) as u32;
+    mobj.player = Some(n);
+    mobj.health = health;
+
+    let deathmatch = context.configuration.deathmatch != 0;
+    let player = &mut context.level.players[n];
+    player.mo = Some(id);
+    player.playerstate = PlayerState::Live;
+    player.message = None;
+    player.damagecount = 0;
+    player.bonuscount = 0;
+    player.extralight = 0;
+    player.viewheight = VIEWHEIGHT;
+    player.deltaviewheight = Fixed::ZERO;
+    // killough 10/98: initialize bobbing to 0
+    player.momx = Fixed::ZERO;
+    player.momy = Fixed::ZERO;
+
+    // give all cards in death match mode
+    if deathmatch {
+        player.cards = [true; NUMCARDS];
+    }
+
+    // setup gun psprite
+    super::pspr::setup_psprites(context, n);
+}
+
+/// Put a mobj into `state` and run its action, carrying on down the
+/// `nextstate` chain while the states take zero tics, like vanilla's
+/// `P_SetMobjState`. Returns false if the mobj is gone afterwards, either
+/// because it reached `S_NULL` or because an action removed it.
+///
+/// killough 4/9/98: a cycle of zero-tic states stops at the first repeat
+/// instead of hanging the game.
+pub fn set_mobj_state(context: &mut Context, id: ThinkerId, state: usize) -> bool {
+    let mut state = state;
+    let mut seen = vec![];
+    loop {
+        if state == Statenum::NULL as usize {
+            remove_mobj(context, id);
+            return false;
+        }
+        let st = STATES.read()[state];
+        let mobj = match context.level.mobj_mut(id) {
+            Some(mobj) => mobj,
+            None => return false,
+        };
+        mobj.state = state;
+        mobj.tics = st.tics;
+        mobj.sprite = st.sprite;
+        mobj.frame = st.frame;
+        seen.push(state);
+
+        if let ActionF::Mobj(action) = st.action {
+            action(context, id);
+        }
+        let tics = match context.level.mobj(id) {
+            Some(mobj) => mobj.tics,
+            None => return false,
+        };
+        state = st.nextstate;
+        if tics != 0 {
+            return true;
+        }
+        if seen.contains(&state) {
+            lprint!(OutputLevel::WARN, "Warning: State Cycle Detected\n");
+            return true;
+        }
+    }
+}
+
+/// The fastest a mobj can move in a tic.
+const MAXMOVE: Fixed = Fixed::from_int(30);
+/// Momentum below which things on the ground stop.
+const STOPSPEED: Fixed = Fixed(0x1000);
+const GRAVITY: Fixed = Fixed::UNIT;
+/// How fast floating monsters rise and sink towards their target.
+pub const FLOATSPEED: Fixed = Fixed::from_int(4);
+
+/// Move a mobj by its horizontal momentum, hitting and sliding along what
+/// it runs into, then apply friction, like vanilla's `P_XYMovement`.
+fn xy_movement(context: &mut Context, id: ThinkerId) {
+    let configuration = &*context.configuration;
+    let cl = configuration.compatibility_level;
+    let mbf = cl >= CompatibilityLevel::Mbf;
+    let comp_moveblock = configuration.comp_moveblock;
+    let variable_friction = configuration.variable_friction;
+
+    let mo = context.level.mobj_mut(id).unwrap();
+    if (mo.momx.0 | mo.momy.0) == 0 {
+        if mo.flags.contains(MobjFlag::SKULLFLY) {
+            // the skull slammed into something
+            mo.flags.remove(MobjFlag::SKULLFLY);
+            mo.momz = Fixed::ZERO;
+            let spawnstate = MOBJINFO.read()[&mo.mobj_type].spawnstate;
+            set_mobj_state(context, id, spawnstate);
+        }
+        return;
+    }
+
+    let player = mo.player;
+    mo.momx = mo.momx.max(-MAXMOVE).min(MAXMOVE);
+    mo.momy = mo.momy.max(-MAXMOVE).min(MAXMOVE);
+    let mut xmove = mo.momx;
+    let mut ymove = mo.momy;
+    // phares 9/10/98: to reduce bobbing and momentum on ice when up
+    // against a wall
+    let (oldx, oldy) = (mo.x, mo.y);
+
+    loop {
+        let mo = context.level.mobj(id).unwrap();
+        // killough 8/9/98: fix bug in original Doom source: Large negative
+        // displacements were never considered. This explains the tendency
+        // for Mancubus fireballs to pass through walls.
+        let (ptryx, ptryy) = if xmove > MAXMOVE / 2
+            || ymove > MAXMOVE / 2
+            || (!comp_moveblock && (xmove < -MAXMOVE / 2 || ymove < -MAXMOVE / 2))
+        {
+            let ptry = (mo.x + xmove / 2, mo.y + ymove / 2);
+            xmove = xmove >> 1;
+            ymove = ymove >> 1;
+            ptry
+        } else {
+            let ptry = (mo.x + xmove, mo.y + ymove);
+            xmove = Fixed::ZERO;
+            ymove = Fixed::ZERO;
+            ptry
+        };
+
+        // killough 3/15/98: Allow objects to drop off
+        if !try_move(context, id, ptryx, ptryy, DropOff::Yes) {
+            // blocked move
+            let blockline = context.level.check.blockline;
+            let ceilingline = context.level.check.ceilingline;
+            let mo = context.level.mobj(id).unwrap();
+            let flags = mo.flags;
+
+            // killough 8/11/98: bouncing off walls
+            // killough 10/98: Add ability for objects other than players
+            // to bounce on ice
+            if !flags.contains(MobjFlag::MISSILE)
+                && mbf
+                && (flags.contains(MobjFlag::BOUNCES)
+                    || (player.is_none()
+                        && blockline.is_some()
+                        && variable_friction
+                        && mo.z <= mo.floorz
+                        && get_friction(context, id).friction > ORIG_FRICTION))
+            {
+                let map = map(context.configuration);
+                let mo = context.level.mobj_mut(id).unwrap();
+                if let Some(line) = blockline {
+                    let ld = &map.lines[line];
+                    let (dx, dy) = (ld.dx.to_int(), ld.dy.to_int());
+                    let r = Fixed(
+                        dx.wrapping_mul(mo.momx.0)
+                            .wrapping_add(dy.wrapping_mul(mo.momy.0))
+                            .checked_div(dx.wrapping_mul(dx).wrapping_add(dy.wrapping_mul(dy)))
+                            .unwrap_or(0),
+                    );
+                    let x = fixed_mul(r, ld.dx);
+                    let y = fixed_mul(r, ld.dy);
+
+                    // reflect momentum away from wall
+                    mo.momx = x * 2 - mo.momx;
+                    mo.momy = y * 2 - mo.momy;
+
+                    // if under gravity, slow down in direction
+                    // perpendicular to wall.
+                    if !flags.contains(MobjFlag::NOGRAVITY) {
+                        mo.momx = (mo.momx + x) / 2;
+                        mo.momy = (mo.momy + y) / 2;
+                    }
+                } else {
+                    mo.momx = Fixed::ZERO;
+                    mo.momy = Fixed::ZERO;
+                }
+            } else if player.is_some() {
+                // try to slide along it
+                slide_move(context, id);
+            } else if flags.contains(MobjFlag::MISSILE) {
+                // explode a missile
+                let map = map(context.configuration);
+                let sky_line = ceilingline
+                    .and_then(|line| map.lines[line].back_sector)
+                    .map(|back| &map.sectors[back])
+                    .filter(|back| is_sky(&back.ceiling_pic));
+                if let Some(back) = sky_line {
+                    // Hack to prevent missiles exploding against the sky.
+                    // Does not handle sky floors.
+                    if cl < CompatibilityLevel::Boom || mo.z > back.ceiling_height {
+                        remove_mobj(context, id);
+                        return;
+                    }
+                }
+                explode_missile(context, id);
+            } else {
+                // whatever else it is, it is now standing still in (x,y)
+                let mo = context.level.mobj_mut(id).unwrap();
+                mo.momx = Fixed::ZERO;
+                mo.momy = Fixed::ZERO;
+            }
+        }
+
+        if (xmove.0 | ymove.0) == 0 {
+            break;
+        }
+    }
+
+    let map = map(context.configuration);
+    let mo = match context.level.mobj(id) {
+        Some(mo) => mo,
+        None => return,
+    };
+
+    // slow down
+
+    // no friction for missiles or skulls ever, no friction when airborne
+    if mo.flags.intersects(MobjFlag::MISSILE | MobjFlag::SKULLFLY) || mo.z > mo.floorz {
+        return;
+    }
+
+    // killough 8/11/98: add bouncers
+    // killough 9/15/98: add objects falling off ledges
+    // killough 11/98: only include bouncers hanging off ledges
+    let quarter = Fixed::UNIT / 4;
+    if ((mo.flags.contains(MobjFlag::BOUNCES) && mo.z > mo.dropoffz)
+        || mo.flags.contains(MobjFlag::CORPSE)
+        || mo.intflags.contains(MobjIntFlag::FALLING))
+        && (mo.momx > quarter || mo.momx < -quarter || mo.momy > quarter || mo.momy < -quarter)
+        && mo.floorz != map.sectors[map.subsectors[mo.subsector].sector].floor_height
+    {
+        // do not stop sliding if halfway off a step with some momentum
+        return;
+    }
+
+    let player_mo = player.and_then(|n| context.level.players[n].mo);
+    let moving = player.is_some_and(|n| {
+        let cmd = &context.level.players[n].cmd;
+        (cmd.forwardmove | cmd.sidemove) != 0
+    });
+
+    // killough 11/98: Stop voodoo dolls that have come to rest, despite
+    // any moving corresponding player, except in old demos
+    if mo.momx > -STOPSPEED
+        && mo.momx < STOPSPEED
+        && mo.momy > -STOPSPEED
+        && mo.momy < STOPSPEED
+        && (!moving || (player_mo != Some(id) && cl >= CompatibilityLevel::LxDoomV1))
+    {
+        // if in a walking frame, stop moving
+        // killough 10/98: Don't affect main player when voodoo dolls stop,
+        // except in old demos
+        if let Some(player_mo) = player_mo {
+            if player_mo == id || cl >= CompatibilityLevel::LxDoomV1 {
+                let state = context.level.mobj(player_mo).map(|mo| mo.state);
+                let run1 = Statenum::PLAY_RUN1 as usize;
+                if state.is_some_and(|state| state.wrapping_sub(run1) < 4) {
+                    set_mobj_state(context, player_mo, Statenum::PLAY as usize);
+                }
+            }
+        }
+
+        if let Some(mo) = context.level.mobj_mut(id) {
+            mo.momx = Fixed::ZERO;
+            mo.momy = Fixed::ZERO;
+        }
+
+        // killough 10/98: kill any bobbing momentum too (except in voodoo
+        // dolls)
+        if player_mo == Some(id) {
+            let player = &mut context.level.players[player.unwrap()];
+            player.momx = Fixed::ZERO;
+            player.momy = Fixed::ZERO;
+        }
+        return;
+    }
+
+    // phares 3/17/98: Friction will have been adjusted by friction thinkers
+    // for icy or muddy floors. Otherwise it was never touched and remained
+    // set at ORIG_FRICTION.
+    // killough 8/28/98: removed inefficient thinker algorithm, instead
+    // using touching_sectorlist in P_GetFriction() to determine friction
+    // (and thus only when it is needed).
+    // killough 10/98: changed to work with new bobbing method. Reducing
+    // player momentum is no longer needed to reduce bobbing, so ice works
+    // much better now.
+    if cl <= CompatibilityLevel::LxDoomV1 {
+        let mo = context.level.mobj_mut(id).unwrap();
+        // phares 9/10/98: reduce bobbing/momentum when on ice & up against
+        // wall, by using original friction if you didn't go anywhere
+        let friction = if cl > CompatibilityLevel::BoomV201 && mo.x == oldx && mo.y == oldy {
+            ORIG_FRICTION
+        } else {
+            mo.friction
+        };
+        mo.momx = fixed_mul(mo.momx, friction);
+        mo.momy = fixed_mul(mo.momy, friction);
+        // reset to normal for next tic
+        mo.friction = ORIG_FRICTION;
+    } else {
+        let friction = get_friction(context, id).friction;
+        let mo = context.level.mobj_mut(id).unwrap();
+        mo.momx = fixed_mul(mo.momx, friction);
+        mo.momy = fixed_mul(mo.momy, friction);
+
+        // killough 10/98: Always decrease player bobbing by ORIG_FRICTION.
+        // This prevents problems with bobbing on ice, where it was not
+        // being reduced fast enough, leading to all sorts of kludges being
+        // developed.
+        if player_mo == Some(id) {
+            let player = &mut context.level.players[player.unwrap()];
+            player.momx = fixed_mul(player.momx, ORIG_FRICTION);
+            player.momy = fixed_mul(player.momy, ORIG_FRICTION);
+        }
+    }
+}
+
+/// Move a mobj by its vertical momentum, apply gravity, and stop it at the
+/// floor and ceiling, like vanilla's `P_ZMovement`.
+fn z_movement(context: &mut Context, id: ThinkerId) {
+    let configuration = &*context.configuration;
+    let cl = configuration.compatibility_level;
+    let comp_soul = configuration.comp_soul;
+
+    // killough 7/11/98: BFG fireballs bounced on floors and ceilings in
+    // Pre-Beta Doom
+    // killough 8/9/98: added support for non-missile objects bouncing
+    // (e.g. grenade, mine, pipebomb)
+    let bouncing = context
+        .level
+        .mobj(id)
+        .is_some_and(|mo| mo.flags.contains(MobjFlag::BOUNCES) && mo.momz != Fixed::ZERO);
+    if bouncing {
+        if !bounce_z_movement(context, id) {
+            return;
+        }
+        let sentient_floater = context
+            .level
+            .mobj(id)
+            .is_some_and(|mo| mo.flags.contains(MobjFlag::FLOAT) && mo.sentient());
+        if !sentient_floater {
+            return;
+        }
+    } else {
+        let (player, z, floorz) = {
+            let mo = context.level.mobj(id).unwrap();
+            (mo.player, mo.z, mo.floorz)
+        };
+        // check for smooth step up
+        if let Some(n) = player {
+            let player = &mut context.level.players[n];
+            // killough 5/12/98: exclude voodoo dolls
+            if player.mo == Some(id) && z < floorz {
+                player.viewheight -= floorz - z;
+                player.deltaviewheight = (VIEWHEIGHT - player.viewheight) >> 3;
+            }
+        }
+        // adjust altitude
+        let mo = context.level.mobj_mut(id).unwrap();
+        mo.z += mo.momz;
+    }
+
+    // float down towards target if too close
+    let target = context
+        .level
+        .mobj(id)
+        .and_then(|mo| mo.target)
+        .and_then(|target| context.level.mobj(target))
+        .map(|target| (target.x, target.y, target.z));
+    let mo = context.level.mobj_mut(id).unwrap();
+    if let Some((tx, ty, tz)) = target {
+        // killough 11/98: simplify
+        if ((mo.flags ^ MobjFlag::FLOAT)
+            & (MobjFlag::FLOAT | MobjFlag::SKULLFLY | MobjFlag::INFLOAT))
+            .is_empty()
+        {
+            let delta = tz + (mo.height >> 1) - mo.z;
+            if approx_distance(mo.x - tx, mo.y - ty) < delta.abs() * 3 {
+                if delta < Fixed::ZERO {
+                    mo.z -= FLOATSPEED;
+                } else {
+                    mo.z += FLOATSPEED;
+                }
+            }
+        }
+    }
+
+    // clip movement
+    if mo.z <= mo.floorz {
+        // hit the floor
+
+        // cph 2001/04/15: charging lost souls were meant to bounce off
+        // floors, but doom2.exe sets momz to 0 first, so only later
+        // versions get this, or comp_soul turned off.
+        if mo.flags.contains(MobjFlag::SKULLFLY)
+            && (!comp_soul
+                || (cl > CompatibilityLevel::Doom2V19 && cl < CompatibilityLevel::PrBoomV23x))
+        {
+            // the skull slammed into something
+            mo.momz = -mo.momz;
+        }
+
+        if mo.momz < Fixed::ZERO {
+            let (momz, health, player) = (mo.momz, mo.health, mo.player);
+            // killough 11/98: touchy objects explode on impact
+            if mo.flags.contains(MobjFlag::TOUCHY)
+                && mo.intflags.contains(MobjIntFlag::ARMED)
+                && health > 0
+            {
+                damage_mobj(context, id, None, None, health);
+            } else if let Some(n) = player {
+                // killough 5/12/98: exclude voodoo dolls
+                let player = &mut context.level.players[n];
+                if player.mo == Some(id) && momz < -GRAVITY * 8 {
+                    // Squat down. Decrease viewheight for a moment after
+                    // hitting the ground (hard).
+                    player.deltaviewheight = momz >> 3;
+                }
+            }
+            let mo = match context.level.mobj_mut(id) {
+                Some(mo) => mo,
+                None => return,
+            };
+            mo.momz = Fixed::ZERO;
+        }
+        let mo = match context.level.mobj_mut(id) {
+            Some(mo) => mo,
+            None => return,
+        };
+        mo.z = mo.floorz;
+
+        // cph 2001/04/15: doom2.exe's buggy lost soul bounce, which might
+        // still reverse upward momentum
+        if mo.flags.contains(MobjFlag::SKULLFLY) && cl <= CompatibilityLevel::Doom2V19 {
+            mo.momz = -mo.momz;
+        }
+
+        if mo.flags.contains(MobjFlag::MISSILE) && !mo.flags.contains(MobjFlag::NOCLIP) {
+            explode_missile(context, id);
+            return;
+        }
+    } else if !mo.flags.contains(MobjFlag::NOGRAVITY) {
+        // still above the floor
+        if mo.momz == Fixed::ZERO {
+            mo.momz = -GRAVITY;
+        }
+        mo.momz -= GRAVITY;
+    }
+
+    let mo = context.level.mobj_mut(id).unwrap();
+    if mo.z + mo.height > mo.ceilingz {
+        // cph 2001/04/15: lost souls were meant to bounce off of ceilings
+        if !comp_soul && mo.flags.contains(MobjFlag::SKULLFLY) {
+            // the skull slammed into something
+            mo.momz = -mo.momz;
+        }
+
+        // hit the ceiling
+        if mo.momz > Fixed::ZERO {
+            mo.momz = Fixed::ZERO;
+        }
+        mo.z = mo.ceilingz - mo.height;
+
+        // cph 2001/04/15: we might have hit a ceiling but had downward
+        // momentum (e.g. ceiling is lowering on us), so for old demos we
+        // must still do the buggy momentum reversal here
+        if comp_soul && mo.flags.contains(MobjFlag::SKULLFLY) {
+            mo.momz = -mo.momz;
+        }
+
+        if mo.flags.contains(MobjFlag::MISSILE) && !mo.flags.contains(MobjFlag::NOCLIP) {
+            explode_missile(context, id);
+        }
+    }
+}
+
+/// killough 8/9/98: the vertical movement of bouncing things. Returns
+/// true if a sentient floater should go on to float towards its target,
+/// as vanilla's `goto floater` does.
+fn bounce_z_movement(context: &mut Context, id: ThinkerId) -> bool {
+    let map = map(context.configuration);
+    let ceilingline = context.level.check.ceilingline;
+    let mo = context.level.mobj_mut(id).unwrap();
+    let mass = MOBJINFO.read()[&mo.mobj_type].mass;
+    let floater = mo.flags.contains(MobjFlag::FLOAT) && mo.sentient();
+    mo.z += mo.momz;
+
+    if mo.z <= mo.floorz {
+        // bounce off floors
+        mo.z = mo.floorz;
+        if mo.momz < Fixed::ZERO {
+            mo.momz = -mo.momz;
+            if !mo.flags.contains(MobjFlag::NOGRAVITY) {
+                // bounce back with decay; floaters fall slowly, at a rate
+                // set by DROPOFF
+                let decay = if mo.flags.contains(MobjFlag::FLOAT) {
+                    if mo.flags.contains(MobjFlag::DROPOFF) {
+                        // FRACUNIT * .85
+                        Fixed(55705)
+                    } else {
+                        // FRACUNIT * .70
+                        Fixed(45875)
+                    }
+                } else {
+                    // FRACUNIT * .45
+                    Fixed(29491)
+                };
+                mo.momz = fixed_mul(mo.momz, decay);
+
+                // Bring it to rest below a certain speed
+                if mo.momz.abs() <= GRAVITY * 4 / 256 * mass {
+                    mo.momz = Fixed::ZERO;
+                }
+            }
+
+            // killough 11/98: touchy objects explode on impact
+            if mo.flags.contains(MobjFlag::TOUCHY)
+                && mo.intflags.contains(MobjIntFlag::ARMED)
+                && mo.health > 0
+            {
+                let health = mo.health;
+                damage_mobj(context, id, None, None, health);
+                return false;
+            }
+            return floater;
+        }
+    } else if mo.z >= mo.ceilingz - mo.height {
+        // bounce off ceilings
+        mo.z = mo.ceilingz - mo.height;
+        if mo.momz > Fixed::ZERO {
+            let sector = &map.sectors[map.subsectors[mo.subsector].sector];
+            if !is_sky(&sector.ceiling_pic) {
+                // always bounce off non-sky ceiling
+                mo.momz = -mo.momz;
+            } else if mo.flags.contains(MobjFlag::MISSILE) {
+                // missiles don't bounce off skies
+                remove_mobj(context, id);
+            } else if mo.flags.contains(MobjFlag::NOGRAVITY) {
+                // bounce unless under gravity
+                mo.momz = -mo.momz;
+            }
+            return floater;
+        }
+    } else {
+        // free-fall under gravity
+        if !mo.flags.contains(MobjFlag::NOGRAVITY) {
+            mo.momz -= GRAVITY / 256 * mass;
+        }
+        return floater;
+    }
+
+    // came to a stop
+    mo.momz = Fixed::ZERO;
+    if mo.flags.contains(MobjFlag::MISSILE) {
+        let sky_line = ceilingline
+            .and_then(|line| map.lines[line].back_sector)
+            .map(|back| &map.sectors[back])
+            .filter(|back| is_sky(&back.ceiling_pic) && mo.z > back.ceiling_height);
+        if sky_line.is_some() {
+            // don't explode on skies
+            remove_mobj(context, id);
+        } else {
+            explode_missile(context, id);
+        }
+    }
+    floater
+}
+
+/// Blow up a missile where it is, like vanilla's `P_ExplodeMissile`.
+pub fn explode_missile(context: &mut Context, id: ThinkerId) {
+    let deathstate = match context.level.mobj_mut(id) {
+        Some(mo) => {
+            mo.momx = Fixed::ZERO;
+            mo.momy = Fixed::ZERO;
+            mo.momz = Fixed::ZERO;
+            MOBJINFO.read()[&mo.mobj_type].deathstate
+        }
+        None => return,
+    };
+    set_mobj_state(context, id, deathstate);
+
+    let random = p_random(context.configuration, PrClass::Explode) as i64;
+    if let Some(mo) = context.level.mobj_mut(id) {
+        mo.tics = (mo.tics - (random & 3)).max(1);
+        mo.flags.remove(MobjFlag::MISSILE);
+    }
+}
+
+/// Put up a puff of smoke where a shot hit, like vanilla's `P_SpawnPuff`.
+/// A shot of `attackrange` `MELEERANGE`, i.e. a punch, doesn't spark.
+pub fn spawn_puff(context: &mut Context, x: Fixed, y: Fixed, z: Fixed, attackrange: Fixed) {
+    // killough 5/5/98: remove dependence on order of evaluation
+    let z = z + Fixed(p_sub_random(context.configuration, PrClass::SpawnPuff) << 10);
+    let th = spawn_mobj(context, x, y, z, MobjType::PUFF);
+    let random = p_random(context.configuration, PrClass::SpawnPuff) as i64;
+    let mo = context.level.mobj_mut(th).unwrap();
+    mo.momz = Fixed::UNIT;
+    mo.tics = (mo.tics - (random & 3)).max(1);
+
+    // don't make punches spark on the wall
+    if attackrange == MELEERANGE {
+        set_mobj_state(context, th, Statenum::PUFF3 as usize);
+    }
+}
+
+/// Splash blood where a shot hit something, less of it for less damage,
+/// like vanilla's `P_SpawnBlood`.
+pub fn spawn_blood(context: &mut Context, x: Fixed, y: Fixed, z: Fixed, damage: i32) {
+    // killough 5/5/98: remove dependence on order of evaluation
+    let z = z + Fixed(p_sub_random(context.configuration, PrClass::SpawnBlood) << 10);
+    let th = spawn_mobj(context, x, y, z, MobjType::BLOOD);
+    let random = p_random(context.configuration, PrClass::SpawnBlood) as i64;
+    let mo = context.level.mobj_mut(th).unwrap();
+    mo.momz = Fixed::from_int(2);
+    mo.tics = (mo.tics - (random & 3)).max(1);
+
+    if (9..=12).contains(&damage) {
+        set_mobj_state(context, th, Statenum::BLOOD2 as usize);
+    } else if damage < 9 {
+        set_mobj_state(context, th, Statenum::BLOOD3 as usize);
+    }
+}
+
+/// Start a missile off a little way along its path, and blow it up if
+/// it's already in a wall, like vanilla's `P_CheckMissileSpawn`.
+pub fn check_missile_spawn(context: &mut Context, th: ThinkerId) {
+    let mbf = context.configuration.compatibility_level >= CompatibilityLevel::Mbf;
+    let random = p_random(context.configuration, PrClass::Missile) as i64;
+    let mo = context.level.mobj_mut(th).unwrap();
+    mo.tics = (mo.tics - (random & 3)).max(1);
+
+    // move a little forward so an angle can be computed if it immediately
+    // explodes
+    mo.x += mo.momx >> 1;
+    mo.y += mo.momy >> 1;
+    mo.z += mo.momz >> 1;
+
+    // killough 8/12/98: for non-missile objects (e.g. grenades)
+    if !mo.flags.contains(MobjFlag::MISSILE) && mbf {
+        return;
+    }
+
+    // killough 3/15/98: no dropoff (really = don't care for missiles)
+    let (x, y) = (mo.x, mo.y);
+    if !try_move(context, th, x, y, DropOff::No) {
+        explode_missile(context, th);
+    }
+}
+
+/// Fire a missile of `mobj_type` from `source` at `dest`, like vanilla's
+/// `P_SpawnMissile`. Returns the missile, or `None` if it was removed as it
+/// spawned.
+pub fn spawn_missile(
+    context: &mut Context,
+    source: ThinkerId,
+    dest: ThinkerId,
+    mobj_type: MobjType,
+) -> Option<ThinkerId> {
+    let (x, y, z, shadow) = {
+        let dest = context.level.mobj(dest)?;
+        (
+            dest.x,
+            dest.y,
+            dest.z,
+            dest.flags.contains(MobjFlag::SHADOW),
+        )
+    };
+    spawn_missile_at(context, source, x, y, z, shadow, mobj_type)
+}
+
+/// Fire a missile of `mobj_type` from `source` at the point `(x, y, z)`,
+/// aiming off a bit if what's there is `shadow`, the rest of
+/// `spawn_missile`. MBF's `A_Mushroom` aims at points around the source.
+pub fn spawn_missile_at(
+    context: &mut Context,
+    source: ThinkerId,
+    x: Fixed,
+    y: Fixed,
+    z: Fixed,
+    shadow: bool,
+    mobj_type: MobjType,
+) -> Option<ThinkerId> {
+    let (sx, sy, sz) = {
+        let mo = context.level.mobj(source)?;
+        (mo.x, mo.y, mo.z)
+    };
+    let th = spawn_mobj(context, sx, sy, sz + Fixed::from_int(4 * 8), mobj_type);
+    let (seesound, speed) = {
+        let mobjinfo = MOBJINFO.read();
+        let info = &mobjinfo[&mobj_type];
+        (info.seesound, info.speed)
+    };
+    context.level.start_sound(Some(th), seesound);
+
+    let mut an = point_to_angle2(sx, sy, x, y);
+    // fuzzy player
+    if shadow {
+        // killough 5/5/98: remove dependence on order of evaluation
+        an += Angle((p_sub_random(context.configuration, PrClass::Shadow) << 20) as u32);
+    }
+    let dist = (approx_distance(x - sx, y - sy).0 / speed).max(1);
+    let mo = context.level.mobj_mut(th).unwrap();
+    // where it came from
+    mo.target = Some(source);
+    mo.angle = an;
+    mo.momx = fixed_mul(Fixed(speed), an.cos());
+    mo.momy = fixed_mul(Fixed(speed), an.sin());
+    mo.momz = Fixed((z - sz).0 / dist);
+    check_missile_spawn(context, th);
+    context.level.mobj(th).map(|_| th)
+}
+
+/// Fire a missile of `mobj_type` from a player's mobj, aimed up or down at
+/// whatever is in front of it, like vanilla's `P_SpawnPlayerMissile`.
+pub fn spawn_player_missile(context: &mut Context, source: ThinkerId, mobj_type: MobjType) {
+    let mbf = context.configuration.compatibility_level >= CompatibilityLevel::Mbf;
+    // killough 8/2/98: prefer autoaiming at enemies
+    let mask = if mbf {
+        MobjFlag::FRIEND
+    } else {
+        MobjFlag::empty()
+    };
+    let (an, slope) = autoaim(context, source, mask);
+
+    let (x, y, z) = match context.level.mobj(source) {
+        Some(mo) => (mo.x, mo.y, mo.z + Fixed::from_int(4 * 8)),
+        None => return,
+    };
+    let th = spawn_mobj(context, x, y, z, mobj_type);
+    let (seesound, speed) = {
+        let mobjinfo = MOBJINFO.read();
+        let info = &mobjinfo[&mobj_type];
+        (info.seesound, Fixed(info.speed))
+    };
+    context.level.start_sound(Some(th), seesound);
+
+    let mo = context.level.mobj_mut(th).unwrap();
+    mo.target = Some(source);
+    mo.angle = an;
+    mo.momx = fixed_mul(speed, an.cos());
+    mo.momy = fixed_mul(speed, an.sin());
+    mo.momz = fixed_mul(speed, slope);
+    check_missile_spawn(context, th);
+}
+
+/// Find what a player firing from `source` should aim at: straight ahead,
+/// or a little to either side, like the search in vanilla's
+/// `P_SpawnPlayerMissile`. Returns the angle and slope to fire at, which
+/// are straight ahead and level if there's nothing to hit. Things sharing
+/// flags in `mask` with the shooter are only aimed at if nothing else is
+/// there.
+pub fn autoaim(context: &mut Context, source: ThinkerId, mask: MobjFlag) -> (Angle, Fixed) {
+    let angle = match context.level.mobj(source) {
+        Some(mo) => mo.angle,
+        None => return (Angle(0), Fixed::ZERO),
+    };
+    let range = Fixed::from_int(16 * 64);
+    let mut mask = mask;
+    loop {
+        let mut an = angle;
+        let mut aim = aim_line_attack(context, source, an, range, mask);
+        if aim.linetarget.is_none() {
+            an += Angle(1 << 26);
+            aim = aim_line_attack(context, source, an, range, mask);
+        }
+        if aim.linetarget.is_none() {
+            an -= Angle(2 << 26);
+            aim = aim_line_attack(context, source, an, range, mask);
+        }
+        if aim.linetarget.is_some() {
+            return (an, aim.slope);
+        }
+        // killough 8/2/98: try again without the mask
+        if mask.is_empty() {
+            return (angle, Fixed::ZERO);
+        }
+        mask = MobjFlag::empty();
+    }
+}
+
+/// A mobj's think: move it, then count down its state, like vanilla's
+/// `P_MobjThinker`.
+pub fn mobj_thinker(context: &mut Context, id: ThinkerId) {
+    let comp_falloff = context.configuration.comp_falloff;
+
+    // momentum movement
+    let mo = context.level.mobj(id).unwrap();
+    if (mo.momx.0 | mo.momy.0) != 0 || mo.flags.contains(MobjFlag::SKULLFLY) {
+        xy_movement(context, id);
+        if context.level.mobj(id).is_none() {
+            // killough - mobj was removed
+            return;
+        }
+    }
+
+    let mo = context.level.mobj_mut(id).unwrap();
+    if mo.z != mo.floorz || mo.momz != Fixed::ZERO {
+        z_movement(context, id);
+        if context.level.mobj(id).is_none() {
+            // killough - mobj was removed
+            return;
+        }
+    } else if (mo.momx.0 | mo.momy.0) == 0 && !mo.sentient() {
+        // non-sentient objects at rest
+        // arm a mine which has come to rest
+        mo.intflags.insert(MobjIntFlag::ARMED);
+
+        // killough 9/12/98: objects fall off ledges if they are hanging
+        // off slightly push off of ledge if hanging more than halfway off
+        if mo.z > mo.dropoffz && !mo.flags.contains(MobjFlag::NOGRAVITY) && !comp_falloff {
+            apply_torque(context, id);
+        } else {
+            // Reset torque
+            mo.intflags.remove(MobjIntFlag::FALLING);
+            mo.gear = 0;
+        }
+    }
+
+    // cycle through states, calling action functions at transitions
+    let configuration = &*context.configuration;
+    let respawnmonsters = configuration.game_skill == SkillLevel::Nm || configuration.respawnparm;
+    let leveltime = context.level.leveltime;
+    let mo = context.level.mobj_mut(id).unwrap();
+    if mo.tics != -1 {
+        mo.tics -= 1;
+        // you can cycle through multiple states in a tic
+        if mo.tics == 0 {
+            let next = STATES.read()[mo.state].nextstate;
+            set_mobj_state(context, id, next);
+        }
+    } else if mo.flags.contains(MobjFlag::COUNTKILL) && respawnmonsters {
+        // check for nightmare respawn
+        mo.movecount += 1;
+        if mo.movecount >= 12 * 35
+            && leveltime & 31 == 0
+            && p_random(context.configuration, PrClass::Respawn) <= 4
+        {
+            nightmare_respawn(context, id);
+        }
+    }
+}
+
+/// Bring a dead monster back at its spawn spot, in a flash of teleport
+/// fog, like vanilla's `P_NightmareRespawn`. Nothing happens if something
+/// is in the way.
+fn nightmare_respawn(context: &mut Context, id: ThinkerId) {
+    let comp_respawn = context.configuration.comp_respawn;
+    let mobj = context.level.mobj(id).unwrap();
+    let (spawnpoint, mobj_type, flags) = (mobj.spawnpoint, mobj.mobj_type, mobj.flags);
+    let mthing = spawnpoint.unwrap_or(Thing {
+        x: 0,
+        y: 0,
+        angle: 0,
+        kind: 0,
+        options: 0,
+    });
+    let mut x = Fixed::from_int(mthing.x as i32);
+    let mut y = Fixed::from_int(mthing.y as i32);
+
+    // haleyjd: stupid nightmare respawning bug fix
+    // cph: like the rest of comp_*, comp_respawn disables the fix. Things
+    // that weren't on the map at level start would otherwise respawn at
+    // (0,0).
+    if !comp_respawn && x == Fixed::ZERO && y == Fixed::ZERO {
+        // spawnpoint was zeroed out, so use point of death instead
+        x = mobj.x;
+        y = mobj.y;
+    }
+
+    // something is occupying its position?
+    if !check_position(context, id, x, y) {
+        // no respawn
+        return;
+    }
+
+    // spawn a teleport fog at old spot because of removal of the body?
+    let map = map(context.configuration);
+    let mobj = context.level.mobj(id).unwrap();
+    let (oldx, oldy) = (mobj.x, mobj.y);
+    let oldfloor = map.sectors[map.subsectors[mobj.subsector].sector].floor_height;
+    let floor = map.sectors[map.subsectors[point_in_subsector(map, x, y)].sector].floor_height;
+    let fog = spawn_mobj(context, oldx, oldy, oldfloor, MobjType::TFOG);
+    // initiate teleport sound
+    context.level.start_sound(Some(fog), Sfx::TELEPT as usize);
+
+    // spawn a teleport fog at the new spot
+    let fog = spawn_mobj(context, x, y, floor, MobjType::TFOG);
+    context.level.start_sound(Some(fog), Sfx::TELEPT as usize);
+
+    // spawn the new monster
+    let info_flags = MobjFlag::from_bits_truncate(MOBJINFO.read()[&mobj_type].flags);
+    let z = if info_flags.contains(MobjFlag::SPAWNCEILING) {
+        ONCEILINGZ
+    } else {
+        ONFLOORZ
+    };
+
+    // inherit attributes from deceased one
+    let mo = spawn_mobj(context, x, y, z, mobj_type);
+    let new = context.level.mobj_mut(mo).unwrap();
+    new.spawnpoint = spawnpoint;
+    new.angle = ANG45 * (mthing.angle as i32 / 45) as u32;
+    if ThingFlags::from_bits_truncate(mthing.options).contains(ThingFlags::AMBUSH) {
+        new.flags.insert(MobjFlag::AMBUSH);
+    }
+    // killough 11/98: transfer friendliness from deceased
+    let friend = new.flags.contains(MobjFlag::FRIEND);
+    new.flags
+        .set(MobjFlag::FRIEND, flags.contains(MobjFlag::FRIEND));
+    new.reactiontime = 18;
+    if friend != flags.contains(MobjFlag::FRIEND) {
+        context.level.update_thinker_class(mo);
+    }
+
+    // remove the old monster
+    remove_mobj(context, id);
+}
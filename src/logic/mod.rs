@@ -1 +1,189 @@
+use crate::info::MobjType;
+use crate::{
+    configuration::Configuration,
+    map::{Map, Thing},
+    think::{self, ThinkerId, Thinkers},
+};
+use enemy::{Brain, SectorSound};
+use mobj::{Mobj, MobjFlag};
+use movement::{Friction, PositionCheck};
+use player::{Player, MAXPLAYERS};
+
+pub mod enemy;
+pub mod inter;
+pub mod maputl;
 pub mod mobj;
+pub mod movement;
+pub mod player;
+pub mod pspr;
+pub mod setup;
+pub mod sight;
+pub mod spec;
+pub mod telept;
+
+/// Anything that can be on the thinker list.
+pub enum Thinker {
+    Mobj(Mobj),
+}
+
+/// The play state of the level being played.
+#[derive(Default)]
+pub struct Level {
+    pub thinkers: Thinkers<Thinker>,
+    pub players: Vec<Player>,
+    /// The mobjs in each blockmap block, newest last.
+    pub blocklinks: Vec<Vec<ThinkerId>>,
+    /// The mobjs in each sector, newest last.
+    pub sector_things: Vec<Vec<ThinkerId>>,
+    /// Where each player starts, if the map has a start for them.
+    pub player_starts: [Option<Thing>; MAXPLAYERS],
+    pub deathmatch_starts: Vec<Thing>,
+    pub total_kills: i32,
+    pub total_items: i32,
+    /// Bumped to mark lines as checked once per blockmap search.
+    pub validcount: i32,
+    pub line_validcount: Vec<i32>,
+    /// What the last position check found.
+    pub check: PositionCheck,
+    /// Boom's friction in each sector, set by friction linedefs.
+    pub sector_friction: Vec<Friction>,
+    /// What each sector last heard.
+    pub sector_sound: Vec<SectorSound>,
+    /// Which way the ceiling mover in each sector is going: 1 up, -1 down,
+    /// 0 for none or one in stasis.
+    pub sector_ceiling_direction: Vec<i32>,
+    /// Whether the floor mover in each sector is a lift, like vanilla's
+    /// `floordata` thinker being a `T_PlatRaise`.
+    pub sector_lift: Vec<bool>,
+    /// killough 8/29/98: the living monsters on each side, in the order
+    /// monsters search them for targets.
+    pub friends: Vec<ThinkerId>,
+    pub enemies: Vec<ThinkerId>,
+    /// Tics since the level started.
+    pub leveltime: i32,
+    /// Set when the level has been finished, like vanilla's `gameaction`
+    /// of `ga_completed`, for the game loop to act on.
+    pub exit_level: bool,
+    /// Whether it was finished through the exit to the secret level.
+    pub secret_exit: bool,
+    /// The boss brain's spawn spots, and where it's aiming next.
+    pub brain: Brain,
+}
+
+impl Level {
+    /// An empty level for `map`, with nothing spawned yet.
+    pub fn new(map: &Map) -> Self {
+        Self {
+            players: (0..MAXPLAYERS).map(|_| Player::default()).collect(),
+            blocklinks: vec![vec![]; map.blockmap.blocks.len()],
+            sector_things: vec![vec![]; map.sectors.len()],
+            line_validcount: vec![0; map.lines.len()],
+            sector_friction: vec![Friction::default(); map.sectors.len()],
+            sector_sound: vec![SectorSound::default(); map.sectors.len()],
+            sector_ceiling_direction: vec![0; map.sectors.len()],
+            sector_lift: vec![false; map.sectors.len()],
+            ..Self::default()
+        }
+    }
+
+    /// The mobj `id` refers to, if it's still on the thinker list.
+    pub fn mobj(&self, id: ThinkerId) -> Option<&Mobj> {
+        match self.thinkers.get(id) {
+            Some(Thinker::Mobj(mobj)) => Some(mobj),
+            None => None,
+        }
+    }
+
+    pub fn mobj_mut(&mut self, id: ThinkerId) -> Option<&mut Mobj> {
+        match self.thinkers.get_mut(id) {
+            Some(Thinker::Mobj(mobj)) => Some(mobj),
+            None => None,
+        }
+    }
+
+    /// The friends list for friendly mobjs, the enemies list otherwise.
+    pub fn class_list(&mut self, friend: bool) -> &mut Vec<ThinkerId> {
+        if friend {
+            &mut self.friends
+        } else {
+            &mut self.enemies
+        }
+    }
+
+    /// Put a mobj on the end of the friends or enemies list if it's a
+    /// living monster, taking it off the other, like MBF's
+    /// `P_UpdateThinker`. Call it when a mobj is added or removed, or
+    /// changes sides.
+    pub fn update_thinker_class(&mut self, id: ThinkerId) {
+        self.friends.retain(|&th| th != id);
+        self.enemies.retain(|&th| th != id);
+        let friend = match self.mobj(id) {
+            Some(mo)
+                if mo.health > 0
+                    && (mo.flags.contains(MobjFlag::COUNTKILL)
+                        || mo.mobj_type == MobjType::SKULL) =>
+            {
+                mo.flags.contains(MobjFlag::FRIEND)
+            }
+            _ => return,
+        };
+        self.class_list(friend).push(id);
+    }
+
+    /// Start a sound from `origin`, or one heard everywhere for `None`,
+    /// like vanilla's `S_StartSound`. Sounds don't touch the game state, so
+    /// this is where the sound code will hook in; until then nothing plays.
+    pub fn start_sound(&mut self, _origin: Option<ThinkerId>, _sfx: usize) {}
+}
+
+/// What thinkers and action functions get to act on.
+pub struct Context<'a> {
+    pub configuration: &'a mut Configuration,
+    pub level: &'a mut Level,
+}
+
+/// Run every thinker on the level for a tic, like vanilla's
+/// `P_RunThinkers`.
+pub fn run_thinkers(context: &mut Context) {
+    think::run_thinkers(context, |context| &mut context.level.thinkers, think);
+}
+
+/// Play a tic of the level: move the players' weapons, run the thinkers,
+/// then count the tic, like the part of vanilla's `P_Ticker` that's written
+/// so far.
+#[allow(dead_code)]
+pub fn ticker(context: &mut Context) {
+    // the part of P_PlayerThink and P_DeathThink written so far
+    for n in 0..context.level.players.len() {
+        if context.level.players[n].mo.is_some() {
+            pspr::move_psprites(context, n);
+        }
+    }
+    run_thinkers(context);
+    context.level.leveltime += 1;
+}
+
+fn think(context: &mut Context, id: ThinkerId) {
+    match context.level.thinkers.get(id) {
+        Some(Thinker::Mobj(_)) => mobj::mobj_thinker(context, id),
+        None => {}
+    }
+}
+
+/// The map of the level being played. There is always one while thinkers
+/// run.
+pub fn map(configuration: &Configuration) -> &Map {
+    configuration
+        .level
+        .as_ref()
+        .expect("no map loaded for the level")
+}
+
+/// The map of the level being played, for the line specials that change
+/// it.
+pub fn map_mut(configuration: &mut Configuration) -> &mut Map {
+    configuration
+        .level
+        .as_mut()
+        .expect("no map loaded for the level")
+}
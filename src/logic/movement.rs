@@ -0,0 +1,1796 @@
+use super::{
+    inter::{damage_mobj, touch_special_thing},
+    map,
+    maputl::{
+        approx_distance, block_lines, block_lines_iterator, block_things_iterator, block_x,
+        block_y, box_on_line_side, boxes_overlap, line_bbox, line_opening, path_traverse,
+        point_in_subsector, point_on_line_side, set_thing_position, slope_type,
+        unset_thing_position, InterceptTarget, PathFlags, SlopeType, BOXBOTTOM, BOXLEFT, BOXRIGHT,
+        BOXTOP, MAXRADIUS,
+    },
+    mobj::{set_mobj_state, spawn_blood, spawn_puff, Mobj, MobjFlag, MobjIntFlag},
+    sight::check_sight,
+    spec::{cross_special_line, shoot_special_line},
+    Context, Level, Thinker,
+};
+use crate::{
+    configuration::{CompatibilityLevel, Configuration, MonsterInfightingLevel},
+    info::{MobjType, MOBJINFO},
+    lprint,
+    map::{LineFlags, Map},
+    misc::{
+        fixed::{fixed_div, fixed_mul, Fixed, FRACBITS, FRACUNIT},
+        lprint::OutputLevel,
+        random::{p_random, PrClass},
+    },
+    tables::{point_to_angle2, Angle, ANG180, ANG45, ANG90, DBITS, TANTOANGLE},
+    think::ThinkerId,
+};
+
+/// The friction of an ordinary floor, applied to momentum every tic.
+pub const ORIG_FRICTION: Fixed = Fixed(0xe800);
+/// How much of a push an ordinary floor turns into momentum.
+pub const ORIG_FRICTION_FACTOR: i32 = 2048;
+/// phares 3/11/98: momentum above which sludge gives better footing.
+const MORE_FRICTION_MOMENTUM: Fixed = Fixed(15000);
+
+/// The sector special bit that turns on friction from linedef 223.
+pub const FRICTION_MASK: i16 = 0x100;
+
+/// How many special lines vanilla's `spechit` held, and where doom2.exe
+/// kept the lines, for emulating what it wrote past them.
+const MAXSPECIALCROSS_ORIGINAL: usize = 8;
+const SPECHIT_MAGIC: u32 = 0x01c09c98;
+
+/// How high a mobj can step up.
+const MAXSTEP: Fixed = Fixed::from_int(24);
+
+/// How far melee attacks and hitscan shots reach.
+pub const MELEERANGE: Fixed = Fixed::from_int(64);
+pub const MISSILERANGE: Fixed = Fixed::from_int(32 * 64);
+
+/// killough 9/12/98: torque gears, for objects falling off ledges.
+const OVERDRIVE: i32 = 6;
+pub const MAXGEAR: i32 = OVERDRIVE + 16;
+
+/// A sector's friction and movefactor, from Boom's friction lines.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Friction {
+    pub friction: Fixed,
+    pub movefactor: i32,
+}
+
+impl Default for Friction {
+    fn default() -> Self {
+        Self {
+            friction: ORIG_FRICTION,
+            movefactor: ORIG_FRICTION_FACTOR,
+        }
+    }
+}
+
+/// What the last `check_position` found out about the spot it checked,
+/// like vanilla's `tm*` globals. `try_move` and the monster AI go on to
+/// use it.
+#[derive(Debug, Default)]
+pub struct PositionCheck {
+    /// The floor and ceiling the mobj would have there, narrowed by the
+    /// lines it would touch.
+    pub floorz: Fixed,
+    pub ceilingz: Fixed,
+    /// killough 11/98: the lowest floor it would touch.
+    pub dropoffz: Fixed,
+    /// The lines that set the ceiling and the floor, and the last line
+    /// that blocked the move.
+    pub ceilingline: Option<usize>,
+    /// killough 8/1/98: the floor line, so stuck players can get free.
+    pub floorline: Option<usize>,
+    pub blockline: Option<usize>,
+    /// The mobj's bounding box at the spot checked.
+    pub bbox: [Fixed; 4],
+    /// Special lines the mobj would touch, to cross or use later.
+    pub spechit: Vec<usize>,
+    /// Whether the mobj can get out of a sticky situation: players in MBF
+    /// may leave walls they're stuck in.
+    pub unstuck: bool,
+    /// Whether a floating mobj could fit by changing height.
+    pub floatok: bool,
+    /// killough 11/98: whether the mobj fell more than a step.
+    pub felldown: bool,
+}
+
+/// Whether `try_move` lets a mobj step off a ledge.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DropOff {
+    No,
+    Yes,
+    /// killough 10/98: dogs' big jumps down, allowed only when chasing a
+    /// target below.
+    Jump,
+}
+
+/// Whether a mobj would fit at `(x, y)`, without moving it, like vanilla's
+/// `P_CheckPosition`. Things in the way may get damaged or picked up.
+/// Leaves what it finds in `level.check`.
+pub fn check_position(context: &mut Context, id: ThinkerId, x: Fixed, y: Fixed) -> bool {
+    let mbf = context.configuration.compatibility_level >= CompatibilityLevel::Mbf;
+    let (flags, radius, player) = match context.level.mobj(id) {
+        Some(mobj) => (mobj.flags, mobj.radius, mobj.player),
+        None => return false,
+    };
+    let mut bbox = [Fixed::ZERO; 4];
+    bbox[BOXTOP] = y + radius;
+    bbox[BOXBOTTOM] = y - radius;
+    bbox[BOXRIGHT] = x + radius;
+    bbox[BOXLEFT] = x - radius;
+
+    // The base floor and ceiling are from the subsector that contains the
+    // point. Any contacted lines the step closer together will adjust
+    // them.
+    let map = map(context.configuration);
+    let sector = &map.sectors[map.subsectors[point_in_subsector(map, x, y)].sector];
+    // only players, not voodoo dolls, and not in old demos
+    let unstuck = mbf && player.is_some_and(|p| context.level.players[p].mo == Some(id));
+    let check = &mut context.level.check;
+    check.floorz = sector.floor_height;
+    check.dropoffz = sector.floor_height;
+    check.ceilingz = sector.ceiling_height;
+    check.floorline = None;
+    check.ceilingline = None;
+    check.blockline = None;
+    check.spechit.clear();
+    check.bbox = bbox;
+    check.unstuck = unstuck;
+    context.level.validcount += 1;
+
+    if flags.contains(MobjFlag::NOCLIP) {
+        return true;
+    }
+
+    // Check things first, possibly picking things up. The bounding box is
+    // extended by MAXRADIUS because mobjs are grouped into mapblocks based
+    // on their origin point, and can overlap into adjacent blocks by up to
+    // MAXRADIUS units.
+    let xl = block_x(map, bbox[BOXLEFT] - MAXRADIUS);
+    let xh = block_x(map, bbox[BOXRIGHT] + MAXRADIUS);
+    let yl = block_y(map, bbox[BOXBOTTOM] - MAXRADIUS);
+    let yh = block_y(map, bbox[BOXTOP] + MAXRADIUS);
+    for bx in xl..=xh {
+        for by in yl..=yh {
+            if !block_things_iterator(context, bx, by, |context, thing| {
+                check_thing(context, id, x, y, thing)
+            }) {
+                return false;
+            }
+        }
+    }
+
+    // check lines
+    let map = super::map(context.configuration);
+    let xl = block_x(map, bbox[BOXLEFT]);
+    let xh = block_x(map, bbox[BOXRIGHT]);
+    let yl = block_y(map, bbox[BOXBOTTOM]);
+    let yh = block_y(map, bbox[BOXTOP]);
+    for bx in xl..=xh {
+        for by in yl..=yh {
+            if !block_lines_iterator(context, bx, by, |context, line| {
+                check_line(context, id, x, y, line)
+            }) {
+                // doesn't fit
+                return false;
+            }
+        }
+    }
+    true
+}
+
+/// Whether a line the mobj's box at `(x, y)` touches blocks it, narrowing
+/// the floor and ceiling it would have there, like vanilla's
+/// `PIT_CheckLine`.
+fn check_line(context: &mut Context, id: ThinkerId, x: Fixed, y: Fixed, line: usize) -> bool {
+    let configuration = &*context.configuration;
+    let map = map(configuration);
+    let bbox = &context.level.check.bbox;
+    if !boxes_overlap(bbox, &line_bbox(map, line)) || box_on_line_side(map, bbox, line).is_some() {
+        // didn't hit it
+        return true;
+    }
+    let Level {
+        thinkers, check, ..
+    } = &mut *context.level;
+    let mobj = match thinkers.get(id) {
+        Some(Thinker::Mobj(mobj)) => mobj,
+        _ => return true,
+    };
+    let ld = &map.lines[line];
+
+    // A line has been hit. The moving thing's destination position will
+    // cross the given line. If this should not be allowed, return false.
+
+    // killough 7/24/98: allow player to move out of 1s wall, to prevent
+    // sticking
+    if ld.back_sector.is_none() {
+        check.blockline = Some(line);
+        return check.unstuck
+            && !untouched(map, mobj, line)
+            && fixed_mul(x - mobj.x, ld.dy) > fixed_mul(y - mobj.y, ld.dx);
+    }
+
+    // killough 8/10/98: allow bouncing objects to pass through as missiles
+    if !mobj.flags.intersects(MobjFlag::MISSILE | MobjFlag::BOUNCES) {
+        if ld.flags.contains(LineFlags::BLOCKING) {
+            // explicitly blocking everything
+            // killough 8/1/98: allow escape
+            return check.unstuck && !untouched(map, mobj, line);
+        }
+        // killough 8/9/98: monster-blockers don't affect friends
+        if !(mobj.flags.contains(MobjFlag::FRIEND) || mobj.player.is_some())
+            && ld.flags.contains(LineFlags::BLOCK_MONSTERS)
+        {
+            // block monsters only
+            return false;
+        }
+    }
+
+    // set openrange, opentop, openbottom
+    let opening = line_opening(map, line);
+
+    // adjust floor and ceiling heights
+    if opening.top < check.ceilingz {
+        check.ceilingz = opening.top;
+        check.ceilingline = Some(line);
+        check.blockline = Some(line);
+    }
+    if opening.bottom > check.floorz {
+        check.floorz = opening.bottom;
+        // killough 8/1/98: remember floor linedef
+        check.floorline = Some(line);
+        check.blockline = Some(line);
+    }
+    if opening.lowfloor < check.dropoffz {
+        check.dropoffz = opening.lowfloor;
+    }
+
+    // if contacted a special line, add it to the list
+    if ld.special != 0 {
+        check.spechit.push(line);
+        // e6y: spechits overrun emulation code
+        if check.spechit.len() > MAXSPECIALCROSS_ORIGINAL
+            && configuration.compatibility_level < CompatibilityLevel::Boom
+        {
+            spechit_overrun(configuration, check, line);
+        }
+    }
+    true
+}
+
+/// Reproduce what vanilla wrote past the end of its `spechit` array, as
+/// prboom does. The array held eight lines, and the line pointers of any
+/// more landed on the globals after it: `tmbbox`, then `nofit` and
+/// `crushchange` in Doom 2 1.9, or `tmfloorz` and `tmceilingz` in the DOS
+/// ports. Which pointers those were is worked out from where doom2.exe
+/// kept the lines.
+fn spechit_overrun(configuration: &Configuration, check: &mut PositionCheck, line: usize) {
+    let numspechit = check.spechit.len();
+    let dos = matches!(
+        configuration.compatibility_level,
+        CompatibilityLevel::DosDoom | CompatibilityLevel::TasDoom
+    );
+    let defaults = &configuration.defaults;
+    if defaults.overrun_spechit_warn {
+        lprint!(
+            OutputLevel::WARN,
+            "check_line: spechit overflow: a mobj touched {} special lines, but vanilla kept {}.\n",
+            numspechit,
+            MAXSPECIALCROSS_ORIGINAL
+        );
+    }
+    if !defaults.overrun_spechit_emulate {
+        return;
+    }
+
+    let addr = Fixed(SPECHIT_MAGIC.wrapping_add(line as u32 * 0x3e) as i32);
+    match (dos, numspechit) {
+        (true, 9) => check.floorz = addr,
+        (true, 10) => check.ceilingz = addr,
+        (false, 9..=12) => check.bbox[numspechit - 9] = addr,
+        // nofit and crushchange, which are set again before they're read
+        (false, 13 | 14) => {}
+        _ => {
+            lprint!(
+                OutputLevel::WARN,
+                "check_line: spechit overflow of {} lines can't be emulated.\n",
+                numspechit
+            );
+        }
+    }
+}
+
+/// killough 8/1/98: whether a mobj where it is now doesn't touch a line,
+/// so it can be allowed to move away from it when stuck.
+fn untouched(map: &Map, mobj: &Mobj, line: usize) -> bool {
+    let mut bbox = [Fixed::ZERO; 4];
+    bbox[BOXRIGHT] = mobj.x + mobj.radius;
+    bbox[BOXLEFT] = mobj.x - mobj.radius;
+    bbox[BOXTOP] = mobj.y + mobj.radius;
+    bbox[BOXBOTTOM] = mobj.y - mobj.radius;
+    !boxes_overlap(&bbox, &line_bbox(map, line)) || box_on_line_side(map, &bbox, line).is_some()
+}
+
+/// Whether a thing blocks the mobj moving to `(x, y)`, like vanilla's
+/// `PIT_CheckThing`. Missiles and charging lost souls hit what they run
+/// into here.
+fn check_thing(context: &mut Context, id: ThinkerId, x: Fixed, y: Fixed, thing: ThinkerId) -> bool {
+    let cl = context.configuration.compatibility_level;
+    let species_infight = context.configuration.monster_infighting == MonsterInfightingLevel::All;
+    let level = &*context.level;
+    let (tm, other) = match (level.mobj(id), level.mobj(thing)) {
+        (Some(tm), Some(other)) => (tm, other),
+        _ => return true,
+    };
+
+    // killough 11/98: add touchy things
+    if !other
+        .flags
+        .intersects(MobjFlag::SOLID | MobjFlag::SPECIAL | MobjFlag::SHOOTABLE | MobjFlag::TOUCHY)
+    {
+        return true;
+    }
+    let blockdist = other.radius + tm.radius;
+    if (other.x - x).abs() >= blockdist || (other.y - y).abs() >= blockdist {
+        // didn't hit it
+        return true;
+    }
+
+    // don't clip against self
+    if thing == id {
+        return true;
+    }
+
+    // killough 11/98: TOUCHY flag, for mines or other objects which die on
+    // contact with solids. If a solid object of a different type comes in
+    // contact with a touchy thing, and the touchy thing is not the sole
+    // one moving relative to fixed surroundings such as walls, then the
+    // touchy thing dies immediately.
+    if other.flags.contains(MobjFlag::TOUCHY)
+        && tm.flags.contains(MobjFlag::SOLID)
+        && other.health > 0
+        && (other.intflags.contains(MobjIntFlag::ARMED) || other.sentient())
+        && (other.mobj_type != tm.mobj_type || other.mobj_type == MobjType::PLAYER)
+        && other.z + other.height >= tm.z
+        && tm.z + tm.height >= other.z
+        // PEs and lost souls are considered the same, but barons and
+        // knights are intentionally not
+        && !(other.mobj_type == MobjType::PAIN && tm.mobj_type == MobjType::SKULL)
+        && !(other.mobj_type == MobjType::SKULL && tm.mobj_type == MobjType::PAIN)
+    {
+        let health = other.health;
+        damage_mobj(context, thing, None, None, health);
+        return true;
+    }
+
+    let (tm_damage, tm_spawnstate) = {
+        let mobjinfo = MOBJINFO.read();
+        let info = &mobjinfo[&tm.mobj_type];
+        (info.damage, info.spawnstate)
+    };
+
+    // check for skulls slamming into things
+    if tm.flags.contains(MobjFlag::SKULLFLY) {
+        let damage = (p_random(context.configuration, PrClass::SkullFly) % 8 + 1) * tm_damage;
+        damage_mobj(context, thing, Some(id), Some(id), damage);
+        if let Some(tm) = context.level.mobj_mut(id) {
+            tm.flags.remove(MobjFlag::SKULLFLY);
+            tm.momx = Fixed::ZERO;
+            tm.momy = Fixed::ZERO;
+            tm.momz = Fixed::ZERO;
+        }
+        set_mobj_state(context, id, tm_spawnstate);
+        // stop moving
+        return false;
+    }
+
+    // missiles can hit other things
+    // killough 8/10/98: bouncing non-solid things can hit other things too
+    if tm.flags.contains(MobjFlag::MISSILE)
+        || (tm.flags.contains(MobjFlag::BOUNCES) && !tm.flags.contains(MobjFlag::SOLID))
+    {
+        // see if it went over / under
+        if tm.z > other.z + other.height {
+            // overhead
+            return true;
+        }
+        if tm.z + tm.height < other.z {
+            // underneath
+            return true;
+        }
+
+        let target_type = tm.target.and_then(|t| level.mobj(t)).map(|t| t.mobj_type);
+        if let Some(target_type) = target_type {
+            if target_type == other.mobj_type
+                || (target_type == MobjType::KNIGHT && other.mobj_type == MobjType::BRUISER)
+                || (target_type == MobjType::BRUISER && other.mobj_type == MobjType::KNIGHT)
+            {
+                if tm.target == Some(thing) {
+                    // Don't hit same species as originator.
+                    return true;
+                } else if other.mobj_type != MobjType::PLAYER && !species_infight {
+                    // Explode, but do no damage. Let players missile other
+                    // players, and monsters their own kind if they all
+                    // infight.
+                    return false;
+                }
+            }
+        }
+
+        // killough 8/10/98: if moving thing is not a missile, no damage is
+        // inflicted, and momentum is reduced if object hit is solid.
+        if !tm.flags.contains(MobjFlag::MISSILE) {
+            if !other.flags.contains(MobjFlag::SOLID) {
+                return true;
+            }
+            let tm = context.level.mobj_mut(id).unwrap();
+            tm.momx = -tm.momx;
+            tm.momy = -tm.momy;
+            if !tm.flags.contains(MobjFlag::NOGRAVITY) {
+                tm.momx = tm.momx >> 2;
+                tm.momy = tm.momy >> 2;
+            }
+            return false;
+        }
+
+        if !other.flags.contains(MobjFlag::SHOOTABLE) {
+            // didn't do any damage
+            return !other.flags.contains(MobjFlag::SOLID);
+        }
+
+        // damage / explode
+        let source = tm.target;
+        let damage = (p_random(context.configuration, PrClass::Damage) % 8 + 1) * tm_damage;
+        damage_mobj(context, thing, Some(id), source, damage);
+        // don't traverse any more
+        return false;
+    }
+
+    // check for special pickup
+    if other.flags.contains(MobjFlag::SPECIAL) {
+        let solid = other.flags.contains(MobjFlag::SOLID);
+        if tm.flags.contains(MobjFlag::PICKUP) {
+            // can remove thing
+            touch_special_thing(context, thing, id);
+        }
+        return !solid;
+    }
+
+    // killough 3/16/98: Allow non-solid moving objects to move through
+    // solid ones, by allowing the moving thing (tm) to move if it's
+    // non-solid, despite another solid thing being in the way.
+    // killough 4/11/98: Treat no-clipping things as not blocking, but not
+    // in demo_compatibility mode
+    !other.flags.contains(MobjFlag::SOLID)
+        || (cl >= CompatibilityLevel::Boom
+            && (other.flags.contains(MobjFlag::NOCLIP) || !tm.flags.contains(MobjFlag::SOLID)))
+}
+
+/// Move a mobj to `(x, y)` if it fits there, like vanilla's `P_TryMove`.
+/// Steps up to 24 units high are climbed; `dropoff` says whether the mobj
+/// may step down off a ledge higher than that.
+pub fn try_move(
+    context: &mut Context,
+    id: ThinkerId,
+    x: Fixed,
+    y: Fixed,
+    dropoff: DropOff,
+) -> bool {
+    context.level.check.felldown = false;
+    context.level.check.floatok = false;
+    if !check_position(context, id, x, y) {
+        // solid wall or thing
+        return false;
+    }
+
+    let configuration = &*context.configuration;
+    let cl = configuration.compatibility_level;
+    let mbf = cl >= CompatibilityLevel::Mbf;
+    let map = map(configuration);
+    let target_z = context
+        .level
+        .mobj(id)
+        .and_then(|mobj| mobj.target)
+        .and_then(|target| context.level.mobj(target))
+        .map(|target| target.z);
+    let Level {
+        thinkers, check, ..
+    } = &mut *context.level;
+    let mobj = match thinkers.get(id) {
+        Some(Thinker::Mobj(mobj)) => mobj,
+        _ => return false,
+    };
+
+    if !mobj.flags.contains(MobjFlag::NOCLIP) {
+        let teleport = mobj.flags.contains(MobjFlag::TELEPORT);
+        // killough 7/26/98: reformatted slightly
+        // killough 8/1/98: Possibly allow escape if otherwise stuck
+        let fits = check.ceilingz - check.floorz >= mobj.height;
+        if fits {
+            check.floatok = true;
+        }
+        if !fits
+            // mobj must lower to fit
+            || (!teleport && check.ceilingz - mobj.z < mobj.height)
+            // too big a step up
+            || (!teleport && check.floorz - mobj.z > MAXSTEP)
+        {
+            return check.unstuck
+                && !check.ceilingline.is_some_and(|l| untouched(map, mobj, l))
+                && !check.floorline.is_some_and(|l| untouched(map, mobj, l));
+        }
+
+        // killough 3/15/98: Allow certain objects to drop off
+        // killough 7/24/98, 8/1/98: Prevent monsters from getting stuck
+        // hanging off ledges
+        // killough 10/98: Allow dropoffs in controlled circumstances
+        // killough 11/98: Improve symmetry of clipping on stairs
+        if !mobj.flags.intersects(MobjFlag::DROPOFF | MobjFlag::FLOAT) {
+            if configuration.comp_dropoff {
+                // e6y: MBF and PrBoom up to 2.1.1 demos never drop off
+                if (cl <= CompatibilityLevel::Boom
+                    || dropoff == DropOff::No
+                    || (mbf && cl <= CompatibilityLevel::PrBoomV210211))
+                    && check.floorz - check.dropoffz > MAXSTEP
+                {
+                    // don't stand over a dropoff
+                    return false;
+                }
+            } else if dropoff == DropOff::No
+                || (dropoff == DropOff::Jump
+                    // large jump down (e.g. dogs)
+                    && (check.floorz - check.dropoffz > Fixed::from_int(128)
+                        || target_z.is_none_or(|z| z > check.dropoffz)))
+            {
+                let too_high = if !configuration.monkeys || !mbf {
+                    check.floorz - check.dropoffz > MAXSTEP
+                } else {
+                    mobj.floorz - check.floorz > MAXSTEP || mobj.dropoffz - check.dropoffz > MAXSTEP
+                };
+                if too_high {
+                    return false;
+                }
+            } else {
+                // dropoff allowed -- check for whether it fell more than 24
+                check.felldown =
+                    !mobj.flags.contains(MobjFlag::NOGRAVITY) && mobj.z - check.floorz > MAXSTEP;
+            }
+        }
+
+        // killough 8/13/98: too big a step up for bouncers under gravity
+        if mobj.flags.contains(MobjFlag::BOUNCES)
+            && !mobj
+                .flags
+                .intersects(MobjFlag::MISSILE | MobjFlag::NOGRAVITY)
+            && !mobj.sentient()
+            && check.floorz - mobj.z > Fixed::from_int(16)
+        {
+            return false;
+        }
+
+        // killough 11/98: prevent falling objects from going up too many
+        // steps
+        if mobj.intflags.contains(MobjIntFlag::FALLING)
+            && check.floorz - mobj.z
+                > fixed_mul(mobj.momx, mobj.momx) + fixed_mul(mobj.momy, mobj.momy)
+        {
+            return false;
+        }
+    }
+
+    // the move is ok, so unlink from the old position and link into the
+    // new position
+    let (floorz, ceilingz, dropoffz) = (check.floorz, check.ceilingz, check.dropoffz);
+    let (oldx, oldy) = (mobj.x, mobj.y);
+    let crosses = !mobj.flags.intersects(MobjFlag::TELEPORT | MobjFlag::NOCLIP);
+    unset_thing_position(context, id);
+    let mobj = context.level.mobj_mut(id).unwrap();
+    mobj.floorz = floorz;
+    mobj.ceilingz = ceilingz;
+    mobj.dropoffz = dropoffz;
+    mobj.x = x;
+    mobj.y = y;
+    set_thing_position(context, id);
+
+    // if any special lines were hit, do the effect
+    // killough 11/98: simplified
+    // The list is taken from as it's walked, so a teleporter that empties
+    // it ends the walk, as it did vanilla's `numspechit` loop.
+    if crosses {
+        while let Some(line) = context.level.check.spechit.pop() {
+            let map = super::map(context.configuration);
+            if map.lines[line].special == 0 {
+                continue;
+            }
+            let (x, y) = match context.level.mobj(id) {
+                Some(mobj) => (mobj.x, mobj.y),
+                None => break,
+            };
+            let oldside = point_on_line_side(map, oldx, oldy, line);
+            if point_on_line_side(map, x, y, line) != oldside {
+                cross_special_line(context, line, oldside, id);
+            }
+        }
+    }
+    true
+}
+
+/// Slide a mobj along the walls it runs into instead of stopping dead,
+/// like vanilla's `P_SlideMove`. Players use this when `try_move` fails.
+pub fn slide_move(context: &mut Context, id: ThinkerId) {
+    let cl = context.configuration.compatibility_level;
+    let mut hitcount = 3;
+    loop {
+        hitcount -= 1;
+        // don't loop forever
+        let mut stairstep = hitcount == 0;
+        let mut best = None;
+        let mut bestslidefrac = Fixed::UNIT + Fixed(1);
+
+        if !stairstep {
+            let (x, y, radius, momx, momy) = match context.level.mobj(id) {
+                Some(mo) => (mo.x, mo.y, mo.radius, mo.momx, mo.momy),
+                None => return,
+            };
+            // trace along the three leading corners
+            let (leadx, trailx) = if momx > Fixed::ZERO {
+                (x + radius, x - radius)
+            } else {
+                (x - radius, x + radius)
+            };
+            let (leady, traily) = if momy > Fixed::ZERO {
+                (y + radius, y - radius)
+            } else {
+                (y - radius, y + radius)
+            };
+            for &(x, y) in &[(leadx, leady), (trailx, leady), (leadx, traily)] {
+                path_traverse(
+                    context,
+                    x,
+                    y,
+                    x + momx,
+                    y + momy,
+                    PathFlags::ADDLINES,
+                    |context, _, intercept| {
+                        let blocking = slide_blocked(context, id, intercept.target);
+                        if let Some(line) = blocking {
+                            // see if it is closer than best so far
+                            if intercept.frac < bestslidefrac {
+                                bestslidefrac = intercept.frac;
+                                best = Some(line);
+                            }
+                        }
+                        blocking.is_none()
+                    },
+                );
+            }
+
+            // move up to the wall
+            if bestslidefrac == Fixed::UNIT + Fixed(1) {
+                // the move must have hit the middle, so stairstep
+                stairstep = true;
+            } else {
+                // fudge a bit to make sure it doesn't hit
+                bestslidefrac -= Fixed(0x800);
+                if bestslidefrac > Fixed::ZERO {
+                    let newx = fixed_mul(momx, bestslidefrac);
+                    let newy = fixed_mul(momy, bestslidefrac);
+                    // killough 3/15/98: Allow objects to drop off ledges
+                    if !try_move(context, id, x + newx, y + newy, DropOff::Yes) {
+                        stairstep = true;
+                    }
+                }
+            }
+        }
+
+        let (x, y, momx, momy) = match context.level.mobj(id) {
+            Some(mo) => (mo.x, mo.y, mo.momx, mo.momy),
+            None => return,
+        };
+        if stairstep {
+            // killough 3/15/98: Allow objects to drop off ledges
+            // phares 5/4/98: kill momentum if you can't move at all. This
+            // eliminates player bobbing if pressed against a wall while on
+            // ice.
+            // cph 2000/09/23: but only in Boom v2.01, where this started
+            if !try_move(context, id, x, y + momy, DropOff::Yes) {
+                let (x, y) = {
+                    let mo = context.level.mobj(id).unwrap();
+                    (mo.x, mo.y)
+                };
+                if !try_move(context, id, x + momx, y, DropOff::Yes)
+                    && cl == CompatibilityLevel::BoomV201
+                {
+                    let mo = context.level.mobj_mut(id).unwrap();
+                    mo.momx = Fixed::ZERO;
+                    mo.momy = Fixed::ZERO;
+                }
+            }
+            return;
+        }
+
+        // Now continue along the wall. First calculate remainder.
+        bestslidefrac = Fixed::UNIT - (bestslidefrac + Fixed(0x800));
+        if bestslidefrac > Fixed::UNIT {
+            bestslidefrac = Fixed::UNIT;
+        }
+        if bestslidefrac <= Fixed::ZERO {
+            return;
+        }
+        let mut xmove = fixed_mul(momx, bestslidefrac);
+        let mut ymove = fixed_mul(momy, bestslidefrac);
+        // clip the moves
+        hit_slide_line(context, id, best.unwrap(), &mut xmove, &mut ymove);
+
+        let mo = context.level.mobj_mut(id).unwrap();
+        mo.momx = xmove;
+        mo.momy = ymove;
+        let player = mo.player;
+        // killough 10/98: affect the bobbing the same way (but not voodoo
+        // dolls)
+        if let Some(n) = player {
+            let player = &mut context.level.players[n];
+            if player.mo == Some(id) {
+                if player.momx.abs() > xmove.abs() {
+                    player.momx = xmove;
+                }
+                if player.momy.abs() > ymove.abs() {
+                    player.momy = ymove;
+                }
+            }
+        }
+
+        // killough 3/15/98: Allow objects to drop off ledges
+        if try_move(context, id, x + xmove, y + ymove, DropOff::Yes) {
+            return;
+        }
+    }
+}
+
+/// The line a sliding mobj's path crosses, if it blocks the mobj, like
+/// vanilla's `PTR_SlideTraverse`.
+fn slide_blocked(context: &Context, id: ThinkerId, target: InterceptTarget) -> Option<usize> {
+    let line = match target {
+        InterceptTarget::Line(line) => line,
+        InterceptTarget::Thing(_) => crate::error("slide_blocked: not a line?"),
+    };
+    let map = map(context.configuration);
+    let mo = context.level.mobj(id).unwrap();
+
+    if !map.lines[line].flags.contains(LineFlags::TWO_SIDED) {
+        // don't hit the back side
+        return if point_on_line_side(map, mo.x, mo.y, line) != 0 {
+            None
+        } else {
+            Some(line)
+        };
+    }
+
+    // set openrange, opentop, openbottom. These define a 'window' from one
+    // sector to another across a line
+    let opening = line_opening(map, line);
+    // doesn't fit, mobj is too high, or too big a step up
+    if opening.range < mo.height
+        || opening.top - mo.z < mo.height
+        || opening.bottom - mo.z > MAXSTEP
+    {
+        Some(line)
+    } else {
+        None
+    }
+}
+
+/// Turn a slide's move so it runs along the line it hit, like vanilla's
+/// `P_HitSlideLine`.
+///
+/// phares: Under icy conditions, if the angle of approach to the wall is
+/// more than 45 degrees, then you'll bounce and lose half your momentum.
+/// If less than 45 degrees, you'll slide along the wall. 45 is arbitrary
+/// and is believable.
+fn hit_slide_line(
+    context: &mut Context,
+    id: ThinkerId,
+    line: usize,
+    xmove: &mut Fixed,
+    ymove: &mut Fixed,
+) {
+    let configuration = &*context.configuration;
+    let cl = configuration.compatibility_level;
+    let map = map(configuration);
+    let mo = context.level.mobj(id).unwrap();
+
+    // killough 10/98: only bounce if hit hard (prevents wobbling)
+    let icyfloor = if cl >= CompatibilityLevel::Mbf {
+        approx_distance(*xmove, *ymove) > Fixed::from_int(4)
+            && configuration.variable_friction
+            && mo.z <= mo.floorz
+            && get_friction(context, id).friction > ORIG_FRICTION
+    } else {
+        cl > CompatibilityLevel::Boom
+            && configuration.variable_friction
+            && mo.player.is_some()
+            && mo.z <= mo.floorz
+            && mo.friction > ORIG_FRICTION
+    };
+
+    match slope_type(map, line) {
+        SlopeType::Horizontal => {
+            if icyfloor && ymove.abs() > xmove.abs() {
+                // absorb half the momentum
+                *xmove = *xmove / 2;
+                *ymove = -*ymove / 2;
+            } else {
+                // no more movement in the Y direction
+                *ymove = Fixed::ZERO;
+            }
+            return;
+        }
+        SlopeType::Vertical => {
+            if icyfloor && xmove.abs() > ymove.abs() {
+                // absorb half the momentum
+                *xmove = -*xmove / 2;
+                *ymove = *ymove / 2;
+            } else {
+                // no more movement in the X direction
+                *xmove = Fixed::ZERO;
+            }
+            return;
+        }
+        _ => {}
+    }
+
+    // The wall is angled. Bounce if the angle of approach is less than 45
+    // degrees.
+    let ld = &map.lines[line];
+    let side = point_on_line_side(map, mo.x, mo.y, line);
+    let mut lineangle = point_to_angle2(Fixed::ZERO, Fixed::ZERO, ld.dx, ld.dy);
+    if side == 1 {
+        lineangle += ANG180;
+    }
+    let mut moveangle = point_to_angle2(Fixed::ZERO, Fixed::ZERO, *xmove, *ymove);
+    // killough 3/2/98: The moveangle+=10 breaks v1.9 demo compatibility in
+    // some demos, so it needs demo_compatibility switch.
+    if cl >= CompatibilityLevel::Boom {
+        // prevents sudden path reversal due to rounding error
+        moveangle += Angle(10);
+    }
+    let mut deltaangle = moveangle - lineangle;
+    let mut movelen = approx_distance(*xmove, *ymove);
+
+    if icyfloor && deltaangle > ANG45 && deltaangle < ANG90 + ANG45 {
+        let moveangle = lineangle - deltaangle;
+        // absorb
+        movelen = movelen / 2;
+        *xmove = fixed_mul(movelen, moveangle.cos());
+        *ymove = fixed_mul(movelen, moveangle.sin());
+    } else {
+        if deltaangle > ANG180 {
+            deltaangle += ANG180;
+        }
+        let newlen = fixed_mul(movelen, deltaangle.cos());
+        *xmove = fixed_mul(newlen, lineangle.cos());
+        *ymove = fixed_mul(newlen, lineangle.sin());
+    }
+}
+
+/// What `aim_line_attack` found: the slope to aim up or down at, and the
+/// thing that would be hit, like vanilla's `aimslope` and `linetarget`.
+#[derive(Debug, Copy, Clone)]
+pub struct Aim {
+    pub slope: Fixed,
+    pub linetarget: Option<ThinkerId>,
+}
+
+/// Find what a shot from `t1` along `angle` would hit within `distance`,
+/// like vanilla's `P_AimLineAttack`. `mask` holds flags that, when the
+/// shooter and a thing share them, make the shot pass the thing by.
+///
+/// killough 8/2/98: that's how friends don't aim at friends.
+pub fn aim_line_attack(
+    context: &mut Context,
+    t1: ThinkerId,
+    angle: Angle,
+    distance: Fixed,
+    mask: MobjFlag,
+) -> Aim {
+    let (x, y, shootz, flags) = match context.level.mobj(t1) {
+        Some(mo) => (
+            mo.x,
+            mo.y,
+            mo.z + (mo.height >> 1) + Fixed::from_int(8),
+            mo.flags,
+        ),
+        None => {
+            return Aim {
+                slope: Fixed::ZERO,
+                linetarget: None,
+            }
+        }
+    };
+    let x2 = x + angle.cos() * (distance.0 >> FRACBITS);
+    let y2 = y + angle.sin() * (distance.0 >> FRACBITS);
+
+    // can't shoot outside view angles
+    let mut topslope = Fixed(100 * FRACUNIT as i32 / 160);
+    let mut bottomslope = Fixed(-100 * FRACUNIT as i32 / 160);
+    let mut aim = Aim {
+        slope: Fixed::ZERO,
+        linetarget: None,
+    };
+
+    path_traverse(
+        context,
+        x,
+        y,
+        x2,
+        y2,
+        PathFlags::ADDLINES | PathFlags::ADDTHINGS,
+        |context, _, intercept| {
+            let map = map(context.configuration);
+            let dist = fixed_mul(distance, intercept.frac);
+            match intercept.target {
+                InterceptTarget::Line(line) => {
+                    let ld = &map.lines[line];
+                    if !ld.flags.contains(LineFlags::TWO_SIDED) {
+                        // stop
+                        return false;
+                    }
+
+                    // Crosses a two sided line. A two sided line will
+                    // restrict the possible target ranges.
+                    let opening = line_opening(map, line);
+                    if opening.bottom >= opening.top {
+                        // stop
+                        return false;
+                    }
+                    let (front, back) = match (ld.front_sector, ld.back_sector) {
+                        (Some(front), Some(back)) => (&map.sectors[front], &map.sectors[back]),
+                        _ => return false,
+                    };
+                    if front.floor_height != back.floor_height {
+                        let slope = fixed_div(opening.bottom - shootz, dist);
+                        if slope > bottomslope {
+                            bottomslope = slope;
+                        }
+                    }
+                    if front.ceiling_height != back.ceiling_height {
+                        let slope = fixed_div(opening.top - shootz, dist);
+                        if slope < topslope {
+                            topslope = slope;
+                        }
+                    }
+                    // shot continues unless the window is shut
+                    topslope > bottomslope
+                }
+                InterceptTarget::Thing(thing) => {
+                    if thing == t1 {
+                        // can't shoot self
+                        return true;
+                    }
+                    let th = match context.level.mobj(thing) {
+                        Some(th) => th,
+                        None => return true,
+                    };
+                    if !th.flags.contains(MobjFlag::SHOOTABLE) {
+                        // corpse or something
+                        return true;
+                    }
+                    // killough 7/19/98, 8/2/98: friends don't aim at
+                    // friends (except players), at least not first
+                    if th.flags.intersects(flags & mask) && th.player.is_none() {
+                        return true;
+                    }
+
+                    // check angles to see if the thing can be aimed at
+                    let mut thingtopslope = fixed_div(th.z + th.height - shootz, dist);
+                    if thingtopslope < bottomslope {
+                        // shot over the thing
+                        return true;
+                    }
+                    let mut thingbottomslope = fixed_div(th.z - shootz, dist);
+                    if thingbottomslope > topslope {
+                        // shot under the thing
+                        return true;
+                    }
+
+                    // this thing can be hit!
+                    if thingtopslope > topslope {
+                        thingtopslope = topslope;
+                    }
+                    if thingbottomslope < bottomslope {
+                        thingbottomslope = bottomslope;
+                    }
+                    aim.slope = Fixed((thingtopslope.0.wrapping_add(thingbottomslope.0)) / 2);
+                    aim.linetarget = Some(thing);
+                    // don't go any farther
+                    false
+                }
+            }
+        },
+    );
+    if aim.linetarget.is_none() {
+        aim.slope = Fixed::ZERO;
+    }
+    aim
+}
+
+/// Fire a hitscan shot from `t1` along `angle` and `slope` as far as
+/// `distance`, like vanilla's `P_LineAttack`. What it hits takes `damage`
+/// and bleeds, or a puff goes up where it hits a wall. Shot lines set off
+/// their specials.
+pub fn line_attack(
+    context: &mut Context,
+    t1: ThinkerId,
+    angle: Angle,
+    distance: Fixed,
+    slope: Fixed,
+    damage: i32,
+) {
+    let (x, y, shootz) = match context.level.mobj(t1) {
+        Some(mo) => (mo.x, mo.y, mo.z + (mo.height >> 1) + Fixed::from_int(8)),
+        None => return,
+    };
+    let x2 = x + angle.cos() * (distance.0 >> FRACBITS);
+    let y2 = y + angle.sin() * (distance.0 >> FRACBITS);
+    let cl = context.configuration.compatibility_level;
+
+    path_traverse(
+        context,
+        x,
+        y,
+        x2,
+        y2,
+        PathFlags::ADDLINES | PathFlags::ADDTHINGS,
+        |context, trace, intercept| {
+            let map = map(context.configuration);
+            // where along the trace to put a puff or blood, a bit short of
+            // what was hit
+            let spot = |back: i32| {
+                let frac = intercept.frac - fixed_div(Fixed::from_int(back), distance);
+                (
+                    trace.x + fixed_mul(trace.dx, frac),
+                    trace.y + fixed_mul(trace.dy, frac),
+                    shootz + fixed_mul(slope, fixed_mul(frac, distance)),
+                )
+            };
+            match intercept.target {
+                InterceptTarget::Line(line) => {
+                    if map.lines[line].special != 0 {
+                        shoot_special_line(context, line, t1);
+                    }
+                    let map = super::map(context.configuration);
+                    let ld = &map.lines[line];
+
+                    if ld.flags.contains(LineFlags::TWO_SIDED) {
+                        // crosses a two sided line
+                        let opening = line_opening(map, line);
+                        let dist = fixed_mul(distance, intercept.frac);
+                        let (front, back) = match (ld.front_sector, ld.back_sector) {
+                            (Some(front), Some(back)) => (&map.sectors[front], &map.sectors[back]),
+                            _ => return false,
+                        };
+                        // killough 11/98: simplify
+                        if (front.floor_height == back.floor_height
+                            || fixed_div(opening.bottom - shootz, dist) <= slope)
+                            && (front.ceiling_height == back.ceiling_height
+                                || fixed_div(opening.top - shootz, dist) >= slope)
+                        {
+                            // shot continues
+                            return true;
+                        }
+                    }
+
+                    // hit line
+                    let (x, y, z) = spot(4);
+                    if let Some(front) = ld.front_sector.map(|s| &map.sectors[s]) {
+                        if is_sky(&front.ceiling_pic) {
+                            // don't shoot the sky!
+                            if z > front.ceiling_height {
+                                return false;
+                            }
+                            // it's a sky hack wall
+                            // fix bullet-eaters -- killough: almost all
+                            // demos will lose sync without the
+                            // demo_compatibility check
+                            let back = ld.back_sector.map(|s| &map.sectors[s]);
+                            if back.is_some_and(|back| {
+                                is_sky(&back.ceiling_pic)
+                                    && (cl < CompatibilityLevel::Boom || back.ceiling_height < z)
+                            }) {
+                                return false;
+                            }
+                        }
+                    }
+
+                    // Spawn bullet puffs.
+                    spawn_puff(context, x, y, z, distance);
+                    // don't go any farther
+                    false
+                }
+                InterceptTarget::Thing(thing) => {
+                    if thing == t1 {
+                        // can't shoot self
+                        return true;
+                    }
+                    let th = match context.level.mobj(thing) {
+                        Some(th) => th,
+                        None => return true,
+                    };
+                    if !th.flags.contains(MobjFlag::SHOOTABLE) {
+                        // corpse or something
+                        return true;
+                    }
+
+                    // check angles to see if the thing can be aimed at
+                    let dist = fixed_mul(distance, intercept.frac);
+                    if fixed_div(th.z + th.height - shootz, dist) < slope {
+                        // shot over the thing
+                        return true;
+                    }
+                    if fixed_div(th.z - shootz, dist) > slope {
+                        // shot under the thing
+                        return true;
+                    }
+
+                    // hit thing
+                    let noblood = th.flags.contains(MobjFlag::NOBLOOD);
+                    let (x, y, z) = spot(10);
+                    // Spawn bullet puffs or blood spots, depending on
+                    // target type.
+                    if noblood {
+                        spawn_puff(context, x, y, z, distance);
+                    } else {
+                        spawn_blood(context, x, y, z, damage);
+                    }
+                    if damage != 0 {
+                        damage_mobj(context, thing, Some(t1), Some(t1), damage);
+                    }
+                    // don't go any farther
+                    false
+                }
+            }
+        },
+    );
+}
+
+/// Hurt everything shootable around `spot` that it can see, less the
+/// farther away it is, like vanilla's `P_RadiusAttack`. `source` gets the
+/// blame.
+pub fn radius_attack(
+    context: &mut Context,
+    spot: ThinkerId,
+    source: Option<ThinkerId>,
+    damage: i32,
+) {
+    let (x, y, bounces) = match context.level.mobj(spot) {
+        Some(mo) => (mo.x, mo.y, mo.flags.contains(MobjFlag::BOUNCES)),
+        None => return,
+    };
+    let source_is_cyborg = source
+        .and_then(|s| context.level.mobj(s))
+        .is_some_and(|s| s.mobj_type == MobjType::CYBORG);
+    // vanilla's MAXRADIUS is already fixed point, so the shift overflows it
+    // away, leaving just the damage
+    let dist = Fixed((damage + MAXRADIUS.0).wrapping_shl(FRACBITS as u32));
+    let map = map(context.configuration);
+    let yh = block_y(map, y + dist);
+    let yl = block_y(map, y - dist);
+    let xh = block_x(map, x + dist);
+    let xl = block_x(map, x - dist);
+
+    for by in yl..=yh {
+        for bx in xl..=xh {
+            block_things_iterator(context, bx, by, |context, thing| {
+                let t = context.level.mobj(thing).unwrap();
+                // killough 8/20/98: allow bouncers to take damage (missile
+                // bouncers are already excluded with NOBLOCKMAP)
+                if !t.flags.intersects(MobjFlag::SHOOTABLE | MobjFlag::BOUNCES) {
+                    return true;
+                }
+                // Boss spider and cyborg take no damage from concussion.
+                // killough 8/10/98: allow grenades to hurt anyone, unless
+                // fired by Cyberdemons, in which case it won't hurt Cybers.
+                let immune = if bounces {
+                    t.mobj_type == MobjType::CYBORG && source_is_cyborg
+                } else {
+                    t.mobj_type == MobjType::CYBORG || t.mobj_type == MobjType::SPIDER
+                };
+                if immune {
+                    return true;
+                }
+
+                let dx = (t.x - x).abs();
+                let dy = (t.y - y).abs();
+                let dist = ((dx.max(dy) - t.radius).0 >> FRACBITS).max(0);
+                if dist >= damage {
+                    // out of range
+                    return true;
+                }
+                if check_sight(context, thing, spot) {
+                    // must be in direct path
+                    damage_mobj(context, thing, Some(spot), source, damage - dist);
+                }
+                true
+            });
+        }
+    }
+}
+
+/// Move a mobj to `(x, y)` regardless of what's in the way, killing
+/// whatever is there if it may telefrag, like vanilla's `P_TeleportMove`.
+/// Returns false, without moving it, if something there can't be
+/// telefragged.
+///
+/// killough 8/9/98: players always telefrag; monsters only do when `boss`
+/// is set, or on MAP30 for compatibility.
+pub fn teleport_move(context: &mut Context, id: ThinkerId, x: Fixed, y: Fixed, boss: bool) -> bool {
+    let configuration = &*context.configuration;
+    let (radius, player) = match context.level.mobj(id) {
+        Some(mo) => (mo.radius, mo.player),
+        None => return false,
+    };
+    let telefrag = player.is_some()
+        || if configuration.comp_telefrag {
+            configuration.game_map == 30
+        } else {
+            boss
+        };
+
+    // The base floor and ceiling are from the subsector that contains the
+    // point.
+    let map = map(configuration);
+    let sector = &map.sectors[map.subsectors[point_in_subsector(map, x, y)].sector];
+    let (floorz, ceilingz) = (sector.floor_height, sector.ceiling_height);
+    context.level.validcount += 1;
+
+    // stomp on any things contacted
+    let xl = block_x(map, x - radius - MAXRADIUS);
+    let xh = block_x(map, x + radius + MAXRADIUS);
+    let yl = block_y(map, y - radius - MAXRADIUS);
+    let yh = block_y(map, y + radius + MAXRADIUS);
+    for bx in xl..=xh {
+        for by in yl..=yh {
+            if !block_things_iterator(context, bx, by, |context, thing| {
+                // don't clip against self
+                if thing == id {
+                    return true;
+                }
+                let t = context.level.mobj(thing).unwrap();
+                // Can't shoot it? Can't stomp it!
+                if !t.flags.contains(MobjFlag::SHOOTABLE) {
+                    return true;
+                }
+                let blockdist = t.radius + radius;
+                if (t.x - x).abs() >= blockdist || (t.y - y).abs() >= blockdist {
+                    // didn't hit it
+                    return true;
+                }
+                if !telefrag {
+                    return false;
+                }
+                damage_mobj(context, thing, Some(id), Some(id), 10000);
+                true
+            }) {
+                return false;
+            }
+        }
+    }
+
+    // the move is ok, so unlink from the old position and link into the
+    // new position
+    unset_thing_position(context, id);
+    let check = &mut context.level.check;
+    check.floorz = floorz;
+    check.ceilingz = ceilingz;
+    check.dropoffz = floorz;
+    // the mobj isn't crossing anything now
+    check.spechit.clear();
+    let mo = context.level.mobj_mut(id).unwrap();
+    mo.floorz = floorz;
+    mo.ceilingz = ceilingz;
+    // killough 11/98
+    mo.dropoffz = floorz;
+    mo.x = x;
+    mo.y = y;
+    set_thing_position(context, id);
+    true
+}
+
+/// The friction under a mobj, and how much of a push it turns into
+/// momentum, like MBF's `P_GetFriction`. The lowest friction of the
+/// sectors it touches wins: muddy has precedence over icy.
+pub fn get_friction(context: &Context, id: ThinkerId) -> Friction {
+    let configuration = &*context.configuration;
+    let cl = configuration.compatibility_level;
+    let map = map(configuration);
+    let mut friction = Friction::default();
+    let mo = match context.level.mobj(id) {
+        Some(mo) => mo,
+        None => return friction,
+    };
+
+    // Assign the friction value to objects on the floor, non-floating, and
+    // clipped.
+    if !mo.flags.intersects(MobjFlag::NOCLIP | MobjFlag::NOGRAVITY)
+        && (cl >= CompatibilityLevel::Mbf || (mo.player.is_some() && cl > CompatibilityLevel::Boom))
+        && configuration.variable_friction
+    {
+        for sector in touching_sectors(map, cl, mo) {
+            let sec = &map.sectors[sector];
+            let sector_friction = context.level.sector_friction[sector];
+            if sec.special & FRICTION_MASK != 0
+                && (sector_friction.friction < friction.friction
+                    || friction.friction == ORIG_FRICTION)
+                && mo.z <= sec.floor_height
+            {
+                friction = sector_friction;
+            }
+        }
+    }
+    friction
+}
+
+/// How hard the floor makes it for a mobj to get moving, and the floor's
+/// friction, like MBF's `P_GetMoveFactor`. Pushes are scaled by the
+/// movefactor over `ORIG_FRICTION_FACTOR`.
+pub fn get_move_factor(context: &mut Context, id: ThinkerId) -> (i32, Fixed) {
+    let configuration = &*context.configuration;
+    let cl = configuration.compatibility_level;
+    let variable_friction = configuration.variable_friction;
+
+    if cl < CompatibilityLevel::Mbf {
+        // Boom's friction thinkers leave their values in the mobj, to be
+        // used once
+        let mo = match context.level.mobj_mut(id) {
+            Some(mo) => mo,
+            None => return (ORIG_FRICTION_FACTOR, ORIG_FRICTION),
+        };
+        let mut movefactor = ORIG_FRICTION_FACTOR;
+        if cl > CompatibilityLevel::Boom
+            && variable_friction
+            && !mo.flags.intersects(MobjFlag::NOGRAVITY | MobjFlag::NOCLIP)
+        {
+            let friction = mo.friction;
+            if friction > ORIG_FRICTION {
+                // ice
+                movefactor = mo.movefactor;
+                mo.movefactor = ORIG_FRICTION_FACTOR;
+            } else if friction < ORIG_FRICTION {
+                // sludge
+                movefactor = sludge_factor(mo, mo.movefactor);
+                mo.movefactor = ORIG_FRICTION_FACTOR;
+            }
+        }
+        return (movefactor, ORIG_FRICTION);
+    }
+
+    let friction = get_friction(context, id);
+    let mut movefactor = friction.movefactor;
+    if friction.friction < ORIG_FRICTION {
+        if let Some(mo) = context.level.mobj(id) {
+            movefactor = sludge_factor(mo, movefactor);
+        }
+    }
+    (movefactor, friction.friction)
+}
+
+/// phares 3/11/98: on sludge you start off slowly, then increase as you
+/// get better footing.
+fn sludge_factor(mo: &Mobj, movefactor: i32) -> i32 {
+    let momentum = approx_distance(mo.momx, mo.momy);
+    if momentum > MORE_FRICTION_MOMENTUM << 2 {
+        movefactor << 3
+    } else if momentum > MORE_FRICTION_MOMENTUM << 1 {
+        movefactor << 2
+    } else if momentum > MORE_FRICTION_MOMENTUM {
+        movefactor << 1
+    } else {
+        movefactor
+    }
+}
+
+/// The sectors a mobj's box touches: its own, and those on either side of
+/// the lines crossing it, like MBF's `touching_sectorlist`.
+pub fn touching_sectors(map: &Map, cl: CompatibilityLevel, mo: &Mobj) -> Vec<usize> {
+    let mut bbox = [Fixed::ZERO; 4];
+    bbox[BOXTOP] = mo.y + mo.radius;
+    bbox[BOXBOTTOM] = mo.y - mo.radius;
+    bbox[BOXRIGHT] = mo.x + mo.radius;
+    bbox[BOXLEFT] = mo.x - mo.radius;
+
+    let mut sectors = vec![];
+    let width = map.blockmap.width as i32;
+    let height = map.blockmap.height as i32;
+    for bx in block_x(map, bbox[BOXLEFT]).max(0)..=block_x(map, bbox[BOXRIGHT]).min(width - 1) {
+        for by in block_y(map, bbox[BOXBOTTOM]).max(0)..=block_y(map, bbox[BOXTOP]).min(height - 1)
+        {
+            for &line in block_lines(map, cl, (by * width + bx) as usize) {
+                if !boxes_overlap(&bbox, &line_bbox(map, line))
+                    || box_on_line_side(map, &bbox, line).is_some()
+                {
+                    continue;
+                }
+                let ld = &map.lines[line];
+                for sector in ld.front_sector.iter().chain(&ld.back_sector) {
+                    if !sectors.contains(sector) {
+                        sectors.push(*sector);
+                    }
+                }
+            }
+        }
+    }
+    let own = map.subsectors[mo.subsector].sector;
+    if !sectors.contains(&own) {
+        sectors.push(own);
+    }
+    sectors
+}
+
+/// killough 9/12/98: Apply "torque" to objects hanging off of ledges, so
+/// that they fall off, like MBF's `P_ApplyTorque`. It's not really
+/// torque, since Doom has no concept of rotation, but it's a convincing
+/// effect which avoids anomalies such as lifeless objects hanging more
+/// than halfway off of ledges, and allows objects to roll off of the
+/// edges of moving lifts, or to slide up and then back down stairs, or to
+/// fall into a ditch. If more than one linedef is contacted, the effects
+/// are cumulative, so balancing is possible.
+pub fn apply_torque(context: &mut Context, id: ThinkerId) {
+    let (x, y, radius, intflags) = match context.level.mobj(id) {
+        Some(mo) => (mo.x, mo.y, mo.radius, mo.intflags),
+        None => return,
+    };
+    let mut bbox = [Fixed::ZERO; 4];
+    bbox[BOXLEFT] = x - radius;
+    bbox[BOXRIGHT] = x + radius;
+    bbox[BOXBOTTOM] = y - radius;
+    bbox[BOXTOP] = y + radius;
+    let map = map(context.configuration);
+    let xl = block_x(map, bbox[BOXLEFT]);
+    let xh = block_x(map, bbox[BOXRIGHT]);
+    let yl = block_y(map, bbox[BOXBOTTOM]);
+    let yh = block_y(map, bbox[BOXTOP]);
+
+    // prevents checking same line twice
+    context.level.validcount += 1;
+    for bx in xl..=xh {
+        for by in yl..=yh {
+            block_lines_iterator(context, bx, by, |context, line| {
+                torque_line(context, id, &bbox, line);
+                true
+            });
+        }
+    }
+
+    let mo = context.level.mobj_mut(id).unwrap();
+    // If any momentum, mark object as 'falling' using engine-internal
+    // flags
+    mo.intflags
+        .set(MobjIntFlag::FALLING, (mo.momx.0 | mo.momy.0) != 0);
+
+    // If the object has been moving, step up the gear. This helps reach
+    // equilibrium and avoid oscillations.
+    if !(mo.intflags | intflags).contains(MobjIntFlag::FALLING) {
+        // not falling for a while, so reset it to full strength
+        mo.gear = 0;
+    } else if mo.gear < MAXGEAR {
+        mo.gear += 1;
+    }
+}
+
+/// Push a mobj away from a two-sided line it straddles, if its center is
+/// over the lower side, like MBF's `PIT_ApplyTorque`.
+fn torque_line(context: &mut Context, id: ThinkerId, bbox: &[Fixed; 4], line: usize) {
+    let map = map(context.configuration);
+    let ld = &map.lines[line];
+    let (front, back) = match (ld.front_sector, ld.back_sector) {
+        (Some(front), Some(back)) => (&map.sectors[front], &map.sectors[back]),
+        _ => return,
+    };
+    if !boxes_overlap(bbox, &line_bbox(map, line)) || box_on_line_side(map, bbox, line).is_some() {
+        return;
+    }
+    let mo = context.level.mobj_mut(id).unwrap();
+    let v1 = &map.vertices[ld.v1];
+    let int = |f: Fixed| f.0 >> FRACBITS;
+
+    // lever arm
+    let mut dist = Fixed(
+        (int(ld.dx).wrapping_mul(int(mo.y)))
+            .wrapping_sub(int(ld.dy).wrapping_mul(int(mo.x)))
+            .wrapping_sub(int(ld.dx).wrapping_mul(int(v1.y)))
+            .wrapping_add(int(ld.dy).wrapping_mul(int(v1.x))),
+    );
+
+    // drop off direction
+    let drops = if dist < Fixed::ZERO {
+        front.floor_height < mo.z && back.floor_height >= mo.z
+    } else {
+        back.floor_height < mo.z && front.floor_height >= mo.z
+    };
+    if !drops {
+        return;
+    }
+
+    // At this point, we know that the object straddles a two-sided
+    // linedef, and that the object's center of mass is above-ground.
+    let mut x = ld.dx.abs();
+    let mut y = ld.dy.abs();
+    if y > x {
+        std::mem::swap(&mut x, &mut y);
+    }
+    let slope = fixed_div(y, x).0 >> DBITS;
+    let y = (Angle(TANTOANGLE[slope as usize]) + ANG90).sin();
+
+    // Momentum is proportional to distance between the object's center of
+    // mass and the pivot linedef. It is scaled by 2^(OVERDRIVE - gear).
+    // When gear is increased, the momentum gradually decreases to 0 for
+    // the same amount of pseudotorque, so that oscillations are prevented,
+    // yet it has a chance to reach equilibrium.
+    let scaled = if mo.gear < OVERDRIVE {
+        y << (OVERDRIVE - mo.gear) as u32
+    } else {
+        y >> (mo.gear - OVERDRIVE) as u32
+    };
+    dist = fixed_div(fixed_mul(dist, scaled), x);
+
+    // Apply momentum away from the pivot linedef.
+    let mut x = fixed_mul(ld.dy, dist);
+    let mut y = fixed_mul(ld.dx, dist);
+
+    // Avoid moving too fast all of a sudden (step into "overdrive")
+    let mut dist = fixed_mul(x, x) + fixed_mul(y, y);
+    while dist > Fixed::from_int(4) && mo.gear < MAXGEAR {
+        mo.gear += 1;
+        x = x >> 1;
+        y = y >> 1;
+        dist = dist >> 1;
+    }
+
+    mo.momx -= x;
+    mo.momy += y;
+}
+
+/// Whether a sector's ceiling is the sky, which missiles vanish into.
+pub fn is_sky(pic: &str) -> bool {
+    pic.eq_ignore_ascii_case("F_SKY1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        configuration::Configuration,
+        info::{init_mobjinfo, init_states},
+        logic::mobj::spawn_mobj,
+        map::{Blockmap, Line, Node, NodeChild, NodeFormat, Sector, Side, Subsector, Vertex},
+    };
+
+    /// A 256 unit square split down the middle by a two-sided line, with
+    /// the floor of the east half at `step`.
+    fn two_rooms(step: i32, flags: LineFlags) -> Map {
+        let fixed = Fixed::from_int;
+        let sector = |floor| Sector {
+            floor_height: fixed(floor),
+            ceiling_height: fixed(128),
+            floor_pic: "FLOOR4_8".to_string(),
+            ceiling_pic: "CEIL3_5".to_string(),
+            light_level: 160,
+            special: 0,
+            tag: 0,
+            lines: vec![0],
+        };
+        let side = |sector| Side {
+            texture_offset: Fixed::ZERO,
+            row_offset: Fixed::ZERO,
+            top_texture: "-".to_string(),
+            bottom_texture: "-".to_string(),
+            mid_texture: "-".to_string(),
+            sector,
+        };
+        let subsector = |sector| Subsector {
+            first_seg: 0,
+            num_segs: 0,
+            sector,
+        };
+        let everywhere = [fixed(256), Fixed::ZERO, Fixed::ZERO, fixed(256)];
+        Map {
+            name: "MAP01".to_string(),
+            namespace: None,
+            things: vec![],
+            vertices: vec![
+                Vertex {
+                    x: fixed(128),
+                    y: Fixed::ZERO,
+                },
+                Vertex {
+                    x: fixed(128),
+                    y: fixed(256),
+                },
+            ],
+            sectors: vec![sector(0), sector(step)],
+            sides: vec![side(1), side(0)],
+            // running north, so the east half is in front
+            lines: vec![Line {
+                v1: 0,
+                v2: 1,
+                dx: Fixed::ZERO,
+                dy: fixed(256),
+                flags: LineFlags::TWO_SIDED | flags,
+                special: 0,
+                tag: 0,
+                sides: [Some(0), Some(1)],
+                front_sector: Some(1),
+                back_sector: Some(0),
+            }],
+            segs: vec![],
+            subsectors: vec![subsector(0), subsector(1)],
+            nodes: vec![Node {
+                x: fixed(128),
+                y: Fixed::ZERO,
+                dx: Fixed::ZERO,
+                dy: fixed(256),
+                bbox: [everywhere; 2],
+                children: [NodeChild::Subsector(1), NodeChild::Subsector(0)],
+            }],
+            node_format: NodeFormat::Vanilla,
+            reject: vec![0],
+            blockmap: Blockmap {
+                origin_x: Fixed::ZERO,
+                origin_y: Fixed::ZERO,
+                width: 2,
+                height: 2,
+                blocks: vec![vec![0, 0]; 4],
+            },
+            gl_nodes: None,
+        }
+    }
+
+    /// Put a `mobj_type` in the west room of `map` and try moving it east
+    /// to `x`. Returns whether it moved, and its position and floor after.
+    fn walk_east(map: Map, mobj_type: MobjType, x: i32) -> (bool, Fixed, Fixed) {
+        init_states();
+        init_mobjinfo();
+        let mut configuration = Configuration::default();
+        let mut level = Level::new(&map);
+        configuration.level = Some(map);
+        let mut context = Context {
+            configuration: &mut configuration,
+            level: &mut level,
+        };
+        let y = Fixed::from_int(128);
+        let id = spawn_mobj(&mut context, Fixed::from_int(64), y, Fixed::ZERO, mobj_type);
+        if mobj_type == MobjType::PLAYER {
+            // a player's own mobj, and not friendly, so it's let through
+            // for being a player
+            let mobj = context.level.mobj_mut(id).unwrap();
+            mobj.player = Some(0);
+            mobj.flags.remove(MobjFlag::FRIEND);
+            context.level.players[0].mo = Some(id);
+        }
+        let moved = try_move(&mut context, id, Fixed::from_int(x), y, DropOff::No);
+        let mobj = context.level.mobj(id).unwrap();
+        (moved, mobj.x, mobj.floorz)
+    }
+
+    #[test]
+    fn walks_across_open_floor() {
+        let (moved, x, floorz) =
+            walk_east(two_rooms(0, LineFlags::empty()), MobjType::POSSESSED, 96);
+        assert!(moved);
+        assert_eq!(x, Fixed::from_int(96));
+        assert_eq!(floorz, Fixed::ZERO);
+    }
+
+    #[test]
+    fn climbs_a_low_step() {
+        let (moved, x, floorz) =
+            walk_east(two_rooms(24, LineFlags::empty()), MobjType::POSSESSED, 120);
+        assert!(moved);
+        assert_eq!(x, Fixed::from_int(120));
+        assert_eq!(floorz, Fixed::from_int(24));
+    }
+
+    #[test]
+    fn stops_at_a_high_step() {
+        let (moved, x, floorz) =
+            walk_east(two_rooms(25, LineFlags::empty()), MobjType::POSSESSED, 120);
+        assert!(!moved);
+        assert_eq!(x, Fixed::from_int(64));
+        assert_eq!(floorz, Fixed::ZERO);
+    }
+
+    #[test]
+    fn monsters_stop_at_lines_that_block_them() {
+        let map = two_rooms(0, LineFlags::BLOCK_MONSTERS);
+        let (moved, x, _) = walk_east(map, MobjType::POSSESSED, 120);
+        assert!(!moved);
+        assert_eq!(x, Fixed::from_int(64));
+
+        // but players walk straight through
+        let map = two_rooms(0, LineFlags::BLOCK_MONSTERS);
+        let (moved, x, _) = walk_east(map, MobjType::PLAYER, 120);
+        assert!(moved);
+        assert_eq!(x, Fixed::from_int(120));
+    }
+
+    #[test]
+    fn crossing_a_teleporter_line_teleports_at_once() {
+        init_states();
+        init_mobjinfo();
+        for (special, stays) in [(97, 97), (39, 0)] {
+            // a teleporter on the line, facing east, to the west room
+            let mut rooms = two_rooms(0, LineFlags::empty());
+            rooms.lines[0].special = special;
+            rooms.lines[0].tag = 1;
+            rooms.sectors[0].tag = 1;
+            let mut configuration = Configuration::default();
+            let mut level = Level::new(&rooms);
+            configuration.level = Some(rooms);
+            let mut context = Context {
+                configuration: &mut configuration,
+                level: &mut level,
+            };
+            let fixed = Fixed::from_int;
+            spawn_mobj(
+                &mut context,
+                fixed(32),
+                fixed(64),
+                Fixed::ZERO,
+                MobjType::TELEPORTMAN,
+            );
+            let id = spawn_mobj(
+                &mut context,
+                fixed(192),
+                fixed(128),
+                Fixed::ZERO,
+                MobjType::POSSESSED,
+            );
+
+            assert!(try_move(
+                &mut context,
+                id,
+                fixed(120),
+                fixed(128),
+                DropOff::No
+            ));
+            let mobj = context.level.mobj(id).unwrap();
+            assert_eq!((mobj.x, mobj.y), (fixed(32), fixed(64)));
+            let fogs = context
+                .level
+                .thinkers
+                .iter()
+                .filter(|(_, Thinker::Mobj(mo))| mo.mobj_type == MobjType::TFOG)
+                .count();
+            assert_eq!(fogs, 2);
+            assert_eq!(map(context.configuration).lines[0].special, stays);
+        }
+    }
+
+    #[test]
+    fn spechit_overrun_lands_on_the_globals_after_the_array() {
+        let mut configuration = Configuration::default();
+        configuration.defaults.overrun_spechit_emulate = true;
+        let line = 3;
+        let addr = Fixed(0x01c09c98 + 3 * 0x3e);
+        for (level, spechits, floorz, bbox0) in [
+            (CompatibilityLevel::Doom2V19, 9, Fixed::ZERO, addr),
+            (CompatibilityLevel::Doom2V19, 10, Fixed::ZERO, Fixed::ZERO),
+            (CompatibilityLevel::DosDoom, 9, addr, Fixed::ZERO),
+        ] {
+            configuration.compatibility_level = level;
+            let mut check = PositionCheck {
+                spechit: vec![line; spechits],
+                ..PositionCheck::default()
+            };
+            spechit_overrun(&configuration, &mut check, line);
+            assert_eq!(check.floorz, floorz);
+            assert_eq!(check.bbox[0], bbox0);
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use super::{inter::MAXAMMO, Context};
+use crate::{
+    doom::def::{AmmoType, WeaponType, NUMAMMO, NUMCARDS, NUMPOWERS, NUMWEAPONS},
+    misc::fixed::{fixed_mul, Fixed},
+    tables::Angle,
+    think::ThinkerId,
+};
+
+pub const MAXPLAYERS: usize = 4;
+
+/// How far above the floor a player's eyes are.
+pub const VIEWHEIGHT: Fixed = Fixed::from_int(41);
+
+/// The health every player starts with.
+pub const INITIAL_HEALTH: i32 = 100;
+/// The bullets every player starts with.
+pub const INITIAL_BULLETS: i32 = 50;
+
+/// The player sprites: the weapon, and the muzzle flash drawn over it.
+pub const PS_WEAPON: usize = 0;
+pub const PS_FLASH: usize = 1;
+pub const NUMPSPRITES: usize = 2;
+
+/// One of the sprites drawn over the player's view.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct PspDef {
+    /// `None` when the sprite isn't drawn.
+    pub state: Option<usize>,
+    pub tics: i64,
+    pub sx: Fixed,
+    pub sy: Fixed,
+}
+
+/// `TicCmd::buttons`: fire the weapon.
+pub const BT_ATTACK: u8 = 1;
+
+/// What a player asked to do in a tic, like vanilla's `ticcmd_t`.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct TicCmd {
+    /// Forward and sideways movement, times 2048 for a move.
+    pub forwardmove: i8,
+    pub sidemove: i8,
+    /// Turning, in the top 16 bits of an angle.
+    #[allow(dead_code)]
+    pub angleturn: i16,
+    pub buttons: u8,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum PlayerState {
+    /// Playing, or camping.
+    Live,
+    /// Dead on the ground, view follows killer.
+    Dead,
+    /// Ready to restart or respawn.
+    #[default]
+    Reborn,
+}
+
+#[derive(Debug, Default)]
+pub struct Player {
+    /// The player's mobj, once spawned.
+    pub mo: Option<ThinkerId>,
+    pub playerstate: PlayerState,
+    pub cmd: TicCmd,
+
+    /// The height of the player's eyes above the floor, and how fast it's
+    /// coming back after landing or stepping up.
+    pub viewheight: Fixed,
+    pub deltaviewheight: Fixed,
+    /// killough 10/98: momentum for view bobbing, kept apart from the
+    /// mobj's so ice doesn't make it worse.
+    pub momx: Fixed,
+    pub momy: Fixed,
+    /// How far the view and weapon bob, from how fast the player moves.
+    pub bob: Fixed,
+
+    /// The player's health, mirrored from the mobj.
+    pub health: i32,
+    pub armorpoints: i32,
+    /// 0 for none, 1 for green armor, 2 for blue.
+    pub armortype: i32,
+    /// Tics left of each power up, by `PowerType`. Negative ones last
+    /// forever.
+    pub powers: [i32; NUMPOWERS],
+    /// The keys held, by `CardType`.
+    pub cards: [bool; NUMCARDS],
+    pub backpack: bool,
+
+    pub readyweapon: WeaponType,
+    /// The weapon being switched to, if any.
+    pub pendingweapon: Option<WeaponType>,
+    pub weaponowned: [bool; NUMWEAPONS],
+    /// The ammo held and the most that can be, by `AmmoType`.
+    pub ammo: [i32; NUMAMMO],
+    pub maxammo: [i32; NUMAMMO],
+    /// Whether fire was held last tic, so the rocket launcher and BFG
+    /// don't fire again until it's let go.
+    pub attackdown: bool,
+    /// How many shots in a row fire has been held for; the pistol and
+    /// chaingun are only accurate on the first.
+    pub refire: i32,
+
+    /// Kills, items and secrets for the intermission, and frags of each
+    /// other player.
+    pub killcount: i32,
+    pub itemcount: i32,
+    pub secretcount: i32,
+    pub frags: [i32; MAXPLAYERS],
+    /// Who last hurt the player.
+    pub attacker: Option<ThinkerId>,
+    /// How red the screen is from being hurt, and how gold from picking
+    /// something up.
+    pub damagecount: i32,
+    pub bonuscount: i32,
+    /// The last message for the player to read.
+    pub message: Option<&'static str>,
+    pub psprites: [PspDef; NUMPSPRITES],
+    /// Extra light from the player's muzzle flash.
+    pub extralight: i32,
+}
+
+impl Player {
+    /// Start the player over, as at the start of a game or after dying
+    /// in a netgame, like vanilla's `G_PlayerReborn`, with a pistol and
+    /// some bullets. Kills, items, secrets and frags are kept.
+    pub fn reborn(&mut self) {
+        let mut weaponowned = [false; NUMWEAPONS];
+        weaponowned[WeaponType::Fist as usize] = true;
+        weaponowned[WeaponType::Pistol as usize] = true;
+        let mut ammo = [0; NUMAMMO];
+        ammo[AmmoType::Clip as usize] = INITIAL_BULLETS;
+        *self = Player {
+            playerstate: PlayerState::Live,
+            health: INITIAL_HEALTH,
+            readyweapon: WeaponType::Pistol,
+            pendingweapon: Some(WeaponType::Pistol),
+            weaponowned,
+            ammo,
+            maxammo: MAXAMMO,
+            killcount: self.killcount,
+            itemcount: self.itemcount,
+            secretcount: self.secretcount,
+            frags: self.frags,
+            ..Player::default()
+        };
+    }
+}
+
+/// Push player `n`'s mobj along `angle`, like vanilla's `P_Thrust`.
+pub fn thrust(context: &mut Context, n: usize, angle: Angle, mv: Fixed) {
+    let mo = context.level.players[n].mo;
+    if let Some(mo) = mo.and_then(|mo| context.level.mobj_mut(mo)) {
+        mo.momx += fixed_mul(mv, angle.cos());
+        mo.momy += fixed_mul(mv, angle.sin());
+    }
+}
@@ -0,0 +1,923 @@
+use super::{
+    enemy::noise_alert,
+    inter::damage_mobj,
+    mobj::{
+        autoaim, check_missile_spawn, set_mobj_state, spawn_mobj, spawn_player_missile, MobjFlag,
+    },
+    movement::{aim_line_attack, line_attack, MELEERANGE, MISSILERANGE},
+    player::{thrust, PlayerState, BT_ATTACK, PS_FLASH, PS_WEAPON},
+    Context,
+};
+use crate::{
+    configuration::CompatibilityLevel,
+    doom::{
+        def::{AmmoType, GameMode, PowerType, WeaponType},
+        items::WEAPONINFO,
+    },
+    info::{MobjType, Statenum, STATES},
+    lprint,
+    misc::{
+        fixed::{fixed_mul, Fixed},
+        lprint::OutputLevel,
+        random::{p_random, p_sub_random, PrClass},
+    },
+    sounds::Sfx,
+    tables::{point_to_angle2, Angle, ANG180, ANG90, DBITS, FINETANGENT, TANTOANGLE},
+    think::{ActionF, ThinkerId},
+};
+
+/// How fast weapons go down and come up when switching.
+const LOWERSPEED: Fixed = Fixed::from_int(6);
+const RAISESPEED: Fixed = Fixed::from_int(6);
+/// Where a weapon is drawn when it's all the way down, and all the way up.
+const WEAPONBOTTOM: Fixed = Fixed::from_int(128);
+const WEAPONTOP: Fixed = Fixed::from_int(32);
+/// The cells a BFG shot takes.
+const BFGCELLS: i32 = 40;
+
+/// phares: how hard each weapon kicks the player back, by `WeaponType`.
+const RECOIL_VALUES: [i32; 9] = [10, 10, 30, 10, 100, 20, 100, 0, 80];
+
+/// killough 3/22/98: the order weapons are switched to in when out of
+/// ammo, best first, as in vanilla. 1 is the fist with berserk, 0 the fist
+/// without; the rest are `WeaponType`s plus one.
+const WEAPON_PREFERENCES: [i32; 10] = [6, 9, 4, 3, 2, 8, 5, 7, 1, 0];
+
+/// Put one of a player's sprites into `state`, running its action, and on
+/// through any zero-tic states after it, like vanilla's `P_SetPsprite`.
+/// `None` or `S_NULL` stops drawing the sprite.
+pub fn set_psprite_state(
+    context: &mut Context,
+    player: usize,
+    position: usize,
+    state: Option<usize>,
+) {
+    let mut state = state;
+    // vanilla would hang on a cycle of zero-tic states
+    let mut seen = vec![];
+    loop {
+        let stnum = match state {
+            Some(stnum) if stnum != Statenum::NULL as usize => stnum,
+            _ => {
+                context.level.players[player].psprites[position].state = None;
+                break;
+            }
+        };
+        let st = STATES.read()[stnum];
+        {
+            let psp = &mut context.level.players[player].psprites[position];
+            psp.state = Some(stnum);
+            psp.tics = st.tics;
+            if st.misc1 != 0 {
+                psp.sx = Fixed::from_int(st.misc1 as i32);
+                psp.sy = Fixed::from_int(st.misc2 as i32);
+            }
+        }
+        seen.push(stnum);
+
+        if let ActionF::PSprite(action) = st.action {
+            action(context, player, position);
+        }
+        let psp = &context.level.players[player].psprites[position];
+        let stnum = match psp.state {
+            // the action took the sprite down
+            None => break,
+            Some(stnum) => stnum,
+        };
+        if psp.tics != 0 {
+            break;
+        }
+        let next = STATES.read()[stnum].nextstate;
+        if seen.contains(&next) {
+            lprint!(OutputLevel::WARN, "Warning: State Cycle Detected\n");
+            break;
+        }
+        state = Some(next);
+    }
+}
+
+/// Start raising the player's pending weapon, or the ready one if none is
+/// pending, like vanilla's `P_BringUpWeapon`.
+fn bring_up_weapon(context: &mut Context, n: usize) {
+    let mbf = context.configuration.compatibility_level >= CompatibilityLevel::Mbf;
+    let player = &mut context.level.players[n];
+    let pending = player.pendingweapon.unwrap_or(player.readyweapon);
+    let mo = player.mo;
+    if pending == WeaponType::Chainsaw {
+        context.level.start_sound(mo, Sfx::SAWUP as usize);
+    }
+
+    let player = &mut context.level.players[n];
+    player.pendingweapon = None;
+    // killough 12/98: prevent pistol from starting visibly at bottom of
+    // screen
+    player.psprites[PS_WEAPON].sy = if mbf {
+        WEAPONBOTTOM + Fixed::from_int(2)
+    } else {
+        WEAPONBOTTOM
+    };
+    set_psprite_state(
+        context,
+        n,
+        PS_WEAPON,
+        Some(WEAPONINFO[pending as usize].upstate),
+    );
+}
+
+/// The best weapon the player has ammo for, like Boom's `P_SwitchWeapon`.
+/// killough 2/8/98: follows the preferences, fixing the BFG and super
+/// shotgun bugs.
+pub fn switch_weapon(context: &Context, n: usize) -> WeaponType {
+    let demo_compatibility = context.configuration.compatibility_level < CompatibilityLevel::Boom;
+    let game_mode = context.configuration.game_mode;
+    let player = &context.level.players[n];
+    let owned = |weapon: WeaponType| player.weaponowned[weapon as usize];
+    let ammo = |ammo: AmmoType| player.ammo[ammo as usize];
+
+    let currentweapon = player.readyweapon;
+    let mut newweapon = currentweapon;
+    for &prefer in &WEAPON_PREFERENCES {
+        match prefer {
+            // allow chainsaw override
+            0 | 1 if prefer == 0 || player.powers[PowerType::Strength as usize] != 0 => {
+                newweapon = WeaponType::Fist;
+            }
+            2 if ammo(AmmoType::Clip) != 0 => newweapon = WeaponType::Pistol,
+            3 if owned(WeaponType::Shotgun) && ammo(AmmoType::Shell) != 0 => {
+                newweapon = WeaponType::Shotgun;
+            }
+            4 if owned(WeaponType::Chaingun) && ammo(AmmoType::Clip) != 0 => {
+                newweapon = WeaponType::Chaingun;
+            }
+            5 if owned(WeaponType::Missile) && ammo(AmmoType::Misl) != 0 => {
+                newweapon = WeaponType::Missile;
+            }
+            6 if owned(WeaponType::Plasma)
+                && ammo(AmmoType::Cell) != 0
+                && game_mode != GameMode::Shareware =>
+            {
+                newweapon = WeaponType::Plasma;
+            }
+            7 if owned(WeaponType::Bfg)
+                && game_mode != GameMode::Shareware
+                && ammo(AmmoType::Cell) >= if demo_compatibility { 41 } else { 40 } =>
+            {
+                newweapon = WeaponType::Bfg;
+            }
+            8 if owned(WeaponType::Chainsaw) => newweapon = WeaponType::Chainsaw,
+            9 if owned(WeaponType::SuperShotgun)
+                && game_mode == GameMode::Commercial
+                && ammo(AmmoType::Shell) >= if demo_compatibility { 3 } else { 2 } =>
+            {
+                newweapon = WeaponType::SuperShotgun;
+            }
+            _ => {}
+        }
+        // killough 5/2/98
+        if newweapon != currentweapon {
+            break;
+        }
+    }
+    newweapon
+}
+
+/// Whether the player has the ammo for a shot of the ready weapon, like
+/// vanilla's `P_CheckAmmo`. If not, old demos switch to another weapon
+/// here; from Boom on, the tic command asks for the switch instead.
+fn check_ammo(context: &mut Context, n: usize) -> bool {
+    let demo_compatibility = context.configuration.compatibility_level < CompatibilityLevel::Boom;
+    let player = &context.level.players[n];
+    let ammo = WEAPONINFO[player.readyweapon as usize].ammo;
+    // Minimal amount for one shot varies.
+    let count = match player.readyweapon {
+        WeaponType::Bfg => BFGCELLS,
+        // Double barrel.
+        WeaponType::SuperShotgun => 2,
+        // Regular
+        _ => 1,
+    };
+
+    // Some do not need ammunition anyway.
+    if ammo == AmmoType::NoAmmo || player.ammo[ammo as usize] >= count {
+        return true;
+    }
+
+    // Out of ammo, pick a weapon to change to.
+    //
+    // killough 3/22/98: for old demos we do the switch here and now; for
+    // Boom games we cannot do this, and have different player preferences
+    // across demos or networks, so we have to use the tic command instead
+    // of making the switch here.
+    if demo_compatibility {
+        let newweapon = switch_weapon(context, n);
+        let player = &mut context.level.players[n];
+        player.pendingweapon = Some(newweapon);
+        // Now set appropriate weapon overlay.
+        let downstate = WEAPONINFO[player.readyweapon as usize].downstate;
+        set_psprite_state(context, n, PS_WEAPON, Some(downstate));
+    }
+    false
+}
+
+/// Start the ready weapon's attack, like vanilla's `P_FireWeapon`.
+fn fire_weapon(context: &mut Context, n: usize) {
+    if !check_ammo(context, n) {
+        return;
+    }
+    let player = &context.level.players[n];
+    let (mo, atkstate) = (player.mo, WEAPONINFO[player.readyweapon as usize].atkstate);
+    if let Some(mo) = mo {
+        set_mobj_state(context, mo, Statenum::PLAY_ATK1 as usize);
+    }
+    set_psprite_state(context, n, PS_WEAPON, Some(atkstate));
+    if let Some(mo) = mo {
+        noise_alert(context, mo, mo);
+    }
+}
+
+/// Put the player's weapon away, as when they die, like vanilla's
+/// `P_DropWeapon`.
+pub fn drop_weapon(context: &mut Context, n: usize) {
+    let downstate = WEAPONINFO[context.level.players[n].readyweapon as usize].downstate;
+    set_psprite_state(context, n, PS_WEAPON, Some(downstate));
+}
+
+/// Take the player's sprites down and bring up their ready weapon, as
+/// when they spawn, like vanilla's `P_SetupPsprites`.
+pub fn setup_psprites(context: &mut Context, n: usize) {
+    // remove all psprites
+    let player = &mut context.level.players[n];
+    for psp in &mut player.psprites {
+        psp.state = None;
+    }
+
+    // spawn the gun
+    player.pendingweapon = Some(player.readyweapon);
+    bring_up_weapon(context, n);
+}
+
+/// Count down the player's sprites' tics and move them on to their next
+/// states, like vanilla's `P_MovePsprites`. The flash goes where the
+/// weapon is.
+pub fn move_psprites(context: &mut Context, n: usize) {
+    for position in 0..context.level.players[n].psprites.len() {
+        // a null state means not active; a -1 tic count never changes
+        let psp = &mut context.level.players[n].psprites[position];
+        let state = match psp.state {
+            Some(state) if psp.tics != -1 => state,
+            _ => continue,
+        };
+        psp.tics -= 1;
+        if psp.tics == 0 {
+            let next = STATES.read()[state].nextstate;
+            set_psprite_state(context, n, position, Some(next));
+        }
+    }
+    let player = &mut context.level.players[n];
+    player.psprites[PS_FLASH].sx = player.psprites[PS_WEAPON].sx;
+    player.psprites[PS_FLASH].sy = player.psprites[PS_WEAPON].sy;
+}
+
+/// Use up `count` of the ready weapon's ammo.
+fn use_ammo(context: &mut Context, n: usize, count: i32) {
+    let player = &mut context.level.players[n];
+    let ammo = WEAPONINFO[player.readyweapon as usize].ammo;
+    if ammo != AmmoType::NoAmmo {
+        player.ammo[ammo as usize] -= count;
+    }
+}
+
+/// Put the player's mobj into `state`, e.g. to show it firing.
+fn set_player_mobj_state(context: &mut Context, n: usize, state: Statenum) {
+    if let Some(mo) = context.level.players[n].mo {
+        set_mobj_state(context, mo, state as usize);
+    }
+}
+
+/// The player presses fire or switches weapons while the weapon is up, and
+/// it bobs as they move.
+pub fn a_weapon_ready(context: &mut Context, n: usize, position: usize) {
+    let leveltime = context.level.leveltime;
+    let player = &context.level.players[n];
+    let mo = player.mo;
+
+    // get out of attack state
+    let mo_state = mo.and_then(|mo| context.level.mobj(mo)).map(|mo| mo.state);
+    if mo_state == Some(Statenum::PLAY_ATK1 as usize)
+        || mo_state == Some(Statenum::PLAY_ATK2 as usize)
+    {
+        set_player_mobj_state(context, n, Statenum::PLAY);
+    }
+
+    let player = &context.level.players[n];
+    if player.readyweapon == WeaponType::Chainsaw
+        && player.psprites[position].state == Some(Statenum::SAW as usize)
+    {
+        context.level.start_sound(mo, Sfx::SAWIDL as usize);
+    }
+
+    // check for change; if player is dead, put the weapon away
+    let player = &context.level.players[n];
+    if player.pendingweapon.is_some() || player.health == 0 {
+        // change weapon (pending weapon should already be validated)
+        let downstate = WEAPONINFO[player.readyweapon as usize].downstate;
+        set_psprite_state(context, n, PS_WEAPON, Some(downstate));
+        return;
+    }
+
+    // check for fire; the missile launcher and bfg do not auto fire
+    let player = &mut context.level.players[n];
+    if player.cmd.buttons & BT_ATTACK != 0 {
+        if !player.attackdown
+            || (player.readyweapon != WeaponType::Missile && player.readyweapon != WeaponType::Bfg)
+        {
+            player.attackdown = true;
+            fire_weapon(context, n);
+            return;
+        }
+    } else {
+        player.attackdown = false;
+    }
+
+    // bob the weapon based on movement speed
+    let angle = Angle((128 * leveltime as u32) << 19);
+    let psp = &mut player.psprites[position];
+    psp.sx = Fixed::UNIT + fixed_mul(player.bob, angle.cos());
+    let angle = Angle(angle.0 & (ANG180.0 - 1));
+    psp.sy = WEAPONTOP + fixed_mul(player.bob, angle.sin());
+}
+
+/// The player can fire again if they're still holding fire; the pistol
+/// and chaingun lose their accuracy if they do.
+pub fn a_refire(context: &mut Context, n: usize, _: usize) {
+    // check for fire (if a weaponchange is pending, let it go through
+    // instead)
+    let player = &mut context.level.players[n];
+    if player.cmd.buttons & BT_ATTACK != 0 && player.pendingweapon.is_none() && player.health != 0 {
+        player.refire += 1;
+        fire_weapon(context, n);
+    } else {
+        player.refire = 0;
+        check_ammo(context, n);
+    }
+}
+
+/// Between super shotgun shots, lower it at once if it's out of ammo.
+pub fn a_check_reload(context: &mut Context, n: usize, _: usize) {
+    // cph 2002/08/08: in old Doom, check_ammo would start the weapon
+    // lowering immediately. This was lost in Boom when the weapon
+    // switching logic was rewritten, but the reload frames needn't be
+    // finished here either.
+    if !check_ammo(context, n)
+        && context.configuration.compatibility_level >= CompatibilityLevel::PrBoomV22x
+    {
+        drop_weapon(context, n);
+    }
+}
+
+/// Lower the weapon, and once it's off the screen bring up the next one,
+/// unless the player is dead.
+pub fn a_lower(context: &mut Context, n: usize, position: usize) {
+    let player = &mut context.level.players[n];
+    let psp = &mut player.psprites[position];
+    psp.sy += LOWERSPEED;
+
+    // Is already down.
+    if psp.sy < WEAPONBOTTOM {
+        return;
+    }
+
+    // Player is dead.
+    if player.playerstate == PlayerState::Dead {
+        player.psprites[position].sy = WEAPONBOTTOM;
+        // don't bring weapon back up
+        return;
+    }
+
+    // The old weapon has been lowered off the screen, so change the weapon
+    // and start raising it
+    if player.health == 0 {
+        // Player is dead, so keep the weapon off screen.
+        set_psprite_state(context, n, PS_WEAPON, None);
+        return;
+    }
+
+    if let Some(pending) = player.pendingweapon {
+        player.readyweapon = pending;
+    }
+    bring_up_weapon(context, n);
+}
+
+/// Raise the weapon, and once it's all the way up make it ready.
+pub fn a_raise(context: &mut Context, n: usize, position: usize) {
+    let player = &mut context.level.players[n];
+    let psp = &mut player.psprites[position];
+    psp.sy -= RAISESPEED;
+    if psp.sy > WEAPONTOP {
+        return;
+    }
+    psp.sy = WEAPONTOP;
+
+    // The weapon has been raised all the way, so change to the ready state.
+    let readystate = WEAPONINFO[player.readyweapon as usize].readystate;
+    set_psprite_state(context, n, PS_WEAPON, Some(readystate));
+}
+
+/// phares: show the muzzle flash and kick the player back. The flash is
+/// set here rather than when fire is pressed so the recoil is in time with
+/// it; the BFG's delay made that necessary.
+fn fire_something(context: &mut Context, n: usize, adder: usize) {
+    let configuration = &*context.configuration;
+    let recoil =
+        configuration.compatibility_level > CompatibilityLevel::Boom && configuration.weapon_recoil;
+    let player = &context.level.players[n];
+    let readyweapon = player.readyweapon;
+    let flashstate = WEAPONINFO[readyweapon as usize].flashstate;
+    let mo = player.mo.and_then(|mo| context.level.mobj(mo));
+    // killough 3/27/98: prevent recoil in no-clipping mode
+    let recoil_angle = mo
+        .filter(|mo| recoil && !mo.flags.contains(MobjFlag::NOCLIP))
+        .map(|mo| ANG180 + mo.angle);
+
+    set_psprite_state(context, n, PS_FLASH, Some(flashstate + adder));
+    if let Some(angle) = recoil_angle {
+        thrust(
+            context,
+            n,
+            angle,
+            Fixed(2048 * RECOIL_VALUES[readyweapon as usize]),
+        );
+    }
+}
+
+/// Show the player firing, and the muzzle flash.
+pub fn a_gun_flash(context: &mut Context, n: usize, _: usize) {
+    set_player_mobj_state(context, n, Statenum::PLAY_ATK2);
+    fire_something(context, n, 0);
+}
+
+/// Aim at what's in front of a melee weapon: enemies first from MBF on.
+fn melee_aim(
+    context: &mut Context,
+    mo: ThinkerId,
+    angle: Angle,
+    range: Fixed,
+) -> (Fixed, Option<ThinkerId>) {
+    let mbf = context.configuration.compatibility_level >= CompatibilityLevel::Mbf;
+    // killough 8/2/98: make autoaiming prefer enemies
+    if mbf {
+        let aim = aim_line_attack(context, mo, angle, range, MobjFlag::FRIEND);
+        if aim.linetarget.is_some() {
+            return (aim.slope, aim.linetarget);
+        }
+    }
+    let aim = aim_line_attack(context, mo, angle, range, MobjFlag::empty());
+    (aim.slope, aim.linetarget)
+}
+
+/// Punch what's in front, ten times as hard with berserk, and turn to
+/// face it.
+pub fn a_punch(context: &mut Context, n: usize, _: usize) {
+    let mut damage = (p_random(context.configuration, PrClass::Punch) % 10 + 1) << 1;
+    let player = &context.level.players[n];
+    if player.powers[PowerType::Strength as usize] != 0 {
+        damage *= 10;
+    }
+    let mo = match player.mo {
+        Some(mo) => mo,
+        None => return,
+    };
+    let angle = context.level.mobj(mo).unwrap().angle;
+    // killough 5/5/98: remove dependence on order of evaluation
+    let angle =
+        angle + Angle((p_sub_random(context.configuration, PrClass::PunchAngle) << 18) as u32);
+
+    let (slope, linetarget) = melee_aim(context, mo, angle, MELEERANGE);
+    line_attack(context, mo, angle, MELEERANGE, slope, damage);
+
+    let linetarget = match linetarget.and_then(|t| context.level.mobj(t)) {
+        Some(t) => (t.x, t.y),
+        None => return,
+    };
+    context.level.start_sound(Some(mo), Sfx::PUNCH as usize);
+
+    // turn to face target
+    let mobj = context.level.mobj_mut(mo).unwrap();
+    mobj.angle = point_to_angle2(mobj.x, mobj.y, linetarget.0, linetarget.1);
+}
+
+/// Saw what's in front, and pull towards it.
+pub fn a_saw(context: &mut Context, n: usize, _: usize) {
+    let damage = 2 * (p_random(context.configuration, PrClass::Saw) % 10 + 1);
+    let mo = match context.level.players[n].mo {
+        Some(mo) => mo,
+        None => return,
+    };
+    let angle = context.level.mobj(mo).unwrap().angle;
+    // killough 5/5/98: remove dependence on order of evaluation
+    let angle = angle + Angle((p_sub_random(context.configuration, PrClass::Saw) << 18) as u32);
+
+    // Use meleerange + 1 so that the puff doesn't skip the flash
+    let range = MELEERANGE + Fixed(1);
+    let (slope, linetarget) = melee_aim(context, mo, angle, range);
+    line_attack(context, mo, angle, range, slope, damage);
+
+    let linetarget = match linetarget.and_then(|t| context.level.mobj(t)) {
+        Some(t) => (t.x, t.y),
+        None => {
+            context.level.start_sound(Some(mo), Sfx::SAWFUL as usize);
+            return;
+        }
+    };
+    context.level.start_sound(Some(mo), Sfx::SAWHIT as usize);
+
+    // turn to face target
+    let mobj = context.level.mobj_mut(mo).unwrap();
+    let angle = point_to_angle2(mobj.x, mobj.y, linetarget.0, linetarget.1);
+    let delta = angle - mobj.angle;
+    if delta > ANG180 {
+        if delta < -(ANG90 / 20) {
+            mobj.angle = angle + ANG90 / 21;
+        } else {
+            mobj.angle -= ANG90 / 20;
+        }
+    } else if delta > ANG90 / 20 {
+        mobj.angle = angle - ANG90 / 21;
+    } else {
+        mobj.angle += ANG90 / 20;
+    }
+    mobj.flags |= MobjFlag::JUSTATTACKED;
+}
+
+/// Fire a rocket.
+pub fn a_fire_missile(context: &mut Context, n: usize, _: usize) {
+    use_ammo(context, n, 1);
+    if let Some(mo) = context.level.players[n].mo {
+        spawn_player_missile(context, mo, MobjType::ROCKET);
+    }
+}
+
+/// Fire the BFG ball.
+pub fn a_fire_bfg(context: &mut Context, n: usize, _: usize) {
+    use_ammo(context, n, BFGCELLS);
+    if let Some(mo) = context.level.players[n].mo {
+        spawn_player_missile(context, mo, MobjType::BFG);
+    }
+}
+
+/// killough 6/6/98: the pre-beta BFG, which sprays alternating plasma
+/// balls about the way it's aimed.
+pub fn a_fire_old_bfg(context: &mut Context, n: usize, _: usize) {
+    let weapon_recoil = context.configuration.weapon_recoil;
+    let mo = match context.level.players[n].mo {
+        Some(mo) => mo,
+        None => return,
+    };
+    let flags = context.level.mobj(mo).unwrap().flags;
+    if weapon_recoil && !flags.contains(MobjFlag::NOCLIP) {
+        let angle = ANG180 + context.level.mobj(mo).unwrap().angle;
+        thrust(
+            context,
+            n,
+            angle,
+            Fixed(512 * RECOIL_VALUES[WeaponType::Plasma as usize]),
+        );
+    }
+    use_ammo(context, n, 1);
+    context.level.players[n].extralight = 2;
+
+    for mobj_type in [MobjType::PLASMA1, MobjType::PLASMA2] {
+        let angle = context.level.mobj(mo).unwrap().angle;
+        let r1 = (p_random(context.configuration, PrClass::Bfg) & 127) - 64;
+        let mut an1 = Angle((r1 * (ANG90.0 / 768) as i32) as u32) + angle;
+        let r2 = (p_random(context.configuration, PrClass::Bfg) & 127) - 64;
+        let mut an2 = Angle((r2 * (ANG90.0 / 640) as i32) as u32) + ANG90;
+
+        // killough 8/2/98: make autoaiming prefer enemies
+        let (an, slope) = autoaim(context, mo, MobjFlag::FRIEND);
+        an1 += an - angle;
+        // MBF reads before the table for a downwards slope; aim as far down
+        // as the same slope would up instead
+        let index = (slope.0 >> DBITS) as isize;
+        an2 += if index < 0 {
+            -Angle(TANTOANGLE[index.unsigned_abs()])
+        } else {
+            Angle(TANTOANGLE[index as usize])
+        };
+
+        let (x, y, z) = {
+            let mobj = context.level.mobj(mo).unwrap();
+            let sy = context.level.players[n].psprites[PS_WEAPON].sy;
+            (mobj.x, mobj.y, mobj.z + Fixed::from_int(62) - sy)
+        };
+        let th = spawn_mobj(context, x, y, z, mobj_type);
+        let ball = context.level.mobj_mut(th).unwrap();
+        ball.target = Some(mo);
+        ball.angle = an1;
+        ball.momx = an1.cos() * 25;
+        ball.momy = an1.sin() * 25;
+        ball.momz = Fixed(FINETANGENT[an2.fine()]) * 25;
+        check_missile_spawn(context, th);
+    }
+}
+
+/// Fire a plasma ball, with one of the two flashes.
+pub fn a_fire_plasma(context: &mut Context, n: usize, _: usize) {
+    use_ammo(context, n, 1);
+    let adder = (p_random(context.configuration, PrClass::Plasma) & 1) as usize;
+    fire_something(context, n, adder);
+    if let Some(mo) = context.level.players[n].mo {
+        spawn_player_missile(context, mo, MobjType::PLASMA);
+    }
+}
+
+/// The slope hitscan weapons fire at, aimed at what's in front of `mo` or
+/// a little to either side, like vanilla's `P_BulletSlope`.
+fn bullet_slope(context: &mut Context, mo: ThinkerId) -> Fixed {
+    let mbf = context.configuration.compatibility_level >= CompatibilityLevel::Mbf;
+    let range = Fixed::from_int(16 * 64);
+    let mut an = context.level.mobj(mo).unwrap().angle;
+    // killough 8/2/98: make autoaiming prefer enemies
+    let mut mask = if mbf {
+        MobjFlag::FRIEND
+    } else {
+        MobjFlag::empty()
+    };
+    loop {
+        let mut aim = aim_line_attack(context, mo, an, range, mask);
+        if aim.linetarget.is_none() {
+            an += Angle(1 << 26);
+            aim = aim_line_attack(context, mo, an, range, mask);
+        }
+        if aim.linetarget.is_none() {
+            an -= Angle(2 << 26);
+            aim = aim_line_attack(context, mo, an, range, mask);
+        }
+        // killough 8/2/98: try again without the mask, from where the
+        // last try left off
+        if mask.is_empty() || aim.linetarget.is_some() {
+            return aim.slope;
+        }
+        mask = MobjFlag::empty();
+    }
+}
+
+/// Fire one bullet along `slope`, off to the side a bit unless
+/// `accurate`, like vanilla's `P_GunShot`.
+fn gun_shot(context: &mut Context, mo: ThinkerId, accurate: bool, slope: Fixed) {
+    let damage = 5 * (p_random(context.configuration, PrClass::Gunshot) % 3 + 1);
+    let mut angle = context.level.mobj(mo).unwrap().angle;
+    if !accurate {
+        // killough 5/5/98: remove dependence on order of evaluation
+        angle += Angle((p_sub_random(context.configuration, PrClass::Misfire) << 18) as u32);
+    }
+    line_attack(context, mo, angle, MISSILERANGE, slope, damage);
+}
+
+/// Fire the pistol, accurately unless fire has been held.
+pub fn a_fire_pistol(context: &mut Context, n: usize, _: usize) {
+    let mo = match context.level.players[n].mo {
+        Some(mo) => mo,
+        None => return,
+    };
+    context.level.start_sound(Some(mo), Sfx::PISTOL as usize);
+    set_mobj_state(context, mo, Statenum::PLAY_ATK2 as usize);
+    use_ammo(context, n, 1);
+    fire_something(context, n, 0);
+    let slope = bullet_slope(context, mo);
+    let accurate = context.level.players[n].refire == 0;
+    gun_shot(context, mo, accurate, slope);
+}
+
+/// Fire the shotgun's seven pellets.
+pub fn a_fire_shotgun(context: &mut Context, n: usize, _: usize) {
+    let mo = match context.level.players[n].mo {
+        Some(mo) => mo,
+        None => return,
+    };
+    context.level.start_sound(Some(mo), Sfx::SHOTGN as usize);
+    set_mobj_state(context, mo, Statenum::PLAY_ATK2 as usize);
+    use_ammo(context, n, 1);
+    fire_something(context, n, 0);
+    let slope = bullet_slope(context, mo);
+    for _ in 0..7 {
+        gun_shot(context, mo, false, slope);
+    }
+}
+
+/// Fire both barrels of the super shotgun: twenty pellets, spread up and
+/// down as well as sideways.
+pub fn a_fire_shotgun2(context: &mut Context, n: usize, _: usize) {
+    let mo = match context.level.players[n].mo {
+        Some(mo) => mo,
+        None => return,
+    };
+    context.level.start_sound(Some(mo), Sfx::DSHTGN as usize);
+    set_mobj_state(context, mo, Statenum::PLAY_ATK2 as usize);
+    use_ammo(context, n, 2);
+    fire_something(context, n, 0);
+    let slope = bullet_slope(context, mo);
+    for _ in 0..20 {
+        let damage = 5 * (p_random(context.configuration, PrClass::Shotgun) % 3 + 1);
+        let mut angle = context.level.mobj(mo).unwrap().angle;
+        // killough 5/5/98: remove dependence on order of evaluation
+        angle += Angle((p_sub_random(context.configuration, PrClass::Shotgun) << 19) as u32);
+        let spread = Fixed(p_sub_random(context.configuration, PrClass::Shotgun) << 5);
+        line_attack(context, mo, angle, MISSILERANGE, slope + spread, damage);
+    }
+}
+
+/// Fire a chaingun bullet, with the flash for whichever frame fired it.
+pub fn a_fire_cgun(context: &mut Context, n: usize, position: usize) {
+    let comp_sound = context.configuration.comp_sound;
+    let player = &context.level.players[n];
+    let mo = match player.mo {
+        Some(mo) => mo,
+        None => return,
+    };
+    let ammo = WEAPONINFO[player.readyweapon as usize].ammo;
+    let has_ammo = ammo == AmmoType::NoAmmo || player.ammo[ammo as usize] != 0;
+    if has_ammo || comp_sound {
+        context.level.start_sound(Some(mo), Sfx::PISTOL as usize);
+    }
+    if !has_ammo {
+        return;
+    }
+
+    set_mobj_state(context, mo, Statenum::PLAY_ATK2 as usize);
+    use_ammo(context, n, 1);
+    let adder = context.level.players[n].psprites[position]
+        .state
+        .map_or(0, |state| state.saturating_sub(Statenum::CHAIN1 as usize));
+    fire_something(context, n, adder);
+    let slope = bullet_slope(context, mo);
+    let accurate = context.level.players[n].refire == 0;
+    gun_shot(context, mo, accurate, slope);
+}
+
+pub fn a_light0(context: &mut Context, player: usize, _: usize) {
+    context.level.players[player].extralight = 0;
+}
+
+pub fn a_light1(context: &mut Context, player: usize, _: usize) {
+    context.level.players[player].extralight = 1;
+}
+
+pub fn a_light2(context: &mut Context, player: usize, _: usize) {
+    context.level.players[player].extralight = 2;
+}
+
+/// The BFG ball's tracers, run from its explosion: forty shots fanned out
+/// from the way it flew, fired by whoever fired the ball.
+pub fn a_bfg_spray(context: &mut Context, actor: ThinkerId) {
+    let mbf = context.configuration.compatibility_level >= CompatibilityLevel::Mbf;
+    let (angle, target) = match context.level.mobj(actor) {
+        Some(mo) => (mo.angle, mo.target),
+        None => return,
+    };
+    let target = match target {
+        Some(target) if context.level.mobj(target).is_some() => target,
+        _ => return,
+    };
+
+    // offset angles from its attack angle
+    for i in 0..40u32 {
+        let an = angle - ANG90 / 2 + ANG90 / 40 * i;
+
+        // killough 8/2/98: make autoaiming prefer enemies
+        let range = Fixed::from_int(16 * 64);
+        let mut linetarget = None;
+        if mbf {
+            linetarget = aim_line_attack(context, target, an, range, MobjFlag::FRIEND).linetarget;
+        }
+        if linetarget.is_none() {
+            linetarget = aim_line_attack(context, target, an, range, MobjFlag::empty()).linetarget;
+        }
+        let (linetarget, x, y, z) = match linetarget.and_then(|t| Some((t, context.level.mobj(t)?)))
+        {
+            Some((t, mo)) => (t, mo.x, mo.y, mo.z + (mo.height >> 2)),
+            None => continue,
+        };
+        spawn_mobj(context, x, y, z, MobjType::EXTRABFG);
+
+        let mut damage = 0;
+        for _ in 0..15 {
+            damage += (p_random(context.configuration, PrClass::Bfg) & 7) + 1;
+        }
+        damage_mobj(context, linetarget, Some(target), Some(target), damage);
+    }
+}
+
+/// The BFG's charging sound.
+pub fn a_bfg_sound(context: &mut Context, n: usize, _: usize) {
+    let mo = context.level.players[n].mo;
+    context.level.start_sound(mo, Sfx::BFG as usize);
+}
+
+/// Break the super shotgun open.
+pub fn a_open_shotgun2(context: &mut Context, n: usize, _: usize) {
+    let mo = context.level.players[n].mo;
+    context.level.start_sound(mo, Sfx::DBOPN as usize);
+}
+
+/// Load the super shotgun.
+pub fn a_load_shotgun2(context: &mut Context, n: usize, _: usize) {
+    let mo = context.level.players[n].mo;
+    context.level.start_sound(mo, Sfx::DBLOAD as usize);
+}
+
+/// Close the super shotgun, and fire again if fire is held.
+pub fn a_close_shotgun2(context: &mut Context, n: usize, position: usize) {
+    let mo = context.level.players[n].mo;
+    context.level.start_sound(mo, Sfx::DBCLS as usize);
+    a_refire(context, n, position);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        configuration::Configuration,
+        info::init_states,
+        logic::{player::Player, Level},
+    };
+
+    /// A level with one live player and nothing else.
+    fn level() -> Level {
+        Level {
+            players: vec![Player {
+                health: 100,
+                ..Player::default()
+            }],
+            ..Level::default()
+        }
+    }
+
+    /// Tic the player's sprites until the weapon reaches `state`, which it
+    /// must within a second.
+    fn tic_until(context: &mut Context, state: usize) {
+        for _ in 0..35 {
+            if context.level.players[0].psprites[PS_WEAPON].state == Some(state) {
+                return;
+            }
+            move_psprites(context, 0);
+        }
+        panic!("the weapon never got to state {}", state);
+    }
+
+    #[test]
+    fn weapons_come_up_and_switch() {
+        init_states();
+        let mut configuration = Configuration::default();
+        let mut level = level();
+        let mut context = Context {
+            configuration: &mut configuration,
+            level: &mut level,
+        };
+
+        setup_psprites(&mut context, 0);
+        let pistol = WEAPONINFO[WeaponType::Pistol as usize];
+        assert_eq!(
+            context.level.players[0].psprites[PS_WEAPON].state,
+            Some(pistol.upstate)
+        );
+        tic_until(&mut context, pistol.readystate);
+        assert_eq!(context.level.players[0].psprites[PS_WEAPON].sy, WEAPONTOP);
+
+        context.level.players[0].pendingweapon = Some(WeaponType::Shotgun);
+        move_psprites(&mut context, 0);
+        assert_eq!(
+            context.level.players[0].psprites[PS_WEAPON].state,
+            Some(pistol.downstate)
+        );
+        tic_until(
+            &mut context,
+            WEAPONINFO[WeaponType::Shotgun as usize].readystate,
+        );
+        let player = &context.level.players[0];
+        assert_eq!(player.readyweapon, WeaponType::Shotgun);
+        assert_eq!(player.pendingweapon, None);
+    }
+
+    #[test]
+    fn dead_players_put_their_weapon_away() {
+        init_states();
+        let mut configuration = Configuration::default();
+        let mut level = level();
+        let mut context = Context {
+            configuration: &mut configuration,
+            level: &mut level,
+        };
+        setup_psprites(&mut context, 0);
+        tic_until(
+            &mut context,
+            WEAPONINFO[WeaponType::Pistol as usize].readystate,
+        );
+
+        context.level.players[0].health = 0;
+        for _ in 0..35 {
+            move_psprites(&mut context, 0);
+        }
+        assert_eq!(context.level.players[0].psprites[PS_WEAPON].state, None);
+    }
+}
@@ -0,0 +1,346 @@
+use super::{
+    map,
+    maputl::approx_distance,
+    mobj::{spawn_mobj, spawn_player, MobjFlag, ONCEILINGZ, ONFLOORZ},
+    movement::{Friction, ORIG_FRICTION},
+    player::MAXPLAYERS,
+    Context, Level, Thinker,
+};
+use crate::{
+    configuration::{CompatibilityLevel, Configuration, SkillLevel},
+    doom::def::GameMode,
+    info::{MobjType, MOBJINFO},
+    lprint,
+    map::{Thing, ThingFlags},
+    misc::{
+        fixed::{Fixed, FRACBITS, FRACUNIT},
+        lprint::OutputLevel,
+        random::{p_random, PrClass},
+    },
+    tables::ANG45,
+};
+use rustc_hash::FxHashMap;
+use strum::IntoEnumIterator;
+
+/// The editor number of deathmatch starts.
+const DEATHMATCH_START: i16 = 11;
+
+/// Start the level for the loaded map, spawning its things and players,
+/// like the end of vanilla's `P_SetupLevel`.
+pub fn setup_level(configuration: &mut Configuration) -> Level {
+    let mut level = Level::new(map(configuration));
+    spawn_friction(configuration, &mut level);
+    configuration.basetic = configuration.gametic;
+    let things = map(configuration).things.clone();
+    let mut context = Context {
+        configuration,
+        level: &mut level,
+    };
+
+    // killough 8/23/98: the highest type wins if two share a number, as
+    // in Boom's search of mobjinfo from the end
+    let mut doomednums = FxHashMap::default();
+    {
+        let mobjinfo = MOBJINFO.read();
+        for mobj_type in MobjType::iter() {
+            if let Some(info) = mobjinfo.get(&mobj_type).filter(|i| i.doomednum != -1) {
+                doomednums.insert(info.doomednum, mobj_type);
+            }
+        }
+    }
+    for thing in &things {
+        if doomednum_allowed(context.configuration, thing.kind) {
+            spawn_map_thing(&mut context, &doomednums, thing);
+        }
+    }
+
+    for n in 0..MAXPLAYERS {
+        let in_game = context
+            .configuration
+            .player_in_game
+            .get(n)
+            .copied()
+            .unwrap_or(false);
+        if !in_game {
+            continue;
+        }
+        if context.configuration.deathmatch != 0 {
+            deathmatch_spawn_player(&mut context, n);
+        } else if context.level.players[n].mo.is_none() {
+            // check all necessary player starts actually exist
+            crate::error(format!("setup_level: missing player {} start", n + 1));
+        }
+    }
+
+    spawn_brain_targets(context.level);
+    level
+}
+
+/// Spawn player `n` at a random deathmatch start, like vanilla's
+/// `G_DeathMatchSpawnPlayer`. A start another player already spawned at is
+/// tried again, up to 20 times in all, before falling back to the player's
+/// own start.
+fn deathmatch_spawn_player(context: &mut Context, n: usize) {
+    let selections = context.level.deathmatch_starts.len();
+    if selections < MAXPLAYERS {
+        crate::error(format!(
+            "deathmatch_spawn_player: Only {} deathmatch spots, {} required",
+            selections, MAXPLAYERS
+        ));
+    }
+
+    for _ in 0..20 {
+        let i = p_random(context.configuration, PrClass::DmSpawn) as usize % selections;
+        if check_spot(context.level, n, &context.level.deathmatch_starts[i]) {
+            let start = &mut context.level.deathmatch_starts[i];
+            start.kind = n as i16 + 1;
+            let start = *start;
+            spawn_player(context, n, &start);
+            return;
+        }
+    }
+
+    // no good spot, so the player will probably get stuck; vanilla's
+    // start for a missing player was all zeros
+    let start = context.level.player_starts[n].unwrap_or(Thing {
+        x: 0,
+        y: 0,
+        angle: 0,
+        kind: 0,
+        options: 0,
+    });
+    spawn_player(context, n, &start);
+}
+
+/// Whether player `n` can spawn at `start` when the level starts, like the
+/// first spawn of the level in vanilla's `G_CheckSpot`: not if a player
+/// before them already did.
+fn check_spot(level: &Level, n: usize, start: &Thing) -> bool {
+    let x = Fixed::from_int(start.x as i32);
+    let y = Fixed::from_int(start.y as i32);
+    !level.players[..n]
+        .iter()
+        .filter_map(|player| level.mobj(player.mo?))
+        .any(|mo| mo.x == x && mo.y == y)
+}
+
+/// Find the boss brain's spawn spots, like Boom's `P_SpawnBrainTargets`.
+///
+/// killough 3/26/98: done once at level start rather than when the brain
+/// wakes up.
+fn spawn_brain_targets(level: &mut Level) {
+    level.brain.targets = level
+        .thinkers
+        .iter()
+        .filter(|(_, Thinker::Mobj(mo))| mo.mobj_type == MobjType::BOSSTARGET)
+        .map(|(id, _)| id)
+        .collect();
+}
+
+/// The linedef special that sets the friction of tagged sectors.
+const FRICTION_SPECIAL: i16 = 223;
+
+/// Set the friction of sectors tagged by friction lines, like Boom's
+/// `P_SpawnFriction`. The longer the line, the icier the floor.
+///
+/// killough 8/28/98: instead of spawning thinkers, which are slow and
+/// expensive, modify the sector's own friction values once at level
+/// startup.
+fn spawn_friction(configuration: &Configuration, level: &mut Level) {
+    let map = map(configuration);
+    let mbf = configuration.compatibility_level >= CompatibilityLevel::Mbf;
+
+    for line in map.lines.iter().filter(|l| l.special == FRICTION_SPECIAL) {
+        let length = approx_distance(line.dx, line.dy).0 >> FRACBITS;
+        let mut friction = (0x1EB8 * length) / 0x80 + 0xD000;
+
+        // The following check might seem odd. At the time of movement, the
+        // move distance is multiplied by 'friction/0x10000', so a higher
+        // friction value actually means 'less friction'.
+        let mut movefactor = if friction > ORIG_FRICTION.0 {
+            // ice
+            ((0x10800 - friction) * 0x70) / 0x100
+        } else {
+            ((friction - 0xDB34) * 0xA) / 0x80
+        };
+
+        if mbf {
+            // killough 8/28/98: prevent odd situations
+            friction = friction.min(FRACUNIT as i32).max(0);
+            movefactor = movefactor.max(32);
+        }
+
+        // TODO Boom's friction thinkers, for Boom demos
+        for (sector, _) in map
+            .sectors
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.tag == line.tag)
+        {
+            level.sector_friction[sector] = Friction {
+                friction: Fixed(friction),
+                movefactor,
+            };
+        }
+    }
+}
+
+/// Doom II's monsters aren't spawned in Doom, where they have no sprites.
+fn doomednum_allowed(configuration: &Configuration, doomednum: i16) -> bool {
+    configuration.game_mode == GameMode::Commercial
+        || !matches!(doomednum, 64 | 65 | 66 | 67 | 68 | 69 | 71 | 84 | 88 | 89)
+}
+
+/// Spawn a map thing, if it belongs in this game, like vanilla's
+/// `P_SpawnMapThing`. Player starts spawn the players, except that in
+/// single player, starts 2 to dogs+1 spawn MBF helper dogs instead.
+/// Deathmatch starts are kept for later.
+fn spawn_map_thing(context: &mut Context, doomednums: &FxHashMap<i32, MobjType>, thing: &Thing) {
+    let configuration = &*context.configuration;
+    let cl = configuration.compatibility_level;
+    let netgame = configuration.netgame;
+    let deathmatch = configuration.deathmatch != 0;
+    let nomonsters = configuration.nomonsters;
+    let dogs = configuration.dogs.get() as usize;
+    let skill = match configuration.game_skill {
+        SkillLevel::Itytd | SkillLevel::Hntr => ThingFlags::EASY,
+        SkillLevel::Uv | SkillLevel::Nm => ThingFlags::HARD,
+        _ => ThingFlags::NORMAL,
+    };
+
+    // killough 2/26/98: type 0 is a no-op, and there are no starts for
+    // players 5 to 8
+    if matches!(thing.kind, 0 | 4001..=4004) {
+        return;
+    }
+
+    let mut options = ThingFlags::from_bits_truncate(thing.options);
+    // killough 11/98: the reserved bit means the extended flags are junk
+    if cl < CompatibilityLevel::Boom
+        || (cl >= CompatibilityLevel::LxDoomV1 && options.contains(ThingFlags::RESERVED))
+    {
+        if cl >= CompatibilityLevel::Boom {
+            lprint!(
+                OutputLevel::WARN,
+                "spawn_map_thing: correcting bad flags ({}) (thing type {})\n",
+                thing.options,
+                thing.kind
+            );
+        }
+        options &= ThingFlags::EASY
+            | ThingFlags::NORMAL
+            | ThingFlags::HARD
+            | ThingFlags::AMBUSH
+            | ThingFlags::NOT_SINGLE;
+    }
+
+    if thing.kind == DEATHMATCH_START {
+        // doom2.exe has at most 10 deathmatch starts
+        if cl > CompatibilityLevel::Boom || context.level.deathmatch_starts.len() < 10 {
+            context.level.deathmatch_starts.push(*thing);
+        }
+        return;
+    }
+
+    let mobj_type = if (1..=4).contains(&thing.kind) {
+        let n = thing.kind as usize - 1;
+        // killough 7/19/98: Marine's best friend :)
+        if !netgame && n >= 1 && n <= dogs && context.level.players[n].secretcount == 0 {
+            // use secretcount to avoid multiple dogs in case of multiple
+            // starts
+            context.level.players[n].secretcount = 1;
+            // killough 10/98: force it to be a friend
+            options |= ThingFlags::FRIEND;
+            MobjType::DOGS
+        } else {
+            context.level.player_starts[n] = Some(*thing);
+            if !deathmatch {
+                spawn_player(context, n, thing);
+            }
+            return;
+        }
+    } else {
+        // jff 3/30/98: the multiplayer, not deathmatch and not coop flags
+        if !netgame && options.contains(ThingFlags::NOT_SINGLE) {
+            return;
+        }
+        if netgame && deathmatch && options.contains(ThingFlags::NOT_DEATHMATCH) {
+            return;
+        }
+        if netgame && !deathmatch && options.contains(ThingFlags::NOT_COOP) {
+            return;
+        }
+
+        if !options.contains(skill) {
+            return;
+        }
+
+        // phares 5/16/98: unknown things are skipped with a warning
+        let mobj_type = match doomednums.get(&(thing.kind as i32)) {
+            Some(&mobj_type) => mobj_type,
+            None => {
+                lprint!(
+                    OutputLevel::WARN,
+                    "Unknown Thing type {} at ({}, {})\n",
+                    thing.kind,
+                    thing.x,
+                    thing.y
+                );
+                return;
+            }
+        };
+        let info_flags = MobjFlag::from_bits_truncate(MOBJINFO.read()[&mobj_type].flags);
+
+        // no keycards in deathmatch
+        if deathmatch && info_flags.contains(MobjFlag::NOTDMATCH) {
+            return;
+        }
+        if nomonsters && (mobj_type == MobjType::SKULL || info_flags.contains(MobjFlag::COUNTKILL))
+        {
+            return;
+        }
+        mobj_type
+    };
+    let info_flags = MobjFlag::from_bits_truncate(MOBJINFO.read()[&mobj_type].flags);
+
+    let x = Fixed::from_int(thing.x as i32);
+    let y = Fixed::from_int(thing.y as i32);
+    let z = if info_flags.contains(MobjFlag::SPAWNCEILING) {
+        ONCEILINGZ
+    } else {
+        ONFLOORZ
+    };
+    let id = spawn_mobj(context, x, y, z, mobj_type);
+
+    // start animations at random, so they don't all move together
+    let tics = context.level.mobj(id).unwrap().tics;
+    let tics = if tics > 0 {
+        1 + p_random(context.configuration, PrClass::SpawnThing) as i64 % tics
+    } else {
+        tics
+    };
+    let mbf = cl >= CompatibilityLevel::Mbf;
+
+    let mobj = context.level.mobj_mut(id).unwrap();
+    mobj.spawnpoint = Some(*thing);
+    mobj.tics = tics;
+    mobj.angle = ANG45 * (thing.angle as i32 / 45) as u32;
+    if options.contains(ThingFlags::AMBUSH) {
+        mobj.flags.insert(MobjFlag::AMBUSH);
+    }
+    // killough 10/98: friends from the map
+    if mbf && options.contains(ThingFlags::FRIEND) && !mobj.flags.contains(MobjFlag::FRIEND) {
+        mobj.flags.insert(MobjFlag::FRIEND);
+        context.level.update_thinker_class(id);
+    }
+
+    let flags = context.level.mobj(id).unwrap().flags;
+
+    // killough 7/20/98: friends don't count as kills
+    if flags.contains(MobjFlag::COUNTKILL) && !flags.contains(MobjFlag::FRIEND) {
+        context.level.total_kills += 1;
+    }
+    if flags.contains(MobjFlag::COUNTITEM) {
+        context.level.total_items += 1;
+    }
+}
@@ -0,0 +1,327 @@
+use super::{
+    map,
+    maputl::{point_on_side, DivLine, BOXBOTTOM, BOXLEFT, BOXRIGHT, BOXTOP},
+    Context,
+};
+use crate::{
+    configuration::CompatibilityLevel,
+    map::{LineFlags, Map, Node, NodeChild},
+    misc::fixed::{fixed_div, Fixed, FRACBITS},
+    think::ThinkerId,
+};
+
+/// The line of sight being checked, like vanilla's `los_t`.
+struct Los {
+    /// The eye height of the looker.
+    sightzstart: Fixed,
+    t2x: Fixed,
+    t2y: Fixed,
+    /// From the looker to the target.
+    strace: DivLine,
+    /// Slopes to the top and bottom of the target, narrowed by the
+    /// openings the line of sight passes through.
+    topslope: Fixed,
+    bottomslope: Fixed,
+    bbox: [Fixed; 4],
+    /// cph: the lowest and highest the line of sight goes, to skip two-sided
+    /// lines it can't touch.
+    maxz: Fixed,
+    minz: Fixed,
+}
+
+/// Whether `t1` can see `t2`, like vanilla's `P_CheckSight`. Looks from
+/// `t1`'s eyes to any part of `t2`, first ruling out sectors REJECT says
+/// can't see each other.
+pub fn check_sight(context: &mut Context, t1: ThinkerId, t2: ThinkerId) -> bool {
+    let cl = context.configuration.compatibility_level;
+    let map = map(context.configuration);
+    let (t1, t2) = match (context.level.mobj(t1), context.level.mobj(t2)) {
+        (Some(t1), Some(t2)) => (t1, t2),
+        _ => return false,
+    };
+
+    // First check for trivial rejection.
+    let s1 = map.subsectors[t1.subsector].sector;
+    let s2 = map.subsectors[t2.subsector].sector;
+    let pnum = s1 * map.sectors.len() + s2;
+    if map.reject[pnum >> 3] & (1 << (pnum & 7)) != 0 {
+        // can't possibly be connected
+        return false;
+    }
+
+    // TODO killough 4/19/98: make fake floors and ceilings block monster
+    // view, once sectors have Boom's height sectors
+
+    // killough 11/98: shortcut for melee situations
+    // cph: compatibility optioned for demo sync, cf HR06-UV.LMP
+    if t1.subsector == t2.subsector && cl >= CompatibilityLevel::Mbf {
+        return true;
+    }
+
+    // An unobstructed LOS is possible. Now look from eyes of t1 to any part
+    // of t2.
+    let sightzstart = t1.z + t1.height - (t1.height >> 2);
+    let bottomslope = t2.z - sightzstart;
+    let mut los = Los {
+        sightzstart,
+        t2x: t2.x,
+        t2y: t2.y,
+        strace: DivLine {
+            x: t1.x,
+            y: t1.y,
+            dx: t2.x - t1.x,
+            dy: t2.y - t1.y,
+        },
+        topslope: bottomslope + t2.height,
+        bottomslope,
+        bbox: [
+            t1.y.max(t2.y),
+            t1.y.min(t2.y),
+            t1.x.min(t2.x),
+            t1.x.max(t2.x),
+        ],
+        // cph: for old demos, this optimisation is disabled by using the
+        // extremes
+        maxz: Fixed::MAX,
+        minz: Fixed::MIN,
+    };
+    if cl == CompatibilityLevel::LxDoomV1 {
+        if los.sightzstart < t2.z {
+            los.maxz = t2.z + t2.height;
+            los.minz = los.sightzstart;
+        } else if los.sightzstart > t2.z + t2.height {
+            los.maxz = los.sightzstart;
+            los.minz = t2.z;
+        } else {
+            los.maxz = t2.z + t2.height;
+            los.minz = t2.z;
+        }
+    }
+
+    context.level.validcount += 1;
+    if map.nodes.is_empty() {
+        return cross_subsector(context, &mut los, 0);
+    }
+    // the head node is the last node output
+    cross_bsp_node(context, &mut los, NodeChild::Node(map.nodes.len() - 1))
+}
+
+/// Which side of a line a point is on: 0 for the front, 1 for the back and
+/// 2 for on the line, like vanilla's `P_DivlineSide`.
+fn divline_side(cl: CompatibilityLevel, x: Fixed, y: Fixed, node: &DivLine) -> usize {
+    if node.dx == Fixed::ZERO {
+        return if x == node.x {
+            2
+        } else if x <= node.x {
+            (node.dy > Fixed::ZERO) as usize
+        } else {
+            (node.dy < Fixed::ZERO) as usize
+        };
+    }
+    if node.dy == Fixed::ZERO {
+        // cph: vanilla compared x here, which prboom 2.3 fixed
+        let on = if cl < CompatibilityLevel::PrBoomV23x {
+            x
+        } else {
+            y
+        };
+        return if on == node.y {
+            2
+        } else if y <= node.y {
+            (node.dx < Fixed::ZERO) as usize
+        } else {
+            (node.dx > Fixed::ZERO) as usize
+        };
+    }
+    let right = ((y - node.y).0 >> FRACBITS).wrapping_mul(node.dx.0 >> FRACBITS);
+    let left = ((x - node.x).0 >> FRACBITS).wrapping_mul(node.dy.0 >> FRACBITS);
+    if right < left {
+        0
+    } else if right == left {
+        2
+    } else {
+        1
+    }
+}
+
+/// A 64 bit intercept, free of vanilla's precision and overflow problems.
+fn intercept_vector2(v2: &DivLine, v1: &DivLine) -> Fixed {
+    let den = (v1.dy.0 as i64 * v2.dx.0 as i64 - v1.dx.0 as i64 * v2.dy.0 as i64) >> 16;
+    if den == 0 {
+        return Fixed::ZERO;
+    }
+    let num = (v1.x - v2.x).0 as i64 * v1.dy.0 as i64 - (v1.y - v2.y).0 as i64 * v1.dx.0 as i64;
+    Fixed((num / den) as i32)
+}
+
+/// Whether the line of sight gets through subsector `num`, like vanilla's
+/// `P_CrossSubsector`.
+fn cross_subsector(context: &mut Context, los: &mut Los, num: usize) -> bool {
+    let cl = context.configuration.compatibility_level;
+    let demo_compatibility = cl < CompatibilityLevel::Boom;
+    let map = map(context.configuration);
+    let level = &mut *context.level;
+    let subsector = &map.subsectors[num];
+
+    for seg in &map.segs[subsector.first_seg..subsector.first_seg + subsector.num_segs] {
+        // skip minisegs
+        let line_num = match seg.linedef {
+            Some(line) => line,
+            None => continue,
+        };
+        // already checked other side?
+        if level.line_validcount[line_num] == level.validcount {
+            continue;
+        }
+        level.line_validcount[line_num] = level.validcount;
+        let line = &map.lines[line_num];
+        let v1 = &map.vertices[line.v1];
+        let v2 = &map.vertices[line.v2];
+
+        // killough 4/20/98: quick bounding box rejection test
+        // cph: this is causing demo desyncs on original Doom demos. Who
+        // knows why. Exclude test for those.
+        if !demo_compatibility
+            && (v1.x.min(v2.x) > los.bbox[BOXRIGHT]
+                || v1.x.max(v2.x) < los.bbox[BOXLEFT]
+                || v1.y.min(v2.y) > los.bbox[BOXTOP]
+                || v1.y.max(v2.y) < los.bbox[BOXBOTTOM])
+        {
+            continue;
+        }
+
+        // cph: do what we can before forced to check intersection
+        let mut opentop = Fixed::ZERO;
+        let mut openbottom = Fixed::ZERO;
+        let two_sided = line.flags.contains(LineFlags::TWO_SIDED);
+        let sectors = match (two_sided, seg.back_sector) {
+            (true, Some(back)) => {
+                let front = &map.sectors[seg.front_sector];
+                let back = &map.sectors[back];
+                // no wall to block sight with?
+                if front.floor_height == back.floor_height
+                    && front.ceiling_height == back.ceiling_height
+                {
+                    continue;
+                }
+                // possible occluder because of ceiling and floor height
+                // differences
+                opentop = front.ceiling_height.min(back.ceiling_height);
+                openbottom = front.floor_height.max(back.floor_height);
+
+                // cph: reject if does not intrude in the z-space of the
+                // possible LOS
+                if opentop >= los.maxz && openbottom <= los.minz {
+                    continue;
+                }
+                Some((front, back))
+            }
+            _ => None,
+        };
+
+        // Forget this line if it doesn't cross the line of sight
+        if divline_side(cl, v1.x, v1.y, &los.strace) == divline_side(cl, v2.x, v2.y, &los.strace) {
+            continue;
+        }
+        let divl = DivLine {
+            x: v1.x,
+            y: v1.y,
+            dx: v2.x - v1.x,
+            dy: v2.y - v1.y,
+        };
+        // line isn't crossed?
+        if divline_side(cl, los.strace.x, los.strace.y, &divl)
+            == divline_side(cl, los.t2x, los.t2y, &divl)
+        {
+            continue;
+        }
+
+        // cph: if bottom >= top or top < minz or bottom > maxz then it
+        // must be solid wrt this LOS
+        let (front, back) = match sectors {
+            Some(sectors)
+                if openbottom < opentop && opentop >= los.minz && openbottom <= los.maxz =>
+            {
+                sectors
+            }
+            _ => return false,
+        };
+
+        // crosses a two sided line
+        // cph 2006/07/15: the 64 bit intercept was missed in 2.4.0 and
+        // 2.4.1, so only those use it
+        let frac = if matches!(
+            cl,
+            CompatibilityLevel::PrBoomV240 | CompatibilityLevel::PrBoomLatest
+        ) {
+            intercept_vector2(&los.strace, &divl)
+        } else {
+            los.strace.intercept(&divl)
+        };
+
+        if front.floor_height != back.floor_height {
+            let slope = fixed_div(openbottom - los.sightzstart, frac);
+            if slope > los.bottomslope {
+                los.bottomslope = slope;
+            }
+        }
+        if front.ceiling_height != back.ceiling_height {
+            let slope = fixed_div(opentop - los.sightzstart, frac);
+            if slope < los.topslope {
+                los.topslope = slope;
+            }
+        }
+        if los.topslope <= los.bottomslope {
+            // stop
+            return false;
+        }
+    }
+    // passed the subsector ok
+    true
+}
+
+/// Whether the line of sight gets through the part of the BSP tree under
+/// `bsp`, like vanilla's `P_CrossBSPNode`. LxDoom v1 demos pick sides the
+/// way the renderer does.
+fn cross_bsp_node(context: &mut Context, los: &mut Los, bsp: NodeChild) -> bool {
+    let cl = context.configuration.compatibility_level;
+    let renderer_sides = cl == CompatibilityLevel::LxDoomV1;
+    let mut bsp = bsp;
+    loop {
+        let node = match bsp {
+            NodeChild::Node(node) => node,
+            NodeChild::Subsector(subsector) => return cross_subsector(context, los, subsector),
+        };
+        let map: &Map = map(context.configuration);
+        let node: &Node = &map.nodes[node];
+        let (side, side2) = if renderer_sides {
+            (
+                point_on_side(los.strace.x, los.strace.y, node),
+                point_on_side(los.t2x, los.t2y, node),
+            )
+        } else {
+            let divline = DivLine {
+                x: node.x,
+                y: node.y,
+                dx: node.dx,
+                dy: node.dy,
+            };
+            (
+                divline_side(cl, los.strace.x, los.strace.y, &divline) & 1,
+                divline_side(cl, los.t2x, los.t2y, &divline),
+            )
+        };
+        let children = node.children;
+
+        if side == side2 {
+            // doesn't touch the other side
+            bsp = children[side];
+        } else if !cross_bsp_node(context, los, children[side]) {
+            // cross the starting side
+            return false;
+        } else {
+            // cross the ending side
+            bsp = children[side ^ 1];
+        }
+    }
+}
@@ -0,0 +1,481 @@
+use super::{
+    map, map_mut,
+    telept::{silent_line_teleport, silent_teleport, teleport},
+    Context,
+};
+use crate::{
+    configuration::CompatibilityLevel,
+    doom::{
+        def::{CardType, GameMode},
+        english::*,
+    },
+    info::MobjType,
+    map::LineFlags,
+    sounds::Sfx,
+    think::ThinkerId,
+};
+
+/// End the level, like vanilla's `G_ExitLevel`.
+pub fn exit_level(context: &mut Context) {
+    context.level.exit_level = true;
+    context.level.secret_exit = false;
+}
+
+/// End the level through the exit to the secret level, like vanilla's
+/// `G_SecretExitLevel`. Doom 2 maps without a MAP31 just go to the next
+/// level.
+pub fn secret_exit_level(context: &mut Context) {
+    let configuration = &*context.configuration;
+    context.level.exit_level = true;
+    context.level.secret_exit = configuration.game_mode != GameMode::Commercial
+        || configuration.lumps.check_num_for_name("MAP31").is_some();
+}
+
+/// Take the special off a line that only works once.
+fn clear_special(context: &mut Context, line: usize) {
+    map_mut(context.configuration).lines[line].special = 0;
+}
+
+/// Let `thing` set off `line`, which it walked over from side `side`, like
+/// vanilla's `P_CrossSpecialLine`. Only the teleporters and exits act
+/// yet; the sector movers will be called from here when there are some.
+pub fn cross_special_line(context: &mut Context, line: usize, side: usize, thing: ThinkerId) {
+    use MobjType::*;
+    let configuration = &*context.configuration;
+    let cl = configuration.compatibility_level;
+    let demo_compatibility = cl < CompatibilityLevel::Boom;
+    let (mobj_type, player) = match context.level.mobj(thing) {
+        Some(mo) => (mo.mobj_type, mo.player),
+        None => return,
+    };
+    let ld = &map(configuration).lines[line];
+    let (special, tag) = (ld.special, ld.tag);
+    let secret = ld.flags.contains(LineFlags::SECRET);
+
+    // Things that should never trigger lines
+    if cl == CompatibilityLevel::DoomV12 {
+        // e6y: improved support for Doom v1.2
+        if special > 98 && special != 104 {
+            return;
+        }
+    } else if player.is_none()
+        && matches!(
+            mobj_type,
+            ROCKET | PLASMA | BFG | TROOPSHOT | HEADSHOT | BRUISERSHOT
+        )
+    {
+        return;
+    }
+
+    // jff 02/04/98 add check here for generalized lindef types; they're
+    // not recognized in old demos
+    let gen = special as u16;
+    if !demo_compatibility && (GEN_CRUSHER_BASE..GEN_END).contains(&gen) {
+        let walk = matches!(gen & TRIGGER_TYPE, WALK_ONCE | WALK_MANY);
+        // jff 4/1/98 check for being a walk type before reporting door type
+        if (GEN_LOCKED_BASE..GEN_DOOR_BASE).contains(&gen) && !walk {
+            return;
+        }
+        if !gen_allowed(context, gen, secret, player) {
+            return;
+        }
+        // jff 2/27/98 all walk generalized types require tag
+        if tag == 0 && !context.configuration.defaults.comperr_zerotag {
+            return;
+        }
+        // TODO: run the generalized mover for walk types once there are
+        // sector movers, clearing `WALK_ONCE` lines when it starts.
+        return;
+    }
+
+    // jff 3/5/98 add ability of monsters etc. to use teleporters
+    if player.is_none()
+        && !matches!(
+            special,
+            39 | 97 | 125 | 126 | 4 | 10 | 88 | 208 | 207 | 243 | 244 | 262..=269
+        )
+    {
+        return;
+    }
+
+    // jff 2/27/98 disallow zero tag on some types
+    if !check_tag(context, line) {
+        return;
+    }
+
+    // killough 10/98: prevent zombies from exiting levels
+    let zombie = player.is_some_and(|n| context.level.players[n].health <= 0)
+        && !context.configuration.comp_zombie;
+    let monster = player.is_none();
+
+    // Dispatch on the line special value to the line's action routine. If
+    // a once only function, and successful, clear the line special.
+    let used = match special {
+        // jff 02/09/98 fix using up with wrong side crossing
+        39 => teleport(context, line, side, thing) || demo_compatibility,
+        97 => {
+            teleport(context, line, side, thing);
+            false
+        }
+        // monster only
+        125 => monster && (teleport(context, line, side, thing) || demo_compatibility),
+        126 => {
+            if monster {
+                teleport(context, line, side, thing);
+            }
+            false
+        }
+        52 => {
+            if !zombie {
+                exit_level(context);
+            }
+            false
+        }
+        124 => {
+            if !zombie {
+                secret_exit_level(context);
+            }
+            false
+        }
+
+        // killough 1/31/98: Boom's, not in old demos
+        _ if demo_compatibility => false,
+        // killough 2/16/98: silent teleporters, the normal kind
+        207 => silent_teleport(context, line, side, thing),
+        208 => {
+            silent_teleport(context, line, side, thing);
+            false
+        }
+        // killough 2/16/98: silent teleporters, the linedef-linedef kind
+        243 => silent_line_teleport(context, line, side, thing, false),
+        244 => {
+            silent_line_teleport(context, line, side, thing, false);
+            false
+        }
+        // jff 4/14/98 add silent line-line reversed
+        262 => silent_line_teleport(context, line, side, thing, true),
+        263 => {
+            silent_line_teleport(context, line, side, thing, true);
+            false
+        }
+        // jff 4/14/98 add monster-only silent line-line reversed
+        264 => monster && silent_line_teleport(context, line, side, thing, true),
+        265 => {
+            if monster {
+                silent_line_teleport(context, line, side, thing, true);
+            }
+            false
+        }
+        // jff 4/14/98 add monster-only silent line-line
+        266 => monster && silent_line_teleport(context, line, side, thing, false),
+        267 => {
+            if monster {
+                silent_line_teleport(context, line, side, thing, false);
+            }
+            false
+        }
+        // jff 4/14/98 add monster-only silent
+        268 => monster && silent_teleport(context, line, side, thing),
+        269 => {
+            if monster {
+                silent_teleport(context, line, side, thing);
+            }
+            false
+        }
+        // TODO: the sector movers, once there are some
+        _ => false,
+    };
+    if used {
+        clear_special(context, line);
+    }
+}
+
+/// Let `thing` set off `line` by shooting it, like vanilla's
+/// `P_ShootSpecialLine`. Only the exits act yet; the sector movers will
+/// be called from here when there are some.
+pub fn shoot_special_line(context: &mut Context, line: usize, thing: ThinkerId) {
+    let configuration = &*context.configuration;
+    let demo_compatibility = configuration.compatibility_level < CompatibilityLevel::Boom;
+    let player = match context.level.mobj(thing) {
+        Some(mo) => mo.player,
+        None => return,
+    };
+    let ld = &map(configuration).lines[line];
+    let (special, tag) = (ld.special, ld.tag);
+    let secret = ld.flags.contains(LineFlags::SECRET);
+
+    // jff 02/04/98 add check here for generalized linedef
+    let gen = special as u16;
+    if !demo_compatibility && (GEN_CRUSHER_BASE..GEN_END).contains(&gen) {
+        let gun = matches!(gen & TRIGGER_TYPE, GUN_ONCE | GUN_MANY);
+        // jff 4/1/98 check for being a gun type before reporting door type
+        if (GEN_LOCKED_BASE..GEN_DOOR_BASE).contains(&gen) && !gun {
+            return;
+        }
+        if !gen_allowed(context, gen, secret, player) {
+            return;
+        }
+        // jff 2/27/98 all gun generalized types require tag
+        if tag == 0 && !context.configuration.defaults.comperr_zerotag {
+            return;
+        }
+        // TODO: run the generalized mover for gun types once there are
+        // sector movers, and change the switch texture when it starts.
+        return;
+    }
+
+    // Impacts that other things can activate: 46 GR open door on impact
+    // weapon is monster activatable
+    if player.is_none() && special != 46 {
+        return;
+    }
+
+    // jff 2/27/98 disallow zero tag on some types
+    if !check_tag(context, line) {
+        return;
+    }
+
+    // killough 10/98: prevent zombies from exiting levels
+    let zombie = player.is_some_and(|n| context.level.players[n].health <= 0)
+        && !context.configuration.comp_zombie;
+    match special {
+        // killough 1/31/98: Boom's, not in old demos
+        _ if demo_compatibility => {}
+        // TODO: change the switch textures of the exits once there are
+        // switches
+        197 if !zombie => exit_level(context),
+        198 if !zombie => secret_exit_level(context),
+        // TODO: the sector movers, once there are some
+        _ => {}
+    }
+}
+
+/// Where Boom's generalized linedef ranges start: floors and ceilings from
+/// `GEN_CEILING_BASE`, then doors, locked doors, and lifts, stairs and
+/// crushers from `GEN_CRUSHER_BASE`. Anything from `GEN_END` up is out of
+/// range.
+const GEN_END: u16 = 0x8000;
+const GEN_CEILING_BASE: u16 = 0x4000;
+const GEN_DOOR_BASE: u16 = 0x3c00;
+const GEN_LOCKED_BASE: u16 = 0x3800;
+const GEN_CRUSHER_BASE: u16 = 0x2f80;
+
+/// Floor and ceiling movers that change textures, and the "allow monsters"
+/// bit they have when they don't.
+const MOVER_CHANGE: u16 = 0x0c00;
+const MOVER_MODEL: u16 = 0x0020;
+/// The "allow monsters" bits of doors, lifts, stairs and crushers.
+const DOOR_MONSTER: u16 = 0x0080;
+const LIFT_MONSTER: u16 = 0x0020;
+/// Which key a locked door wants, and whether skulls and cards count as
+/// the same key.
+const LOCKED_KEY: u16 = 0x01c0;
+const LOCKED_KEY_SHIFT: u16 = 6;
+const LOCKED_NKEYS: u16 = 0x0200;
+const LOCKED_NKEYS_SHIFT: u16 = 9;
+
+/// How a generalized line is set off, the low three bits of its special.
+const TRIGGER_TYPE: u16 = 0x0007;
+const WALK_ONCE: u16 = 0;
+const WALK_MANY: u16 = 1;
+const GUN_ONCE: u16 = 4;
+const GUN_MANY: u16 = 5;
+
+/// Whether player `n` has the keys for a generalized locked door, like
+/// Boom's `P_CanUnlockGenDoor`. If not, they're told what they need.
+fn can_unlock_gen_door(context: &mut Context, special: u16, n: usize) -> bool {
+    use CardType::*;
+    let player = &context.level.players[n];
+    let has = |card: CardType| player.cards[card as usize];
+    let skulliscard = (special & LOCKED_NKEYS) >> LOCKED_NKEYS_SHIFT != 0;
+    let missing = match (special & LOCKED_KEY) >> LOCKED_KEY_SHIFT {
+        // any key
+        0 if !player.cards.iter().any(|&card| card) => Some(PD_ANY),
+        1 if !has(RedCard) && (!skulliscard || !has(RedSkull)) => {
+            Some(if skulliscard { PD_REDK } else { PD_REDC })
+        }
+        2 if !has(BlueCard) && (!skulliscard || !has(BlueSkull)) => {
+            Some(if skulliscard { PD_BLUEK } else { PD_BLUEC })
+        }
+        3 if !has(YellowCard) && (!skulliscard || !has(YellowSkull)) => {
+            Some(if skulliscard { PD_YELLOWK } else { PD_YELLOWC })
+        }
+        4 if !has(RedSkull) && (skulliscard || !has(RedCard)) => {
+            Some(if skulliscard { PD_REDK } else { PD_REDS })
+        }
+        5 if !has(BlueSkull) && (skulliscard || !has(BlueCard)) => {
+            Some(if skulliscard { PD_BLUEK } else { PD_BLUES })
+        }
+        6 if !has(YellowSkull) && (skulliscard || !has(YellowCard)) => {
+            Some(if skulliscard { PD_YELLOWK } else { PD_YELLOWS })
+        }
+        // all keys
+        7 if !skulliscard && !player.cards.iter().all(|&card| card) => Some(PD_ALL6),
+        7 if skulliscard
+            && (!(has(BlueCard) || has(BlueSkull))
+                || !(has(YellowCard) || has(YellowSkull))
+                || !(has(RedCard) || has(RedSkull))) =>
+        {
+            Some(PD_ALL3)
+        }
+        _ => None,
+    };
+
+    match missing {
+        Some(message) => {
+            let player = &mut context.level.players[n];
+            player.message = Some(message);
+            let mo = player.mo;
+            // killough 3/20/98
+            context.level.start_sound(mo, Sfx::OOF as usize);
+            false
+        }
+        None => true,
+    }
+}
+
+/// Whether `player`, or a monster for `None`, may set off the generalized
+/// line `special`, like the range checks at the top of Boom's
+/// `P_CrossSpecialLine`, `P_UseSpecialLine` and `P_ShootSpecialLine`.
+/// Players without the keys for a locked door are told what they need.
+fn gen_allowed(context: &mut Context, special: u16, secret: bool, player: Option<usize>) -> bool {
+    let monster = player.is_none();
+    if special >= GEN_CEILING_BASE {
+        // floors and ceilings: the model bit is "allow monsters" when
+        // they don't change textures
+        !monster || (special & MOVER_CHANGE == 0 && special & MOVER_MODEL != 0)
+    } else if special >= GEN_DOOR_BASE {
+        // they can't open secret doors either
+        !monster || (special & DOOR_MONSTER != 0 && !secret)
+    } else if special >= GEN_LOCKED_BASE {
+        // monsters disallowed from unlocking doors
+        match player {
+            Some(n) => can_unlock_gen_door(context, special, n),
+            None => false,
+        }
+    } else {
+        // lifts, stairs and crushers
+        !monster || special & LIFT_MONSTER != 0
+    }
+}
+
+/// Whether a line with no tag can still be set off, like Boom's
+/// `P_CheckTag`: only the manual ones, which act on the sector behind
+/// them, unless zero tags are allowed.
+fn check_tag(context: &Context, line: usize) -> bool {
+    let ld = &map(context.configuration).lines[line];
+    // jff 2/27/98 allow zero tags
+    context.configuration.comp_zerotags
+        || ld.tag != 0
+        || matches!(
+            ld.special,
+            // doors
+            1 | 26 | 27 | 28 | 31 | 32 | 33 | 34 | 117 | 118 | 139 | 170 | 79 | 35 | 138 | 171
+            | 81 | 13 | 192 | 169 | 80 | 12 | 194 | 173 | 157 | 104 | 193 | 172 | 156 | 17
+            // teleporters
+            | 195 | 174 | 97 | 39 | 126 | 125 | 210 | 209 | 208 | 207
+            // exits
+            | 11 | 52 | 197 | 51 | 124 | 198
+            // scrollers
+            | 48 | 85
+        )
+}
+
+/// Let `thing` press use on `line` from side `side`, like vanilla's
+/// `P_UseSpecialLine`. Returns whether the line took it, which is what
+/// monsters walking into doors go by. Only the teleporter and exit
+/// switches act yet; the rest return false until the sector movers they
+/// start are written.
+pub fn use_special_line(context: &mut Context, thing: ThinkerId, line: usize, side: usize) -> bool {
+    let configuration = &*context.configuration;
+    let cl = configuration.compatibility_level;
+    let demo_compatibility = cl < CompatibilityLevel::Boom;
+    let (player, player_health) = match context.level.mobj(thing) {
+        Some(mo) => (
+            mo.player,
+            mo.player.map(|n| context.level.players[n].health),
+        ),
+        None => return false,
+    };
+    let ld = &map(configuration).lines[line];
+    let (special, tag) = (ld.special as u16, ld.tag);
+    let secret = ld.flags.contains(LineFlags::SECRET);
+
+    // e6y: b.m. side test was broken in boom201
+    // jff 6/1/98 fix inadvertent deletion of side test
+    if cl != CompatibilityLevel::BoomV201 && side != 0 {
+        return false;
+    }
+
+    // jff 02/04/98 add check here for generalized floor/ceil mover
+    if !demo_compatibility && (GEN_CRUSHER_BASE..GEN_END).contains(&special) {
+        // jff 2/27/98 all non-manual generalized types require tag
+        if !gen_allowed(context, special, secret, player) || (tag == 0 && special & 6 != 6) {
+            return false;
+        }
+        // TODO: run the generalized mover for switch and push types once
+        // there are sector movers, and say the line took it when it does.
+        return false;
+    }
+
+    // Switches that other things can activate.
+    if player.is_none() {
+        // never open secret doors
+        if secret {
+            return false;
+        }
+        // manual doors, and jff 3/5/98 switch teleporters
+        if !matches!(special, 1 | 32 | 33 | 34 | 195 | 174 | 210 | 209) {
+            return false;
+        }
+    }
+
+    // jff 2/27/98 disallow zero tag on some types
+    if !check_tag(context, line) {
+        return false;
+    }
+
+    // killough 10/98: prevent zombies from exiting levels
+    if matches!(special, 11 | 51 | 197 | 198)
+        && player_health.is_some_and(|health| health <= 0)
+        && !context.configuration.comp_zombie
+    {
+        context.level.start_sound(Some(thing), Sfx::NOWAY as usize);
+        return false;
+    }
+
+    // Once only switches are used up when they act, as vanilla's
+    // `P_ChangeSwitchTexture` does.
+    // TODO: change the switch textures once there are switches
+    let used = match special {
+        11 => {
+            exit_level(context);
+            true
+        }
+        51 => {
+            secret_exit_level(context);
+            true
+        }
+
+        // killough 1/31/98: Boom's, not in old demos
+        _ if demo_compatibility => return false,
+        // killough 2/16/98: S1 and SR teleporters
+        174 => teleport(context, line, side, thing),
+        195 => {
+            teleport(context, line, side, thing);
+            false
+        }
+        // killough 2/16/98: S1 and SR silent teleporters
+        209 => silent_teleport(context, line, side, thing),
+        210 => {
+            silent_teleport(context, line, side, thing);
+            false
+        }
+        // TODO: the sector movers, once there are some
+        _ => return false,
+    };
+    if used {
+        clear_special(context, line);
+    }
+    true
+}
@@ -0,0 +1,273 @@
+use super::{
+    map,
+    maputl::point_on_line_side,
+    mobj::{spawn_mobj, MobjFlag},
+    movement::teleport_move,
+    Context, Thinker,
+};
+use crate::{
+    configuration::CompatibilityLevel,
+    info::MobjType,
+    misc::fixed::{fixed_div, fixed_mul, Fixed},
+    sounds::Sfx,
+    tables::{point_to_angle2, ANG180, ANG90},
+    think::ThinkerId,
+};
+
+/// How far a thing coming out of a line teleporter may be nudged to get it
+/// on the right side of the exit line.
+const FUDGEFACTOR: i32 = 10;
+
+/// The teleport destination in the first sector tagged like `line` that
+/// has one, like Boom's `P_TeleportDestination`.
+fn teleport_destination(context: &Context, line: usize) -> Option<ThinkerId> {
+    let map = map(context.configuration);
+    let tag = map.lines[line].tag;
+    // killough 1/31/98: the tagged sectors in order, then the first
+    // destination in each
+    (0..map.sectors.len())
+        .filter(|&i| map.sectors[i].tag == tag)
+        .find_map(|i| {
+            context
+                .level
+                .thinkers
+                .iter()
+                .find(|(_, Thinker::Mobj(m))| {
+                    m.mobj_type == MobjType::TELEPORTMAN && map.subsectors[m.subsector].sector == i
+                })
+                .map(|(id, _)| id)
+        })
+}
+
+/// The player whose view goes with `thing`, if it's a player's own mobj
+/// rather than a voodoo doll.
+fn view_player(context: &Context, thing: ThinkerId) -> Option<usize> {
+    let n = context.level.mobj(thing)?.player?;
+    (context.level.players[n].mo == Some(thing)).then_some(n)
+}
+
+/// Teleport `thing` to the destination in the sector tagged like `line`,
+/// with fog and a sound at both ends, like vanilla's `EV_Teleport`.
+/// Returns whether it went.
+pub fn teleport(context: &mut Context, line: usize, side: usize, thing: ThinkerId) -> bool {
+    let (oldx, oldy, oldz, missile, player) = match context.level.mobj(thing) {
+        Some(mo) => (
+            mo.x,
+            mo.y,
+            mo.z,
+            mo.flags.contains(MobjFlag::MISSILE),
+            mo.player,
+        ),
+        None => return false,
+    };
+    // don't teleport missiles, and don't teleport if hit back of line, so
+    // you can get out of teleporter
+    if side != 0 || missile {
+        return false;
+    }
+    let m = match teleport_destination(context, line) {
+        Some(m) => m,
+        None => return false,
+    };
+    let (mx, my, mangle) = {
+        let m = context.level.mobj(m).unwrap();
+        (m.x, m.y, m.angle)
+    };
+    // killough 5/12/98: exclude voodoo dolls
+    let view = view_player(context, thing);
+
+    // killough 8/9/98
+    if !teleport_move(context, thing, mx, my, false) {
+        return false;
+    }
+    let finaldoom = context.configuration.compatibility_level == CompatibilityLevel::FinalDoom;
+    let mo = context.level.mobj_mut(thing).unwrap();
+    if !finaldoom {
+        mo.z = mo.floorz;
+    }
+    let z = mo.z;
+
+    // spawn teleport fog and emit sound at source
+    let fog = spawn_mobj(context, oldx, oldy, oldz, MobjType::TFOG);
+    context.level.start_sound(Some(fog), Sfx::TELEPT as usize);
+    // spawn teleport fog at destination
+    let fog = spawn_mobj(
+        context,
+        mx + mangle.cos() * 20,
+        my + mangle.sin() * 20,
+        z,
+        MobjType::TFOG,
+    );
+    context.level.start_sound(Some(fog), Sfx::TELEPT as usize);
+
+    let mo = context.level.mobj_mut(thing).unwrap();
+    // don't move for a bit
+    // cph - DEMOSYNC - BOOM had (player) here?
+    if player.is_some() {
+        mo.reactiontime = 18;
+    }
+    mo.angle = mangle;
+    mo.momx = Fixed::ZERO;
+    mo.momy = Fixed::ZERO;
+    mo.momz = Fixed::ZERO;
+    // killough 10/98: kill all bobbing momentum too
+    if let Some(n) = view {
+        let player = &mut context.level.players[n];
+        player.momx = Fixed::ZERO;
+        player.momy = Fixed::ZERO;
+    }
+    true
+}
+
+/// Teleport `thing` to the destination in the sector tagged like `line`
+/// without fog or sound, keeping its height above the floor and turning
+/// it and its momentum as the destination is turned from the line, like
+/// Boom's `EV_SilentTeleport`. Returns whether it went.
+pub fn silent_teleport(context: &mut Context, line: usize, side: usize, thing: ThinkerId) -> bool {
+    let (z, momx, momy, missile) = match context.level.mobj(thing) {
+        Some(mo) => (
+            mo.z - mo.floorz,
+            mo.momx,
+            mo.momy,
+            mo.flags.contains(MobjFlag::MISSILE),
+        ),
+        None => return false,
+    };
+    // don't teleport missiles, and don't teleport if hit back of line, so
+    // you can get out of teleporter
+    if side != 0 || missile {
+        return false;
+    }
+    let m = match teleport_destination(context, line) {
+        Some(m) => m,
+        None => return false,
+    };
+    let (mx, my, mangle) = {
+        let m = context.level.mobj(m).unwrap();
+        (m.x, m.y, m.angle)
+    };
+
+    // Get the angle between the exit thing and source linedef. Rotate 90
+    // degrees, so that walking perpendicularly across teleporter linedef
+    // causes thing to exit in the direction indicated by the exit thing.
+    let ld = &map(context.configuration).lines[line];
+    let angle = point_to_angle2(Fixed::ZERO, Fixed::ZERO, ld.dx, ld.dy) - mangle + ANG90;
+    let (s, c) = (angle.sin(), angle.cos());
+
+    // attempt to teleport, aborting if blocked (killough 8/9/98)
+    if !teleport_move(context, thing, mx, my, false) {
+        return false;
+    }
+    let mo = context.level.mobj_mut(thing).unwrap();
+    // rotate thing according to difference in angles
+    mo.angle += angle;
+    // adjust z position to be same height above ground as before
+    mo.z = z + mo.floorz;
+    // rotate thing's momentum to come out of exit just like it entered
+    mo.momx = fixed_mul(momx, c) - fixed_mul(momy, s);
+    mo.momy = fixed_mul(momy, c) + fixed_mul(momx, s);
+    true
+}
+
+/// Teleport `thing` from `line` to the same spot along the other line
+/// with the same tag, keeping its height above the floor and turning it
+/// and its momentum as the lines are turned, like Boom's
+/// `EV_SilentLineTeleport`. `reverse` flips it across the exit line for
+/// teleporters that go both ways. Returns whether it went.
+pub fn silent_line_teleport(
+    context: &mut Context,
+    line: usize,
+    side: usize,
+    thing: ThinkerId,
+    reverse: bool,
+) -> bool {
+    let (tx, ty, z, missile) = match context.level.mobj(thing) {
+        Some(mo) => (
+            mo.x,
+            mo.y,
+            mo.z - mo.floorz,
+            mo.flags.contains(MobjFlag::MISSILE),
+        ),
+        None => return false,
+    };
+    if side != 0 || missile {
+        return false;
+    }
+    let map = map(context.configuration);
+    let ld = &map.lines[line];
+    let exit = (0..map.lines.len())
+        .find(|&i| i != line && map.lines[i].tag == ld.tag && map.lines[i].back_sector.is_some());
+    let l = match exit {
+        Some(l) => l,
+        None => return false,
+    };
+    let el = &map.lines[l];
+
+    // get the thing's position along the source linedef
+    let v1 = &map.vertices[ld.v1];
+    let mut pos = if ld.dx.abs() > ld.dy.abs() {
+        fixed_div(tx - v1.x, ld.dx)
+    } else {
+        fixed_div(ty - v1.y, ld.dy)
+    };
+
+    // Get the angle between the two linedefs, for rotating orientation
+    // and momentum. Rotate 180 degrees, and flip the position across the
+    // exit linedef, if reversed.
+    let mut angle = point_to_angle2(Fixed::ZERO, Fixed::ZERO, el.dx, el.dy)
+        - point_to_angle2(Fixed::ZERO, Fixed::ZERO, ld.dx, ld.dy);
+    if reverse {
+        pos = Fixed::UNIT - pos;
+    } else {
+        angle += ANG180;
+    }
+
+    // interpolate position across the exit linedef
+    let v2 = &map.vertices[el.v2];
+    let mut x = v2.x - fixed_mul(pos, el.dx);
+    let mut y = v2.y - fixed_mul(pos, el.dy);
+    let (s, c) = (angle.sin(), angle.cos());
+
+    // Voodoo dolls are excluded by making sure thing->player->mo==thing.
+    let view = view_player(context, thing);
+
+    // whether walking towards first side of exit linedef steps down
+    let stepdown = map.sectors[el.front_sector.unwrap()].floor_height
+        < map.sectors[el.back_sector.unwrap()].floor_height;
+
+    // Side to exit the linedef on positionally. Exiting on side 1 is
+    // always safe, as far as avoiding oscillations and stuck-in-wall
+    // problems; side 0 is needed for reversed teleporters to not
+    // oscillate, but gets non-reversed ones stuck in walls.
+    let side = usize::from(reverse || (view.is_some() && stepdown));
+
+    // make sure we are on correct side of exit linedef
+    let mut fudge = FUDGEFACTOR;
+    while point_on_line_side(map, x, y, l) != side && fudge > 0 {
+        fudge -= 1;
+        if el.dx.abs() > el.dy.abs() {
+            y -= Fixed(if (el.dx.0 < 0) != (side != 0) { -1 } else { 1 });
+        } else {
+            x += Fixed(if (el.dy.0 < 0) != (side != 0) { -1 } else { 1 });
+        }
+    }
+    // Ground level at the exit is measured as the higher of the two floor
+    // heights at the exit linedef.
+    let floor =
+        map.sectors[map.sides[el.sides[usize::from(stepdown)].unwrap()].sector].floor_height;
+
+    // attempt to teleport, aborting if blocked (killough 8/9/98)
+    if !teleport_move(context, thing, x, y, false) {
+        return false;
+    }
+    let mo = context.level.mobj_mut(thing).unwrap();
+    // adjust z position to be same height above ground as before
+    mo.z = z + floor;
+    // rotate thing's orientation according to difference in linedef angles
+    mo.angle += angle;
+    // rotate thing's momentum to come out of exit just like it entered
+    let (momx, momy) = (mo.momx, mo.momy);
+    mo.momx = fixed_mul(momx, c) - fixed_mul(momy, s);
+    mo.momy = fixed_mul(momy, c) + fixed_mul(momx, s);
+    true
+}
@@ -0,0 +1,214 @@
+use super::{
+    check_index, records, Blockmap, Line, LineFlags, MapError, Sector, Side, Thing, Vertex,
+};
+use crate::misc::fixed::Fixed;
+use byteorder::{ByteOrder, LittleEndian};
+
+/// The value vanilla uses for "no sidedef", and to end a blockmap list.
+const NO_INDEX: u16 = 0xffff;
+
+/// Read a 16 bit map unit value as fixed point.
+pub fn fixed(r: &[u8]) -> Fixed {
+    Fixed::from_int(LittleEndian::read_i16(r) as i32)
+}
+
+/// Texture and flat names are NUL padded, and compared without case.
+fn texture_name(r: &[u8]) -> String {
+    let len = r.iter().position(|&c| c == 0).unwrap_or(r.len());
+    String::from_utf8_lossy(&r[..len]).to_ascii_uppercase()
+}
+
+pub fn parse_things(map: &str, data: &[u8]) -> Result<Vec<Thing>, MapError> {
+    Ok(records(map, "THINGS", data, 10)?
+        .map(|r| Thing {
+            x: LittleEndian::read_i16(&r[0..]),
+            y: LittleEndian::read_i16(&r[2..]),
+            angle: LittleEndian::read_i16(&r[4..]),
+            kind: LittleEndian::read_i16(&r[6..]),
+            options: LittleEndian::read_u16(&r[8..]),
+        })
+        .collect())
+}
+
+pub fn parse_vertices(map: &str, data: &[u8]) -> Result<Vec<Vertex>, MapError> {
+    Ok(records(map, "VERTEXES", data, 4)?
+        .map(|r| Vertex {
+            x: fixed(&r[0..]),
+            y: fixed(&r[2..]),
+        })
+        .collect())
+}
+
+pub fn parse_sectors(map: &str, data: &[u8]) -> Result<Vec<Sector>, MapError> {
+    Ok(records(map, "SECTORS", data, 26)?
+        .map(|r| Sector {
+            floor_height: fixed(&r[0..]),
+            ceiling_height: fixed(&r[2..]),
+            floor_pic: texture_name(&r[4..12]),
+            ceiling_pic: texture_name(&r[12..20]),
+            light_level: LittleEndian::read_i16(&r[20..]),
+            special: LittleEndian::read_i16(&r[22..]),
+            tag: LittleEndian::read_i16(&r[24..]),
+            lines: vec![],
+        })
+        .collect())
+}
+
+pub fn parse_sides(map: &str, data: &[u8], num_sectors: usize) -> Result<Vec<Side>, MapError> {
+    records(map, "SIDEDEFS", data, 30)?
+        .enumerate()
+        .map(|(i, r)| {
+            Ok(Side {
+                texture_offset: fixed(&r[0..]),
+                row_offset: fixed(&r[2..]),
+                top_texture: texture_name(&r[4..12]),
+                bottom_texture: texture_name(&r[12..20]),
+                mid_texture: texture_name(&r[20..28]),
+                sector: check_index(
+                    map,
+                    "SIDEDEFS",
+                    i,
+                    "sector",
+                    LittleEndian::read_u16(&r[28..]) as usize,
+                    num_sectors,
+                )?,
+            })
+        })
+        .collect()
+}
+
+/// Parse LINEDEFS, resolving the sectors on either side through `sides`.
+pub fn parse_lines(
+    map: &str,
+    data: &[u8],
+    vertices: &[Vertex],
+    sides: &[Side],
+) -> Result<Vec<Line>, MapError> {
+    records(map, "LINEDEFS", data, 14)?
+        .enumerate()
+        .map(|(i, r)| {
+            let vertex = |field, offset| {
+                check_index(
+                    map,
+                    "LINEDEFS",
+                    i,
+                    field,
+                    LittleEndian::read_u16(&r[offset..]) as usize,
+                    vertices.len(),
+                )
+            };
+            let v1 = vertex("start vertex", 0)?;
+            let v2 = vertex("end vertex", 2)?;
+
+            let mut line_sides = [None; 2];
+            for (j, side) in line_sides.iter_mut().enumerate() {
+                let raw = LittleEndian::read_u16(&r[10 + j * 2..]);
+                if raw != NO_INDEX {
+                    let field = if j == 0 {
+                        "front sidedef"
+                    } else {
+                        "back sidedef"
+                    };
+                    *side = Some(check_index(
+                        map,
+                        "LINEDEFS",
+                        i,
+                        field,
+                        raw as usize,
+                        sides.len(),
+                    )?);
+                }
+            }
+
+            Ok(make_line(
+                vertices,
+                sides,
+                v1,
+                v2,
+                LineFlags::from_bits_truncate(LittleEndian::read_u16(&r[4..])),
+                LittleEndian::read_i16(&r[6..]),
+                LittleEndian::read_i16(&r[8..]),
+                line_sides,
+            ))
+        })
+        .collect()
+}
+
+/// Build a line from checked indices, working out its direction and the
+/// sectors on either side.
+#[allow(clippy::too_many_arguments)]
+pub fn make_line(
+    vertices: &[Vertex],
+    sides: &[Side],
+    v1: usize,
+    v2: usize,
+    flags: LineFlags,
+    special: i16,
+    tag: i16,
+    line_sides: [Option<usize>; 2],
+) -> Line {
+    Line {
+        v1,
+        v2,
+        dx: vertices[v2].x - vertices[v1].x,
+        dy: vertices[v2].y - vertices[v1].y,
+        flags,
+        special,
+        tag,
+        sides: line_sides,
+        front_sector: line_sides[0].map(|s| sides[s].sector),
+        back_sector: line_sides[1].map(|s| sides[s].sector),
+    }
+}
+
+pub fn parse_blockmap(map: &str, data: &[u8], num_lines: usize) -> Result<Blockmap, MapError> {
+    let words: Vec<u16> = records(map, "BLOCKMAP", data, 2)?
+        .map(LittleEndian::read_u16)
+        .collect();
+    if words.len() < 4 {
+        return Err(MapError::BadLumpSize {
+            map: map.to_string(),
+            lump: "BLOCKMAP",
+            size: data.len(),
+            record: 8,
+        });
+    }
+    let width = words[2] as usize;
+    let height = words[3] as usize;
+    let num_blocks = width * height;
+    if words.len() < 4 + num_blocks {
+        return Err(MapError::BadLumpSize {
+            map: map.to_string(),
+            lump: "BLOCKMAP",
+            size: data.len(),
+            record: 8 + num_blocks * 2,
+        });
+    }
+
+    let mut blocks = Vec::with_capacity(num_blocks);
+    for (i, &offset) in words[4..4 + num_blocks].iter().enumerate() {
+        let start = check_index(map, "BLOCKMAP", i, "offset", offset as usize, words.len())?;
+        let end = match words[start..].iter().position(|&w| w == NO_INDEX) {
+            Some(len) => start + len,
+            None => {
+                return Err(MapError::UnterminatedBlockList {
+                    map: map.to_string(),
+                    block: i,
+                })
+            }
+        };
+        let lines = words[start..end]
+            .iter()
+            .map(|&line| check_index(map, "BLOCKMAP", i, "line", line as usize, num_lines))
+            .collect::<Result<_, _>>()?;
+        blocks.push(lines);
+    }
+
+    Ok(Blockmap {
+        origin_x: fixed(&data[0..]),
+        origin_y: fixed(&data[2..]),
+        width,
+        height,
+        blocks,
+    })
+}
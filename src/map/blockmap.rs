@@ -0,0 +1,60 @@
+use super::{Blockmap, Line, Vertex};
+use crate::misc::fixed::Fixed;
+
+/// The size of a block in map units.
+const BLOCK_SIZE: i64 = 128;
+const BLOCK_SHIFT: u32 = 7;
+
+/// Build a blockmap from the lines, for maps that have none or whose lump
+/// can't be used. The grid starts at the bottom left vertex and covers
+/// every vertex, and each line is put in every block it passes through.
+pub fn build(vertices: &[Vertex], lines: &[Line]) -> Blockmap {
+    if vertices.is_empty() {
+        return Blockmap::default();
+    }
+    let to_units = |v: &Vertex| (v.x.to_int() as i64, v.y.to_int() as i64);
+    let (mut min_x, mut min_y) = to_units(&vertices[0]);
+    let (mut max_x, mut max_y) = (min_x, min_y);
+    for (x, y) in vertices.iter().map(to_units) {
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+    }
+    let width = ((max_x - min_x) >> BLOCK_SHIFT) as usize + 1;
+    let height = ((max_y - min_y) >> BLOCK_SHIFT) as usize + 1;
+
+    // every list starts with the 0 a blockmap lump has, like prboom's
+    // P_CreateBlockMap
+    let mut blocks = vec![vec![0]; width * height];
+    for (i, line) in lines.iter().enumerate() {
+        let (x1, y1) = to_units(&vertices[line.v1]);
+        let (x2, y2) = to_units(&vertices[line.v2]);
+        let (x1, y1, x2, y2) = (x1 - min_x, y1 - min_y, x2 - min_x, y2 - min_y);
+        // walk the columns the line crosses, adding the blocks between
+        // where it enters and leaves each one
+        let (left, right) = (x1.min(x2), x1.max(x2));
+        for column in (left >> BLOCK_SHIFT)..=(right >> BLOCK_SHIFT) {
+            let (low, high) = if x1 == x2 {
+                (y1.min(y2), y1.max(y2))
+            } else {
+                let enter = left.max(column * BLOCK_SIZE);
+                let leave = right.min(column * BLOCK_SIZE + BLOCK_SIZE - 1);
+                let y_at = |x: i64| y1 + ((y2 - y1) * (x - x1)) / (x2 - x1);
+                let (a, b) = (y_at(enter), y_at(leave));
+                (a.min(b), a.max(b))
+            };
+            for row in (low >> BLOCK_SHIFT)..=(high >> BLOCK_SHIFT) {
+                blocks[row as usize * width + column as usize].push(i);
+            }
+        }
+    }
+
+    Blockmap {
+        origin_x: Fixed::from_int(min_x as i32),
+        origin_y: Fixed::from_int(min_y as i32),
+        width,
+        height,
+        blocks,
+    }
+}
@@ -0,0 +1,523 @@
+use super::{Line, MapError, NodeFormat, Vertex};
+use crate::{
+    misc::fixed::{Fixed, FRACUNIT},
+    wad::{WadBuilder, WadError, WadKind},
+};
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::path::Path;
+
+/// Points closer than this to a partition line, in map units, are on it.
+const EPSILON: f64 = 1.0 / 128.0;
+/// What a split seg costs when choosing a partition, in segs of imbalance
+/// between the two sides.
+const SPLIT_COST: usize = 8;
+/// The flag on a node child that makes it a subsector.
+const SUBSECTOR: u32 = 0x8000_0000;
+/// Bumped whenever the builder changes its output, so old caches are
+/// rebuilt.
+const CACHE_VERSION: u8 = 1;
+
+/// A seg while the tree is being built. Minisegs have no linedef.
+#[derive(Debug, Copy, Clone)]
+struct BuildSeg {
+    v1: usize,
+    v2: usize,
+    linedef: Option<usize>,
+    side: usize,
+}
+
+/// Which side of a partition line a seg is on.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Side {
+    Right,
+    Left,
+    Split,
+}
+
+/// A wall leaving a vertex, and whether there is a sector on either side
+/// of it. Used to tell whether a partition line runs through a sector or
+/// through the void.
+#[derive(Debug, Copy, Clone)]
+struct Tip {
+    /// In degrees, from 0 up to 360.
+    angle: f64,
+    left: bool,
+    right: bool,
+}
+
+/// A partition line: start and direction, in map units.
+type Partition = [f64; 4];
+
+struct BuildNode {
+    partition: Partition,
+    bbox: [[f64; 4]; 2],
+    children: [u32; 2],
+}
+
+struct Builder<'a> {
+    lines: &'a [Line],
+    vertices: Vec<Vertex>,
+    tips: Vec<Vec<Tip>>,
+    gl: bool,
+    subsectors: Vec<Vec<BuildSeg>>,
+    nodes: Vec<BuildNode>,
+}
+
+/// Build the BSP tree of a map with no nodes, returning it as a ZDBSP
+/// lump that `nodes::load_zdbsp` reads: `XNOD`, or `XGL3` if `gl` is set.
+///
+/// GL nodes close every subsector with minisegs along the partition lines,
+/// where those run through a sector. Split points are kept in fixed point,
+/// but `XNOD` partition lines are whole map units, so non-GL nodes for maps
+/// with fractional vertices are slightly off.
+pub fn build(
+    map: &str,
+    vertices: &[Vertex],
+    lines: &[Line],
+    gl: bool,
+) -> Result<(NodeFormat, Vec<u8>), MapError> {
+    if !gl && lines.len() >= 0xffff {
+        return Err(MapError::UnsupportedFormat {
+            map: map.to_string(),
+            format: "more than 65534 linedefs without GL nodes".to_string(),
+        });
+    }
+
+    let mut builder = Builder {
+        lines,
+        vertices: vertices.to_vec(),
+        tips: vec![vec![]; vertices.len()],
+        gl,
+        subsectors: vec![],
+        nodes: vec![],
+    };
+    let mut segs = vec![];
+    for (i, line) in lines.iter().enumerate() {
+        if line.v1 == line.v2 || (line.dx == Fixed::ZERO && line.dy == Fixed::ZERO) {
+            continue;
+        }
+        let (dx, dy) = (line.dx.to_f64(), line.dy.to_f64());
+        let (front, back) = (line.sides[0].is_some(), line.sides[1].is_some());
+        builder.add_tip(line.v1, dx, dy, back, front);
+        builder.add_tip(line.v2, -dx, -dy, front, back);
+        if front {
+            segs.push(BuildSeg {
+                v1: line.v1,
+                v2: line.v2,
+                linedef: Some(i),
+                side: 0,
+            });
+        }
+        if back {
+            segs.push(BuildSeg {
+                v1: line.v2,
+                v2: line.v1,
+                linedef: Some(i),
+                side: 1,
+            });
+        }
+    }
+    builder.build_node(segs);
+    Ok(builder.write(vertices.len()))
+}
+
+impl<'a> Builder<'a> {
+    fn pos(&self, v: usize) -> (f64, f64) {
+        let v = self.vertices[v];
+        (v.x.to_f64(), v.y.to_f64())
+    }
+
+    /// The partition line along a seg's linedef, facing the same way as
+    /// the seg. Only segs with linedefs are used as partitions.
+    fn partition(&self, seg: &BuildSeg) -> Partition {
+        let line = &self.lines[seg.linedef.unwrap()];
+        let (v1, v2) = if seg.side == 0 {
+            (line.v1, line.v2)
+        } else {
+            (line.v2, line.v1)
+        };
+        let (x1, y1) = self.pos(v1);
+        let (x2, y2) = self.pos(v2);
+        [x1, y1, x2 - x1, y2 - y1]
+    }
+
+    /// Distance from the partition line, positive on the right.
+    fn distance(&self, v: usize, [x, y, dx, dy]: Partition) -> f64 {
+        let (px, py) = self.pos(v);
+        ((px - x) * dy - (py - y) * dx) / dx.hypot(dy)
+    }
+
+    fn classify(&self, seg: &BuildSeg, partition: Partition) -> (Side, f64, f64) {
+        let a = self.distance(seg.v1, partition);
+        let b = self.distance(seg.v2, partition);
+        let side = if a.abs() < EPSILON && b.abs() < EPSILON {
+            // along the partition, so it goes with the side it faces
+            let (x1, y1) = self.pos(seg.v1);
+            let (x2, y2) = self.pos(seg.v2);
+            if (x2 - x1) * partition[2] + (y2 - y1) * partition[3] > 0.0 {
+                Side::Right
+            } else {
+                Side::Left
+            }
+        } else if a > -EPSILON && b > -EPSILON {
+            Side::Right
+        } else if a < EPSILON && b < EPSILON {
+            Side::Left
+        } else {
+            Side::Split
+        };
+        (side, a, b)
+    }
+
+    fn add_tip(&mut self, v: usize, dx: f64, dy: f64, left: bool, right: bool) {
+        let angle = dy.atan2(dx).to_degrees().rem_euclid(360.0);
+        let tips = &mut self.tips[v];
+        let i = tips
+            .iter()
+            .position(|t| t.angle > angle)
+            .unwrap_or(tips.len());
+        tips.insert(i, Tip { angle, left, right });
+    }
+
+    /// Whether there is a sector next to `v` in the direction `dx`, `dy`.
+    fn is_open(&self, v: usize, dx: f64, dy: f64) -> bool {
+        let angle = dy.atan2(dx).to_degrees().rem_euclid(360.0);
+        let tips = &self.tips[v];
+        let along = |t: &Tip| {
+            let diff = (t.angle - angle).abs();
+            !(1e-6..=360.0 - 1e-6).contains(&diff)
+        };
+        if tips.iter().any(along) {
+            // a wall runs that way
+            return false;
+        }
+        match tips.iter().find(|t| t.angle > angle) {
+            Some(tip) => tip.right,
+            None => tips.last().is_some_and(|tip| tip.left),
+        }
+    }
+
+    /// Pick the partition that splits the fewest segs and leaves the sides
+    /// most even, or `None` if the segs already make a convex subsector.
+    fn choose(&self, segs: &[BuildSeg]) -> Option<Partition> {
+        let mut best: Option<(usize, Partition)> = None;
+        let mut tried = FxHashSet::default();
+        for seg in segs {
+            // both sides of a line give the same partition
+            if seg.linedef.is_none_or(|linedef| !tried.insert(linedef)) {
+                continue;
+            }
+            let partition = self.partition(seg);
+            let (mut right, mut left, mut splits) = (0, 0, 0);
+            for other in segs {
+                match self.classify(other, partition).0 {
+                    Side::Right => right += 1,
+                    Side::Left => left += 1,
+                    Side::Split => splits += 1,
+                }
+                if matches!(best, Some((cost, _)) if splits * SPLIT_COST > cost) {
+                    break;
+                }
+            }
+            if left == 0 && splits == 0 {
+                continue;
+            }
+            let cost = splits * SPLIT_COST + (right as isize - left as isize).unsigned_abs();
+            if best.is_none_or(|(best, _)| cost < best) {
+                best = Some((cost, partition));
+            }
+        }
+        best.map(|(_, partition)| partition)
+    }
+
+    /// Split the segs between the two sides of the partition.
+    fn divide(
+        &mut self,
+        segs: Vec<BuildSeg>,
+        partition: Partition,
+    ) -> (Vec<BuildSeg>, Vec<BuildSeg>) {
+        let (mut right, mut left) = (vec![], vec![]);
+        // the two sides of a line are split at the same new vertex
+        let mut splits = FxHashMap::default();
+        let mut cuts = vec![];
+        for seg in segs {
+            let (side, a, b) = self.classify(&seg, partition);
+            if a.abs() < EPSILON {
+                cuts.push(seg.v1);
+            }
+            if b.abs() < EPSILON {
+                cuts.push(seg.v2);
+            }
+            match side {
+                Side::Right => right.push(seg),
+                Side::Left => left.push(seg),
+                Side::Split => {
+                    let key = (seg.v1.min(seg.v2), seg.v1.max(seg.v2));
+                    let v = match splits.get(&key) {
+                        Some(&v) => v,
+                        None => {
+                            let v = self.split_vertex(&seg, a / (a - b));
+                            splits.insert(key, v);
+                            v
+                        }
+                    };
+                    cuts.push(v);
+                    let first = BuildSeg { v2: v, ..seg };
+                    let second = BuildSeg { v1: v, ..seg };
+                    if a > 0.0 {
+                        right.push(first);
+                        left.push(second);
+                    } else {
+                        left.push(first);
+                        right.push(second);
+                    }
+                }
+            }
+        }
+        if self.gl {
+            self.add_minisegs(partition, cuts, &mut right, &mut left);
+        }
+        (right, left)
+    }
+
+    /// Add a vertex `t` of the way along a seg, with the seg's walls.
+    fn split_vertex(&mut self, seg: &BuildSeg, t: f64) -> usize {
+        let (x1, y1) = self.pos(seg.v1);
+        let (x2, y2) = self.pos(seg.v2);
+        let (dx, dy) = (x2 - x1, y2 - y1);
+        let to_fixed = |c: f64| Fixed((c * FRACUNIT as f64).round() as i32);
+        self.vertices.push(Vertex {
+            x: to_fixed(x1 + dx * t),
+            y: to_fixed(y1 + dy * t),
+        });
+        self.tips.push(vec![]);
+        let v = self.vertices.len() - 1;
+        match seg.linedef {
+            Some(linedef) => {
+                let line = &self.lines[linedef];
+                let (front, back) = (line.sides[0].is_some(), line.sides[1].is_some());
+                let (dx, dy) = (line.dx.to_f64(), line.dy.to_f64());
+                self.add_tip(v, dx, dy, back, front);
+                self.add_tip(v, -dx, -dy, front, back);
+            }
+            // minisegs are inside a sector
+            None => {
+                self.add_tip(v, dx, dy, true, true);
+                self.add_tip(v, -dx, -dy, true, true);
+            }
+        }
+        v
+    }
+
+    /// Add minisegs to both sides along the stretches of the partition
+    /// line that are inside a sector.
+    fn add_minisegs(
+        &self,
+        [x, y, dx, dy]: Partition,
+        cuts: Vec<usize>,
+        right: &mut Vec<BuildSeg>,
+        left: &mut Vec<BuildSeg>,
+    ) {
+        let len = dx.hypot(dy);
+        let mut points: Vec<(f64, usize)> = cuts
+            .into_iter()
+            .map(|v| {
+                let (px, py) = self.pos(v);
+                (((px - x) * dx + (py - y) * dy) / len, v)
+            })
+            .collect();
+        points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        points.dedup_by(|next, prev| next.0 - prev.0 < EPSILON);
+        for pair in points.windows(2) {
+            let (from, to) = (pair[0].1, pair[1].1);
+            if self.is_open(from, dx, dy) && self.is_open(to, -dx, -dy) {
+                right.push(BuildSeg {
+                    v1: from,
+                    v2: to,
+                    linedef: None,
+                    side: 0,
+                });
+                left.push(BuildSeg {
+                    v1: to,
+                    v2: from,
+                    linedef: None,
+                    side: 0,
+                });
+            }
+        }
+    }
+
+    fn bbox(&self, segs: &[BuildSeg]) -> [f64; 4] {
+        let mut bbox = [f64::MIN, f64::MAX, f64::MAX, f64::MIN];
+        for seg in segs {
+            for &v in &[seg.v1, seg.v2] {
+                let (x, y) = self.pos(v);
+                bbox = [
+                    bbox[0].max(y),
+                    bbox[1].min(y),
+                    bbox[2].min(x),
+                    bbox[3].max(x),
+                ];
+            }
+        }
+        bbox
+    }
+
+    /// Build the subtree for `segs`, returning the child number to store in
+    /// its parent. Children are added before their parents, so the root is
+    /// the last node.
+    fn build_node(&mut self, segs: Vec<BuildSeg>) -> u32 {
+        let partition = match self.choose(&segs) {
+            Some(partition) => partition,
+            None => {
+                let segs = if self.gl { self.clockwise(segs) } else { segs };
+                self.subsectors.push(segs);
+                return (self.subsectors.len() - 1) as u32 | SUBSECTOR;
+            }
+        };
+        let (right, left) = self.divide(segs, partition);
+        let bbox = [self.bbox(&right), self.bbox(&left)];
+        let children = [self.build_node(right), self.build_node(left)];
+        self.nodes.push(BuildNode {
+            partition,
+            bbox,
+            children,
+        });
+        (self.nodes.len() - 1) as u32
+    }
+
+    /// Sort a subsector's segs clockwise, so each GL seg ends where the next
+    /// one starts.
+    fn clockwise(&self, mut segs: Vec<BuildSeg>) -> Vec<BuildSeg> {
+        let n = (segs.len() * 2) as f64;
+        let (mut cx, mut cy) = (0.0, 0.0);
+        for seg in &segs {
+            for &v in &[seg.v1, seg.v2] {
+                let (x, y) = self.pos(v);
+                cx += x / n;
+                cy += y / n;
+            }
+        }
+        let angle = |seg: &BuildSeg| {
+            let (x, y) = self.pos(seg.v1);
+            (y - cy).atan2(x - cx)
+        };
+        segs.sort_by(|a, b| angle(b).partial_cmp(&angle(a)).unwrap());
+        segs
+    }
+
+    /// Lay out the tree as a ZDBSP lump.
+    fn write(&self, original: usize) -> (NodeFormat, Vec<u8>) {
+        let mut out = vec![];
+        let put = |out: &mut Vec<u8>, n: u32| out.extend_from_slice(&n.to_le_bytes());
+        let put16 = |out: &mut Vec<u8>, n: f64| out.extend_from_slice(&(n as i16).to_le_bytes());
+
+        out.extend_from_slice(if self.gl { b"XGL3" } else { b"XNOD" });
+        put(&mut out, original as u32);
+        put(&mut out, (self.vertices.len() - original) as u32);
+        for v in &self.vertices[original..] {
+            put(&mut out, v.x.0 as u32);
+            put(&mut out, v.y.0 as u32);
+        }
+
+        put(&mut out, self.subsectors.len() as u32);
+        for segs in &self.subsectors {
+            put(&mut out, segs.len() as u32);
+        }
+        let segs: Vec<&BuildSeg> = self.subsectors.iter().flatten().collect();
+        let index: FxHashMap<(usize, usize), usize> = segs
+            .iter()
+            .enumerate()
+            .map(|(i, seg)| ((seg.v1, seg.v2), i))
+            .collect();
+        put(&mut out, segs.len() as u32);
+        for seg in &segs {
+            put(&mut out, seg.v1 as u32);
+            if self.gl {
+                let partner = index.get(&(seg.v2, seg.v1));
+                put(&mut out, partner.map_or(0xffff_ffff, |&p| p as u32));
+                put(&mut out, seg.linedef.map_or(0xffff_ffff, |l| l as u32));
+            } else {
+                put(&mut out, seg.v2 as u32);
+                out.extend_from_slice(&(seg.linedef.unwrap() as u16).to_le_bytes());
+            }
+            out.push(seg.side as u8);
+        }
+
+        put(&mut out, self.nodes.len() as u32);
+        for node in &self.nodes {
+            for &c in &node.partition {
+                if self.gl {
+                    put(&mut out, (c * FRACUNIT as f64).round() as i32 as u32);
+                } else {
+                    put16(&mut out, c.round());
+                }
+            }
+            for bbox in &node.bbox {
+                put16(&mut out, bbox[0].ceil());
+                put16(&mut out, bbox[1].floor());
+                put16(&mut out, bbox[2].floor());
+                put16(&mut out, bbox[3].ceil());
+            }
+            for &child in &node.children {
+                put(&mut out, child);
+            }
+        }
+
+        let format = if self.gl {
+            NodeFormat::Xgl3
+        } else {
+            NodeFormat::Xnod
+        };
+        (format, out)
+    }
+}
+
+/// A checksum of everything the builder looks at, so a cached tree is only
+/// used for the map it was built from.
+pub fn cache_key(vertices: &[Vertex], lines: &[Line], gl: bool) -> [u8; 16] {
+    let mut context = md5::Context::new();
+    context.consume([CACHE_VERSION, gl as u8]);
+    for v in vertices {
+        context.consume(v.x.0.to_le_bytes());
+        context.consume(v.y.0.to_le_bytes());
+    }
+    for line in lines {
+        context.consume((line.v1 as u32).to_le_bytes());
+        context.consume((line.v2 as u32).to_le_bytes());
+        context.consume([line.sides[0].is_some() as u8, line.sides[1].is_some() as u8]);
+    }
+    context.compute().0
+}
+
+/// Look for a tree built for `map` with the checksum `key` in the cache
+/// file. A missing or unreadable cache is the same as an empty one.
+pub fn read_cache(path: &Path, map: &str, key: &[u8; 16]) -> Option<Vec<u8>> {
+    let wad = WadBuilder::open(path).ok()?;
+    let lumps = wad.lumps();
+    let marker = wad.position(map)?;
+    match lumps.get(marker + 1..marker + 3)? {
+        [md5, znodes] if md5.is_named("MD5") && md5.data == key && znodes.is_named("ZNODES") => {
+            Some(znodes.data.clone())
+        }
+        _ => None,
+    }
+}
+
+/// Store a tree in the cache file, as a map marker followed by `MD5` and
+/// `ZNODES` lumps, replacing any older tree for the same map.
+pub fn write_cache(path: &Path, map: &str, key: &[u8; 16], data: &[u8]) -> Result<(), WadError> {
+    let mut wad = WadBuilder::open(path).unwrap_or_else(|_| WadBuilder::new(WadKind::Pwad));
+    if let Some(marker) = wad.position(map) {
+        wad.remove(marker);
+        for name in &["MD5", "ZNODES"] {
+            if wad.lumps().get(marker).is_some_and(|l| l.is_named(name)) {
+                wad.remove(marker);
+            }
+        }
+    }
+    wad.push(map, vec![])?
+        .push("MD5", key.to_vec())?
+        .push("ZNODES", data.to_vec())?;
+    wad.write_to_file(path)
+}
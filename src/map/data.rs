@@ -0,0 +1,164 @@
+use crate::{misc::fixed::Fixed, tables::Angle};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Vertex {
+    pub x: Fixed,
+    pub y: Fixed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sector {
+    pub floor_height: Fixed,
+    pub ceiling_height: Fixed,
+    pub floor_pic: String,
+    pub ceiling_pic: String,
+    pub light_level: i16,
+    pub special: i16,
+    pub tag: i16,
+    /// Every line with this sector on either side.
+    pub lines: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Side {
+    pub texture_offset: Fixed,
+    pub row_offset: Fixed,
+    /// Texture names, `-` for none.
+    pub top_texture: String,
+    pub bottom_texture: String,
+    pub mid_texture: String,
+    pub sector: usize,
+}
+
+bitflags::bitflags! {
+    /// The flags field of a linedef.
+    pub struct LineFlags: u16 {
+        const BLOCKING = 1;
+        const BLOCK_MONSTERS = 2;
+        const TWO_SIDED = 4;
+        const DONT_PEG_TOP = 8;
+        const DONT_PEG_BOTTOM = 16;
+        const SECRET = 32;
+        const SOUND_BLOCK = 64;
+        const DONT_DRAW = 128;
+        const MAPPED = 256;
+        /// Boom: pass use actions through this line.
+        const PASS_USE = 512;
+        /// MBF21: blocks land monsters only.
+        const BLOCK_LAND_MONSTERS = 4096;
+        /// MBF21: blocks players.
+        const BLOCK_PLAYERS = 8192;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    pub v1: usize,
+    pub v2: usize,
+    pub dx: Fixed,
+    pub dy: Fixed,
+    pub flags: LineFlags,
+    pub special: i16,
+    pub tag: i16,
+    /// The front and back sidedefs.
+    pub sides: [Option<usize>; 2],
+    pub front_sector: Option<usize>,
+    pub back_sector: Option<usize>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Seg {
+    pub v1: usize,
+    pub v2: usize,
+    /// Angle of the seg, from `v1` to `v2`.
+    pub angle: Angle,
+    /// `None` for minisegs from GL nodes, which run along a partition line
+    /// rather than a linedef.
+    pub linedef: Option<usize>,
+    /// 0 if the seg runs along the front of its linedef, 1 for the back.
+    pub side: usize,
+    /// Distance along the linedef to the start of the seg.
+    pub offset: Fixed,
+    pub front_sector: usize,
+    pub back_sector: Option<usize>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Subsector {
+    pub first_seg: usize,
+    pub num_segs: usize,
+    pub sector: usize,
+}
+
+/// What is on one side of a node's partition line.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NodeChild {
+    Node(usize),
+    Subsector(usize),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Node {
+    /// Partition line start.
+    pub x: Fixed,
+    pub y: Fixed,
+    /// Partition line direction.
+    pub dx: Fixed,
+    pub dy: Fixed,
+    /// Bounding boxes of the right and left children, each top, bottom,
+    /// left, right.
+    pub bbox: [[Fixed; 4]; 2],
+    /// The right and left children.
+    pub children: [NodeChild; 2],
+}
+
+bitflags::bitflags! {
+    /// The options field of a map thing.
+    pub struct ThingFlags: u16 {
+        /// Spawned on the two easiest skills.
+        const EASY = 1;
+        const NORMAL = 2;
+        /// Spawned on Ultra-Violence and Nightmare.
+        const HARD = 4;
+        const AMBUSH = 8;
+        /// Only spawned in multiplayer.
+        const NOT_SINGLE = 16;
+        /// Boom: not spawned in deathmatch.
+        const NOT_DEATHMATCH = 32;
+        /// Boom: not spawned in cooperative.
+        const NOT_COOP = 64;
+        /// MBF: spawned friendly.
+        const FRIEND = 128;
+        /// Always 0 from Boom on. Editors that set it were setting bits
+        /// Doom didn't use, so the extended flags are meaningless.
+        const RESERVED = 256;
+    }
+}
+
+/// A map thing, as stored in the THINGS lump. Unlike the other structures
+/// these stay in map units, since they only describe what to spawn.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Thing {
+    pub x: i16,
+    pub y: i16,
+    /// Facing angle in degrees.
+    pub angle: i16,
+    /// The editor number, as in `MobjInfo::doomednum`.
+    pub kind: i16,
+    pub options: u16,
+}
+
+/// The lines touching each 128x128 block of the map, used to find which
+/// lines a moving object might hit.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Blockmap {
+    /// Bottom left corner of the grid.
+    pub origin_x: Fixed,
+    pub origin_y: Fixed,
+    pub width: usize,
+    pub height: usize,
+    /// The lines in each block, row by row from the bottom, as on disk.
+    /// Lists start with a 0 that vanilla mistook for line 0, which Boom
+    /// skips; it's kept here so iterators can decide by complevel.
+    pub blocks: Vec<Vec<usize>>,
+}
@@ -0,0 +1,170 @@
+use crate::wad::WadError;
+use std::{error::Error, fmt, io, path::PathBuf};
+
+/// Everything that can go wrong while loading a map.
+///
+/// Every variant names the map, so the message alone is enough to find the
+/// broken lump in an editor.
+#[derive(Debug)]
+pub enum MapError {
+    /// A lump could not be read from its file.
+    Wad(WadError),
+    /// A lump the map needs is not there.
+    MissingLump { map: String, lump: &'static str },
+    /// A lump is not a whole number of records long.
+    BadLumpSize {
+        map: String,
+        lump: &'static str,
+        size: usize,
+        record: usize,
+    },
+    /// A record refers to something that does not exist, such as a seg
+    /// using vertex 500 in a map with 300 vertices.
+    BadIndex {
+        map: String,
+        lump: &'static str,
+        record: usize,
+        field: &'static str,
+        value: usize,
+        limit: usize,
+    },
+    /// A seg runs along a side of its linedef that has no sidedef.
+    MissingSidedef {
+        map: String,
+        seg: usize,
+        linedef: usize,
+        side: usize,
+    },
+    /// A subsector has no segs, so it has no sector either.
+    EmptySubsector { map: String, subsector: usize },
+    /// None of a GL subsector's segs lead to a sector.
+    UnknownSubsectorSector { map: String, subsector: usize },
+    /// A lump ends in the middle of a structure.
+    Truncated { map: String, lump: &'static str },
+    /// A compressed lump could not be decompressed.
+    Zlib {
+        map: String,
+        lump: &'static str,
+        error: io::Error,
+    },
+    /// A blockmap list runs off the end of the lump.
+    UnterminatedBlockList { map: String, block: usize },
+    /// A TEXTMAP lump can't be parsed, or is missing something required.
+    Udmf {
+        map: String,
+        line: usize,
+        message: String,
+    },
+    /// There is no marker lump for the map.
+    MapNotFound(String),
+    /// The map is in Hexen format, which has a different THINGS and
+    /// LINEDEFS layout and needs ACS scripts.
+    HexenFormat { map: String, wad: PathBuf },
+    /// The map marker is followed by neither THINGS nor TEXTMAP.
+    UnknownFormat {
+        map: String,
+        wad: PathBuf,
+        next: Option<String>,
+    },
+    /// The lumps use a format this port doesn't know how to read.
+    UnsupportedFormat { map: String, format: String },
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapError::Wad(e) => write!(f, "{}", e),
+            MapError::MissingLump { map, lump } => write!(f, "{}: {} lump is missing", map, lump),
+            MapError::BadLumpSize {
+                map,
+                lump,
+                size,
+                record,
+            } => write!(
+                f,
+                "{}: {} lump is {} bytes, which is not a multiple of {}",
+                map, lump, size, record
+            ),
+            MapError::BadIndex {
+                map,
+                lump,
+                record,
+                field,
+                value,
+                limit,
+            } => write!(
+                f,
+                "{}: {} entry {} has {} {}, but there are only {}",
+                map, lump, record, field, value, limit
+            ),
+            MapError::MissingSidedef {
+                map,
+                seg,
+                linedef,
+                side,
+            } => write!(
+                f,
+                "{}: seg {} is on the {} of linedef {}, which has no sidedef there",
+                map,
+                seg,
+                if *side == 0 { "front" } else { "back" },
+                linedef
+            ),
+            MapError::EmptySubsector { map, subsector } => {
+                write!(f, "{}: subsector {} has no segs", map, subsector)
+            }
+            MapError::UnknownSubsectorSector { map, subsector } => write!(
+                f,
+                "{}: can't tell which sector subsector {} is in",
+                map, subsector
+            ),
+            MapError::Truncated { map, lump } => write!(f, "{}: {} lump is truncated", map, lump),
+            MapError::Zlib { map, lump, error } => {
+                write!(f, "{}: can't decompress {} lump: {}", map, lump, error)
+            }
+            MapError::UnterminatedBlockList { map, block } => write!(
+                f,
+                "{}: the BLOCKMAP list for block {} runs past the end of the lump",
+                map, block
+            ),
+            MapError::Udmf { map, line, message } => {
+                write!(f, "{}: TEXTMAP line {}: {}", map, line, message)
+            }
+            MapError::MapNotFound(map) => write!(f, "{}: map not found", map),
+            MapError::HexenFormat { map, wad } => write!(
+                f,
+                "{} in {} is a Hexen format map (it has a BEHAVIOR lump), which can't be played \
+                 in a Boom port",
+                map,
+                wad.display()
+            ),
+            MapError::UnknownFormat { map, wad, next } => write!(
+                f,
+                "{} in {} is not in a known map format: the marker is followed by {} instead \
+                 of THINGS or TEXTMAP",
+                map,
+                wad.display(),
+                next.as_deref().unwrap_or("nothing")
+            ),
+            MapError::UnsupportedFormat { map, format } => {
+                write!(f, "{}: unsupported map format ({})", map, format)
+            }
+        }
+    }
+}
+
+impl Error for MapError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MapError::Wad(e) => Some(e),
+            MapError::Zlib { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<WadError> for MapError {
+    fn from(e: WadError) -> Self {
+        MapError::Wad(e)
+    }
+}
@@ -0,0 +1,411 @@
+use super::{binary::fixed, check_index, records, MapError, NodeChild};
+use crate::{misc::fixed::Fixed, wad::LumpDirectory};
+use byteorder::{ByteOrder, LittleEndian};
+
+/// The GL node versions defined by the glBSP specification. Each lump
+/// marks its version with a 4 byte magic at the start, except for V1.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GlNodesVersion {
+    /// No magic, 16 bit vertices and indices.
+    V1,
+    /// `gNd2` vertices in 16.16 fixed point, 16 bit indices.
+    V2,
+    /// `gNd2` vertices, `gNd3` segs and subsectors with 32 bit indices.
+    V3,
+    /// `gNd5` vertices, 32 bit indices everywhere and no other magic.
+    V5,
+}
+
+/// A vertex added by the node builder.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct GlVertex {
+    pub x: Fixed,
+    pub y: Fixed,
+}
+
+/// Which table a GL seg's vertex comes from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VertexRef {
+    /// An index into the map's VERTEXES.
+    Normal(usize),
+    /// An index into GL_VERT.
+    Gl(usize),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct GlSeg {
+    pub v1: VertexRef,
+    pub v2: VertexRef,
+    /// `None` for minisegs, which run along a partition line rather than a
+    /// linedef.
+    pub linedef: Option<usize>,
+    /// 0 for the front side of the linedef, 1 for the back.
+    pub side: usize,
+    /// The seg on the other side of the same line, if there is one.
+    pub partner: Option<usize>,
+}
+
+/// A convex polygon, made of `count` segs starting at `first_seg`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct GlSubsector {
+    pub count: usize,
+    pub first_seg: usize,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct GlNode {
+    /// Partition line start, in map units.
+    pub x: i16,
+    pub y: i16,
+    /// Partition line direction, in map units.
+    pub dx: i16,
+    pub dy: i16,
+    /// Bounding boxes of the right and left children, each top, bottom,
+    /// left, right.
+    pub bbox: [[i16; 4]; 2],
+    /// The right and left children.
+    pub children: [NodeChild; 2],
+}
+
+/// The GL nodes for one map, from a GWA file or built into the WAD itself.
+///
+/// References inside the GL lumps are checked while parsing. Indices into
+/// the map's own VERTEXES and LINEDEFS are checked by `check_map_refs` once
+/// the map is loaded.
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct GlNodes {
+    pub version: GlNodesVersion,
+    pub vertices: Vec<GlVertex>,
+    pub segs: Vec<GlSeg>,
+    pub subsectors: Vec<GlSubsector>,
+    pub nodes: Vec<GlNode>,
+    /// One row of bits per subsector, set if the subsector in that column
+    /// can be seen from it. Empty if the map has no usable GL_PVS.
+    pvs: Vec<u8>,
+}
+
+/// The lumps following a `GL_<map>` marker, in the order glBSP writes them.
+const GL_LUMPS: [&str; 4] = ["GL_VERT", "GL_SEGS", "GL_SSECT", "GL_NODES"];
+
+#[allow(dead_code)]
+impl GlNodes {
+    /// Find and parse the GL nodes for the map whose marker is lump
+    /// `map_lump`. GWA files are loaded straight after their WAD, so the
+    /// latest `GL_<map>` marker is either from the GWA or built into the WAD
+    /// itself; either way it has to come after the map, or it belongs to an
+    /// older version of it.
+    ///
+    /// Returns `Ok(None)` if there are no GL nodes for this map. Map names
+    /// longer than 5 characters use the `GL_LEVEL` marker, which is not
+    /// supported.
+    pub fn find(
+        lumps: &LumpDirectory,
+        map: &str,
+        map_lump: usize,
+    ) -> Result<Option<Self>, MapError> {
+        if map.len() > 5 {
+            return Ok(None);
+        }
+        let marker = match lumps.check_num_for_name(&format!("GL_{}", map)) {
+            Some(marker) if marker > map_lump => marker,
+            _ => return Ok(None),
+        };
+        for (i, name) in GL_LUMPS.iter().enumerate() {
+            let lump = marker + 1 + i;
+            if lump >= lumps.len() || lumps.lump(lump).name_str() != *name {
+                return Err(MapError::MissingLump {
+                    map: map.to_string(),
+                    lump: name,
+                });
+            }
+        }
+        let pvs = match marker + 5 {
+            lump if lump < lumps.len() && lumps.lump(lump).name_str() == "GL_PVS" => {
+                lumps.read_lump(lump)?
+            }
+            _ => vec![],
+        };
+        Self::parse(
+            map,
+            &lumps.read_lump(marker + 1)?,
+            &lumps.read_lump(marker + 2)?,
+            &lumps.read_lump(marker + 3)?,
+            &lumps.read_lump(marker + 4)?,
+            pvs,
+        )
+        .map(Some)
+    }
+
+    /// Parse the contents of the GL lumps, working out the version from
+    /// their magic.
+    pub fn parse(
+        map: &str,
+        vert: &[u8],
+        segs: &[u8],
+        ssect: &[u8],
+        nodes: &[u8],
+        pvs: Vec<u8>,
+    ) -> Result<Self, MapError> {
+        let (version, vert) = match vert.get(..4) {
+            Some(b"gNd2") if segs.starts_with(b"gNd3") => (GlNodesVersion::V3, &vert[4..]),
+            Some(b"gNd2") => (GlNodesVersion::V2, &vert[4..]),
+            Some(b"gNd5") => (GlNodesVersion::V5, &vert[4..]),
+            Some(magic) if magic.starts_with(b"gNd") => {
+                return Err(MapError::UnsupportedFormat {
+                    map: map.to_string(),
+                    format: format!("GL nodes {}", String::from_utf8_lossy(magic)),
+                })
+            }
+            _ => (GlNodesVersion::V1, vert),
+        };
+        let segs = segs.strip_prefix(b"gNd3").unwrap_or(segs);
+        let ssect = ssect.strip_prefix(b"gNd3").unwrap_or(ssect);
+
+        let vertices = parse_vertices(map, version, vert)?;
+        let segs = parse_segs(map, version, segs, vertices.len())?;
+        let subsectors = parse_subsectors(map, version, ssect, segs.len())?;
+        let nodes = parse_nodes(map, version, nodes, subsectors.len())?;
+
+        // a PVS of the wrong size is ignored rather than trusted
+        let row = subsectors.len().div_ceil(8);
+        let pvs = if pvs.len() >= row * subsectors.len() {
+            pvs
+        } else {
+            vec![]
+        };
+
+        Ok(Self {
+            version,
+            vertices,
+            segs,
+            subsectors,
+            nodes,
+            pvs,
+        })
+    }
+
+    /// Check the segs' references to the map's vertices and linedefs.
+    pub fn check_map_refs(
+        &self,
+        map: &str,
+        num_vertices: usize,
+        num_lines: usize,
+    ) -> Result<(), MapError> {
+        for (i, seg) in self.segs.iter().enumerate() {
+            for (field, v) in &[("start vertex", seg.v1), ("end vertex", seg.v2)] {
+                if let VertexRef::Normal(v) = *v {
+                    check_index(map, "GL_SEGS", i, field, v, num_vertices)?;
+                }
+            }
+            if let Some(linedef) = seg.linedef {
+                check_index(map, "GL_SEGS", i, "linedef", linedef, num_lines)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the map came with a potentially visible set.
+    pub fn has_pvs(&self) -> bool {
+        !self.pvs.is_empty()
+    }
+
+    /// Whether subsector `to` may be visible from subsector `from`. Always
+    /// true if there is no PVS.
+    pub fn visible(&self, from: usize, to: usize) -> bool {
+        if self.pvs.is_empty() {
+            return true;
+        }
+        let row = self.subsectors.len().div_ceil(8);
+        self.pvs[from * row + to / 8] & (1 << (to % 8)) != 0
+    }
+}
+
+fn parse_vertices(
+    map: &str,
+    version: GlNodesVersion,
+    data: &[u8],
+) -> Result<Vec<GlVertex>, MapError> {
+    Ok(match version {
+        GlNodesVersion::V1 => records(map, "GL_VERT", data, 4)?
+            .map(|r| GlVertex {
+                x: fixed(&r[0..]),
+                y: fixed(&r[2..]),
+            })
+            .collect(),
+        _ => records(map, "GL_VERT", data, 8)?
+            .map(|r| GlVertex {
+                x: Fixed(LittleEndian::read_i32(&r[0..])),
+                y: Fixed(LittleEndian::read_i32(&r[4..])),
+            })
+            .collect(),
+    })
+}
+
+fn parse_segs(
+    map: &str,
+    version: GlNodesVersion,
+    data: &[u8],
+    num_vertices: usize,
+) -> Result<Vec<GlSeg>, MapError> {
+    // (record size, flag marking a GL vertex, "no partner" value)
+    let (size, gl_flag, no_partner) = match version {
+        GlNodesVersion::V1 | GlNodesVersion::V2 => (10, 0x8000, 0xffff),
+        GlNodesVersion::V3 => (16, 0x4000_0000, 0xffff_ffff),
+        GlNodesVersion::V5 => (16, 0x8000_0000, 0xffff_ffff),
+    };
+    let raw: Vec<_> = records(map, "GL_SEGS", data, size)?
+        .map(|r| {
+            if size == 10 {
+                (
+                    LittleEndian::read_u16(&r[0..]) as u32,
+                    LittleEndian::read_u16(&r[2..]) as u32,
+                    LittleEndian::read_u16(&r[4..]),
+                    LittleEndian::read_u16(&r[6..]),
+                    LittleEndian::read_u16(&r[8..]) as u32,
+                )
+            } else {
+                (
+                    LittleEndian::read_u32(&r[0..]),
+                    LittleEndian::read_u32(&r[4..]),
+                    LittleEndian::read_u16(&r[8..]),
+                    LittleEndian::read_u16(&r[10..]),
+                    LittleEndian::read_u32(&r[12..]),
+                )
+            }
+        })
+        .collect();
+
+    let num_segs = raw.len();
+    let vertex = |i, field, v: u32| {
+        if v & gl_flag != 0 {
+            let v = (v & !gl_flag) as usize;
+            check_index(map, "GL_SEGS", i, field, v, num_vertices).map(VertexRef::Gl)
+        } else {
+            Ok(VertexRef::Normal(v as usize))
+        }
+    };
+    raw.into_iter()
+        .enumerate()
+        .map(|(i, (v1, v2, linedef, side, partner))| {
+            Ok(GlSeg {
+                v1: vertex(i, "start vertex", v1)?,
+                v2: vertex(i, "end vertex", v2)?,
+                linedef: if linedef == 0xffff {
+                    None
+                } else {
+                    Some(linedef as usize)
+                },
+                side: side as usize,
+                partner: if partner == no_partner {
+                    None
+                } else {
+                    Some(check_index(
+                        map,
+                        "GL_SEGS",
+                        i,
+                        "partner seg",
+                        partner as usize,
+                        num_segs,
+                    )?)
+                },
+            })
+        })
+        .collect()
+}
+
+fn parse_subsectors(
+    map: &str,
+    version: GlNodesVersion,
+    data: &[u8],
+    num_segs: usize,
+) -> Result<Vec<GlSubsector>, MapError> {
+    let wide = matches!(version, GlNodesVersion::V3 | GlNodesVersion::V5);
+    records(map, "GL_SSECT", data, if wide { 8 } else { 4 })?
+        .enumerate()
+        .map(|(i, r)| {
+            let (count, first_seg) = if wide {
+                (
+                    LittleEndian::read_u32(&r[0..]) as usize,
+                    LittleEndian::read_u32(&r[4..]) as usize,
+                )
+            } else {
+                (
+                    LittleEndian::read_u16(&r[0..]) as usize,
+                    LittleEndian::read_u16(&r[2..]) as usize,
+                )
+            };
+            // the last seg has to exist, so the end is checked inclusively
+            check_index(
+                map,
+                "GL_SSECT",
+                i,
+                "last seg",
+                (first_seg + count).saturating_sub(1),
+                num_segs,
+            )?;
+            Ok(GlSubsector { count, first_seg })
+        })
+        .collect()
+}
+
+fn parse_nodes(
+    map: &str,
+    version: GlNodesVersion,
+    data: &[u8],
+    num_subsectors: usize,
+) -> Result<Vec<GlNode>, MapError> {
+    let (size, subsector_flag) = match version {
+        GlNodesVersion::V5 => (32, 0x8000_0000),
+        _ => (28, 0x8000),
+    };
+    let records = records(map, "GL_NODES", data, size)?;
+    let num_nodes = records.len();
+    records
+        .enumerate()
+        .map(|(i, r)| {
+            let mut bbox = [[0; 4]; 2];
+            for (j, side) in bbox.iter_mut().enumerate() {
+                for (k, coord) in side.iter_mut().enumerate() {
+                    *coord = LittleEndian::read_i16(&r[8 + j * 8 + k * 2..]);
+                }
+            }
+            let mut children = [NodeChild::Node(0); 2];
+            for (j, child) in children.iter_mut().enumerate() {
+                let raw = if size == 32 {
+                    LittleEndian::read_u32(&r[24 + j * 4..])
+                } else {
+                    LittleEndian::read_u16(&r[24 + j * 2..]) as u32
+                };
+                *child = if raw & subsector_flag != 0 {
+                    let s = (raw & !subsector_flag) as usize;
+                    NodeChild::Subsector(check_index(
+                        map,
+                        "GL_NODES",
+                        i,
+                        "child subsector",
+                        s,
+                        num_subsectors,
+                    )?)
+                } else {
+                    NodeChild::Node(check_index(
+                        map,
+                        "GL_NODES",
+                        i,
+                        "child node",
+                        raw as usize,
+                        num_nodes,
+                    )?)
+                };
+            }
+            Ok(GlNode {
+                x: LittleEndian::read_i16(&r[0..]),
+                y: LittleEndian::read_i16(&r[2..]),
+                dx: LittleEndian::read_i16(&r[4..]),
+                dy: LittleEndian::read_i16(&r[6..]),
+                bbox,
+                children,
+            })
+        })
+        .collect()
+}
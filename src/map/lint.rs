@@ -0,0 +1,722 @@
+use super::{wad_name, Line, LineFlags, Map, MapError, MAP_LUMPS};
+use crate::{
+    configuration::{CompatibilityLevel, Configuration},
+    info::MOBJINFO,
+    logic::mobj::MobjFlag,
+    wad::{LumpNamespace, WadSource},
+};
+use byteorder::{ByteOrder, LittleEndian};
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde_derive::Serialize;
+use std::path::PathBuf;
+
+/// Specials that do what they are meant to with a zero tag, from prboom's
+/// `P_CheckTag`: manual doors, lights, thing teleporters, exits and the
+/// vanilla scrollers.
+const ZERO_TAG_OK: [i16; 47] = [
+    1, 26, 27, 28, 31, 32, 33, 34, 117, 118, 139, 170, 79, 35, 138, 171, 81, 13, 192, 169, 80, 12,
+    194, 173, 157, 104, 193, 172, 156, 17, 195, 174, 97, 39, 126, 125, 210, 209, 208, 207, 11, 52,
+    197, 51, 124, 198, 48,
+];
+
+/// Boom and MBF specials that take effect when the level starts rather
+/// than when a line is activated. A zero tag means "sectors tagged 0" for
+/// these, like any other tag.
+const SETUP_SPECIALS: [i16; 27] = [
+    85, 213, 214, 215, 216, 217, 218, 223, 224, 225, 226, 242, 245, 246, 247, 248, 249, 250, 251,
+    252, 253, 254, 255, 260, 261, 271, 272,
+];
+
+/// The first and last of Boom's generalized linedef types.
+const GENERALIZED: (i16, i16) = (0x2f80, 0x7fff);
+
+/// Everything wrong with one map.
+#[derive(Debug, Serialize)]
+pub struct MapReport {
+    pub map: String,
+    pub wad: PathBuf,
+    pub problems: Vec<Problem>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The map won't load, or will crash or misbehave in vanilla.
+    Error,
+    /// The map works, but probably not as the mapper meant.
+    Warning,
+}
+
+/// One problem, with whatever it was found on.
+#[derive(Debug, Serialize)]
+pub struct Problem {
+    pub kind: &'static str,
+    pub severity: Severity,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub side: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sector: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thing: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vertex: Option<usize>,
+}
+
+impl Problem {
+    fn new(kind: &'static str, severity: Severity, message: String) -> Self {
+        Self {
+            kind,
+            severity,
+            message,
+            line: None,
+            side: None,
+            sector: None,
+            thing: None,
+            vertex: None,
+        }
+    }
+}
+
+/// Check every map in the loaded PWADs. Maps from the IWAD are left out,
+/// since there is nothing a mapper can do about them.
+pub fn lint_maps(configuration: &Configuration) -> Vec<MapReport> {
+    let lumps = &configuration.lumps;
+    let mut names = vec![];
+    let mut seen = FxHashSet::default();
+    for lump in 0..lumps.len() {
+        let info = lumps.lump(lump);
+        let next = (lump + 1 < lumps.len()).then(|| lumps.lump(lump + 1).name_str());
+        if info.source != WadSource::Iwad
+            && matches!(next, Some("THINGS") | Some("TEXTMAP"))
+            && seen.insert(info.name_str())
+        {
+            names.push(info.name_str());
+        }
+    }
+
+    let resources = Resources::new(configuration);
+    names
+        .into_iter()
+        .map(|name| {
+            let marker = lumps.check_num_for_name(name).unwrap();
+            let mut problems = raw_side_sectors(configuration, name, marker);
+            match Map::load(configuration, name) {
+                Ok(map) => lint_map(configuration, &resources, &map, &mut problems),
+                // reported above, along with any other bad sidedefs
+                Err(MapError::BadIndex {
+                    lump: "SIDEDEFS",
+                    field: "sector",
+                    ..
+                }) => {}
+                Err(e) => problems.push(Problem::new("load_error", Severity::Error, e.to_string())),
+            }
+            MapReport {
+                map: name.to_string(),
+                wad: wad_name(configuration, marker),
+                problems,
+            }
+        })
+        .collect()
+}
+
+/// The textures, flats and things maps can refer to.
+struct Resources {
+    textures: FxHashSet<String>,
+    flats: FxHashSet<String>,
+    /// Radius in map units of every solid thing, by editor number.
+    solid_things: FxHashMap<i16, i32>,
+}
+
+impl Resources {
+    fn new(configuration: &Configuration) -> Self {
+        let lumps = &configuration.lumps;
+        let mut textures = FxHashSet::default();
+        for name in &["TEXTURE1", "TEXTURE2"] {
+            if let Some(data) = lumps
+                .check_num_for_name(name)
+                .and_then(|lump| lumps.read_lump(lump).ok())
+            {
+                textures.extend(texture_names(&data));
+            }
+        }
+        let flats = (0..lumps.len())
+            .map(|lump| lumps.lump(lump))
+            .filter(|info| info.namespace == LumpNamespace::Flats)
+            .map(|info| info.name_str().to_string())
+            .collect();
+
+        let mut solid_things = FxHashMap::default();
+        for info in MOBJINFO.read().values() {
+            let flags = MobjFlag::from_bits_truncate(info.flags);
+            if flags.contains(MobjFlag::SOLID) {
+                let radius = info.radius.to_int();
+                if info.doomednum > 0 {
+                    solid_things.insert(info.doomednum as i16, radius);
+                } else if info.doomednum == -1 && flags.contains(MobjFlag::PICKUP) {
+                    // the player, spawned from the player starts
+                    for &start in &[1, 2, 3, 4, 11] {
+                        solid_things.insert(start, radius);
+                    }
+                }
+            }
+        }
+        Self {
+            textures,
+            flats,
+            solid_things,
+        }
+    }
+}
+
+/// The names in a TEXTURE1 or TEXTURE2 lump. Anything cut short is left
+/// out rather than failing the whole lint.
+fn texture_names(data: &[u8]) -> Vec<String> {
+    if data.len() < 4 {
+        return vec![];
+    }
+    let count = LittleEndian::read_i32(data).max(0) as usize;
+    (0..count)
+        .filter_map(|i| {
+            let offset = LittleEndian::read_i32(data.get(4 + i * 4..8 + i * 4)?) as usize;
+            let name = data.get(offset..offset.checked_add(8)?)?;
+            let len = name.iter().position(|&c| c == 0).unwrap_or(8);
+            Some(String::from_utf8_lossy(&name[..len]).to_ascii_uppercase())
+        })
+        .collect()
+}
+
+/// Find every sidedef of a binary map that refers to a sector that isn't
+/// there. Loading the map stops at the first one.
+fn raw_side_sectors(configuration: &Configuration, name: &str, marker: usize) -> Vec<Problem> {
+    let lumps = &configuration.lumps;
+    let find = |lump_name: &str| {
+        (marker + 1..lumps.len())
+            .take_while(|&lump| MAP_LUMPS.contains(&lumps.lump(lump).name_str()))
+            .find(|&lump| lumps.lump(lump).name_str() == lump_name)
+    };
+    let (sides, sectors) = match (find("SIDEDEFS"), find("SECTORS")) {
+        (Some(sides), Some(sectors)) => (sides, sectors),
+        _ => return vec![],
+    };
+    let num_sectors = lumps.lump_length(sectors) / 26;
+    let data = match lumps.read_lump(sides) {
+        Ok(data) => data,
+        Err(_) => return vec![],
+    };
+    data.chunks_exact(30)
+        .enumerate()
+        .filter_map(|(i, r)| {
+            let sector = LittleEndian::read_u16(&r[28..]) as usize;
+            (sector >= num_sectors).then(|| Problem {
+                side: Some(i),
+                sector: Some(sector),
+                ..Problem::new(
+                    "invalid_side_sector",
+                    Severity::Error,
+                    format!(
+                        "{}: sidedef {} is in sector {}, but there are only {}",
+                        name, i, sector, num_sectors
+                    ),
+                )
+            })
+        })
+        .collect()
+}
+
+fn lint_map(
+    configuration: &Configuration,
+    resources: &Resources,
+    map: &Map,
+    problems: &mut Vec<Problem>,
+) {
+    missing_textures(resources, map, problems);
+    unclosed_sectors(map, problems);
+    zero_tags(configuration, map, problems);
+    stuck_things(resources, map, problems);
+    unsupported_specials(configuration, map, problems);
+}
+
+fn missing_textures(resources: &Resources, map: &Map, problems: &mut Vec<Problem>) {
+    for (i, line) in map.lines.iter().enumerate() {
+        // Boom reads colormap and translucency lump names from these
+        if line.special == 242 || line.special == 260 {
+            continue;
+        }
+        for &side in line.sides.iter().flatten() {
+            let s = &map.sides[side];
+            for (part, texture) in &[
+                ("upper", &s.top_texture),
+                ("lower", &s.bottom_texture),
+                ("middle", &s.mid_texture),
+            ] {
+                if texture.starts_with('-') || resources.textures.contains(texture.as_str()) {
+                    continue;
+                }
+                problems.push(Problem {
+                    line: Some(i),
+                    side: Some(side),
+                    ..Problem::new(
+                        "missing_texture",
+                        Severity::Error,
+                        format!(
+                            "{}: sidedef {} of line {} has a missing {} texture {:?}",
+                            map.name, side, i, part, texture
+                        ),
+                    )
+                });
+            }
+        }
+    }
+    for (i, sector) in map.sectors.iter().enumerate() {
+        for (part, flat) in &[
+            ("floor", &sector.floor_pic),
+            ("ceiling", &sector.ceiling_pic),
+        ] {
+            if resources.flats.contains(flat.as_str()) {
+                continue;
+            }
+            problems.push(Problem {
+                sector: Some(i),
+                ..Problem::new(
+                    "missing_flat",
+                    Severity::Error,
+                    format!(
+                        "{}: sector {} has a missing {} flat {:?}",
+                        map.name, i, part, flat
+                    ),
+                )
+            });
+        }
+    }
+}
+
+/// A closed sector's lines meet in pairs at each of its vertices. Lines
+/// with the sector on both sides don't count, since they add one of each.
+fn unclosed_sectors(map: &Map, problems: &mut Vec<Problem>) {
+    for (i, sector) in map.sectors.iter().enumerate() {
+        let mut ends = FxHashMap::default();
+        for &line in &sector.lines {
+            let line = &map.lines[line];
+            if line.front_sector == line.back_sector {
+                continue;
+            }
+            for &v in &[line.v1, line.v2] {
+                *ends.entry(v).or_insert(0) += 1;
+            }
+        }
+        let mut open: Vec<usize> = ends
+            .into_iter()
+            .filter(|&(_, count)| count % 2 == 1)
+            .map(|(v, _)| v)
+            .collect();
+        open.sort_unstable();
+        if let Some(&v) = open.first() {
+            let vertex = map.vertices[v];
+            problems.push(Problem {
+                sector: Some(i),
+                vertex: Some(v),
+                ..Problem::new(
+                    "unclosed_sector",
+                    Severity::Error,
+                    format!(
+                        "{}: sector {} is not closed at {} vertices, the first is vertex {} at ({}, {})",
+                        map.name,
+                        i,
+                        open.len(),
+                        v,
+                        vertex.x.to_int(),
+                        vertex.y.to_int()
+                    ),
+                )
+            });
+        }
+    }
+}
+
+/// Whether a line special acts on tagged sectors when it is activated.
+fn needs_tag(special: i16) -> bool {
+    if special >= GENERALIZED.0 {
+        // the manual triggers, D1 and DR, act on the sector behind the line
+        return special & 7 < 6;
+    }
+    special_level(special).is_some()
+        && special != 0
+        && !ZERO_TAG_OK.contains(&special)
+        && !SETUP_SPECIALS.contains(&special)
+}
+
+/// Tagged specials with a zero tag do nothing in Boom, but act on every
+/// sector with tag 0 with `comp_zerotags`, or `comperr_zerotag` to allow it
+/// at any level.
+fn zero_tags(configuration: &Configuration, map: &Map, problems: &mut Vec<Problem>) {
+    let vanilla = configuration.comp_zerotags || configuration.defaults.comperr_zerotag;
+    for (i, line) in map.lines.iter().enumerate() {
+        if line.tag != 0 || !needs_tag(line.special) {
+            continue;
+        }
+        let (severity, effect) = if vanilla {
+            (Severity::Warning, "acts on every sector with tag 0")
+        } else {
+            (Severity::Error, "does nothing")
+        };
+        problems.push(Problem {
+            line: Some(i),
+            ..Problem::new(
+                "zero_tag",
+                severity,
+                format!(
+                    "{}: line {} has special {} with tag 0, which {}",
+                    map.name, i, line.special, effect
+                ),
+            )
+        });
+    }
+}
+
+/// Whether a line crosses the box, touching doesn't count.
+fn crosses_box(map: &Map, line: &Line, [left, bottom, right, top]: [f64; 4]) -> bool {
+    let to_units = |v: usize| {
+        let v = map.vertices[v];
+        (v.x.to_f64(), v.y.to_f64())
+    };
+    let (x1, y1) = to_units(line.v1);
+    let (x2, y2) = to_units(line.v2);
+    if x1.max(x2) <= left || x1.min(x2) >= right || y1.max(y2) <= bottom || y1.min(y2) >= top {
+        return false;
+    }
+    let side = |x: f64, y: f64| (x - x1) * (y2 - y1) - (y - y1) * (x2 - x1);
+    let corners = [
+        side(left, bottom),
+        side(left, top),
+        side(right, bottom),
+        side(right, top),
+    ];
+    corners.iter().any(|&s| s > 0.0) && corners.iter().any(|&s| s < 0.0)
+}
+
+/// Solid things overlapping a wall can't move, and monsters stuck like
+/// that never wake up properly.
+fn stuck_things(resources: &Resources, map: &Map, problems: &mut Vec<Problem>) {
+    for (i, thing) in map.things.iter().enumerate() {
+        let radius = match resources.solid_things.get(&thing.kind) {
+            Some(&radius) => radius as f64,
+            None => continue,
+        };
+        let (x, y) = (thing.x as f64, thing.y as f64);
+        let bbox = [x - radius, y - radius, x + radius, y + radius];
+        let wall = map.lines.iter().position(|line| {
+            (line.back_sector.is_none() || line.flags.contains(LineFlags::BLOCKING))
+                && crosses_box(map, line, bbox)
+        });
+        if let Some(wall) = wall {
+            problems.push(Problem {
+                thing: Some(i),
+                line: Some(wall),
+                ..Problem::new(
+                    "stuck_thing",
+                    Severity::Warning,
+                    format!(
+                        "{}: thing {} (type {}) at ({}, {}) is stuck in line {}",
+                        map.name, i, thing.kind, thing.x, thing.y, wall
+                    ),
+                )
+            });
+        }
+    }
+}
+
+/// The first compatibility level that knows a line special, or `None` if
+/// none do.
+fn special_level(special: i16) -> Option<CompatibilityLevel> {
+    match special {
+        0 => Some(CompatibilityLevel::DoomV12),
+        78 | 85 => Some(CompatibilityLevel::Boom),
+        1..=141 => Some(CompatibilityLevel::DoomV12),
+        142..=269 => Some(CompatibilityLevel::Boom),
+        271 | 272 => Some(CompatibilityLevel::Mbf),
+        s if s >= GENERALIZED.0 && s <= GENERALIZED.1 => Some(CompatibilityLevel::Boom),
+        _ => None,
+    }
+}
+
+/// The first compatibility level that knows a sector special. The low 5
+/// bits are the vanilla types; Boom adds damage, secret, friction and
+/// pusher bits above them.
+fn sector_special_level(special: i16) -> Option<CompatibilityLevel> {
+    let base = special & 31;
+    if !(0..1024).contains(&special) || matches!(base, 6 | 15 | 18..=31) {
+        None
+    } else if special >= 32 {
+        Some(CompatibilityLevel::Boom)
+    } else {
+        Some(CompatibilityLevel::DoomV12)
+    }
+}
+
+fn unsupported_specials(configuration: &Configuration, map: &Map, problems: &mut Vec<Problem>) {
+    let level = configuration.compatibility_level;
+    let describe = |needed: Option<CompatibilityLevel>| match needed {
+        Some(needed) => format!("needs compatibility level {:?} or later", needed),
+        None => "is not known at any compatibility level".to_string(),
+    };
+    for (i, line) in map.lines.iter().enumerate() {
+        let needed = special_level(line.special);
+        if needed.is_some_and(|needed| needed <= level) {
+            continue;
+        }
+        problems.push(Problem {
+            line: Some(i),
+            ..Problem::new(
+                "unsupported_special",
+                Severity::Error,
+                format!(
+                    "{}: line {} has special {}, which {}",
+                    map.name,
+                    i,
+                    line.special,
+                    describe(needed)
+                ),
+            )
+        });
+    }
+    for (i, sector) in map.sectors.iter().enumerate() {
+        let needed = sector_special_level(sector.special);
+        if needed.is_some_and(|needed| needed <= level) {
+            continue;
+        }
+        problems.push(Problem {
+            sector: Some(i),
+            ..Problem::new(
+                "unsupported_special",
+                Severity::Error,
+                format!(
+                    "{}: sector {} has special {}, which {}",
+                    map.name,
+                    i,
+                    sector.special,
+                    describe(needed)
+                ),
+            )
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        info::init_mobjinfo,
+        map::tests::{name8, square_room},
+        wad::{test_path, LumpDirectory, WadBuilder, WadFileInfo},
+    };
+
+    /// Overwrite the 16 bit word at `offset` in lump `name`.
+    fn patch(builder: &mut WadBuilder, name: &str, offset: usize, value: i16) {
+        let lump = builder.position(name).unwrap();
+        let mut data = builder.lumps()[lump].data.clone();
+        data[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+        builder.replace(name, data).unwrap();
+    }
+
+    /// Add the texture and flats the square room uses.
+    fn add_resources(builder: &mut WadBuilder) {
+        let mut texture1 = vec![1, 0, 0, 0, 8, 0, 0, 0];
+        texture1.extend(name8("STARTAN3"));
+        texture1.resize(8 + 22, 0);
+        builder
+            .push("TEXTURE1", texture1)
+            .and_then(|b| b.push("F_START", vec![]))
+            .and_then(|b| b.push("FLOOR4_8", vec![0; 4096]))
+            .and_then(|b| b.push("CEIL3_5", vec![0; 4096]))
+            .and_then(|b| b.push("F_END", vec![]))
+            .unwrap();
+    }
+
+    /// Lint the maps in `builder`, loaded from a file of type `src`.
+    fn lint(
+        prefix: &str,
+        builder: &WadBuilder,
+        src: WadSource,
+        configure: impl FnOnce(&mut Configuration),
+    ) -> Vec<MapReport> {
+        init_mobjinfo();
+        let path = test_path(&format!("{}.wad", prefix));
+        builder.write_to_file(&path).unwrap();
+        let mut configuration = Configuration::default();
+        configuration.wad_files = vec![WadFileInfo {
+            name: path.clone(),
+            src,
+            handle: 0,
+        }];
+        configuration.lumps = LumpDirectory::open(&mut configuration.wad_files).unwrap();
+        configure(&mut configuration);
+        let reports = lint_maps(&configuration);
+        std::fs::remove_file(path).unwrap();
+        reports
+    }
+
+    /// The kinds and severities of the problems found in the square room
+    /// after `edit`.
+    fn problems(
+        prefix: &str,
+        edit: impl FnOnce(&mut WadBuilder),
+        configure: impl FnOnce(&mut Configuration),
+    ) -> Vec<(&'static str, Severity)> {
+        let mut builder = square_room();
+        add_resources(&mut builder);
+        edit(&mut builder);
+        let reports = lint(prefix, &builder, WadSource::Pwad, configure);
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].map, "MAP01");
+        reports[0]
+            .problems
+            .iter()
+            .map(|p| (p.kind, p.severity))
+            .collect()
+    }
+
+    #[test]
+    fn clean_map() {
+        assert_eq!(problems("lint-clean", |_| {}, |_| {}), []);
+    }
+
+    #[test]
+    fn iwad_maps_are_skipped() {
+        let mut builder = square_room();
+        builder.kind = crate::wad::WadKind::Iwad;
+        assert!(lint("lint-iwad", &builder, WadSource::Iwad, |_| {}).is_empty());
+    }
+
+    #[test]
+    fn invalid_side_sector() {
+        let mut builder = square_room();
+        add_resources(&mut builder);
+        patch(&mut builder, "SIDEDEFS", 30 + 28, 5);
+        patch(&mut builder, "SIDEDEFS", 90 + 28, 6);
+        let reports = lint("lint-side-sector", &builder, WadSource::Pwad, |_| {});
+        let problems = &reports[0].problems;
+        // every bad sidedef, and no load error on top
+        assert_eq!(problems.len(), 2);
+        assert_eq!(problems[0].kind, "invalid_side_sector");
+        assert_eq!((problems[0].side, problems[0].sector), (Some(1), Some(5)));
+        assert_eq!((problems[1].side, problems[1].sector), (Some(3), Some(6)));
+    }
+
+    #[test]
+    fn load_error() {
+        assert_eq!(
+            problems(
+                "lint-load-error",
+                |b| {
+                    b.replace("THINGS", vec![0; 11]).unwrap();
+                },
+                |_| {}
+            ),
+            [("load_error", Severity::Error)]
+        );
+    }
+
+    #[test]
+    fn missing_texture() {
+        let mut builder = square_room();
+        add_resources(&mut builder);
+        let sides = builder.position("SIDEDEFS").unwrap();
+        let mut data = builder.lumps()[sides].data.clone();
+        data[4..12].copy_from_slice(b"STARTAN9");
+        builder.replace("SIDEDEFS", data).unwrap();
+        let reports = lint("lint-texture", &builder, WadSource::Pwad, |_| {});
+        let problems = &reports[0].problems;
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].kind, "missing_texture");
+        assert_eq!((problems[0].line, problems[0].side), (Some(0), Some(0)));
+        assert!(problems[0].message.contains("upper texture \"STARTAN9\""));
+    }
+
+    #[test]
+    fn missing_flat() {
+        assert_eq!(
+            problems(
+                "lint-flat",
+                |b| {
+                    b.remove(b.position("CEIL3_5").unwrap());
+                },
+                |_| {}
+            ),
+            [("missing_flat", Severity::Error)]
+        );
+    }
+
+    #[test]
+    fn unclosed_sector() {
+        // the last line goes back to vertex 1 instead of 0
+        assert_eq!(
+            problems(
+                "lint-unclosed",
+                |b| patch(b, "LINEDEFS", 3 * 14 + 2, 1),
+                |_| {}
+            ),
+            [("unclosed_sector", Severity::Error)]
+        );
+    }
+
+    #[test]
+    fn zero_tag() {
+        // S1 lower floor, with no tag
+        let edit = |b: &mut WadBuilder| patch(b, "LINEDEFS", 6, 23);
+        assert_eq!(
+            problems("lint-zero-tag", edit, |_| {}),
+            [("zero_tag", Severity::Error)]
+        );
+        assert_eq!(
+            problems("lint-zero-tag-comp", edit, |configuration| {
+                configuration.comp_zerotags = true
+            }),
+            [("zero_tag", Severity::Warning)]
+        );
+        // manual doors act on the sector behind the line
+        assert_eq!(
+            problems("lint-zero-tag-door", |b| patch(b, "LINEDEFS", 6, 1), |_| {}),
+            []
+        );
+    }
+
+    #[test]
+    fn stuck_thing() {
+        // an imp 10 units from the west wall, with a radius of 20
+        let edit = |b: &mut WadBuilder| {
+            patch(b, "THINGS", 0, 10);
+            patch(b, "THINGS", 6, 3001);
+        };
+        assert_eq!(
+            problems("lint-stuck", edit, |_| {}),
+            [("stuck_thing", Severity::Warning)]
+        );
+        // touching the wall is fine
+        let edit = |b: &mut WadBuilder| {
+            patch(b, "THINGS", 0, 20);
+            patch(b, "THINGS", 6, 3001);
+        };
+        assert_eq!(problems("lint-touching", edit, |_| {}), []);
+    }
+
+    #[test]
+    fn unsupported_special() {
+        let vanilla = |configuration: &mut Configuration| {
+            configuration.compatibility_level = CompatibilityLevel::Doom2V19
+        };
+        // a Boom line special, a Boom sector special, and a line special
+        // nothing knows
+        let edit = |b: &mut WadBuilder| {
+            patch(b, "LINEDEFS", 6, 195);
+            patch(b, "LINEDEFS", 14 + 6, 500);
+            patch(b, "SECTORS", 22, 64 + 9);
+        };
+        let found = problems("lint-special", edit, vanilla);
+        assert_eq!(found, [("unsupported_special", Severity::Error); 3]);
+        let found = problems("lint-special-boom", edit, |_| {});
+        assert_eq!(found, [("unsupported_special", Severity::Error); 1]);
+    }
+}
@@ -0,0 +1,919 @@
+use crate::{
+    configuration::{CompatibilityLevel, Configuration},
+    doom::def::GameMode,
+    lprint,
+    misc::lprint::OutputLevel,
+};
+use std::{path::PathBuf, slice::ChunksExact};
+
+/// Contains the parsers for the binary lumps of vanilla format maps.
+mod binary;
+/// Contains the builder for maps without a usable BLOCKMAP lump.
+mod blockmap;
+/// Contains the node builder for maps saved without nodes, and the cache
+/// it keeps next to their WADs.
+mod builder;
+/// Contains the in-memory map structures. Positions, heights and offsets
+/// are `Fixed`.
+mod data;
+/// Contains the error type for maps that can't be loaded.
+mod error;
+/// Contains the GL node formats written by glBSP and friends.
+mod glnodes;
+/// Contains the checks behind `-lintmap`.
+mod lint;
+/// Contains the vanilla and extended formats of SEGS, SSECTORS and NODES.
+mod nodes;
+/// Contains the parser for UDMF's TEXTMAP lump.
+mod udmf;
+
+#[allow(unused_imports)]
+pub use data::{
+    Blockmap, Line, LineFlags, Node, NodeChild, Sector, Seg, Side, Subsector, Thing, ThingFlags,
+    Vertex,
+};
+pub use error::MapError;
+#[allow(unused_imports)]
+pub use glnodes::{GlNode, GlNodes, GlNodesVersion, GlSeg, GlSubsector, GlVertex, VertexRef};
+#[allow(unused_imports)]
+pub use lint::{lint_maps, MapReport, Problem, Severity};
+use nodes::Bsp;
+pub use nodes::NodeFormat;
+
+/// The lumps that make up a vanilla format map, in the order they follow
+/// the map marker.
+const MAP_LUMPS: [&str; 10] = [
+    "THINGS", "LINEDEFS", "SIDEDEFS", "VERTEXES", "SEGS", "SSECTORS", "NODES", "SECTORS", "REJECT",
+    "BLOCKMAP",
+];
+
+/// The name of the marker lump for a map, `MAPxx` in Doom II and `ExMy`
+/// otherwise.
+pub fn map_name(game_mode: GameMode, episode: usize, map: usize) -> String {
+    if game_mode == GameMode::Commercial {
+        format!("MAP{:02}", map)
+    } else {
+        format!("E{}M{}", episode, map)
+    }
+}
+
+/// A fully loaded level. Every index in it has been checked, so code using
+/// it can index the vectors without worrying about bad maps.
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct Map {
+    pub name: String,
+    /// The UDMF namespace, or `None` for binary maps.
+    pub namespace: Option<String>,
+    pub things: Vec<Thing>,
+    pub vertices: Vec<Vertex>,
+    pub sectors: Vec<Sector>,
+    pub sides: Vec<Side>,
+    pub lines: Vec<Line>,
+    pub segs: Vec<Seg>,
+    pub subsectors: Vec<Subsector>,
+    pub nodes: Vec<Node>,
+    /// The format SEGS, SSECTORS and NODES were stored in.
+    pub node_format: NodeFormat,
+    /// One bit per pair of sectors, set if the first can't see the second.
+    /// Always long enough for every pair.
+    pub reject: Vec<u8>,
+    pub blockmap: Blockmap,
+    /// GL nodes from a GWA file or the WAD itself, if there are any.
+    pub gl_nodes: Option<GlNodes>,
+}
+
+impl Map {
+    /// Load the map with the marker lump `name`. The newest map of that name
+    /// wins, like any other lump. Maps with a TEXTMAP lump after the marker
+    /// are UDMF, anything else is the vanilla binary format.
+    pub fn load(configuration: &Configuration, name: &str) -> Result<Self, MapError> {
+        let lumps = &configuration.lumps;
+        let marker = lumps
+            .check_num_for_name(name)
+            .ok_or_else(|| MapError::MapNotFound(name.to_string()))?;
+        let next = (marker + 1 < lumps.len()).then(|| lumps.lump(marker + 1).name_str());
+        let mut map = match next {
+            Some("TEXTMAP") => Self::load_udmf(configuration, name, marker)?,
+            Some("THINGS") => {
+                // Hexen maps have the same lumps as Doom maps, with different
+                // records, and a BEHAVIOR lump after them for the scripts
+                let hexen = (marker + 1..lumps.len())
+                    .map(|lump| lumps.lump(lump).name_str())
+                    .take_while(|&lump| MAP_LUMPS.contains(&lump) || lump == "BEHAVIOR")
+                    .any(|lump| lump == "BEHAVIOR");
+                if hexen {
+                    return Err(MapError::HexenFormat {
+                        map: name.to_string(),
+                        wad: wad_name(configuration, marker),
+                    });
+                }
+                Self::load_binary(configuration, name, marker)?
+            }
+            next => {
+                return Err(MapError::UnknownFormat {
+                    map: name.to_string(),
+                    wad: wad_name(configuration, marker),
+                    next: next.map(str::to_string),
+                })
+            }
+        };
+
+        for (i, line) in map.lines.iter().enumerate() {
+            if let Some(sector) = line.front_sector {
+                map.sectors[sector].lines.push(i);
+            }
+            // a line with the same sector on both sides is only listed once
+            if let Some(sector) = line.back_sector.filter(|&s| Some(s) != line.front_sector) {
+                map.sectors[sector].lines.push(i);
+            }
+        }
+        map.pad_reject(configuration);
+        Ok(map)
+    }
+
+    /// Pad a REJECT lump that is too short for the number of sectors, as
+    /// prboom does. Vanilla read whatever followed the lump in memory: the
+    /// header of the next zone block, which held the sector line lists.
+    /// With `overrun_reject_emulate` set, those bytes are reproduced for
+    /// the vanilla compatibility levels so that demos stay in sync; the
+    /// rest of the padding is zeros.
+    fn pad_reject(&mut self, configuration: &Configuration) {
+        let num_sectors = self.sectors.len();
+        let required = (num_sectors * num_sectors).div_ceil(8);
+        let length = self.reject.len();
+        if length >= required {
+            return;
+        }
+        self.reject.resize(required, 0);
+
+        let defaults = &configuration.defaults;
+        if configuration.compatibility_level >= CompatibilityLevel::Boom {
+            return;
+        }
+        if defaults.overrun_reject_warn {
+            lprint!(
+                OutputLevel::WARN,
+                "map::load: {}: REJECT overflow: the lump is {} bytes, but {} sectors need {}.\n",
+                self.name,
+                length,
+                num_sectors,
+                required
+            );
+        }
+        if defaults.overrun_reject_emulate {
+            let total_lines: usize = self.sectors.iter().map(|s| s.lines.len()).sum();
+            let header: [u32; 4] = [
+                // the size of the block, which holds a pointer per line
+                ((total_lines as u32 * 4 + 3) & !3) + 24,
+                // the user pointer
+                0,
+                // PU_LEVEL
+                50,
+                // ZONEID
+                0x1d4a11,
+            ];
+            let bytes = header.iter().flat_map(|word| word.to_le_bytes());
+            for (pad, byte) in self.reject[length..].iter_mut().zip(bytes) {
+                *pad = byte;
+            }
+        }
+    }
+
+    /// Load a map in the vanilla binary format.
+    ///
+    /// Extended node formats are detected from their signatures, unless
+    /// `force_old_bsp` is set (`-forceoldbsp`); then only vanilla nodes are
+    /// read and GL nodes are ignored. Maps saved without SEGS, SSECTORS or
+    /// NODES get nodes from the built-in builder. REJECT and BLOCKMAP may
+    /// be missing too.
+    fn load_binary(
+        configuration: &Configuration,
+        name: &str,
+        marker: usize,
+    ) -> Result<Self, MapError> {
+        let lumps = &configuration.lumps;
+        let map_lumps: Vec<usize> = (marker + 1..lumps.len())
+            .take_while(|&lump| MAP_LUMPS.contains(&lumps.lump(lump).name_str()))
+            .collect();
+        let read = |lump_name: &str| {
+            map_lumps
+                .iter()
+                .find(|&&lump| lumps.lump(lump).name_str() == lump_name)
+                .map(|&lump| lumps.read_lump(lump))
+                .transpose()
+        };
+        let require = |lump_name: &'static str| {
+            read(lump_name)?.ok_or_else(|| MapError::MissingLump {
+                map: name.to_string(),
+                lump: lump_name,
+            })
+        };
+
+        let things = binary::parse_things(name, &require("THINGS")?)?;
+        let mut vertices = binary::parse_vertices(name, &require("VERTEXES")?)?;
+        let sectors = binary::parse_sectors(name, &require("SECTORS")?)?;
+        let sides = binary::parse_sides(name, &require("SIDEDEFS")?, sectors.len())?;
+        let lines = binary::parse_lines(name, &require("LINEDEFS")?, &vertices, &sides)?;
+
+        let segs = read("SEGS")?.unwrap_or_default();
+        let ssectors = read("SSECTORS")?.unwrap_or_default();
+        let nodes = read("NODES")?;
+        let (node_format, compressed) = if configuration.force_old_bsp {
+            (NodeFormat::Vanilla, false)
+        } else {
+            NodeFormat::detect(&ssectors, nodes.as_deref().unwrap_or_default())
+        };
+        let missing = match node_format {
+            // a map that is a single subsector has no nodes, but the lump
+            // is still there
+            NodeFormat::Vanilla | NodeFormat::DeePBsp => {
+                segs.is_empty() || ssectors.is_empty() || nodes.is_none()
+            }
+            NodeFormat::Xnod => nodes.is_none(),
+            _ => ssectors.is_empty(),
+        };
+        let (node_format, bsp) = if missing {
+            build_nodes(configuration, name, marker, &mut vertices, &lines, &sides)?
+        } else {
+            let bsp = nodes::load(
+                name,
+                node_format,
+                compressed,
+                &segs,
+                &ssectors,
+                &nodes.unwrap_or_default(),
+                &mut vertices,
+                &lines,
+                &sides,
+            )?;
+            (node_format, bsp)
+        };
+        let blockmap = load_blockmap(
+            configuration,
+            name,
+            read("BLOCKMAP")?.as_deref(),
+            &vertices,
+            &lines,
+        );
+
+        let gl_nodes = if configuration.force_old_bsp {
+            None
+        } else {
+            GlNodes::find(lumps, name, marker)?
+        };
+        if let Some(gl_nodes) = &gl_nodes {
+            gl_nodes.check_map_refs(name, vertices.len(), lines.len())?;
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            namespace: None,
+            things,
+            vertices,
+            sectors,
+            sides,
+            lines,
+            segs: bsp.segs,
+            subsectors: bsp.subsectors,
+            nodes: bsp.nodes,
+            node_format,
+            reject: read("REJECT")?.unwrap_or_default(),
+            blockmap,
+            gl_nodes,
+        })
+    }
+
+    /// Load a UDMF map: a TEXTMAP lump followed by any other lumps up to
+    /// ENDMAP. The BSP tree has to be in a ZNODES lump in one of ZDBSP's
+    /// formats, or is built if there is none.
+    fn load_udmf(
+        configuration: &Configuration,
+        name: &str,
+        marker: usize,
+    ) -> Result<Self, MapError> {
+        let lumps = &configuration.lumps;
+        let end = (marker + 1..lumps.len())
+            .find(|&lump| lumps.lump(lump).name_str() == "ENDMAP")
+            .ok_or_else(|| MapError::MissingLump {
+                map: name.to_string(),
+                lump: "ENDMAP",
+            })?;
+        let find = |lump_name: &str| {
+            (marker + 1..end).find(|&lump| lumps.lump(lump).name_str() == lump_name)
+        };
+        let text_map = udmf::parse(name, &lumps.read_lump(marker + 1)?)?;
+        let mut vertices = text_map.vertices;
+        let lines = text_map.lines;
+        let sides = text_map.sides;
+
+        let znodes = find("ZNODES")
+            .map(|lump| lumps.read_lump(lump))
+            .transpose()?
+            .unwrap_or_default();
+        let (node_format, bsp) = if znodes.is_empty() {
+            build_nodes(configuration, name, marker, &mut vertices, &lines, &sides)?
+        } else {
+            let (node_format, compressed) = match NodeFormat::detect(&znodes, &znodes) {
+                (NodeFormat::Vanilla, _) | (NodeFormat::DeePBsp, _) => {
+                    return Err(MapError::UnsupportedFormat {
+                        map: name.to_string(),
+                        format: "ZNODES lump without a ZDBSP signature".to_string(),
+                    })
+                }
+                format => format,
+            };
+            let bsp = nodes::load_zdbsp(
+                name,
+                "ZNODES",
+                node_format,
+                compressed,
+                &znodes,
+                &mut vertices,
+                &lines,
+                &sides,
+            )?;
+            (node_format, bsp)
+        };
+
+        let blockmap_lump = find("BLOCKMAP")
+            .map(|lump| lumps.read_lump(lump))
+            .transpose()?;
+        let blockmap = load_blockmap(
+            configuration,
+            name,
+            blockmap_lump.as_deref(),
+            &vertices,
+            &lines,
+        );
+        let reject = match find("REJECT") {
+            Some(lump) => lumps.read_lump(lump)?,
+            None => vec![],
+        };
+
+        Ok(Self {
+            name: name.to_string(),
+            namespace: Some(text_map.namespace),
+            things: text_map.things,
+            vertices,
+            sectors: text_map.sectors,
+            sides,
+            lines,
+            segs: bsp.segs,
+            subsectors: bsp.subsectors,
+            nodes: bsp.nodes,
+            node_format,
+            reject,
+            blockmap,
+            gl_nodes: None,
+        })
+    }
+}
+
+/// Build the nodes for a map saved without them, or read them from the
+/// cache next to its WAD if `cache_nodes` is set (`-cachenodes`) and they
+/// were built for the same lines. GL nodes are built if `build_gl_nodes` is
+/// set (`-buildglnodes`).
+fn build_nodes(
+    configuration: &Configuration,
+    name: &str,
+    marker: usize,
+    vertices: &mut Vec<Vertex>,
+    lines: &[Line],
+    sides: &[Side],
+) -> Result<(NodeFormat, Bsp), MapError> {
+    let gl = configuration.build_gl_nodes;
+    let cache = {
+        let mut path = wad_name(configuration, marker).into_os_string();
+        path.push(".nodes");
+        PathBuf::from(path)
+    };
+    let key = builder::cache_key(vertices, lines, gl);
+    let cached = if configuration.cache_nodes {
+        builder::read_cache(&cache, name, &key)
+    } else {
+        None
+    };
+
+    let data = match cached {
+        Some(data) => {
+            lprint!(
+                OutputLevel::INFO,
+                "map::load: {}: using cached nodes from {}.\n",
+                name,
+                cache.display()
+            );
+            data
+        }
+        None => {
+            lprint!(OutputLevel::INFO, "map::load: {}: building nodes.\n", name);
+            let (_, data) = builder::build(name, vertices, lines, gl)?;
+            if configuration.cache_nodes {
+                if let Err(e) = builder::write_cache(&cache, name, &key, &data) {
+                    lprint!(
+                        OutputLevel::WARN,
+                        "map::load: {}: can't cache nodes: {}\n",
+                        name,
+                        e
+                    );
+                }
+            }
+            data
+        }
+    };
+    let (node_format, compressed) = NodeFormat::detect(&data, &data);
+    let bsp = nodes::load_zdbsp(
+        name,
+        "ZNODES",
+        node_format,
+        compressed,
+        &data,
+        vertices,
+        lines,
+        sides,
+    )?;
+    Ok((node_format, bsp))
+}
+
+/// Parse the BLOCKMAP lump, or build a blockmap from the lines if it is
+/// missing or unusable, or if `force_blockmap` is set (`-blockmap`).
+///
+/// Offsets in the lump are 16 bits, so a lump of 64k words or more can't
+/// point at all of its lists; maps that big always get a new blockmap.
+fn load_blockmap(
+    configuration: &Configuration,
+    name: &str,
+    data: Option<&[u8]>,
+    vertices: &[Vertex],
+    lines: &[Line],
+) -> Blockmap {
+    if configuration.force_blockmap {
+        return blockmap::build(vertices, lines);
+    }
+    let problem = match data {
+        None => "there is no BLOCKMAP lump".to_string(),
+        Some(data) if data.len() < 8 => "the BLOCKMAP lump is too small".to_string(),
+        Some(data) if data.len() / 2 >= 0x10000 => {
+            "the BLOCKMAP lump is too big for 16 bit offsets".to_string()
+        }
+        Some(data) => match binary::parse_blockmap(name, data, lines.len()) {
+            Ok(mut blockmap) => {
+                tolerate_missing_zeros(configuration, name, &mut blockmap);
+                return blockmap;
+            }
+            Err(e) => e.to_string(),
+        },
+    };
+    lprint!(
+        OutputLevel::WARN,
+        "map::load: {}: rebuilding the blockmap, because {}.\n",
+        name,
+        problem
+    );
+    blockmap::build(vertices, lines)
+}
+
+/// Boom skips the first line of every block list, taking it for the 0 that
+/// node builders start them with. Some builders leave the 0 out, and then
+/// Boom misses a real line in every block. With `comperr_blockmap` set,
+/// such blockmaps get the 0s put back, as Eternity does when not every
+/// list starts with one; vanilla compatibility levels read every entry
+/// anyway, so they are left alone.
+fn tolerate_missing_zeros(configuration: &Configuration, name: &str, blockmap: &mut Blockmap) {
+    if !configuration.defaults.comperr_blockmap
+        || configuration.compatibility_level < CompatibilityLevel::Boom
+        || blockmap.blocks.iter().all(|list| list.first() == Some(&0))
+    {
+        return;
+    }
+    lprint!(
+        OutputLevel::INFO,
+        "map::load: {}: the BLOCKMAP lists don't start with 0, so none are skipped.\n",
+        name
+    );
+    for list in &mut blockmap.blocks {
+        list.insert(0, 0);
+    }
+}
+
+/// The file a lump was loaded from.
+fn wad_name(configuration: &Configuration, lump: usize) -> PathBuf {
+    let wadfile = configuration.lumps.lump(lump).wadfile;
+    configuration.wad_files[wadfile].name.clone()
+}
+
+/// Split a lump into fixed size records, failing if there is anything left
+/// over.
+fn records<'a>(
+    map: &str,
+    lump: &'static str,
+    data: &'a [u8],
+    record: usize,
+) -> Result<ChunksExact<'a, u8>, MapError> {
+    if !data.len().is_multiple_of(record) {
+        return Err(MapError::BadLumpSize {
+            map: map.to_string(),
+            lump,
+            size: data.len(),
+            record,
+        });
+    }
+    Ok(data.chunks_exact(record))
+}
+
+/// Check that `value` is a valid index into something with `limit` entries.
+fn check_index(
+    map: &str,
+    lump: &'static str,
+    record: usize,
+    field: &'static str,
+    value: usize,
+    limit: usize,
+) -> Result<usize, MapError> {
+    if value >= limit {
+        return Err(MapError::BadIndex {
+            map: map.to_string(),
+            lump,
+            record,
+            field,
+            value,
+            limit,
+        });
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wad::{test_path, LumpDirectory, WadBuilder, WadFileInfo, WadKind, WadSource};
+    use crate::{misc::fixed::Fixed, tables::Angle};
+
+    /// Little endian 16 bit words, as most map lumps are made of.
+    pub fn words(values: &[i32]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|&v| (v as i16).to_le_bytes())
+            .collect()
+    }
+
+    /// A name field, NUL padded to 8 bytes.
+    pub fn name8(name: &str) -> Vec<u8> {
+        let mut data = name.as_bytes().to_vec();
+        data.resize(8, 0);
+        data
+    }
+
+    /// MAP01, a 256 unit square room: one sector, four one-sided lines
+    /// running clockwise, a single subsector (so NODES is empty) and a one
+    /// block BLOCKMAP.
+    pub fn square_room() -> WadBuilder {
+        let mut sector = words(&[0, 128]);
+        sector.extend(name8("FLOOR4_8"));
+        sector.extend(name8("CEIL3_5"));
+        sector.extend(words(&[160, 0, 0]));
+        let mut sides = vec![];
+        let mut segs = vec![];
+        for (i, &angle) in [0x4000, 0, 0xc000, 0x8000].iter().enumerate() {
+            sides.extend(words(&[0, 0]));
+            sides.extend(name8("-"));
+            sides.extend(name8("-"));
+            sides.extend(name8("STARTAN3"));
+            sides.extend(words(&[0]));
+            segs.extend(words(&[
+                i as i32,
+                (i as i32 + 1) % 4,
+                angle,
+                i as i32,
+                0,
+                0,
+            ]));
+        }
+
+        let mut builder = WadBuilder::new(WadKind::Pwad);
+        builder
+            .push("MAP01", vec![])
+            .and_then(|b| b.push("THINGS", words(&[64, 64, 90, 1, 7])))
+            .and_then(|b| {
+                b.push(
+                    "LINEDEFS",
+                    words(&[
+                        0, 1, 1, 0, 0, 0, -1, //
+                        1, 2, 1, 0, 0, 1, -1, //
+                        2, 3, 1, 0, 0, 2, -1, //
+                        3, 0, 1, 0, 0, 3, -1,
+                    ]),
+                )
+            })
+            .and_then(|b| b.push("SIDEDEFS", sides))
+            .and_then(|b| b.push("VERTEXES", words(&[0, 0, 0, 256, 256, 256, 256, 0])))
+            .and_then(|b| b.push("SEGS", segs))
+            .and_then(|b| b.push("SSECTORS", words(&[4, 0])))
+            .and_then(|b| b.push("NODES", vec![]))
+            .and_then(|b| b.push("SECTORS", sector))
+            .and_then(|b| b.push("REJECT", vec![0]))
+            .and_then(|b| b.push("BLOCKMAP", words(&[0, 0, 1, 1, 5, 0, 0, 1, 2, 3, -1])))
+            .unwrap();
+        builder
+    }
+
+    /// Write `builder` to a WAD named after `prefix`, load it as the only
+    /// file, and load MAP01 from it after `configure` has had its say.
+    pub fn load_map(
+        prefix: &str,
+        builder: &WadBuilder,
+        configure: impl FnOnce(&mut Configuration),
+    ) -> Result<Map, MapError> {
+        let path = test_path(&format!("{}.wad", prefix));
+        builder.write_to_file(&path).unwrap();
+        let mut configuration = Configuration::default();
+        configuration.wad_files = vec![WadFileInfo {
+            name: path.clone(),
+            src: WadSource::Pwad,
+            handle: 0,
+        }];
+        configuration.lumps = LumpDirectory::open(&mut configuration.wad_files).unwrap();
+        configure(&mut configuration);
+        let map = Map::load(&configuration, "MAP01");
+        std::fs::remove_file(path).unwrap();
+        map
+    }
+
+    #[test]
+    fn loads_a_vanilla_map() {
+        let map = load_map("map-vanilla", &square_room(), |_| {}).unwrap();
+        assert_eq!(map.namespace, None);
+        assert_eq!(map.node_format, NodeFormat::Vanilla);
+        assert_eq!(
+            map.things,
+            [Thing {
+                x: 64,
+                y: 64,
+                angle: 90,
+                kind: 1,
+                options: 7,
+            }]
+        );
+        assert_eq!(map.vertices.len(), 4);
+        assert_eq!(map.vertices[2].x, Fixed::from_int(256));
+        assert_eq!(map.sectors.len(), 1);
+        assert_eq!(map.sectors[0].ceiling_height, Fixed::from_int(128));
+        assert_eq!(map.sectors[0].floor_pic, "FLOOR4_8");
+        assert_eq!(map.sectors[0].lines, [0, 1, 2, 3]);
+        assert_eq!(map.sides[3].mid_texture, "STARTAN3");
+
+        let line = &map.lines[1];
+        assert_eq!((line.v1, line.v2), (1, 2));
+        assert_eq!((line.dx, line.dy), (Fixed::from_int(256), Fixed::ZERO));
+        assert_eq!(line.flags, LineFlags::BLOCKING);
+        assert_eq!(line.sides, [Some(1), None]);
+        assert_eq!((line.front_sector, line.back_sector), (Some(0), None));
+
+        assert_eq!(map.segs.len(), 4);
+        assert_eq!(map.segs[3].linedef, Some(3));
+        assert_eq!(map.segs[1].angle, Angle(0));
+        assert_eq!(
+            map.subsectors,
+            [Subsector {
+                first_seg: 0,
+                num_segs: 4,
+                sector: 0,
+            }]
+        );
+        assert!(map.nodes.is_empty());
+        assert_eq!(map.reject, [0]);
+        assert_eq!(map.blockmap.blocks, [vec![0, 0, 1, 2, 3]]);
+        assert!(map.gl_nodes.is_none());
+    }
+
+    #[test]
+    fn missing_lumps_and_maps() {
+        let mut builder = square_room();
+        let sectors = builder.position("SECTORS").unwrap();
+        builder.remove(sectors);
+        assert!(matches!(
+            load_map("map-missing-lump", &builder, |_| {}),
+            Err(MapError::MissingLump {
+                lump: "SECTORS",
+                ..
+            })
+        ));
+
+        let mut builder = square_room();
+        builder.remove(0);
+        builder.insert(0, "MAP02", vec![]).unwrap();
+        assert!(matches!(
+            load_map("map-not-found", &builder, |_| {}),
+            Err(MapError::MapNotFound(ref map)) if map == "MAP01"
+        ));
+
+        let mut builder = square_room();
+        builder.insert(1, "DEHACKED", vec![]).unwrap();
+        assert!(matches!(
+            load_map("map-unknown", &builder, |_| {}),
+            Err(MapError::UnknownFormat { next: Some(ref next), .. }) if next == "DEHACKED"
+        ));
+    }
+
+    #[test]
+    fn bad_records_are_reported() {
+        let mut builder = square_room();
+        builder.replace("THINGS", vec![0; 11]).unwrap();
+        assert!(matches!(
+            load_map("map-bad-size", &builder, |_| {}),
+            Err(MapError::BadLumpSize {
+                lump: "THINGS",
+                size: 11,
+                record: 10,
+                ..
+            })
+        ));
+
+        // the third line ends at vertex 9 of 4
+        let mut builder = square_room();
+        let mut lines = builder.lumps()[2].data.clone();
+        lines[14 * 2 + 2..14 * 2 + 4].copy_from_slice(&9u16.to_le_bytes());
+        builder.replace("LINEDEFS", lines).unwrap();
+        assert!(matches!(
+            load_map("map-bad-index", &builder, |_| {}),
+            Err(MapError::BadIndex {
+                lump: "LINEDEFS",
+                record: 2,
+                field: "end vertex",
+                value: 9,
+                limit: 4,
+                ..
+            })
+        ));
+    }
+
+    /// Load the square room with `blockmap` as its BLOCKMAP lump.
+    fn room_blockmap(
+        prefix: &str,
+        blockmap: Option<Vec<u8>>,
+        configure: impl FnOnce(&mut Configuration),
+    ) -> Blockmap {
+        let mut builder = square_room();
+        match blockmap {
+            Some(data) => {
+                builder.replace("BLOCKMAP", data).unwrap();
+            }
+            None => {
+                builder.remove(builder.position("BLOCKMAP").unwrap());
+            }
+        }
+        load_map(prefix, &builder, configure).unwrap().blockmap
+    }
+
+    /// Whether `blockmap` is the one built for the square room: 3 by 3
+    /// blocks, since the far walls are on the edge of the third.
+    fn is_rebuilt(blockmap: &Blockmap) -> bool {
+        blockmap.width == 3 && blockmap.height == 3 && blockmap.blocks[0] == [0, 0, 3]
+    }
+
+    #[test]
+    fn unusable_blockmaps_are_rebuilt() {
+        let good = words(&[0, 0, 1, 1, 5, 0, 0, 1, 2, 3, -1]);
+        assert!(!is_rebuilt(&room_blockmap(
+            "blockmap-good",
+            Some(good.clone()),
+            |_| {}
+        )));
+        assert!(is_rebuilt(&room_blockmap("blockmap-missing", None, |_| {})));
+        assert!(is_rebuilt(&room_blockmap(
+            "blockmap-small",
+            Some(words(&[0, 0, 1])),
+            |_| {}
+        )));
+        let mut big = good.clone();
+        big.resize(0x10000 * 2, 0);
+        assert!(is_rebuilt(&room_blockmap(
+            "blockmap-big",
+            Some(big),
+            |_| {}
+        )));
+        assert!(is_rebuilt(&room_blockmap(
+            "blockmap-unterminated",
+            Some(words(&[0, 0, 1, 1, 5, 0, 0, 1])),
+            |_| {}
+        )));
+        assert!(is_rebuilt(&room_blockmap(
+            "blockmap-bad-line",
+            Some(words(&[0, 0, 1, 1, 5, 0, 4, -1])),
+            |_| {}
+        )));
+        assert!(is_rebuilt(&room_blockmap(
+            "blockmap-offset",
+            Some(words(&[0, 0, 1, 1, 50, 0, -1])),
+            |_| {}
+        )));
+        assert!(is_rebuilt(&room_blockmap(
+            "blockmap-forced",
+            Some(good),
+            |configuration| configuration.force_blockmap = true
+        )));
+    }
+
+    #[test]
+    fn comperr_blockmap_restores_missing_zeros() {
+        let no_zeros = words(&[0, 0, 1, 1, 5, 1, 2, 3, -1]);
+        let load = |prefix, comperr, compatibility_level| {
+            room_blockmap(prefix, Some(no_zeros.clone()), |configuration| {
+                configuration.defaults.comperr_blockmap = comperr;
+                configuration.compatibility_level = compatibility_level;
+            })
+            .blocks
+        };
+        assert_eq!(
+            load("blockmap-comperr", true, CompatibilityLevel::Mbf),
+            [vec![0, 1, 2, 3]]
+        );
+        assert_eq!(
+            load("blockmap-no-comperr", false, CompatibilityLevel::Mbf),
+            [vec![1, 2, 3]]
+        );
+        // vanilla reads every entry, so nothing is missing
+        assert_eq!(
+            load("blockmap-vanilla", true, CompatibilityLevel::Doom2V19),
+            [vec![1, 2, 3]]
+        );
+    }
+
+    /// The square room turned into 12 sectors of 3 lines each, so REJECT
+    /// needs 18 bytes, with `reject` as its lump.
+    fn twelve_sectors(prefix: &str, reject: Vec<u8>) -> Map {
+        let mut map = load_map(prefix, &square_room(), |_| {}).unwrap();
+        let mut sector = map.sectors[0].clone();
+        sector.lines = vec![0, 1, 2];
+        map.sectors = vec![sector; 12];
+        map.reject = reject;
+        map
+    }
+
+    fn pad(
+        prefix: &str,
+        reject: Vec<u8>,
+        emulate: bool,
+        compatibility_level: CompatibilityLevel,
+    ) -> Vec<u8> {
+        let mut configuration = Configuration::default();
+        configuration.defaults.overrun_reject_emulate = emulate;
+        configuration.compatibility_level = compatibility_level;
+        let mut map = twelve_sectors(prefix, reject);
+        map.pad_reject(&configuration);
+        map.reject
+    }
+
+    #[test]
+    fn short_reject_gets_the_zone_header() {
+        // Chocolate Doom's PadRejectArray: the block size for 36 line
+        // pointers, the user pointer, PU_LEVEL and ZONEID, then zeros
+        let header = [
+            168, 0, 0, 0, 0, 0, 0, 0, 50, 0, 0, 0, 0x11, 0x4a, 0x1d, 0, 0, 0,
+        ];
+        assert_eq!(
+            pad("reject-empty", vec![], true, CompatibilityLevel::Doom2V19),
+            header
+        );
+
+        // the padding starts where the lump ends
+        let mut expected = vec![0xff; 5];
+        expected.extend(&header[..13]);
+        assert_eq!(
+            pad(
+                "reject-short",
+                vec![0xff; 5],
+                true,
+                CompatibilityLevel::DoomV1666
+            ),
+            expected
+        );
+    }
+
+    #[test]
+    fn reject_padding_is_zeros_without_emulation() {
+        assert_eq!(
+            pad(
+                "reject-no-emulate",
+                vec![],
+                false,
+                CompatibilityLevel::Doom2V19
+            ),
+            [0; 18]
+        );
+        assert_eq!(
+            pad("reject-boom", vec![], true, CompatibilityLevel::Boom),
+            [0; 18]
+        );
+        // long enough already
+        assert_eq!(
+            pad(
+                "reject-long",
+                vec![1; 20],
+                true,
+                CompatibilityLevel::Doom2V19
+            ),
+            [1; 20]
+        );
+    }
+}
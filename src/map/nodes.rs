@@ -0,0 +1,758 @@
+use super::{
+    binary::fixed, check_index, records, Line, LineFlags, MapError, Node, NodeChild, Seg, Side,
+    Subsector, Vertex,
+};
+use crate::{
+    misc::fixed::Fixed,
+    tables::{point_to_angle2, Angle},
+};
+use byteorder::{ByteOrder, LittleEndian};
+use flate2::read::ZlibDecoder;
+use std::io::Read;
+
+/// The formats the BSP tree of a map can be stored in. Everything but
+/// `Vanilla` is recognized by the signature at the start of NODES or
+/// SSECTORS.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NodeFormat {
+    /// 16 bit indices in SEGS, SSECTORS and NODES.
+    Vanilla,
+    /// DeePBSP's `xNd4`: the same three lumps with 32 bit indices.
+    DeePBsp,
+    /// ZDBSP's `XNOD` in NODES, or `ZNOD` if compressed.
+    Xnod,
+    /// ZDBSP's GL nodes in SSECTORS: `XGLN`, or `ZGLN` if compressed.
+    Xgln,
+    /// `XGLN` with 32 bit linedef indices: `XGL2` or `ZGL2`.
+    Xgl2,
+    /// `XGL2` with fixed point partition lines: `XGL3` or `ZGL3`.
+    Xgl3,
+}
+
+impl NodeFormat {
+    /// Work out the format from the start of the lumps, and whether the
+    /// data after the signature is zlib compressed.
+    pub fn detect(ssectors: &[u8], nodes: &[u8]) -> (Self, bool) {
+        if nodes.starts_with(b"xNd4\0\0\0\0") {
+            return (NodeFormat::DeePBsp, false);
+        }
+        match (nodes.get(..4), ssectors.get(..4)) {
+            (Some(b"XNOD"), _) => (NodeFormat::Xnod, false),
+            (Some(b"ZNOD"), _) => (NodeFormat::Xnod, true),
+            (_, Some(b"XGLN")) => (NodeFormat::Xgln, false),
+            (_, Some(b"ZGLN")) => (NodeFormat::Xgln, true),
+            (_, Some(b"XGL2")) => (NodeFormat::Xgl2, false),
+            (_, Some(b"ZGL2")) => (NodeFormat::Xgl2, true),
+            (_, Some(b"XGL3")) => (NodeFormat::Xgl3, false),
+            (_, Some(b"ZGL3")) => (NodeFormat::Xgl3, true),
+            _ => (NodeFormat::Vanilla, false),
+        }
+    }
+}
+
+/// The BSP tree of a map.
+pub struct Bsp {
+    pub segs: Vec<Seg>,
+    pub subsectors: Vec<Subsector>,
+    pub nodes: Vec<Node>,
+}
+
+/// A seg as stored on disk, before its sectors are worked out.
+struct RawSeg {
+    v1: usize,
+    v2: usize,
+    linedef: Option<usize>,
+    side: usize,
+    /// The seg on the other side of a GL seg's line.
+    partner: Option<usize>,
+    /// ZDBSP doesn't store angles and offsets, they are worked out from the
+    /// vertices.
+    angle: Option<Angle>,
+    offset: Option<Fixed>,
+}
+
+/// A node as stored on disk: partition line, bounding boxes and children.
+type RawNode = ([Fixed; 4], [[Fixed; 4]; 2], [u32; 2]);
+
+/// Load the BSP tree in `format`. ZDBSP formats are read from whichever of
+/// `ssectors` and `nodes` has the signature, and may add vertices.
+#[allow(clippy::too_many_arguments)]
+pub fn load(
+    map: &str,
+    format: NodeFormat,
+    compressed: bool,
+    segs: &[u8],
+    ssectors: &[u8],
+    nodes: &[u8],
+    vertices: &mut Vec<Vertex>,
+    lines: &[Line],
+    sides: &[Side],
+) -> Result<Bsp, MapError> {
+    match format {
+        NodeFormat::Vanilla => {
+            let raw = records(map, "SEGS", segs, 12)?
+                .map(|r| RawSeg {
+                    v1: LittleEndian::read_u16(&r[0..]) as usize,
+                    v2: LittleEndian::read_u16(&r[2..]) as usize,
+                    angle: Some(Angle((LittleEndian::read_u16(&r[4..]) as u32) << 16)),
+                    linedef: Some(LittleEndian::read_u16(&r[6..]) as usize),
+                    // anything but 0 is the back, like vanilla
+                    side: (LittleEndian::read_i16(&r[8..]) != 0) as usize,
+                    offset: Some(fixed(&r[10..])),
+                    partner: None,
+                })
+                .collect();
+            let ranges = records(map, "SSECTORS", ssectors, 4)?
+                .map(|r| {
+                    (
+                        LittleEndian::read_u16(&r[2..]) as usize,
+                        LittleEndian::read_u16(&r[0..]) as usize,
+                    )
+                })
+                .collect();
+            let (segs, subsectors) =
+                build(map, "SEGS", "SSECTORS", raw, ranges, vertices, lines, sides)?;
+            let nodes = records(map, "NODES", nodes, 28)?
+                .map(|r| {
+                    let children = [
+                        LittleEndian::read_u16(&r[24..]) as u32,
+                        LittleEndian::read_u16(&r[26..]) as u32,
+                    ];
+                    (i16_partition(r), bbox(&r[8..]), children)
+                })
+                .collect();
+            let nodes = build_nodes(map, "NODES", nodes, 0x8000, subsectors.len())?;
+            Ok(Bsp {
+                segs,
+                subsectors,
+                nodes,
+            })
+        }
+        NodeFormat::DeePBsp => {
+            let raw = records(map, "SEGS", segs, 16)?
+                .map(|r| RawSeg {
+                    v1: LittleEndian::read_u32(&r[0..]) as usize,
+                    v2: LittleEndian::read_u32(&r[4..]) as usize,
+                    angle: Some(Angle((LittleEndian::read_u16(&r[8..]) as u32) << 16)),
+                    linedef: Some(LittleEndian::read_u16(&r[10..]) as usize),
+                    side: (LittleEndian::read_i16(&r[12..]) != 0) as usize,
+                    offset: Some(fixed(&r[14..])),
+                    partner: None,
+                })
+                .collect();
+            let ranges = records(map, "SSECTORS", ssectors, 6)?
+                .map(|r| {
+                    (
+                        LittleEndian::read_u32(&r[2..]) as usize,
+                        LittleEndian::read_u16(&r[0..]) as usize,
+                    )
+                })
+                .collect();
+            let (segs, subsectors) =
+                build(map, "SEGS", "SSECTORS", raw, ranges, vertices, lines, sides)?;
+            let nodes = records(map, "NODES", &nodes[8..], 32)?
+                .map(|r| {
+                    let children = [
+                        LittleEndian::read_u32(&r[24..]),
+                        LittleEndian::read_u32(&r[28..]),
+                    ];
+                    (i16_partition(r), bbox(&r[8..]), children)
+                })
+                .collect();
+            let nodes = build_nodes(map, "NODES", nodes, 0x8000_0000, subsectors.len())?;
+            Ok(Bsp {
+                segs,
+                subsectors,
+                nodes,
+            })
+        }
+        NodeFormat::Xnod => load_zdbsp(
+            map, "NODES", format, compressed, nodes, vertices, lines, sides,
+        ),
+        _ => load_zdbsp(
+            map, "SSECTORS", format, compressed, ssectors, vertices, lines, sides,
+        ),
+    }
+}
+
+/// Load ZDBSP nodes from `data`, signature included. New vertices are
+/// added to the end of `vertices`.
+#[allow(clippy::too_many_arguments)]
+pub fn load_zdbsp(
+    map: &str,
+    lump: &'static str,
+    format: NodeFormat,
+    compressed: bool,
+    data: &[u8],
+    vertices: &mut Vec<Vertex>,
+    lines: &[Line],
+    sides: &[Side],
+) -> Result<Bsp, MapError> {
+    let decompressed;
+    let data = if compressed {
+        let mut out = vec![];
+        ZlibDecoder::new(&data[4..])
+            .read_to_end(&mut out)
+            .map_err(|error| MapError::Zlib {
+                map: map.to_string(),
+                lump,
+                error,
+            })?;
+        decompressed = out;
+        &decompressed[..]
+    } else {
+        &data[4..]
+    };
+    let mut s = Stream { map, lump, data };
+
+    // vertices below `original` are the map's own, the rest are new
+    let original = s.u32()? as usize;
+    if original > vertices.len() {
+        return Err(MapError::BadIndex {
+            map: map.to_string(),
+            lump,
+            record: 0,
+            field: "original vertex count",
+            value: original,
+            limit: vertices.len(),
+        });
+    }
+    let num_new = s.u32()? as usize;
+    let base = vertices.len();
+    for _ in 0..num_new {
+        let x = Fixed(s.i32()?);
+        let y = Fixed(s.i32()?);
+        vertices.push(Vertex { x, y });
+    }
+    let vertex = |v: u32| {
+        let v = v as usize;
+        if v < original {
+            v
+        } else {
+            // out of range values stay out of range, for `build` to report
+            base + (v - original)
+        }
+    };
+
+    let num_subsectors = s.u32()? as usize;
+    let mut ranges = vec![];
+    let mut first_seg = 0;
+    for _ in 0..num_subsectors {
+        let count = s.u32()? as usize;
+        ranges.push((first_seg, count));
+        first_seg += count;
+    }
+
+    let num_segs = s.u32()? as usize;
+    if num_segs != first_seg {
+        return Err(MapError::BadIndex {
+            map: map.to_string(),
+            lump,
+            record: 0,
+            field: "seg count",
+            value: first_seg,
+            limit: num_segs,
+        });
+    }
+    let mut raw = vec![];
+    for _ in 0..num_segs {
+        let v1 = vertex(s.u32()?);
+        let (v2, partner) = match format {
+            NodeFormat::Xnod => (vertex(s.u32()?), None),
+            _ => (0, Some(s.u32()?).filter(|&p| p != 0xffff_ffff)),
+        };
+        let linedef = match format {
+            NodeFormat::Xgl2 | NodeFormat::Xgl3 => Some(s.u32()?).filter(|&l| l != 0xffff_ffff),
+            _ => Some(s.u16()? as u32).filter(|&l| l != 0xffff),
+        };
+        raw.push(RawSeg {
+            v1,
+            v2,
+            linedef: linedef.map(|l| l as usize),
+            side: (s.u8()? != 0) as usize,
+            partner: partner.map(|p| p as usize),
+            angle: None,
+            offset: None,
+        });
+    }
+    if format != NodeFormat::Xnod {
+        // GL segs go all the way around their subsector, so each one ends
+        // where the next one starts
+        for &(first, count) in &ranges {
+            for i in first..first + count {
+                let next = if i + 1 == first + count { first } else { i + 1 };
+                raw[i].v2 = raw[next].v1;
+            }
+        }
+    }
+    let (segs, subsectors) = build(map, lump, lump, raw, ranges, vertices, lines, sides)?;
+
+    let num_nodes = s.u32()? as usize;
+    let mut nodes = vec![];
+    for _ in 0..num_nodes {
+        let partition = if format == NodeFormat::Xgl3 {
+            [
+                Fixed(s.i32()?),
+                Fixed(s.i32()?),
+                Fixed(s.i32()?),
+                Fixed(s.i32()?),
+            ]
+        } else {
+            [
+                fixed(s.take(2)?),
+                fixed(s.take(2)?),
+                fixed(s.take(2)?),
+                fixed(s.take(2)?),
+            ]
+        };
+        let bbox = bbox(s.take(16)?);
+        let children = [s.u32()?, s.u32()?];
+        nodes.push((partition, bbox, children));
+    }
+    let nodes = build_nodes(map, lump, nodes, 0x8000_0000, subsectors.len())?;
+    Ok(Bsp {
+        segs,
+        subsectors,
+        nodes,
+    })
+}
+
+/// Reads little endian values from a ZDBSP lump, failing cleanly if it is
+/// truncated.
+struct Stream<'a> {
+    map: &'a str,
+    lump: &'static str,
+    data: &'a [u8],
+}
+
+impl<'a> Stream<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], MapError> {
+        if self.data.len() < n {
+            return Err(MapError::Truncated {
+                map: self.map.to_string(),
+                lump: self.lump,
+            });
+        }
+        let (head, tail) = self.data.split_at(n);
+        self.data = tail;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8, MapError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, MapError> {
+        Ok(LittleEndian::read_u16(self.take(2)?))
+    }
+
+    fn u32(&mut self) -> Result<u32, MapError> {
+        Ok(LittleEndian::read_u32(self.take(4)?))
+    }
+
+    fn i32(&mut self) -> Result<i32, MapError> {
+        Ok(LittleEndian::read_i32(self.take(4)?))
+    }
+}
+
+fn i16_partition(r: &[u8]) -> [Fixed; 4] {
+    [
+        fixed(&r[0..]),
+        fixed(&r[2..]),
+        fixed(&r[4..]),
+        fixed(&r[6..]),
+    ]
+}
+
+fn bbox(r: &[u8]) -> [[Fixed; 4]; 2] {
+    let mut bbox = [[Fixed::ZERO; 4]; 2];
+    for (j, side) in bbox.iter_mut().enumerate() {
+        for (k, coord) in side.iter_mut().enumerate() {
+            *coord = fixed(&r[j * 8 + k * 2..]);
+        }
+    }
+    bbox
+}
+
+/// Check the segs and subsectors, and work out which sectors they are in.
+///
+/// A subsector takes the sector in front of its first seg along a linedef.
+/// GL nodes may have subsectors bounded only by minisegs; those take the
+/// sector of a neighbouring subsector through the minisegs' partners, since
+/// a miniseg never crosses a linedef.
+#[allow(clippy::too_many_arguments)]
+fn build(
+    map: &str,
+    segs_lump: &'static str,
+    ssectors_lump: &'static str,
+    raw: Vec<RawSeg>,
+    ranges: Vec<(usize, usize)>,
+    vertices: &[Vertex],
+    lines: &[Line],
+    sides: &[Side],
+) -> Result<(Vec<Seg>, Vec<Subsector>), MapError> {
+    let num_segs = raw.len();
+    let mut segs = Vec::with_capacity(num_segs);
+    let mut partners = Vec::with_capacity(num_segs);
+    for (i, seg) in raw.into_iter().enumerate() {
+        let v1 = check_index(map, segs_lump, i, "start vertex", seg.v1, vertices.len())?;
+        let v2 = check_index(map, segs_lump, i, "end vertex", seg.v2, vertices.len())?;
+        if let Some(partner) = seg.partner {
+            check_index(map, segs_lump, i, "partner seg", partner, num_segs)?;
+        }
+        partners.push(seg.partner);
+
+        let (linedef, front_sector, back_sector, offset) = match seg.linedef {
+            Some(linedef) => {
+                let linedef = check_index(map, segs_lump, i, "linedef", linedef, lines.len())?;
+                let (front, back) = seg_sectors(map, i, lines, sides, linedef, seg.side)?;
+                let offset = seg.offset.unwrap_or_else(|| {
+                    let line = &lines[linedef];
+                    let start = vertices[if seg.side == 0 { line.v1 } else { line.v2 }];
+                    let dx = vertices[v1].x.to_f64() - start.x.to_f64();
+                    let dy = vertices[v1].y.to_f64() - start.y.to_f64();
+                    Fixed::from_f64(dx.hypot(dy))
+                });
+                (Some(linedef), front, back, offset)
+            }
+            // minisegs get their sector from their subsector below
+            None => (None, 0, None, Fixed::ZERO),
+        };
+        segs.push(Seg {
+            v1,
+            v2,
+            angle: seg.angle.unwrap_or_else(|| {
+                let (a, b) = (vertices[v1], vertices[v2]);
+                point_to_angle2(a.x, a.y, b.x, b.y)
+            }),
+            linedef,
+            side: seg.side,
+            offset,
+            front_sector,
+            back_sector,
+        });
+    }
+
+    let mut owner = vec![0; num_segs];
+    let mut sectors = Vec::with_capacity(ranges.len());
+    for (i, &(first_seg, num_segs)) in ranges.iter().enumerate() {
+        if num_segs == 0 {
+            return Err(MapError::EmptySubsector {
+                map: map.to_string(),
+                subsector: i,
+            });
+        }
+        check_index(
+            map,
+            ssectors_lump,
+            i,
+            "last seg",
+            first_seg + num_segs - 1,
+            segs.len(),
+        )?;
+        let range = first_seg..first_seg + num_segs;
+        for seg in range.clone() {
+            owner[seg] = i;
+        }
+        sectors.push(
+            segs[range]
+                .iter()
+                .find(|seg| seg.linedef.is_some())
+                .map(|seg| seg.front_sector),
+        );
+    }
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (i, &(first_seg, num_segs)) in ranges.iter().enumerate() {
+            if sectors[i].is_some() {
+                continue;
+            }
+            let neighbour = (first_seg..first_seg + num_segs)
+                .filter_map(|seg| partners[seg])
+                .find_map(|partner| sectors[owner[partner]]);
+            if neighbour.is_some() {
+                sectors[i] = neighbour;
+                changed = true;
+            }
+        }
+    }
+
+    let mut subsectors = Vec::with_capacity(ranges.len());
+    for (i, (&(first_seg, num_segs), sector)) in ranges.iter().zip(sectors).enumerate() {
+        let sector = sector.ok_or_else(|| MapError::UnknownSubsectorSector {
+            map: map.to_string(),
+            subsector: i,
+        })?;
+        for seg in &mut segs[first_seg..first_seg + num_segs] {
+            if seg.linedef.is_none() {
+                seg.front_sector = sector;
+            }
+        }
+        subsectors.push(Subsector {
+            first_seg,
+            num_segs,
+            sector,
+        });
+    }
+    Ok((segs, subsectors))
+}
+
+/// The sectors in front of and behind a seg on the `side` of `linedef`.
+fn seg_sectors(
+    map: &str,
+    seg: usize,
+    lines: &[Line],
+    sides: &[Side],
+    linedef: usize,
+    side: usize,
+) -> Result<(usize, Option<usize>), MapError> {
+    let line = &lines[linedef];
+    let front_side = line.sides[side].ok_or_else(|| MapError::MissingSidedef {
+        map: map.to_string(),
+        seg,
+        linedef,
+        side,
+    })?;
+    let back_sector = if line.flags.contains(LineFlags::TWO_SIDED) {
+        line.sides[side ^ 1].map(|s| sides[s].sector)
+    } else {
+        None
+    };
+    Ok((sides[front_side].sector, back_sector))
+}
+
+/// Check the children of nodes, where `subsector_flag` marks a child as a
+/// subsector.
+fn build_nodes(
+    map: &str,
+    lump: &'static str,
+    raw: Vec<RawNode>,
+    subsector_flag: u32,
+    num_subsectors: usize,
+) -> Result<Vec<Node>, MapError> {
+    let num_nodes = raw.len();
+    raw.into_iter()
+        .enumerate()
+        .map(|(i, ([x, y, dx, dy], bbox, raw_children))| {
+            let mut children = [NodeChild::Node(0); 2];
+            for (child, &raw) in children.iter_mut().zip(&raw_children) {
+                *child = if raw & subsector_flag != 0 {
+                    NodeChild::Subsector(check_index(
+                        map,
+                        lump,
+                        i,
+                        "child subsector",
+                        (raw & !subsector_flag) as usize,
+                        num_subsectors,
+                    )?)
+                } else {
+                    NodeChild::Node(check_index(
+                        map,
+                        lump,
+                        i,
+                        "child node",
+                        raw as usize,
+                        num_nodes,
+                    )?)
+                };
+            }
+            Ok(Node {
+                x,
+                y,
+                dx,
+                dy,
+                bbox,
+                children,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::{
+        tests::{load_map, square_room, words},
+        MapError,
+    };
+    use flate2::{write::ZlibEncoder, Compression};
+    use std::io::Write;
+
+    #[test]
+    fn detects_formats_from_signatures() {
+        let detect = |ssectors: &[u8], nodes: &[u8]| NodeFormat::detect(ssectors, nodes);
+        assert_eq!(detect(b"", b""), (NodeFormat::Vanilla, false));
+        assert_eq!(detect(b"\x04\0\0\0", b"XNO"), (NodeFormat::Vanilla, false));
+        assert_eq!(detect(b"", b"xNd4\0\0\0\0"), (NodeFormat::DeePBsp, false));
+        assert_eq!(detect(b"", b"xNd4"), (NodeFormat::Vanilla, false));
+        assert_eq!(detect(b"", b"XNOD...."), (NodeFormat::Xnod, false));
+        assert_eq!(detect(b"", b"ZNOD...."), (NodeFormat::Xnod, true));
+        assert_eq!(detect(b"XGLN", b""), (NodeFormat::Xgln, false));
+        assert_eq!(detect(b"ZGLN", b""), (NodeFormat::Xgln, true));
+        assert_eq!(detect(b"XGL2", b""), (NodeFormat::Xgl2, false));
+        assert_eq!(detect(b"ZGL2", b""), (NodeFormat::Xgl2, true));
+        assert_eq!(detect(b"XGL3", b""), (NodeFormat::Xgl3, false));
+        assert_eq!(detect(b"ZGL3", b""), (NodeFormat::Xgl3, true));
+        // the signature in NODES wins
+        assert_eq!(detect(b"XGLN", b"XNOD"), (NodeFormat::Xnod, false));
+    }
+
+    /// The square room's BSP in a ZDBSP format, signature included: its
+    /// one subsector, and a node with that subsector on both sides.
+    fn zdbsp(signature: &[u8; 4], format: NodeFormat) -> Vec<u8> {
+        let mut body = vec![];
+        // 4 original vertices, no new ones, one subsector of 4 segs
+        for n in [4u32, 0, 1, 4, 4].iter() {
+            body.extend(&n.to_le_bytes());
+        }
+        for i in 0..4u32 {
+            body.extend(&i.to_le_bytes());
+            match format {
+                NodeFormat::Xnod => body.extend(&((i + 1) % 4).to_le_bytes()),
+                _ => body.extend(&u32::MAX.to_le_bytes()),
+            }
+            match format {
+                NodeFormat::Xgl2 | NodeFormat::Xgl3 => body.extend(&i.to_le_bytes()),
+                _ => body.extend(&(i as u16).to_le_bytes()),
+            }
+            body.push(0);
+        }
+        body.extend(&1u32.to_le_bytes());
+        if format == NodeFormat::Xgl3 {
+            for v in [128i32 << 16, 0, 0, 256 << 16].iter() {
+                body.extend(&v.to_le_bytes());
+            }
+        } else {
+            body.extend(words(&[128, 0, 0, 256]));
+        }
+        body.extend(words(&[256, 0, 0, 128, 256, 0, 128, 256]));
+        body.extend(&0x8000_0000u32.to_le_bytes());
+        body.extend(&0x8000_0000u32.to_le_bytes());
+
+        let mut data = signature.to_vec();
+        if signature[0] == b'Z' {
+            let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+            encoder.write_all(&body).unwrap();
+            data.extend(encoder.finish().unwrap());
+        } else {
+            data.extend(body);
+        }
+        data
+    }
+
+    /// Check the room loaded from ZDBSP nodes came out like the vanilla one.
+    fn check_zdbsp_room(map: &crate::map::Map, format: NodeFormat) {
+        assert_eq!(map.node_format, format);
+        assert_eq!(map.vertices.len(), 4);
+        assert_eq!(map.subsectors.len(), 1);
+        assert_eq!(map.subsectors[0].num_segs, 4);
+        for (i, seg) in map.segs.iter().enumerate() {
+            assert_eq!((seg.v1, seg.v2), (i, (i + 1) % 4), "{:?}", format);
+            assert_eq!(seg.linedef, Some(i));
+            assert_eq!(seg.offset, Fixed::ZERO);
+        }
+        assert_eq!(map.segs[1].angle, Angle(0));
+        let node = &map.nodes[0];
+        assert_eq!(
+            (node.x, node.dy),
+            (Fixed::from_int(128), Fixed::from_int(256))
+        );
+        assert_eq!(node.children, [NodeChild::Subsector(0); 2]);
+    }
+
+    #[test]
+    fn loads_zdbsp_nodes() {
+        let mut builder = square_room();
+        for (signature, format) in [(b"XNOD", NodeFormat::Xnod), (b"ZNOD", NodeFormat::Xnod)].iter()
+        {
+            builder.replace("NODES", zdbsp(signature, *format)).unwrap();
+            let map = load_map("nodes-xnod", &builder, |_| {}).unwrap();
+            check_zdbsp_room(&map, *format);
+        }
+
+        let mut builder = square_room();
+        builder.replace("NODES", vec![]).unwrap();
+        let gl = [
+            (b"XGLN", NodeFormat::Xgln),
+            (b"ZGLN", NodeFormat::Xgln),
+            (b"XGL2", NodeFormat::Xgl2),
+            (b"ZGL2", NodeFormat::Xgl2),
+            (b"XGL3", NodeFormat::Xgl3),
+            (b"ZGL3", NodeFormat::Xgl3),
+        ];
+        for (signature, format) in gl.iter() {
+            builder
+                .replace("SSECTORS", zdbsp(signature, *format))
+                .unwrap();
+            let map = load_map("nodes-xgln", &builder, |_| {}).unwrap();
+            check_zdbsp_room(&map, *format);
+        }
+    }
+
+    #[test]
+    fn loads_deepbsp_nodes() {
+        let mut builder = square_room();
+        let mut segs = vec![];
+        for (i, angle) in [0x4000u16, 0, 0xc000, 0x8000].iter().enumerate() {
+            segs.extend(&(i as u32).to_le_bytes());
+            segs.extend(&((i as u32 + 1) % 4).to_le_bytes());
+            segs.extend(&angle.to_le_bytes());
+            segs.extend(words(&[i as i32, 0, 0]));
+        }
+        let mut ssectors = 4u16.to_le_bytes().to_vec();
+        ssectors.extend(&0u32.to_le_bytes());
+        builder
+            .replace("SEGS", segs)
+            .and_then(|b| b.replace("SSECTORS", ssectors))
+            .and_then(|b| b.replace("NODES", b"xNd4\0\0\0\0".to_vec()))
+            .unwrap();
+        let map = load_map("nodes-deepbsp", &builder, |_| {}).unwrap();
+        assert_eq!(map.node_format, NodeFormat::DeePBsp);
+        assert_eq!(map.segs[2].v1, 2);
+        assert_eq!(map.segs[2].angle, Angle(0xc000 << 16));
+        assert_eq!(map.subsectors[0].num_segs, 4);
+        assert!(map.nodes.is_empty());
+    }
+
+    #[test]
+    fn force_old_bsp_ignores_signatures() {
+        let mut builder = square_room();
+        builder
+            .replace("NODES", zdbsp(b"XNOD", NodeFormat::Xnod))
+            .unwrap();
+        let map = load_map("nodes-forced", &builder, |configuration| {
+            configuration.force_old_bsp = true
+        });
+        // the XNOD lump is read as vanilla nodes, which it doesn't fit
+        assert!(matches!(
+            map,
+            Err(MapError::BadLumpSize {
+                lump: "NODES",
+                record: 28,
+                ..
+            })
+        ));
+
+        // vanilla nodes load the same either way
+        let map = load_map("nodes-forced-vanilla", &square_room(), |configuration| {
+            configuration.force_old_bsp = true
+        })
+        .unwrap();
+        assert_eq!(map.node_format, NodeFormat::Vanilla);
+        assert_eq!(map.segs.len(), 4);
+    }
+
+    #[test]
+    fn truncated_zdbsp_nodes_are_reported() {
+        let mut builder = square_room();
+        let mut nodes = zdbsp(b"XNOD", NodeFormat::Xnod);
+        nodes.truncate(30);
+        builder.replace("NODES", nodes).unwrap();
+        assert!(matches!(
+            load_map("nodes-truncated", &builder, |_| {}),
+            Err(MapError::Truncated { lump: "NODES", .. })
+        ));
+    }
+}
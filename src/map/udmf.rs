@@ -0,0 +1,659 @@
+use super::{binary::make_line, Line, LineFlags, MapError, Sector, Side, Thing, Vertex};
+use crate::misc::fixed::Fixed;
+use rustc_hash::FxHashMap;
+
+/// The UDMF namespaces this port can play. Anything else uses features of
+/// other games or ports.
+const NAMESPACES: [&str; 3] = ["doom", "boom", "mbf21"];
+
+/// The map data defined by a TEXTMAP lump. Everything but the BSP tree,
+/// which lives in the ZNODES lump.
+pub struct TextMap {
+    pub namespace: String,
+    pub things: Vec<Thing>,
+    pub vertices: Vec<Vertex>,
+    pub sectors: Vec<Sector>,
+    pub sides: Vec<Side>,
+    pub lines: Vec<Line>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Bool(bool),
+}
+
+/// One `kind { field = value; ... }` block. Field names are lowercase, since
+/// UDMF ignores case.
+struct Block {
+    kind: String,
+    /// The line the block starts on, for error messages.
+    line: usize,
+    fields: FxHashMap<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Value(Value),
+    Punct(u8),
+}
+
+/// Parse a TEXTMAP lump. Unknown blocks and fields are ignored, as the UDMF
+/// specification requires.
+pub fn parse(map: &str, text: &[u8]) -> Result<TextMap, MapError> {
+    let mut lexer = Lexer {
+        map,
+        text,
+        pos: 0,
+        line: 1,
+    };
+    let mut namespace = None;
+    let mut blocks = vec![];
+    while let Some(token) = lexer.next()? {
+        let line = lexer.line;
+        let name = match token {
+            Token::Ident(name) => name.to_ascii_lowercase(),
+            _ => return Err(lexer.error("expected a field or block name")),
+        };
+        match lexer.next()? {
+            Some(Token::Punct(b'=')) => {
+                let value = lexer.value()?;
+                lexer.expect(b';')?;
+                if name == "namespace" {
+                    namespace = Some(value);
+                }
+            }
+            Some(Token::Punct(b'{')) => {
+                let mut fields = FxHashMap::default();
+                loop {
+                    let field = match lexer.next()? {
+                        Some(Token::Punct(b'}')) => break,
+                        Some(Token::Ident(field)) => field.to_ascii_lowercase(),
+                        _ => return Err(lexer.error("expected a field name or '}'")),
+                    };
+                    lexer.expect(b'=')?;
+                    let value = lexer.value()?;
+                    lexer.expect(b';')?;
+                    fields.insert(field, value);
+                }
+                blocks.push(Block {
+                    kind: name,
+                    line,
+                    fields,
+                });
+            }
+            _ => return Err(lexer.error("expected '=' or '{'")),
+        }
+    }
+
+    let namespace = match namespace {
+        Some(Value::Str(namespace)) => namespace.to_ascii_lowercase(),
+        _ => return Err(lexer.error("no namespace given")),
+    };
+    if !NAMESPACES.contains(&namespace.as_str()) {
+        return Err(MapError::UnsupportedFormat {
+            map: map.to_string(),
+            format: format!("UDMF namespace \"{}\"", namespace),
+        });
+    }
+    let mbf21 = namespace == "mbf21";
+
+    let of_kind = |kind: &'static str| blocks.iter().filter(move |b| b.kind == kind);
+    let vertices = of_kind("vertex")
+        .map(|b| {
+            Ok(Vertex {
+                x: Fixed::from_f64(b.float(map, "x")?.ok_or_else(|| b.missing(map, "x"))?),
+                y: Fixed::from_f64(b.float(map, "y")?.ok_or_else(|| b.missing(map, "y"))?),
+            })
+        })
+        .collect::<Result<Vec<_>, MapError>>()?;
+
+    let sectors = of_kind("sector")
+        .map(|b| {
+            Ok(Sector {
+                floor_height: Fixed::from_int(b.int(map, "heightfloor")?.unwrap_or(0) as i32),
+                ceiling_height: Fixed::from_int(b.int(map, "heightceiling")?.unwrap_or(0) as i32),
+                floor_pic: b
+                    .string(map, "texturefloor")?
+                    .ok_or_else(|| b.missing(map, "texturefloor"))?,
+                ceiling_pic: b
+                    .string(map, "textureceiling")?
+                    .ok_or_else(|| b.missing(map, "textureceiling"))?,
+                light_level: b.int(map, "lightlevel")?.unwrap_or(160) as i16,
+                special: b.int(map, "special")?.unwrap_or(0) as i16,
+                tag: b.int(map, "id")?.unwrap_or(0) as i16,
+                lines: vec![],
+            })
+        })
+        .collect::<Result<Vec<_>, MapError>>()?;
+
+    let sides = of_kind("sidedef")
+        .map(|b| {
+            let sector = b
+                .int(map, "sector")?
+                .ok_or_else(|| b.missing(map, "sector"))?;
+            Ok(Side {
+                texture_offset: Fixed::from_int(b.int(map, "offsetx")?.unwrap_or(0) as i32),
+                row_offset: Fixed::from_int(b.int(map, "offsety")?.unwrap_or(0) as i32),
+                top_texture: b.string(map, "texturetop")?.unwrap_or_else(|| "-".into()),
+                bottom_texture: b
+                    .string(map, "texturebottom")?
+                    .unwrap_or_else(|| "-".into()),
+                mid_texture: b
+                    .string(map, "texturemiddle")?
+                    .unwrap_or_else(|| "-".into()),
+                sector: b.index(map, "sector", sector, sectors.len())?,
+            })
+        })
+        .collect::<Result<Vec<_>, MapError>>()?;
+
+    let lines = of_kind("linedef")
+        .map(|b| {
+            let mut flags = LineFlags::empty();
+            let mut names = vec![
+                ("blocking", LineFlags::BLOCKING),
+                ("blockmonsters", LineFlags::BLOCK_MONSTERS),
+                ("twosided", LineFlags::TWO_SIDED),
+                ("dontpegtop", LineFlags::DONT_PEG_TOP),
+                ("dontpegbottom", LineFlags::DONT_PEG_BOTTOM),
+                ("secret", LineFlags::SECRET),
+                ("blocksound", LineFlags::SOUND_BLOCK),
+                ("dontdraw", LineFlags::DONT_DRAW),
+                ("mapped", LineFlags::MAPPED),
+                ("passuse", LineFlags::PASS_USE),
+            ];
+            if mbf21 {
+                names.push(("blocklandmonsters", LineFlags::BLOCK_LAND_MONSTERS));
+                names.push(("blockplayers", LineFlags::BLOCK_PLAYERS));
+            }
+            for (name, flag) in names {
+                if b.bool(map, name)? {
+                    flags |= flag;
+                }
+            }
+
+            let v1 = b.int(map, "v1")?.ok_or_else(|| b.missing(map, "v1"))?;
+            let v2 = b.int(map, "v2")?.ok_or_else(|| b.missing(map, "v2"))?;
+            let front = b
+                .int(map, "sidefront")?
+                .ok_or_else(|| b.missing(map, "sidefront"))?;
+            let back = b.int(map, "sideback")?.unwrap_or(-1);
+            let mut line_sides = [None; 2];
+            for (side, (field, value)) in line_sides
+                .iter_mut()
+                .zip(&[("sidefront", front), ("sideback", back)])
+            {
+                if *value >= 0 {
+                    *side = Some(b.index(map, field, *value, sides.len())?);
+                }
+            }
+            Ok(make_line(
+                &vertices,
+                &sides,
+                b.index(map, "v1", v1, vertices.len())?,
+                b.index(map, "v2", v2, vertices.len())?,
+                flags,
+                b.int(map, "special")?.unwrap_or(0) as i16,
+                // the line id is the tag in Doom namespaces, -1 means none
+                b.int(map, "id")?.unwrap_or(-1).max(0) as i16,
+                line_sides,
+            ))
+        })
+        .collect::<Result<Vec<_>, MapError>>()?;
+
+    let things = of_kind("thing")
+        .map(|b| {
+            // translate the flags back into the bits of the THINGS lump
+            let mut options = 0;
+            let bits = [
+                (b.bool(map, "skill1")? || b.bool(map, "skill2")?, 1),
+                (b.bool(map, "skill3")?, 2),
+                (b.bool(map, "skill4")? || b.bool(map, "skill5")?, 4),
+                (b.bool(map, "ambush")?, 8),
+                (!b.bool(map, "single")?, 16),
+                (!b.bool(map, "dm")?, 32),
+                (!b.bool(map, "coop")?, 64),
+                (b.bool(map, "friend")?, 128),
+            ];
+            for &(set, bit) in &bits {
+                if set {
+                    options |= bit;
+                }
+            }
+            Ok(Thing {
+                x: b.float(map, "x")?.ok_or_else(|| b.missing(map, "x"))? as i16,
+                y: b.float(map, "y")?.ok_or_else(|| b.missing(map, "y"))? as i16,
+                angle: b.int(map, "angle")?.unwrap_or(0) as i16,
+                kind: b.int(map, "type")?.ok_or_else(|| b.missing(map, "type"))? as i16,
+                options,
+            })
+        })
+        .collect::<Result<Vec<_>, MapError>>()?;
+
+    Ok(TextMap {
+        namespace,
+        things,
+        vertices,
+        sectors,
+        sides,
+        lines,
+    })
+}
+
+impl Block {
+    fn get(&self, field: &str) -> Option<&Value> {
+        self.fields.get(field)
+    }
+
+    fn error(&self, map: &str, message: String) -> MapError {
+        MapError::Udmf {
+            map: map.to_string(),
+            line: self.line,
+            message,
+        }
+    }
+
+    fn missing(&self, map: &str, field: &str) -> MapError {
+        self.error(map, format!("{} has no {}", self.kind, field))
+    }
+
+    fn wrong_type(&self, map: &str, field: &str, expected: &str) -> MapError {
+        self.error(
+            map,
+            format!("{} {} should be {}", self.kind, field, expected),
+        )
+    }
+
+    fn int(&self, map: &str, field: &str) -> Result<Option<i64>, MapError> {
+        match self.get(field) {
+            None => Ok(None),
+            Some(Value::Int(i)) => Ok(Some(*i)),
+            Some(_) => Err(self.wrong_type(map, field, "an integer")),
+        }
+    }
+
+    fn float(&self, map: &str, field: &str) -> Result<Option<f64>, MapError> {
+        match self.get(field) {
+            None => Ok(None),
+            Some(Value::Float(f)) => Ok(Some(*f)),
+            Some(Value::Int(i)) => Ok(Some(*i as f64)),
+            Some(_) => Err(self.wrong_type(map, field, "a number")),
+        }
+    }
+
+    /// Flags are false unless given.
+    fn bool(&self, map: &str, field: &str) -> Result<bool, MapError> {
+        match self.get(field) {
+            None => Ok(false),
+            Some(Value::Bool(b)) => Ok(*b),
+            Some(_) => Err(self.wrong_type(map, field, "true or false")),
+        }
+    }
+
+    /// Texture names are compared without case, like lump names.
+    fn string(&self, map: &str, field: &str) -> Result<Option<String>, MapError> {
+        match self.get(field) {
+            None => Ok(None),
+            Some(Value::Str(s)) => Ok(Some(s.to_ascii_uppercase())),
+            Some(_) => Err(self.wrong_type(map, field, "a string")),
+        }
+    }
+
+    fn index(&self, map: &str, field: &str, value: i64, limit: usize) -> Result<usize, MapError> {
+        if value < 0 || value as usize >= limit {
+            return Err(self.error(
+                map,
+                format!(
+                    "{} {} is {}, but there are only {}",
+                    self.kind, field, value, limit
+                ),
+            ));
+        }
+        Ok(value as usize)
+    }
+}
+
+struct Lexer<'a> {
+    map: &'a str,
+    text: &'a [u8],
+    pos: usize,
+    line: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn error(&self, message: &str) -> MapError {
+        MapError::Udmf {
+            map: self.map.to_string(),
+            line: self.line,
+            message: message.to_string(),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.pos).copied()
+    }
+
+    /// Skip whitespace and `//` and `/* */` comments.
+    fn skip_space(&mut self) -> Result<(), MapError> {
+        while let Some(c) = self.peek() {
+            if c == b'\n' {
+                self.line += 1;
+                self.pos += 1;
+            } else if c.is_ascii_whitespace() {
+                self.pos += 1;
+            } else if self.text[self.pos..].starts_with(b"//") {
+                while self.peek().is_some_and(|c| c != b'\n') {
+                    self.pos += 1;
+                }
+            } else if self.text[self.pos..].starts_with(b"/*") {
+                self.pos += 2;
+                loop {
+                    match self.peek() {
+                        None => return Err(self.error("unterminated comment")),
+                        Some(b'\n') => self.line += 1,
+                        Some(b'*') if self.text[self.pos..].starts_with(b"*/") => {
+                            self.pos += 2;
+                            break;
+                        }
+                        _ => {}
+                    }
+                    self.pos += 1;
+                }
+            } else {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<Option<Token>, MapError> {
+        self.skip_space()?;
+        let c = match self.peek() {
+            Some(c) => c,
+            None => return Ok(None),
+        };
+        let start = self.pos;
+        if c.is_ascii_alphabetic() || c == b'_' {
+            while self
+                .peek()
+                .is_some_and(|c| c.is_ascii_alphanumeric() || c == b'_')
+            {
+                self.pos += 1;
+            }
+            let word = String::from_utf8_lossy(&self.text[start..self.pos]).into_owned();
+            return Ok(Some(match word.to_ascii_lowercase().as_str() {
+                "true" => Token::Value(Value::Bool(true)),
+                "false" => Token::Value(Value::Bool(false)),
+                _ => Token::Ident(word),
+            }));
+        }
+        if c == b'"' {
+            self.pos += 1;
+            let mut s = vec![];
+            loop {
+                match self.peek() {
+                    None => return Err(self.error("unterminated string")),
+                    Some(b'"') => break,
+                    Some(b'\\') if self.pos + 1 < self.text.len() => {
+                        self.pos += 1;
+                        s.push(self.text[self.pos]);
+                    }
+                    Some(c) => {
+                        if c == b'\n' {
+                            self.line += 1;
+                        }
+                        s.push(c);
+                    }
+                }
+                self.pos += 1;
+            }
+            self.pos += 1;
+            return Ok(Some(Token::Value(Value::Str(
+                String::from_utf8_lossy(&s).into_owned(),
+            ))));
+        }
+        if c.is_ascii_digit() || c == b'-' || c == b'+' || c == b'.' {
+            while self
+                .peek()
+                .is_some_and(|c| c.is_ascii_alphanumeric() || b"+-.".contains(&c))
+            {
+                // a sign is only part of the number at the start or after an
+                // exponent
+                let c = self.text[self.pos];
+                if (c == b'-' || c == b'+')
+                    && self.pos != start
+                    && !matches!(self.text[self.pos - 1], b'e' | b'E')
+                {
+                    break;
+                }
+                self.pos += 1;
+            }
+            let number = String::from_utf8_lossy(&self.text[start..self.pos]).into_owned();
+            return parse_number(&number)
+                .map(|v| Some(Token::Value(v)))
+                .ok_or_else(|| self.error(&format!("bad number {:?}", number)));
+        }
+        self.pos += 1;
+        Ok(Some(Token::Punct(c)))
+    }
+
+    fn value(&mut self) -> Result<Value, MapError> {
+        match self.next()? {
+            Some(Token::Value(value)) => Ok(value),
+            _ => Err(self.error("expected a value")),
+        }
+    }
+
+    fn expect(&mut self, punct: u8) -> Result<(), MapError> {
+        match self.next()? {
+            Some(Token::Punct(c)) if c == punct => Ok(()),
+            _ => Err(self.error(&format!("expected '{}'", punct as char))),
+        }
+    }
+}
+
+/// Parse an integer (decimal, octal with a leading 0, or hex with 0x) or a
+/// float.
+fn parse_number(number: &str) -> Option<Value> {
+    let (negative, digits) = match number.as_bytes().first() {
+        Some(b'-') => (true, &number[1..]),
+        Some(b'+') => (false, &number[1..]),
+        _ => (false, number),
+    };
+    let sign = if negative { -1 } else { 1 };
+    if digits.contains('.') || (!digits.starts_with("0x") && digits.contains(['e', 'E'])) {
+        return number.parse().ok().map(Value::Float);
+    }
+    let value = if let Some(hex) = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        i64::from_str_radix(hex, 16).ok()?
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        i64::from_str_radix(&digits[1..], 8).ok()?
+    } else {
+        digits.parse().ok()?
+    };
+    Some(Value::Int(sign * value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::{tests::load_map, NodeFormat};
+    use crate::wad::{WadBuilder, WadKind};
+
+    /// A 256 unit square room in `namespace`, with a line using the MBF21
+    /// flags and a thing using most of the UDMF thing flags.
+    fn square_room(namespace: &str) -> String {
+        format!(
+            r#"// written by hand
+namespace = "{}";
+/* four corners */
+vertex {{ x = 0; y = 0; }}
+vertex {{ x = 0.0; y = 256.0; }}
+vertex {{ X = 256; Y = 256; }}
+vertex {{ x = 256; y = 0; }}
+sector {{
+    heightceiling = 128; texturefloor = "floor4_8"; textureceiling = "CEIL3_5";
+    lightlevel = 192; special = 0x9; id = 010;
+}}
+sidedef {{ sector = 0; texturemiddle = "STARTAN3"; offsetx = -16; }}
+linedef {{ v1 = 0; v2 = 1; sidefront = 0; blocking = true; id = 7; special = 11; }}
+linedef {{ v1 = 1; v2 = 2; sidefront = 0; blockplayers = true; blocklandmonsters = TRUE; }}
+linedef {{ v1 = 2; v2 = 3; sidefront = 0; sideback = -1; blocking = false; }}
+linedef {{ v1 = 3; v2 = 0; sidefront = 0; comment = "ignored"; }}
+thing {{
+    x = 64.5; y = 64; angle = 90; type = 3004;
+    skill1 = true; skill3 = true; single = true; coop = true; friend = true; ambush = true;
+}}
+unknownblock {{ whatever = 1e3; }}
+"#,
+            namespace
+        )
+    }
+
+    #[test]
+    fn parses_each_supported_namespace() {
+        for namespace in ["doom", "Boom", "mbf21"].iter() {
+            let text = square_room(namespace);
+            let map = parse("MAP01", text.as_bytes()).unwrap();
+            assert_eq!(map.namespace, namespace.to_ascii_lowercase());
+            assert_eq!(map.vertices.len(), 4);
+            assert_eq!(map.vertices[1].y, Fixed::from_int(256));
+            assert_eq!(map.vertices[2].x, Fixed::from_int(256));
+
+            let sector = &map.sectors[0];
+            assert_eq!(sector.floor_height, Fixed::ZERO);
+            assert_eq!(sector.ceiling_height, Fixed::from_int(128));
+            assert_eq!(sector.floor_pic, "FLOOR4_8");
+            assert_eq!(
+                (sector.light_level, sector.special, sector.tag),
+                (192, 9, 8)
+            );
+
+            let side = &map.sides[0];
+            assert_eq!(side.mid_texture, "STARTAN3");
+            assert_eq!(side.top_texture, "-");
+            assert_eq!(side.texture_offset, Fixed::from_int(-16));
+
+            assert_eq!(map.lines.len(), 4);
+            let line = &map.lines[0];
+            assert_eq!(
+                (line.flags, line.special, line.tag),
+                (LineFlags::BLOCKING, 11, 7)
+            );
+            assert_eq!((line.front_sector, line.back_sector), (Some(0), None));
+            assert_eq!(map.lines[1].tag, 0);
+            assert_eq!(map.lines[2].flags, LineFlags::empty());
+            // the MBF21 flags only mean something in its namespace
+            let mbf21_flags = if *namespace == "mbf21" {
+                LineFlags::BLOCK_PLAYERS | LineFlags::BLOCK_LAND_MONSTERS
+            } else {
+                LineFlags::empty()
+            };
+            assert_eq!(map.lines[1].flags, mbf21_flags);
+
+            assert_eq!(
+                map.things,
+                [Thing {
+                    x: 64,
+                    y: 64,
+                    angle: 90,
+                    kind: 3004,
+                    // easy, normal, ambush, not in deathmatch, friend
+                    options: 1 | 2 | 8 | 32 | 128,
+                }]
+            );
+        }
+    }
+
+    #[test]
+    fn other_namespaces_are_unsupported() {
+        let text = square_room("zdoomtranslated");
+        assert!(matches!(
+            parse("MAP01", text.as_bytes()),
+            Err(MapError::UnsupportedFormat { ref format, .. })
+                if format == "UDMF namespace \"zdoomtranslated\""
+        ));
+        assert!(matches!(
+            parse("MAP01", b"vertex { x = 0; y = 0; }"),
+            Err(MapError::Udmf { ref message, .. }) if message == "no namespace given"
+        ));
+    }
+
+    #[test]
+    fn errors_give_the_line() {
+        let check = |text: &str, line: usize, message: &str| match parse("MAP01", text.as_bytes()) {
+            Err(MapError::Udmf {
+                line: l,
+                message: m,
+                ..
+            }) => assert_eq!((l, m.as_str()), (line, message), "{}", text),
+            _ => panic!("{} should not parse", text),
+        };
+        check(
+            "namespace = \"doom\";\n\nvertex { x = 0; }",
+            3,
+            "vertex has no y",
+        );
+        check(
+            "namespace = \"doom\";\nvertex { x = \"0\"; y = 0; }",
+            2,
+            "vertex x should be a number",
+        );
+        check(
+            "namespace = \"doom\";\nsector {\n}\n",
+            2,
+            "sector has no texturefloor",
+        );
+        check(
+            "namespace = \"doom\";\nvertex { x = 0; y = 0 }",
+            2,
+            "expected ';'",
+        );
+        check("namespace = \"doom\";\n/* open", 2, "unterminated comment");
+        check(
+            "namespace = \"doom\";\nvertex { x = 0; y = 0; }\nlinedef { v1 = 0; v2 = 1; sidefront = 0; }",
+            3,
+            "linedef sidefront is 0, but there are only 0",
+        );
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(parse_number("0x1F"), Some(Value::Int(31)));
+        assert_eq!(parse_number("-010"), Some(Value::Int(-8)));
+        assert_eq!(parse_number("+7"), Some(Value::Int(7)));
+        assert_eq!(parse_number("0"), Some(Value::Int(0)));
+        assert_eq!(parse_number("1.5"), Some(Value::Float(1.5)));
+        assert_eq!(parse_number("-2e2"), Some(Value::Float(-200.0)));
+        assert_eq!(parse_number("09"), None);
+    }
+
+    #[test]
+    fn loads_a_udmf_map() {
+        let mut builder = WadBuilder::new(WadKind::Pwad);
+        builder
+            .push("MAP01", vec![])
+            .and_then(|b| b.push("TEXTMAP", square_room("boom").into_bytes()))
+            .and_then(|b| b.push("ENDMAP", vec![]))
+            .unwrap();
+        let map = load_map("udmf-load", &builder, |_| {}).unwrap();
+        assert_eq!(map.namespace.as_deref(), Some("boom"));
+        assert_eq!(map.sectors[0].lines, [0, 1, 2, 3]);
+        // with no ZNODES the nodes are built
+        assert_eq!(map.node_format, NodeFormat::Xnod);
+        assert_eq!(map.subsectors.len(), 1);
+        assert_eq!(map.blockmap.width, 3);
+
+        builder.remove(2);
+        assert!(matches!(
+            load_map("udmf-no-endmap", &builder, |_| {}),
+            Err(MapError::MissingLump { lump: "ENDMAP", .. })
+        ));
+    }
+}
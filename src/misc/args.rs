@@ -1,4 +1,5 @@
 use serde_derive::{Deserialize, Serialize};
+use std::path::Path;
 
 const PARM_RECORD: &str = "-record";
 const PARM_RECORDFROM: &str = "-recordfrom";
@@ -50,6 +51,7 @@ pub trait ArgList {
     ///
     /// For example, `ez_boom sunlust.wad sl01.lmp` will be changed to
     /// `ez_boom -file sunlust.wad -playdemo sl01.lmp` by this function.
+    /// Directories are passed to `-file` so they get mounted as WADs.
     ///
     /// Importantly, this function skips any loose file args that occur
     /// after switches (args starting with '-').
@@ -101,7 +103,7 @@ impl ArgList for Args {
     fn handle_loose_files(&mut self) {
         let mut loose_lmps = LooseArg::new(vec![".lmp"]);
         let mut loose_dehs = LooseArg::new(vec![".deh", ".bex"]);
-        let mut loose_wads = LooseArg::new(vec![".wad", ""]);
+        let mut loose_wads = LooseArg::new(vec![".wad", ".pk3", ".zip", ""]).with_dirs();
         let mut loose_args = [&mut loose_lmps, &mut loose_dehs, &mut loose_wads];
 
         let mut skip = vec![false; self.len()];
@@ -110,10 +112,14 @@ impl ArgList for Args {
                 // quit at first switch
                 break;
             }
-            if let Some(loose) = loose_args
-                .iter_mut()
-                .find(|loose| loose.exts.iter().any(|ext| arg.ends_with(ext)))
-            {
+            let is_dir = Path::new(arg).is_dir();
+            if let Some(loose) = loose_args.iter_mut().find(|loose| {
+                if is_dir {
+                    loose.dirs
+                } else {
+                    loose.exts.iter().any(|ext| arg.ends_with(ext))
+                }
+            }) {
                 loose.list.push(arg.clone());
             }
             skip[i] = true;
@@ -154,11 +160,21 @@ impl ArgList for Args {
 
 struct LooseArg {
     exts: Vec<&'static str>,
+    /// Whether directories belong in this list.
+    dirs: bool,
     list: Vec<String>,
 }
 impl LooseArg {
     fn new(exts: Vec<&'static str>) -> Self {
-        Self { exts, list: vec![] }
+        Self {
+            exts,
+            dirs: false,
+            list: vec![],
+        }
+    }
+    fn with_dirs(mut self) -> Self {
+        self.dirs = true;
+        self
     }
     fn empty(&self) -> bool {
         self.list.is_empty()
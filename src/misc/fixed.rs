@@ -1,2 +1,227 @@
+use serde_derive::{Deserialize, Serialize};
+use std::{
+    fmt,
+    ops::{Add, AddAssign, Div, Mul, Neg, Shl, Shr, Sub, SubAssign},
+};
+
 pub const FRACBITS: usize = 16;
 pub const FRACUNIT: usize = 1 << FRACBITS;
+
+/// A 16.16 fixed point number, the type of every position, distance and
+/// speed in the game.
+///
+/// The arithmetic is exactly vanilla's, so demos stay in sync: addition and
+/// subtraction wrap like 32 bit ints on the x86, and multiplication and
+/// division go through `fixed_mul` and `fixed_div`. Conversions from `f64`
+/// are for tools and setup code only, never for anything a demo depends
+/// on.
+#[derive(
+    Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct Fixed(pub i32);
+
+#[allow(dead_code)]
+impl Fixed {
+    pub const ZERO: Fixed = Fixed(0);
+    pub const UNIT: Fixed = Fixed(1 << FRACBITS);
+    pub const MAX: Fixed = Fixed(i32::MAX);
+    pub const MIN: Fixed = Fixed(i32::MIN);
+
+    /// A whole number of map units.
+    pub const fn from_int(n: i32) -> Self {
+        Fixed(n.wrapping_shl(FRACBITS as u32))
+    }
+
+    /// The whole part, rounded down like `>> FRACBITS`.
+    pub const fn to_int(self) -> i32 {
+        self.0 >> FRACBITS
+    }
+
+    /// Truncates toward zero, and saturates if `f` is out of range.
+    pub fn from_f64(f: f64) -> Self {
+        Fixed((f * FRACUNIT as f64) as i32)
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / FRACUNIT as f64
+    }
+
+    /// Like C's `abs`, `Fixed::MIN` stays negative.
+    pub const fn abs(self) -> Self {
+        Fixed(self.0.wrapping_abs())
+    }
+}
+
+/// Multiply two fixed point numbers, keeping the low 32 bits of the result
+/// like vanilla's `FixedMul`.
+pub fn fixed_mul(a: Fixed, b: Fixed) -> Fixed {
+    Fixed(((a.0 as i64 * b.0 as i64) >> FRACBITS) as i32)
+}
+
+/// Divide two fixed point numbers like prboom's `FixedDiv`. Quotients too
+/// big to fit, division by zero included, saturate to `Fixed::MAX` or
+/// `Fixed::MIN` by the signs of `a` and `b`. The guard compares the
+/// absolute values as unsigned, as prboom does, so `Fixed::MIN` can't get
+/// past it.
+pub fn fixed_div(a: Fixed, b: Fixed) -> Fixed {
+    if (a.0.unsigned_abs() >> 14) >= b.0.unsigned_abs() {
+        Fixed(((a.0 ^ b.0) >> 31) ^ i32::MAX)
+    } else {
+        Fixed((((a.0 as i64) << FRACBITS) / b.0 as i64) as i32)
+    }
+}
+
+impl fmt::Display for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.to_f64().fmt(f)
+    }
+}
+
+impl From<f64> for Fixed {
+    fn from(f: f64) -> Self {
+        Fixed::from_f64(f)
+    }
+}
+
+impl From<Fixed> for f64 {
+    fn from(f: Fixed) -> Self {
+        f.to_f64()
+    }
+}
+
+impl Add for Fixed {
+    type Output = Fixed;
+    fn add(self, rhs: Fixed) -> Fixed {
+        Fixed(self.0.wrapping_add(rhs.0))
+    }
+}
+
+impl AddAssign for Fixed {
+    fn add_assign(&mut self, rhs: Fixed) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Fixed;
+    fn sub(self, rhs: Fixed) -> Fixed {
+        Fixed(self.0.wrapping_sub(rhs.0))
+    }
+}
+
+impl SubAssign for Fixed {
+    fn sub_assign(&mut self, rhs: Fixed) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Fixed;
+    fn neg(self) -> Fixed {
+        Fixed(self.0.wrapping_neg())
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Fixed;
+    fn mul(self, rhs: Fixed) -> Fixed {
+        fixed_mul(self, rhs)
+    }
+}
+
+impl Div for Fixed {
+    type Output = Fixed;
+    fn div(self, rhs: Fixed) -> Fixed {
+        fixed_div(self, rhs)
+    }
+}
+
+/// Scaling by a plain int, wrapping like C.
+impl Mul<i32> for Fixed {
+    type Output = Fixed;
+    fn mul(self, rhs: i32) -> Fixed {
+        Fixed(self.0.wrapping_mul(rhs))
+    }
+}
+
+/// Division by a plain int, rounding toward zero like C.
+impl Div<i32> for Fixed {
+    type Output = Fixed;
+    fn div(self, rhs: i32) -> Fixed {
+        Fixed(self.0.wrapping_div(rhs))
+    }
+}
+
+impl Shl<u32> for Fixed {
+    type Output = Fixed;
+    fn shl(self, rhs: u32) -> Fixed {
+        Fixed(self.0.wrapping_shl(rhs))
+    }
+}
+
+/// An arithmetic shift, like `>>` on a C int.
+impl Shr<u32> for Fixed {
+    type Output = Fixed;
+    fn shr(self, rhs: u32) -> Fixed {
+        Fixed(self.0.wrapping_shr(rhs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ints_round_trip() {
+        assert_eq!(Fixed::from_int(3), Fixed(3 << 16));
+        assert_eq!(Fixed::from_int(-3).to_int(), -3);
+        // to_int rounds down, like a shift
+        assert_eq!(Fixed(-1).to_int(), -1);
+        assert_eq!(Fixed(Fixed::UNIT.0 - 1).to_int(), 0);
+    }
+
+    #[test]
+    fn mul_keeps_the_low_bits() {
+        assert_eq!(
+            fixed_mul(Fixed::from_int(3), Fixed::from_int(4)),
+            Fixed::from_int(12)
+        );
+        assert_eq!(
+            fixed_mul(Fixed::from_int(-3), Fixed::UNIT / 2),
+            Fixed(-3 << 15)
+        );
+        // rounds toward minus infinity, like the shift in FixedMul
+        assert_eq!(fixed_mul(Fixed(-1), Fixed(1)), Fixed(-1));
+        // overflow wraps to the low 32 bits
+        let big = fixed_mul(Fixed::from_int(0x8000), Fixed::from_int(2));
+        assert_eq!(big, Fixed(0));
+    }
+
+    #[test]
+    fn div_saturates() {
+        assert_eq!(
+            fixed_div(Fixed::from_int(12), Fixed::from_int(4)),
+            Fixed::from_int(3)
+        );
+        assert_eq!(
+            fixed_div(Fixed::from_int(-1), Fixed::from_int(4)),
+            Fixed(-(1 << 14))
+        );
+        assert_eq!(fixed_div(Fixed::UNIT, Fixed::ZERO), Fixed::MAX);
+        assert_eq!(fixed_div(-Fixed::UNIT, Fixed::ZERO), Fixed::MIN);
+        assert_eq!(fixed_div(Fixed::MIN, Fixed(1)), Fixed::MIN);
+        assert_eq!(fixed_div(Fixed::from_int(0x4000), Fixed::UNIT), Fixed::MAX);
+    }
+
+    #[test]
+    fn ops_wrap_like_c() {
+        assert_eq!(Fixed::MAX + Fixed(1), Fixed::MIN);
+        assert_eq!(Fixed::MIN - Fixed(1), Fixed::MAX);
+        assert_eq!(-Fixed::MIN, Fixed::MIN);
+        assert_eq!(Fixed::MIN.abs(), Fixed::MIN);
+        assert_eq!(Fixed(-7) / 2, Fixed(-3));
+        assert_eq!(Fixed(-7) >> 1, Fixed(-4));
+        assert_eq!(Fixed::from_int(3) * 2, Fixed::from_int(6));
+    }
+}
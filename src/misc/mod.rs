@@ -15,6 +15,7 @@ use crate::configuration::Configuration;
 pub mod args;
 pub mod fixed;
 pub mod lprint;
+pub mod random;
 
 pub const BOOM_CFG: &str = "ezboom.cfg";
 
@@ -0,0 +1,302 @@
+use crate::configuration::{CompatibilityLevel, Configuration, DemoInsurance};
+use serde_derive::{Deserialize, Serialize};
+
+/// Vanilla's random numbers. Every demo, savegame and netgame depends on
+/// these, in this order.
+#[rustfmt::skip]
+const RNDTABLE: [u8; 256] = [
+    0,   8, 109, 220, 222, 241, 149, 107,  75, 248, 254, 140,  16,  66,
+    74,  21, 211,  47,  80, 242, 154,  27, 205, 128, 161,  89,  77,  36,
+    95, 110,  85,  48, 212, 140, 211, 249,  22,  79, 200,  50,  28, 188,
+    52, 140, 202, 120,  68, 145,  62,  70, 184, 190,  91, 197, 152, 224,
+    149, 104,  25, 178, 252, 182, 202, 182, 141, 197,   4,  81, 181, 242,
+    145,  42,  39, 227, 156, 198, 225, 193, 219,  93, 122, 175, 249,   0,
+    175, 143,  70, 239,  46, 246, 163,  53, 163, 109, 168, 135,   2, 235,
+    25,  92,  20, 145, 138,  77,  69, 166,  78, 176, 173, 212, 166, 113,
+    94, 161,  41,  50, 239,  49, 111, 164,  70,  60,   2,  37, 171,  75,
+    136, 156,  11,  56,  42, 146, 138, 229,  73, 146,  77,  61,  98, 196,
+    135, 106,  63, 197, 195,  86,  96, 203, 113, 101, 170, 247, 181, 113,
+    80, 250, 108,   7, 255, 237, 129, 226,  79, 107, 112, 166, 103, 241,
+    24, 223, 239, 120, 198,  58,  60,  82, 128,   3, 184,  66, 143, 224,
+    145, 224,  81, 206, 163,  45,  63,  90, 168, 114,  59,  33, 159,  95,
+    28, 139, 123,  98, 125, 196,  15,  70, 194, 253,  54,  14, 109, 226,
+    71,  17, 161,  93, 186,  87, 244, 138,  20,  52, 123, 251,  26,  36,
+    17,  46,  52, 231, 232,  76,  31, 221,  84,  37, 216, 165, 212, 106,
+    197, 242,  98,  43,  39, 175, 254, 145, 190,  84, 118, 222, 187, 136,
+    120, 163, 236, 249,
+];
+
+/// What a random number is for. From Boom on each class has its own
+/// stream, so that adding a call in one place doesn't change the numbers
+/// everything else gets.
+///
+/// The order matters: the class number is part of each stream's seed.
+#[allow(dead_code)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PrClass {
+    SkullFly,
+    Damage,
+    Crush,
+    GenLift,
+    KillTics,
+    DamageMobj,
+    PainChance,
+    Lights,
+    Explode,
+    Respawn,
+    LastLook,
+    SpawnThing,
+    SpawnPuff,
+    SpawnBlood,
+    Missile,
+    Shadow,
+    Plats,
+    Punch,
+    PunchAngle,
+    Saw,
+    Plasma,
+    Gunshot,
+    Misfire,
+    Shotgun,
+    Bfg,
+    SlimeHurt,
+    DmSpawn,
+    MissRange,
+    TryWalk,
+    NewChase,
+    NewChaseDir,
+    See,
+    FaceTarget,
+    PosAttack,
+    SPosAttack,
+    CPosAttack,
+    SpidReFire,
+    TroopAttack,
+    SargAttack,
+    HeadAttack,
+    BruisAttack,
+    Tracer,
+    SkelFist,
+    Scream,
+    BrainScream,
+    CPosReFire,
+    BrainExp,
+    SpawnFly,
+    /// Anything that doesn't affect the game, so it's never merged into
+    /// `AllInOne`.
+    Misc,
+    /// The one stream every other class shares without demo insurance.
+    AllInOne,
+    // MBF's classes
+    OpenDoor,
+    TargetSearch,
+    Friends,
+    Threshold,
+    SkipTarget,
+    EnemyStrafe,
+    AvoidCrush,
+    StayOnLift,
+    HelpFriend,
+    DropOff,
+    RandomJump,
+    Defect,
+}
+
+pub const NUMPRCLASS: usize = PrClass::Defect as usize + 1;
+
+/// The state of both generators, which savegames and netgames have to
+/// carry along.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rng {
+    pub seed: Vec<u32>,
+    /// The `RNDTABLE` position for `PrClass::Misc` at vanilla complevels,
+    /// like vanilla's `rndindex` for `M_Random`.
+    pub rndindex: u8,
+    /// The `RNDTABLE` position for every other class at vanilla
+    /// complevels, like vanilla's `prndindex` for `P_Random`.
+    pub prndindex: u8,
+}
+
+impl Default for Rng {
+    /// Every stream seeded with 0, as before `clear_random` is first
+    /// called.
+    fn default() -> Self {
+        Self {
+            seed: vec![0; NUMPRCLASS],
+            rndindex: 0,
+            prndindex: 0,
+        }
+    }
+}
+
+/// A random number from 0 to 255 for things that don't affect the game,
+/// like the menus and the status bar face, so using it never desyncs a
+/// demo. Like prboom's `M_Random`, it's `p_random` of `PrClass::Misc`.
+#[allow(dead_code)]
+pub fn m_random(configuration: &mut Configuration) -> i32 {
+    p_random(configuration, PrClass::Misc)
+}
+
+/// A random number from 0 to 255 for game logic.
+///
+/// Before Boom this is the next entry of `RNDTABLE`, like vanilla, with
+/// `PrClass::Misc` keeping its own place in the table as `M_Random` did.
+/// From Boom on, MBF included, the number comes from the class's own
+/// stream, or from `PrClass::AllInOne` when demo insurance is off; with
+/// insurance the tics since the level started are mixed in.
+pub fn p_random(configuration: &mut Configuration, class: PrClass) -> i32 {
+    let insurance = configuration.demo_insurance != DemoInsurance::None;
+    let misc = class == PrClass::Misc;
+    let class = if !misc && !insurance {
+        PrClass::AllInOne
+    } else {
+        class
+    };
+    let rng = &mut configuration.rng;
+
+    // killough 2/16/98: We always update both sets of random number
+    // generators, to ensure repeatability if the demo_compatibility flag
+    // is changed while the program is running.
+    let index = if misc {
+        rng.rndindex = rng.rndindex.wrapping_add(1);
+        rng.rndindex
+    } else {
+        rng.prndindex = rng.prndindex.wrapping_add(1);
+        rng.prndindex
+    };
+    let boom = rng.seed[class as usize];
+    rng.seed[class as usize] = boom
+        .wrapping_mul(1664525)
+        .wrapping_add(221297)
+        .wrapping_add(class as u32 * 2);
+
+    if configuration.compatibility_level < CompatibilityLevel::Boom {
+        return RNDTABLE[index as usize] as i32;
+    }
+
+    let mut boom = boom >> 20;
+    if insurance {
+        let tics = configuration.gametic.wrapping_sub(configuration.basetic);
+        boom = boom.wrapping_add((tics as u32).wrapping_mul(7));
+    }
+    (boom & 255) as i32
+}
+
+/// The difference of two random numbers, from -255 to 255, drawn in that
+/// order.
+pub fn p_sub_random(configuration: &mut Configuration, class: PrClass) -> i32 {
+    let first = p_random(configuration, class);
+    first - p_random(configuration, class)
+}
+
+/// Reset both generators, seeding every class's stream from `rngseed`.
+/// Games, demos and netgames start from this, so the same seed always
+/// gives the same numbers.
+pub fn clear_random(configuration: &mut Configuration) {
+    let mut seed = configuration.rngseed.wrapping_mul(2).wrapping_add(1);
+    configuration.rng = Rng {
+        seed: (0..NUMPRCLASS)
+            .map(|_| {
+                seed = seed.wrapping_mul(69069);
+                seed
+            })
+            .collect(),
+        rndindex: 0,
+        prndindex: 0,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn configuration(cl: CompatibilityLevel, insurance: DemoInsurance) -> Configuration {
+        let mut configuration = Configuration {
+            compatibility_level: cl,
+            demo_insurance: insurance,
+            ..Configuration::default()
+        };
+        clear_random(&mut configuration);
+        configuration
+    }
+
+    #[test]
+    fn vanilla_walks_the_table() {
+        let mut c = configuration(CompatibilityLevel::Doom2V19, DemoInsurance::None);
+        let got: Vec<i32> = (0..4).map(|_| p_random(&mut c, PrClass::Damage)).collect();
+        assert_eq!(got, [8, 109, 220, 222]);
+        // the index wraps around to the start of the table
+        for _ in 4..255 {
+            p_random(&mut c, PrClass::Damage);
+        }
+        assert_eq!(p_random(&mut c, PrClass::Damage), 0);
+        assert_eq!(p_random(&mut c, PrClass::Damage), 8);
+    }
+
+    #[test]
+    fn vanilla_misc_keeps_its_own_index() {
+        let mut c = configuration(CompatibilityLevel::Doom2V19, DemoInsurance::None);
+        assert_eq!(p_random(&mut c, PrClass::Damage), 8);
+        assert_eq!(m_random(&mut c), 8);
+        assert_eq!(m_random(&mut c), 109);
+        assert_eq!(p_random(&mut c, PrClass::Damage), 109);
+        assert_eq!((c.rng.rndindex, c.rng.prndindex), (2, 2));
+    }
+
+    #[test]
+    fn default_rng_can_be_used_before_clear_random() {
+        let mut c = Configuration {
+            compatibility_level: CompatibilityLevel::Mbf,
+            ..Configuration::default()
+        };
+        assert_eq!(c.rng.seed.len(), NUMPRCLASS);
+        // every stream starts from a seed of 0, and the misc stream is
+        // apart from the others
+        assert_eq!(p_random(&mut c, PrClass::Damage), 0);
+        assert_eq!(m_random(&mut c), 0);
+        assert_eq!(p_random(&mut c, PrClass::Damage), 0);
+        assert_eq!(m_random(&mut c), 0);
+        assert_eq!(p_random(&mut c, PrClass::Damage), 61);
+        assert_eq!(p_random(&mut c, PrClass::Damage), 174);
+    }
+
+    #[test]
+    fn boom_streams_repeat_from_the_seed() {
+        let cl = CompatibilityLevel::Boom;
+        let mut a = configuration(cl, DemoInsurance::None);
+        let mut b = configuration(cl, DemoInsurance::None);
+        let first: Vec<i32> = (0..16).map(|_| p_random(&mut a, PrClass::Damage)).collect();
+        let second: Vec<i32> = (0..16)
+            .map(|_| p_random(&mut b, PrClass::Shotgun))
+            .collect();
+        // without insurance every class but misc shares one stream
+        assert_eq!(first, second);
+        assert!(first.iter().all(|n| (0..256).contains(n)));
+
+        b.rngseed = 42;
+        clear_random(&mut b);
+        let other: Vec<i32> = (0..16).map(|_| p_random(&mut b, PrClass::Damage)).collect();
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn boom_insurance_keeps_classes_apart() {
+        let cl = CompatibilityLevel::Boom;
+        let mut a = configuration(cl, DemoInsurance::Always);
+        let mut b = configuration(cl, DemoInsurance::Always);
+        let expected: Vec<i32> = (0..8).map(|_| p_random(&mut a, PrClass::Damage)).collect();
+        let got: Vec<i32> = (0..8)
+            .map(|_| {
+                p_random(&mut b, PrClass::Shotgun);
+                p_random(&mut b, PrClass::Damage)
+            })
+            .collect();
+        assert_eq!(expected, got);
+    }
+
+    #[test]
+    fn sub_random_draws_in_order() {
+        let mut c = configuration(CompatibilityLevel::Doom2V19, DemoInsurance::None);
+        assert_eq!(p_sub_random(&mut c, PrClass::Damage), 8 - 109);
+    }
+}
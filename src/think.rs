@@ -1 +1,213 @@
-pub type ActionF = fn();
+use crate::logic::Context;
+
+/// A handle to a thinker on a `Thinkers` list. Handles stay valid until the
+/// thinker is removed, and then never match whatever reuses its slot, so
+/// mobjs can keep their target or tracer around safely.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ThinkerId {
+    index: u32,
+    generation: u32,
+}
+
+/// The function a state runs when a mobj or player sprite enters it, like
+/// vanilla's `actionf_t`.
+#[derive(Copy, Clone, Default)]
+pub enum ActionF {
+    #[default]
+    Null,
+    /// Acts on a mobj, e.g. `A_Chase`.
+    Mobj(fn(&mut Context, ThinkerId)),
+    /// Acts on a player's sprite, given the player and psprite numbers,
+    /// e.g. `A_WeaponReady`.
+    PSprite(fn(&mut Context, usize, usize)),
+}
+
+impl std::fmt::Debug for ActionF {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ActionF::Null => "Null",
+            ActionF::Mobj(_) => "Mobj",
+            ActionF::PSprite(_) => "PSprite",
+        })
+    }
+}
+
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+    /// Removed thinkers stay linked until `run_thinkers` gets to them, so
+    /// removing one mid-tic never breaks the walk.
+    removed: bool,
+    prev: Option<u32>,
+    next: Option<u32>,
+}
+
+/// The thinker list. Thinkers run in the order they were added, which
+/// demos depend on; new ones go on the end, so something spawned during a
+/// tic thinks in that same tic, as in vanilla.
+pub struct Thinkers<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+    head: Option<u32>,
+    tail: Option<u32>,
+}
+
+impl<T> Default for Thinkers<T> {
+    fn default() -> Self {
+        Self {
+            slots: vec![],
+            free: vec![],
+            head: None,
+            tail: None,
+        }
+    }
+}
+
+impl<T> Thinkers<T> {
+    /// Remove every thinker at once, for starting a level. Old handles
+    /// stop matching anything.
+    #[allow(dead_code)]
+    pub fn clear(&mut self) {
+        for (i, slot) in self.slots.iter_mut().enumerate() {
+            if slot.value.is_some() {
+                slot.value = None;
+                slot.generation = slot.generation.wrapping_add(1);
+                self.free.push(i as u32);
+            }
+        }
+        self.head = None;
+        self.tail = None;
+    }
+
+    /// Put a thinker on the end of the list.
+    pub fn add(&mut self, value: T) -> ThinkerId {
+        let slot = Slot {
+            generation: 0,
+            value: Some(value),
+            removed: false,
+            prev: self.tail,
+            next: None,
+        };
+        let index = match self.free.pop() {
+            Some(index) => {
+                let generation = self.slots[index as usize].generation;
+                self.slots[index as usize] = Slot { generation, ..slot };
+                index
+            }
+            None => {
+                self.slots.push(slot);
+                self.slots.len() as u32 - 1
+            }
+        };
+        match self.tail {
+            Some(tail) => self.slots[tail as usize].next = Some(index),
+            None => self.head = Some(index),
+        }
+        self.tail = Some(index);
+        ThinkerId {
+            index,
+            generation: self.slots[index as usize].generation,
+        }
+    }
+
+    /// Take a thinker off the list. Its handle stops working straight away,
+    /// but it's only unlinked the next time `run_thinkers` passes it.
+    pub fn remove(&mut self, id: ThinkerId) {
+        if let Some(slot) = self.slot_mut(id) {
+            slot.removed = true;
+        }
+    }
+
+    fn slot(&self, id: ThinkerId) -> Option<&Slot<T>> {
+        self.slots
+            .get(id.index as usize)
+            .filter(|s| s.generation == id.generation && s.value.is_some())
+    }
+
+    fn slot_mut(&mut self, id: ThinkerId) -> Option<&mut Slot<T>> {
+        self.slots
+            .get_mut(id.index as usize)
+            .filter(|s| s.generation == id.generation && s.value.is_some())
+    }
+
+    #[allow(dead_code)]
+    pub fn contains(&self, id: ThinkerId) -> bool {
+        self.get(id).is_some()
+    }
+
+    pub fn get(&self, id: ThinkerId) -> Option<&T> {
+        self.slot(id)
+            .filter(|s| !s.removed)
+            .and_then(|s| s.value.as_ref())
+    }
+
+    pub fn get_mut(&mut self, id: ThinkerId) -> Option<&mut T> {
+        self.slot_mut(id)
+            .filter(|s| !s.removed)
+            .and_then(|s| s.value.as_mut())
+    }
+
+    /// The thinkers still on the list, in order.
+    pub fn iter(&self) -> impl Iterator<Item = (ThinkerId, &T)> + '_ {
+        let mut cursor = self.head;
+        std::iter::from_fn(move || {
+            while let Some(index) = cursor {
+                let slot = &self.slots[index as usize];
+                cursor = slot.next;
+                if !slot.removed {
+                    let id = ThinkerId {
+                        index,
+                        generation: slot.generation,
+                    };
+                    return slot.value.as_ref().map(|v| (id, v));
+                }
+            }
+            None
+        })
+    }
+
+    /// Unlink a removed thinker and free its slot.
+    fn unlink(&mut self, id: ThinkerId) {
+        let (prev, next) = {
+            let slot = &mut self.slots[id.index as usize];
+            slot.value = None;
+            slot.generation = slot.generation.wrapping_add(1);
+            (slot.prev, slot.next)
+        };
+        match prev {
+            Some(prev) => self.slots[prev as usize].next = next,
+            None => self.head = next,
+        }
+        match next {
+            Some(next) => self.slots[next as usize].prev = prev,
+            None => self.tail = prev,
+        }
+        self.free.push(id.index);
+    }
+}
+
+/// Run every thinker on the list once, and unlink the removed ones, like
+/// vanilla's `P_RunThinkers`. `list` finds the list in the context, since
+/// thinkers are free to add and remove others while they run.
+pub fn run_thinkers<C, T>(
+    context: &mut C,
+    list: fn(&mut C) -> &mut Thinkers<T>,
+    think: fn(&mut C, ThinkerId),
+) {
+    let mut cursor = list(context).head;
+    while let Some(index) = cursor {
+        let thinkers = list(context);
+        let id = ThinkerId {
+            index,
+            generation: thinkers.slots[index as usize].generation,
+        };
+        if thinkers.slots[index as usize].removed {
+            cursor = thinkers.slots[index as usize].next;
+            thinkers.unlink(id);
+        } else {
+            think(context, id);
+            // the thinker may have removed itself, but it's still linked
+            cursor = list(context).slots[index as usize].next;
+        }
+    }
+}
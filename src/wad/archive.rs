@@ -0,0 +1,62 @@
+use super::{normalize_lump_name, LumpNamespace};
+
+/// A lump found inside an archive (a PK3 or a directory), before it is
+/// added to the lump directory.
+pub struct ArchiveLump {
+    pub name: [u8; 8],
+    pub namespace: LumpNamespace,
+    /// Index into the archive's own entry table, used to read the lump back.
+    pub entry: usize,
+    pub size: usize,
+}
+
+/// What to do with a file found in an archive, based on the top level
+/// directory it is in.
+pub enum ArchivePlacement {
+    /// Add the file as a single lump in this namespace.
+    Lump(LumpNamespace),
+    /// The file is a WAD holding a map; add each of its lumps.
+    MapWad,
+    /// The file can't be looked up by lump name.
+    Skip,
+}
+
+/// Map a path inside an archive, with `/` separators, onto the namespaces
+/// used by WAD markers:
+///
+/// * `sprites/` goes into the sprite namespace, like lumps between `S_START`
+///   and `S_END` in a WAD.
+/// * `flats/` goes into the flat namespace.
+/// * `colormaps/` goes into the colormap namespace.
+/// * Every WAD in `maps/` is opened and its lumps are added in order, so
+///   `maps/map01.wad` provides `MAP01`, `THINGS`, `LINEDEFS`, ...; other
+///   files there are skipped.
+/// * Everything else, from `sounds/`, `music/`, `graphics/`, `patches/`,
+///   `textures/`, `hires/` or any other directory, or from the root, goes
+///   into the global namespace.
+pub fn placement(path: &str) -> ArchivePlacement {
+    let (top, file_name) = match (path.find('/'), path.rfind('/')) {
+        (Some(first), Some(last)) => (path[..first].to_ascii_lowercase(), &path[last + 1..]),
+        _ => (String::new(), path),
+    };
+    match top.as_str() {
+        "sprites" => ArchivePlacement::Lump(LumpNamespace::Sprites),
+        "flats" => ArchivePlacement::Lump(LumpNamespace::Flats),
+        "colormaps" => ArchivePlacement::Lump(LumpNamespace::Colormaps),
+        "maps" if file_name.to_ascii_lowercase().ends_with(".wad") => ArchivePlacement::MapWad,
+        "maps" => ArchivePlacement::Skip,
+        _ => ArchivePlacement::Lump(LumpNamespace::Global),
+    }
+}
+
+/// The lump name for a file in an archive: its name up to the first `.`.
+/// Returns `None` for names that don't fit in 8 ASCII characters.
+pub fn lump_name(path: &str) -> Option<[u8; 8]> {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    let stem = file_name.split('.').next().unwrap_or("");
+    if stem.is_empty() || stem.len() > 8 || !stem.is_ascii() {
+        None
+    } else {
+        Some(normalize_lump_name(stem.as_bytes()))
+    }
+}
@@ -0,0 +1,358 @@
+use super::{
+    archive::ArchiveLump, folder::FolderArchive, pk3::Pk3Archive, read_directory, WadError,
+    WadFileInfo, WadSource,
+};
+use rustc_hash::FxHashMap;
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::Path,
+};
+
+/// The marker range a lump was found in. Lumps between `S_START` and
+/// `S_END` are sprites, lumps between `F_START` and `F_END` are flats, and
+/// so on. Lookups only match lumps in the requested namespace, so a flat
+/// and a wall patch may share a name.
+#[allow(dead_code)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum LumpNamespace {
+    Global,
+    Sprites,
+    Flats,
+    Colormaps,
+}
+
+/// A single entry in the merged lump directory.
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct LumpInfo {
+    /// Upper case, NUL padded.
+    pub name: [u8; 8],
+    pub size: usize,
+    /// Byte offset for lumps in WADs, entry index for lumps in archives.
+    pub position: usize,
+    /// Index into the `wad_files` list the directory was built from.
+    pub wadfile: usize,
+    pub namespace: LumpNamespace,
+    pub source: WadSource,
+}
+
+impl LumpInfo {
+    /// The lump name without its NUL padding.
+    pub fn name_str(&self) -> &str {
+        let len = self.name.iter().position(|&c| c == 0).unwrap_or(8);
+        std::str::from_utf8(&self.name[..len]).unwrap_or("")
+    }
+}
+
+/// Where the lumps of one loaded file are read from.
+enum Container {
+    /// A WAD, or a plain file loaded as a single lump.
+    File(File),
+    /// A zip archive.
+    Pk3(Pk3Archive),
+    /// A directory of loose files.
+    Folder(FolderArchive),
+}
+
+/// Every lump from every loaded file, merged in load order.
+///
+/// When two files contain a lump with the same name in the same namespace,
+/// lookups return the one loaded last. This is what lets a PWAD replace
+/// resources from the IWAD.
+#[derive(Default)]
+pub struct LumpDirectory {
+    /// Parallel to the `wad_files` list, `None` for skipped files.
+    files: Vec<Option<Container>>,
+    lumps: Vec<LumpInfo>,
+    hash: FxHashMap<(LumpNamespace, [u8; 8]), usize>,
+}
+
+/// Convert a lump name to the form stored in the directory: upper case,
+/// truncated to 8 characters and padded with NUL bytes.
+#[allow(dead_code)]
+pub fn normalize_lump_name(name: &[u8]) -> [u8; 8] {
+    let mut result = [0u8; 8];
+    for (dst, &src) in result.iter_mut().zip(name.iter().take_while(|&&c| c != 0)) {
+        *dst = src.to_ascii_uppercase();
+    }
+    result
+}
+
+#[allow(dead_code)]
+impl LumpDirectory {
+    /// Open every file in `wad_files` and build the lump directory. Each
+    /// file's `handle` is set to its index in `wad_files` if it was opened,
+    /// or -1 if it was skipped.
+    ///
+    /// `.pk3` and `.zip` files are mounted as archives, see `Pk3Archive`, and
+    /// directories are mounted the same way, see `FolderArchive`. Other
+    /// files that are not WADs are added as a single lump named after the
+    /// file, like vanilla does for `-file foo.lmp`. Missing `.gwa` files are
+    /// skipped silently.
+    pub fn open(wad_files: &mut [WadFileInfo]) -> Result<Self, WadError> {
+        let mut directory = Self::default();
+        for (i, wadfile) in wad_files.iter_mut().enumerate() {
+            wadfile.handle = -1;
+            let name = &wadfile.name;
+            if has_extension(name, "gwa") && !name.exists() {
+                directory.files.push(None);
+                continue;
+            }
+
+            let container = if name.is_dir() {
+                FolderArchive::open(name).map(|(archive, lumps)| {
+                    directory.add_archive_lumps(i, wadfile, lumps);
+                    Container::Folder(archive)
+                })
+            } else if has_extension(name, "pk3") || has_extension(name, "zip") {
+                Pk3Archive::open(name).map(|(archive, lumps)| {
+                    directory.add_archive_lumps(i, wadfile, lumps);
+                    Container::Pk3(archive)
+                })
+            } else {
+                directory.add_file(i, wadfile)
+            }
+            .map_err(|e| e.in_file(&wadfile.name))?;
+
+            wadfile.handle = i as i32;
+            directory.files.push(Some(container));
+        }
+        directory.hash_lumps();
+        Ok(directory)
+    }
+
+    /// Add the lumps of a WAD, or a single lump for any other kind of file.
+    fn add_file(&mut self, i: usize, wadfile: &WadFileInfo) -> Result<Container, WadError> {
+        let mut file = File::open(&wadfile.name)?;
+        let first_lump = self.lumps.len();
+        if has_extension(&wadfile.name, "wad") || has_extension(&wadfile.name, "gwa") {
+            let (_, fileinfo) = read_directory(&mut file)?;
+            for lump in fileinfo {
+                self.lumps.push(LumpInfo {
+                    name: normalize_lump_name(&lump.name),
+                    size: lump.size as usize,
+                    position: lump.filepos as usize,
+                    wadfile: i,
+                    namespace: LumpNamespace::Global,
+                    source: wadfile.src,
+                });
+            }
+            self.mark_namespaces(first_lump);
+        } else {
+            // single lump file
+            let stem = wadfile
+                .name
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("");
+            self.lumps.push(LumpInfo {
+                name: normalize_lump_name(stem.as_bytes()),
+                size: file.metadata()?.len() as usize,
+                position: 0,
+                wadfile: i,
+                namespace: LumpNamespace::Global,
+                source: wadfile.src,
+            });
+        }
+        Ok(Container::File(file))
+    }
+
+    /// Add the lumps listed by an archive. Their namespaces come from the
+    /// archive's layout rather than from markers.
+    fn add_archive_lumps(&mut self, i: usize, wadfile: &WadFileInfo, lumps: Vec<ArchiveLump>) {
+        for lump in lumps {
+            self.lumps.push(LumpInfo {
+                name: lump.name,
+                size: lump.size,
+                position: lump.entry,
+                wadfile: i,
+                namespace: lump.namespace,
+                source: wadfile.src,
+            });
+        }
+    }
+
+    /// Assign namespaces to the lumps added since `first_lump` based on the
+    /// `X_START`/`X_END` markers around them. The markers themselves stay in
+    /// the global namespace.
+    fn mark_namespaces(&mut self, first_lump: usize) {
+        let mut current = LumpNamespace::Global;
+        for lump in &mut self.lumps[first_lump..] {
+            let name = lump.name_str();
+            let marker = match name {
+                "S_START" | "SS_START" => Some(LumpNamespace::Sprites),
+                "F_START" | "FF_START" => Some(LumpNamespace::Flats),
+                "C_START" => Some(LumpNamespace::Colormaps),
+                "S_END" | "SS_END" | "F_END" | "FF_END" | "C_END" => Some(LumpNamespace::Global),
+                _ => None,
+            };
+            match marker {
+                Some(ns) => current = ns,
+                // 0-size lumps inside a range are nested markers like F1_START
+                None if lump.size > 0 => lump.namespace = current,
+                None => {}
+            }
+        }
+    }
+
+    fn hash_lumps(&mut self) {
+        self.hash.clear();
+        for (i, lump) in self.lumps.iter().enumerate() {
+            // later lumps replace earlier ones
+            self.hash.insert((lump.namespace, lump.name), i);
+        }
+    }
+
+    /// The number of lumps in the directory.
+    pub fn len(&self) -> usize {
+        self.lumps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lumps.is_empty()
+    }
+
+    /// Information about the lump at `lump`.
+    pub fn lump(&self, lump: usize) -> &LumpInfo {
+        &self.lumps[lump]
+    }
+
+    /// Look up a lump in the global namespace. Returns `None` if no loaded
+    /// file contains it.
+    pub fn check_num_for_name(&self, name: &str) -> Option<usize> {
+        self.check_num_for_name_ns(name, LumpNamespace::Global)
+    }
+
+    /// Look up a lump in a specific namespace.
+    pub fn check_num_for_name_ns(&self, name: &str, namespace: LumpNamespace) -> Option<usize> {
+        self.hash
+            .get(&(namespace, normalize_lump_name(name.as_bytes())))
+            .copied()
+    }
+
+    /// Look up a lump in the global namespace, quitting the program if it
+    /// is missing. Only use this for lumps the game can't run without.
+    pub fn num_for_name(&self, name: &str) -> usize {
+        self.check_num_for_name(name)
+            .unwrap_or_else(|| crate::error(format!("num_for_name: {} not found", name)))
+    }
+
+    /// The size of a lump in bytes.
+    pub fn lump_length(&self, lump: usize) -> usize {
+        self.lumps[lump].size
+    }
+
+    /// Read the contents of a lump into memory.
+    pub fn read_lump(&self, lump: usize) -> Result<Vec<u8>, WadError> {
+        let info = &self.lumps[lump];
+        match self.files[info.wadfile]
+            .as_ref()
+            .expect("lump belongs to a file that was not opened")
+        {
+            Container::File(file) => {
+                let mut file = file;
+                let mut data = vec![0; info.size];
+                file.seek(SeekFrom::Start(info.position as u64))?;
+                file.read_exact(&mut data)?;
+                Ok(data)
+            }
+            Container::Pk3(archive) => archive.read(info.position),
+            Container::Folder(archive) => archive.read(info.position),
+        }
+    }
+}
+
+fn has_extension(path: &Path, ext: &str) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case(ext))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wad::{test_path, WadBuilder, WadKind};
+    use std::path::PathBuf;
+
+    fn wad_file(name: PathBuf, src: WadSource) -> WadFileInfo {
+        WadFileInfo {
+            name,
+            src,
+            handle: 0,
+        }
+    }
+
+    /// Open an IWAD with marker ranges and a PWAD replacing one of its lumps.
+    /// `prefix` keeps the files of tests running in parallel apart.
+    fn open_test_wads(prefix: &str) -> LumpDirectory {
+        let iwad = test_path(&format!("{}-iwad.wad", prefix));
+        WadBuilder::new(WadKind::Iwad)
+            .push("PLAYPAL", b"iwad".to_vec())
+            .and_then(|b| b.push("TROOA1", b"global".to_vec()))
+            .and_then(|b| b.push("S_START", vec![]))
+            .and_then(|b| b.push("TROOA1", b"sprite".to_vec()))
+            .and_then(|b| b.push("S_END", vec![]))
+            .and_then(|b| b.push("F_START", vec![]))
+            .and_then(|b| b.push("F1_START", vec![]))
+            .and_then(|b| b.push("FLOOR0_1", b"flat".to_vec()))
+            .and_then(|b| b.push("F_END", vec![]))
+            .and_then(|b| b.write_to_file(&iwad))
+            .unwrap();
+        let pwad = test_path(&format!("{}-pwad.wad", prefix));
+        WadBuilder::new(WadKind::Pwad)
+            .push("playpal", b"pwad".to_vec())
+            .and_then(|b| b.write_to_file(&pwad))
+            .unwrap();
+
+        let mut wad_files = vec![
+            wad_file(iwad.clone(), WadSource::Iwad),
+            wad_file(pwad.clone(), WadSource::Pwad),
+            wad_file(
+                test_path(&format!("{}-missing.gwa", prefix)),
+                WadSource::Pwad,
+            ),
+        ];
+        let directory = LumpDirectory::open(&mut wad_files).unwrap();
+        assert_eq!(
+            wad_files.iter().map(|w| w.handle).collect::<Vec<_>>(),
+            [0, 1, -1]
+        );
+        std::fs::remove_file(iwad).unwrap();
+        std::fs::remove_file(pwad).unwrap();
+        directory
+    }
+
+    #[test]
+    fn later_files_override_earlier_ones() {
+        let directory = open_test_wads("override");
+        assert_eq!(directory.len(), 10);
+        let playpal = directory.check_num_for_name("PlayPal").unwrap();
+        assert_eq!(playpal, 9);
+        assert_eq!(directory.lump(playpal).source, WadSource::Pwad);
+        assert_eq!(directory.read_lump(playpal).unwrap(), b"pwad");
+        assert_eq!(directory.read_lump(0).unwrap(), b"iwad");
+        assert!(directory.check_num_for_name("DEMO1").is_none());
+    }
+
+    #[test]
+    fn markers_put_lumps_in_namespaces() {
+        let directory = open_test_wads("namespaces");
+        let global = directory.check_num_for_name("TROOA1").unwrap();
+        let sprite = directory
+            .check_num_for_name_ns("TROOA1", LumpNamespace::Sprites)
+            .unwrap();
+        assert_eq!(directory.read_lump(global).unwrap(), b"global");
+        assert_eq!(directory.read_lump(sprite).unwrap(), b"sprite");
+
+        let flat = directory
+            .check_num_for_name_ns("FLOOR0_1", LumpNamespace::Flats)
+            .unwrap();
+        assert_eq!(directory.lump_length(flat), 4);
+        assert!(directory.check_num_for_name("FLOOR0_1").is_none());
+        // markers, nested ones included, stay global
+        for marker in ["S_START", "S_END", "F_START", "F1_START", "F_END"] {
+            assert!(directory.check_num_for_name(marker).is_some(), "{}", marker);
+        }
+    }
+}
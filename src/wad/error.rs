@@ -0,0 +1,131 @@
+use std::{error::Error, fmt, io, path::PathBuf};
+use zip::result::ZipError;
+
+/// Everything that can go wrong while reading a WAD file.
+///
+/// The variants carry enough detail to tell a mapper exactly which part of
+/// their file is broken.
+#[derive(Debug)]
+pub enum WadError {
+    /// The underlying read failed, usually because the file is truncated.
+    Io(io::Error),
+    /// The header does not start with `IWAD` or `PWAD`.
+    BadMagic([u8; 4]),
+    /// The file is a valid WAD, but not an IWAD.
+    NotIwad([u8; 4]),
+    /// The header claims a negative number of lumps.
+    NegativeLumpCount(i32),
+    /// The lump table starts or ends past the end of the file.
+    DirectoryPastEof {
+        infotableofs: i32,
+        numlumps: i32,
+        file_len: u64,
+    },
+    /// A lump's data starts or ends past the end of the file.
+    LumpPastEof {
+        index: usize,
+        name: String,
+        filepos: i32,
+        size: i32,
+        file_len: u64,
+    },
+    /// A lump name contains bytes outside of ASCII.
+    NonAsciiName { index: usize, name: [u8; 8] },
+    /// A zip archive could not be read.
+    Zip(ZipError),
+    /// A name given for a new lump is empty, too long or not ASCII.
+    BadLumpName(String),
+    /// A lump was to be inserted past the end of the directory.
+    BadLumpIndex { index: usize, numlumps: usize },
+    /// Wraps another error with the path of the file it came from.
+    InFile(PathBuf, Box<WadError>),
+}
+
+impl WadError {
+    /// Attach the path of the file being read to this error.
+    pub fn in_file<P: Into<PathBuf>>(self, path: P) -> Self {
+        WadError::InFile(path.into(), Box::new(self))
+    }
+}
+
+impl fmt::Display for WadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WadError::Io(e) => write!(f, "read error: {}", e),
+            WadError::BadMagic(magic) => write!(
+                f,
+                "not a WAD file (header starts with {:?}, expected \"IWAD\" or \"PWAD\")",
+                String::from_utf8_lossy(magic)
+            ),
+            WadError::NotIwad(magic) => write!(
+                f,
+                "IWAD tag not present (header starts with {:?})",
+                String::from_utf8_lossy(magic)
+            ),
+            WadError::NegativeLumpCount(numlumps) => {
+                write!(f, "header claims a negative lump count ({})", numlumps)
+            }
+            WadError::DirectoryPastEof {
+                infotableofs,
+                numlumps,
+                file_len,
+            } => write!(
+                f,
+                "directory of {} lumps at offset {} does not fit in the file ({} bytes); \
+                 the file is probably truncated",
+                numlumps, infotableofs, file_len
+            ),
+            WadError::LumpPastEof {
+                index,
+                name,
+                filepos,
+                size,
+                file_len,
+            } => write!(
+                f,
+                "lump {} ({}) at offset {} with size {} does not fit in the file ({} bytes)",
+                index, name, filepos, size, file_len
+            ),
+            WadError::NonAsciiName { index, name } => write!(
+                f,
+                "lump {} has a name with non-ASCII characters ({:02x?})",
+                index, name
+            ),
+            WadError::Zip(e) => write!(f, "zip error: {}", e),
+            WadError::BadLumpName(name) => write!(
+                f,
+                "{:?} is not a valid lump name (1 to 8 ASCII characters)",
+                name
+            ),
+            WadError::BadLumpIndex { index, numlumps } => write!(
+                f,
+                "can't insert a lump at index {} of a WAD with {} lumps",
+                index, numlumps
+            ),
+            WadError::InFile(path, e) => write!(f, "{}: {}", path.display(), e),
+        }
+    }
+}
+
+impl Error for WadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WadError::Io(e) => Some(e),
+            WadError::Zip(e) => Some(e),
+            WadError::InFile(_, e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for WadError {
+    fn from(e: io::Error) -> Self {
+        WadError::Io(e)
+    }
+}
+
+impl From<ZipError> for WadError {
+    fn from(e: ZipError) -> Self {
+        WadError::Zip(e)
+    }
+}
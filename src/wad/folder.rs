@@ -0,0 +1,228 @@
+use super::{
+    archive::{lump_name, placement, ArchiveLump, ArchivePlacement},
+    normalize_lump_name, read_directory, LumpNamespace, WadError,
+};
+use std::{
+    fs::{self, File},
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+/// Where a lump's bytes live inside the directory.
+enum FolderEntry {
+    /// The whole file is the lump.
+    File(PathBuf),
+    /// A lump inside a WAD in `maps/`.
+    WadLump {
+        path: PathBuf,
+        offset: usize,
+        size: usize,
+    },
+}
+
+/// A directory of loose files mounted as if it were a WAD. Paths inside it
+/// become lumps exactly like paths inside a PK3, see `archive::placement`.
+///
+/// The lumps and their sizes are fixed when the directory is mounted, so
+/// edits made while the game runs aren't picked up; restart to see them.
+pub struct FolderArchive {
+    entries: Vec<FolderEntry>,
+}
+
+impl FolderArchive {
+    /// Scan a directory and list the lumps it provides, in the order they
+    /// should be added to the lump directory.
+    pub fn open<P: AsRef<Path>>(root: P) -> Result<(Self, Vec<ArchiveLump>), WadError> {
+        let root = root.as_ref();
+        let mut files = vec![];
+        list_files(root, root, &mut files)?;
+        // sort by path so the lump order does not depend on the file system
+        files.sort();
+
+        let mut archive = Self { entries: vec![] };
+        let mut lumps = vec![];
+        for (relative, path) in files {
+            let namespace = match placement(&relative) {
+                ArchivePlacement::Lump(namespace) => namespace,
+                ArchivePlacement::MapWad => {
+                    archive
+                        .add_map_wad(path, &mut lumps)
+                        .map_err(|e| e.in_file(&relative))?;
+                    continue;
+                }
+                ArchivePlacement::Skip => continue,
+            };
+            let name = match lump_name(&relative) {
+                Some(name) => name,
+                None => continue,
+            };
+            lumps.push(ArchiveLump {
+                name,
+                namespace,
+                entry: archive.entries.len(),
+                size: fs::metadata(&path)?.len() as usize,
+            });
+            archive.entries.push(FolderEntry::File(path));
+        }
+        Ok((archive, lumps))
+    }
+
+    /// Add the lumps of a WAD stored in `maps/`.
+    fn add_map_wad(&mut self, path: PathBuf, lumps: &mut Vec<ArchiveLump>) -> Result<(), WadError> {
+        let (_, fileinfo) = read_directory(&mut File::open(&path)?)?;
+        for lump in fileinfo {
+            lumps.push(ArchiveLump {
+                name: normalize_lump_name(&lump.name),
+                namespace: LumpNamespace::Global,
+                entry: self.entries.len(),
+                size: lump.size as usize,
+            });
+            self.entries.push(FolderEntry::WadLump {
+                path: path.clone(),
+                offset: lump.filepos as usize,
+                size: lump.size as usize,
+            });
+        }
+        Ok(())
+    }
+
+    /// Read the lump at `entry`, as given by `ArchiveLump::entry`.
+    pub fn read(&self, entry: usize) -> Result<Vec<u8>, WadError> {
+        match &self.entries[entry] {
+            FolderEntry::File(path) => Ok(fs::read(path)?),
+            FolderEntry::WadLump { path, offset, size } => {
+                let mut file = File::open(path)?;
+                let mut data = vec![0; *size];
+                file.seek(SeekFrom::Start(*offset as u64))?;
+                file.read_exact(&mut data)?;
+                Ok(data)
+            }
+        }
+    }
+}
+
+/// Recursively collect every file under `dir`, along with its path relative
+/// to `root` using `/` separators.
+fn list_files(root: &Path, dir: &Path, files: &mut Vec<(String, PathBuf)>) -> Result<(), WadError> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            list_files(root, &path, files)?;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            let relative = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.push((relative, path));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wad::{test_path, LumpDirectory, WadBuilder, WadFileInfo, WadKind, WadSource};
+
+    /// Create a directory holding `files`, replacing any left over from an
+    /// earlier run.
+    fn write_folder(name: &str, files: &[(&str, Vec<u8>)]) -> PathBuf {
+        let root = test_path(name);
+        let _ = fs::remove_dir_all(&root);
+        for (path, data) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, data).unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn folder_paths_become_lumps() {
+        let mut map = vec![];
+        WadBuilder::new(WadKind::Pwad)
+            .push("MAP01", vec![])
+            .and_then(|b| b.push("THINGS", b"things".to_vec()))
+            .and_then(|b| b.write(&mut map))
+            .unwrap();
+        let root = write_folder(
+            "folder-paths",
+            &[
+                ("Sprites/trooa1.png", b"sprite".to_vec()),
+                ("flats/sub/floor0_1.lmp", b"flat".to_vec()),
+                ("maps/map01.wad", map),
+                ("maps/readme.txt", b"notes".to_vec()),
+                ("music/d_runnin.mus", b"music".to_vec()),
+                ("decorate.txt", b"root".to_vec()),
+            ],
+        );
+        let (archive, lumps) = FolderArchive::open(&root).unwrap();
+        let found: Vec<_> = lumps
+            .iter()
+            .map(|l| (l.name, l.namespace, archive.read(l.entry).unwrap()))
+            .collect();
+        fs::remove_dir_all(&root).unwrap();
+
+        // sorted by path, so the upper case directory comes first
+        let expected = [
+            ("TROOA1", LumpNamespace::Sprites, &b"sprite"[..]),
+            ("DECORATE", LumpNamespace::Global, b"root"),
+            ("FLOOR0_1", LumpNamespace::Flats, b"flat"),
+            ("MAP01", LumpNamespace::Global, b""),
+            ("THINGS", LumpNamespace::Global, b"things"),
+            ("D_RUNNIN", LumpNamespace::Global, b"music"),
+        ];
+        assert_eq!(found.len(), expected.len());
+        for ((name, namespace, data), (e_name, e_namespace, e_data)) in found.iter().zip(&expected)
+        {
+            assert_eq!(
+                (*name, *namespace),
+                (normalize_lump_name(e_name.as_bytes()), *e_namespace)
+            );
+            assert_eq!(data, e_data, "{}", e_name);
+        }
+    }
+
+    #[test]
+    fn folder_lumps_override_wad_lumps() {
+        let wad = test_path("folder-override.wad");
+        WadBuilder::new(WadKind::Iwad)
+            .push("PLAYPAL", b"iwad".to_vec())
+            .and_then(|b| b.push("S_START", vec![]))
+            .and_then(|b| b.push("TROOA1", b"iwad".to_vec()))
+            .and_then(|b| b.push("S_END", vec![]))
+            .and_then(|b| b.write_to_file(&wad))
+            .unwrap();
+        let root = write_folder(
+            "folder-override",
+            &[
+                ("playpal.lmp", b"folder".to_vec()),
+                ("sprites/trooa1.lmp", b"folder".to_vec()),
+            ],
+        );
+
+        let mut wad_files = vec![
+            WadFileInfo {
+                name: wad.clone(),
+                src: WadSource::Iwad,
+                handle: 0,
+            },
+            WadFileInfo {
+                name: root.clone(),
+                src: WadSource::Pwad,
+                handle: 0,
+            },
+        ];
+        let directory = LumpDirectory::open(&mut wad_files).unwrap();
+        fs::remove_file(wad).unwrap();
+        let playpal = directory.check_num_for_name("PLAYPAL").unwrap();
+        let sprite = directory
+            .check_num_for_name_ns("TROOA1", LumpNamespace::Sprites)
+            .unwrap();
+        assert_eq!(directory.read_lump(playpal).unwrap(), b"folder");
+        assert_eq!(directory.read_lump(sprite).unwrap(), b"folder");
+        assert_eq!(wad_files[1].handle, 1);
+        fs::remove_dir_all(root).unwrap();
+    }
+}
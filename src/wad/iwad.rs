@@ -0,0 +1,144 @@
+use crate::doom::def::{GameMission, GameMode, Language};
+use counted_array::counted_array;
+use std::{
+    fs::File,
+    io::{self, Read},
+    path::Path,
+};
+
+/// A released IWAD, identified by the MD5 checksum of the whole file.
+pub struct KnownIwad {
+    pub md5: &'static str,
+    /// The name the file was shipped with.
+    pub file_name: &'static str,
+    /// A human readable name for this exact release.
+    pub release: &'static str,
+    pub game_mode: GameMode,
+    pub game_mission: GameMission,
+    pub language: Language,
+    pub bfg_edition: bool,
+}
+
+const fn iwad(
+    md5: &'static str,
+    file_name: &'static str,
+    release: &'static str,
+    game_mode: GameMode,
+    game_mission: GameMission,
+) -> KnownIwad {
+    KnownIwad {
+        md5,
+        file_name,
+        release,
+        game_mode,
+        game_mission,
+        language: Language::English,
+        bfg_edition: false,
+    }
+}
+
+const fn bfg(
+    md5: &'static str,
+    file_name: &'static str,
+    release: &'static str,
+    game_mode: GameMode,
+    game_mission: GameMission,
+) -> KnownIwad {
+    KnownIwad {
+        bfg_edition: true,
+        ..iwad(md5, file_name, release, game_mode, game_mission)
+    }
+}
+
+const fn translated(
+    md5: &'static str,
+    file_name: &'static str,
+    release: &'static str,
+    game_mode: GameMode,
+    game_mission: GameMission,
+    language: Language,
+) -> KnownIwad {
+    KnownIwad {
+        language,
+        ..iwad(md5, file_name, release, game_mode, game_mission)
+    }
+}
+
+use GameMission as GMi;
+use GameMode as GMo;
+
+counted_array!(
+    pub const KNOWN_IWADS: [KnownIwad; _] = [
+        // Doom shareware
+        iwad("52cbc8882f445573ce421fa5453ddc9c", "doom1.wad", "DOOM Shareware v1.1", GMo::Shareware, GMi::Doom),
+        iwad("30aa5beb9e5ebfbbe1e1765561c08f38", "doom1.wad", "DOOM Shareware v1.2", GMo::Shareware, GMi::Doom),
+        iwad("17aebd6b5f2ed8ce07aa526a32af8d99", "doom1.wad", "DOOM Shareware v1.25", GMo::Shareware, GMi::Doom),
+        iwad("a21ae40c388cb6f2c3cc1b95589ee693", "doom1.wad", "DOOM Shareware v1.4", GMo::Shareware, GMi::Doom),
+        iwad("e280233d533dcc28c1acd6ccdc7742d4", "doom1.wad", "DOOM Shareware v1.5", GMo::Shareware, GMi::Doom),
+        iwad("762fd6d4b960d4b759730f01387a50a1", "doom1.wad", "DOOM Shareware v1.6", GMo::Shareware, GMi::Doom),
+        iwad("c428ea394dc52835f2580d5bfd50d76f", "doom1.wad", "DOOM Shareware v1.666", GMo::Shareware, GMi::Doom),
+        iwad("5f4eb849b1af12887dec04a2a12e5e62", "doom1.wad", "DOOM Shareware v1.8", GMo::Shareware, GMi::Doom),
+        iwad("f0cefca49926d00903cf57551d901abe", "doom1.wad", "DOOM Shareware v1.9", GMo::Shareware, GMi::Doom),
+        // Doom registered and The Ultimate Doom
+        iwad("981b03e6d1dc033301aa3095acc437ce", "doom.wad", "DOOM Registered v1.1", GMo::Registered, GMi::Doom),
+        iwad("792fd1fea023d61210857089a7c1e351", "doom.wad", "DOOM Registered v1.2", GMo::Registered, GMi::Doom),
+        iwad("54978d12de87f213efa2f9e6b0f2dc0a", "doom.wad", "DOOM Registered v1.666", GMo::Registered, GMi::Doom),
+        iwad("11e1cd216801ea2657723abc86ecb01f", "doom.wad", "DOOM Registered v1.8", GMo::Registered, GMi::Doom),
+        iwad("1cd63c5ddff1bf8ce844237f580e9cf3", "doom.wad", "DOOM Registered v1.9", GMo::Registered, GMi::Doom),
+        iwad("c4fe9fd920207691a9f493668e0a2083", "doom.wad", "The Ultimate DOOM v1.9", GMo::Retail, GMi::Doom),
+        bfg("fb35c4a5a9fd49ec29ab6e900572c524", "doom.wad", "The Ultimate DOOM (BFG Edition)", GMo::Retail, GMi::Doom),
+        iwad("8517c4e8f0eef90b82852667d345eb86", "doom.wad", "The Ultimate DOOM (Unity v1.0)", GMo::Retail, GMi::Doom),
+        iwad("4461d4511386518e784c647e3128e7bc", "doom.wad", "The Ultimate DOOM (Unity, updated)", GMo::Retail, GMi::Doom),
+        iwad("3b37188f6337f15718b617c16e6e7a9c", "doom.wad", "The Ultimate DOOM (KEX)", GMo::Retail, GMi::Doom),
+        // Doom II
+        iwad("30e3c2d0350b67bfbf47271970b74b2f", "doom2.wad", "DOOM 2 v1.666", GMo::Commercial, GMi::Doom2),
+        translated("d9153ced9fd5b898b36cc5844e35b520", "doom2.wad", "DOOM 2 v1.666 (German)", GMo::Commercial, GMi::Doom2, Language::German),
+        iwad("ea74a47a791fdef2e9f2ea8b8a9da13b", "doom2.wad", "DOOM 2 v1.7", GMo::Commercial, GMi::Doom2),
+        iwad("d7a07e5d3f4625074312bc299d7ed33f", "doom2.wad", "DOOM 2 v1.8", GMo::Commercial, GMi::Doom2),
+        translated("3cb02349b3df649c86290907eed64e7b", "doom2f.wad", "DOOM 2 v1.8 (French)", GMo::Commercial, GMi::Doom2, Language::French),
+        iwad("25e1459ca71d321525f84628f45ca8cd", "doom2.wad", "DOOM 2 v1.9", GMo::Commercial, GMi::Doom2),
+        bfg("c3bea40570c23e511a7ed3ebcd9865f7", "doom2.wad", "DOOM 2 (BFG Edition)", GMo::Commercial, GMi::Doom2),
+        iwad("8ab6d0527a29efdc1ef200e5687b5cae", "doom2.wad", "DOOM 2 (Unity v1.0)", GMo::Commercial, GMi::Doom2),
+        iwad("9aa3cbf65b961d0bdac98ec403b832e1", "doom2.wad", "DOOM 2 (KEX)", GMo::Commercial, GMi::Doom2),
+        // Final Doom
+        iwad("4e158d9953c79ccf97bd0663244cc6b6", "tnt.wad", "Final DOOM - TNT: Evilution v1.9", GMo::Commercial, GMi::TNT),
+        iwad("1d39e405bf6ee3df69a8d2646c8d5c49", "tnt.wad", "Final DOOM - TNT: Evilution v1.9 (id Anthology)", GMo::Commercial, GMi::TNT),
+        iwad("75c8cf89566741fa9d22447604053bd7", "plutonia.wad", "Final DOOM - The Plutonia Experiment v1.9", GMo::Commercial, GMi::Plutonia),
+        iwad("3493be7e1e2588bc9c8b31eab2587a04", "plutonia.wad", "Final DOOM - The Plutonia Experiment v1.9 (id Anthology)", GMo::Commercial, GMi::Plutonia),
+        iwad("f5528f6fd55cf9629141d79eda169630", "tnt.wad", "Final DOOM - TNT: Evilution (Unity)", GMo::Commercial, GMi::TNT),
+        iwad("24037397056e919961005e08611623f4", "plutonia.wad", "Final DOOM - The Plutonia Experiment (Unity)", GMo::Commercial, GMi::Plutonia),
+        // Freedoom
+        iwad("b36aa44a23045e503c19af4b4c438a78", "freedoom1.wad", "Freedoom: Phase 1 v0.12.1", GMo::Retail, GMi::Doom),
+        iwad("ea471a3d38fcee0fb3a69bcd3221e335", "freedoom1.wad", "Freedoom: Phase 1 v0.13.0", GMo::Retail, GMi::Doom),
+        iwad("ca9a4f9e28c28ec8d83dfe4dae0cc5c3", "freedoom2.wad", "Freedoom: Phase 2 v0.12.1", GMo::Commercial, GMi::Doom2),
+        iwad("984f99af08f085e38070f51095ab7c31", "freedoom2.wad", "Freedoom: Phase 2 v0.13.0", GMo::Commercial, GMi::Doom2),
+        iwad("bd4f359f1963e388beda014c5548b420", "freedm.wad", "FreeDM v0.12.1", GMo::Commercial, GMi::Doom2),
+        iwad("87ee2494d921633420ce9bdb418127c4", "freedm.wad", "FreeDM v0.13.0", GMo::Commercial, GMi::Doom2),
+        // Others
+        iwad("25485721882b050afa96a56e5758dd52", "chex.wad", "Chex(R) Quest", GMo::Retail, GMi::Chex),
+        iwad("b7fd2f43f3382cf012dc6b097a3cb182", "hacx.wad", "HACX v1.0", GMo::Commercial, GMi::Hacx),
+        iwad("65ed74d522bdf6649c2831b13b9e02b4", "hacx.wad", "HACX v1.2", GMo::Commercial, GMi::Hacx),
+    ]
+);
+
+/// Compute the MD5 checksum of a file as a lowercase hex string.
+pub fn file_md5<P: AsRef<Path>>(path: P) -> Result<String, io::Error> {
+    let mut file = File::open(path)?;
+    let mut context = md5::Context::new();
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        context.consume(&buf[..n]);
+    }
+    Ok(format!("{:x}", context.compute()))
+}
+
+/// Look up an IWAD in the table of known releases by its checksum, so it is
+/// recognized no matter what it is called.
+pub fn identify_iwad<P: AsRef<Path>>(path: P) -> Result<Option<&'static KnownIwad>, io::Error> {
+    let md5 = file_md5(path)?;
+    Ok(KNOWN_IWADS.iter().find(|iwad| iwad.md5 == md5))
+}
@@ -1,7 +1,32 @@
 use byteorder::{LittleEndian, ReadBytesExt};
-use io::Read;
+use io::{Read, Seek, SeekFrom};
 use serde_derive::{Deserialize, Serialize};
-use std::{io, mem, path::Path, path::PathBuf};
+use std::{io, path::Path, path::PathBuf};
+
+/// Contains the parts shared by PK3 and directory archives.
+mod archive;
+/// Contains the merged lump directory built from every loaded file.
+mod directory;
+/// Contains the error type for malformed WAD files.
+mod error;
+/// Contains support for mounting directories of loose lumps.
+mod folder;
+/// Contains the checksums of known IWAD releases.
+mod iwad;
+/// Contains support for reading lumps from PK3 (zip) archives.
+mod pk3;
+/// Contains a builder for creating and patching WAD files.
+mod writer;
+
+#[allow(unused_imports)]
+pub use directory::{normalize_lump_name, LumpDirectory, LumpInfo, LumpNamespace};
+pub use error::WadError;
+pub use iwad::identify_iwad;
+#[allow(unused_imports)]
+pub use writer::{BuilderLump, WadBuilder, WadKind, WriteWadExt};
+
+/// The size of one lump table entry on disk.
+pub const FILELUMP_SIZE: i64 = 16;
 
 #[derive(Debug)]
 pub struct WadInfo {
@@ -25,36 +50,92 @@ pub struct FileLump {
 }
 
 pub trait ReadWadExt {
-    fn read_wadinfo(&mut self) -> Result<WadInfo, io::Error>;
-    fn read_filelump(&mut self) -> Result<FileLump, io::Error>;
+    /// Read a WAD header, checking the magic and lump count.
+    fn read_wadinfo(&mut self) -> Result<WadInfo, WadError>;
+    /// Read one entry of a lump table. The entry is not validated against
+    /// the file; `read_directory` does that.
+    fn read_filelump(&mut self) -> Result<FileLump, WadError>;
 }
 
 impl<T> ReadWadExt for T
 where
     T: Read,
 {
-    fn read_wadinfo(&mut self) -> Result<WadInfo, io::Error> {
-        let mut info: WadInfo = unsafe { mem::zeroed() };
-        for i in 0..4 {
-            info.identification[i] = self.read_u8()?;
+    fn read_wadinfo(&mut self) -> Result<WadInfo, WadError> {
+        let mut identification = [0; 4];
+        self.read_exact(&mut identification)?;
+        if &identification != b"IWAD" && &identification != b"PWAD" {
+            return Err(WadError::BadMagic(identification));
         }
-        info.numlumps = self.read_i32::<LittleEndian>()?;
-        info.infotableofs = self.read_i32::<LittleEndian>()?;
-        Ok(info)
-    }
-    fn read_filelump(&mut self) -> Result<FileLump, io::Error> {
-        let mut lump: FileLump = unsafe { mem::zeroed() };
-        lump.filepos = self.read_i32::<LittleEndian>()?;
-        lump.size = self.read_i32::<LittleEndian>()?;
-        for i in 0..8 {
-            lump.name[i] = self.read_u8()?;
+        let numlumps = self.read_i32::<LittleEndian>()?;
+        if numlumps < 0 {
+            return Err(WadError::NegativeLumpCount(numlumps));
         }
-        Ok(lump)
+        let infotableofs = self.read_i32::<LittleEndian>()?;
+        Ok(WadInfo {
+            identification,
+            numlumps,
+            infotableofs,
+        })
+    }
+    fn read_filelump(&mut self) -> Result<FileLump, WadError> {
+        let filepos = self.read_i32::<LittleEndian>()?;
+        let size = self.read_i32::<LittleEndian>()?;
+        let mut name = [0; 8];
+        self.read_exact(&mut name)?;
+        Ok(FileLump {
+            filepos,
+            size,
+            name,
+        })
     }
 }
 
+/// Read the header and lump table of a WAD file, making sure the table and
+/// every lump it describes lie inside the file and every name is ASCII.
+pub fn read_directory<R: Read + Seek>(r: &mut R) -> Result<(WadInfo, Vec<FileLump>), WadError> {
+    let file_len = r.seek(SeekFrom::End(0))?;
+    r.seek(SeekFrom::Start(0))?;
+    let header = r.read_wadinfo()?;
+
+    let directory_end = header.infotableofs as i64 + header.numlumps as i64 * FILELUMP_SIZE;
+    if header.infotableofs < 0 || directory_end as u64 > file_len {
+        return Err(WadError::DirectoryPastEof {
+            infotableofs: header.infotableofs,
+            numlumps: header.numlumps,
+            file_len,
+        });
+    }
+
+    r.seek(SeekFrom::Start(header.infotableofs as u64))?;
+    let mut fileinfo = Vec::with_capacity(header.numlumps as usize);
+    for index in 0..header.numlumps as usize {
+        let lump = r.read_filelump()?;
+        // anything after the terminating NUL is garbage in some old WADs
+        let name_len = lump.name.iter().position(|&c| c == 0).unwrap_or(8);
+        if !lump.name[..name_len].is_ascii() {
+            return Err(WadError::NonAsciiName {
+                index,
+                name: lump.name,
+            });
+        }
+        let lump_end = lump.filepos as i64 + lump.size as i64;
+        if lump.filepos < 0 || lump.size < 0 || lump_end as u64 > file_len {
+            return Err(WadError::LumpPastEof {
+                index,
+                name: String::from_utf8_lossy(&lump.name[..name_len]).into_owned(),
+                filepos: lump.filepos,
+                size: lump.size,
+                file_len,
+            });
+        }
+        fileinfo.push(lump);
+    }
+    Ok((header, fileinfo))
+}
+
 #[allow(dead_code)]
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub enum WadSource {
     Iwad = 0,
     Pre,
@@ -66,6 +147,13 @@ pub enum WadSource {
     Err,
 }
 
+/// A path in the temp directory for a file written by a test, unique to
+/// this process so parallel test runs don't share it.
+#[cfg(test)]
+pub fn test_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("ez-boom-{}-{}", std::process::id(), name))
+}
+
 pub fn add_default_extension<P: AsRef<Path>>(path: P, ext: &str) -> PathBuf {
     let path = path.as_ref().to_str().unwrap();
     let pb = path.as_bytes();
@@ -86,3 +174,123 @@ pub fn add_default_extension<P: AsRef<Path>>(path: P, ext: &str) -> PathBuf {
     path.push_str(ext);
     PathBuf::from(path)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use byteorder::WriteBytesExt;
+    use std::io::Cursor;
+
+    /// A WAD header followed by `rest`.
+    fn wad(identification: &[u8; 4], numlumps: i32, infotableofs: i32, rest: &[u8]) -> Vec<u8> {
+        let mut data = identification.to_vec();
+        data.write_i32::<LittleEndian>(numlumps).unwrap();
+        data.write_i32::<LittleEndian>(infotableofs).unwrap();
+        data.extend_from_slice(rest);
+        data
+    }
+
+    /// A lump table entry.
+    fn filelump(filepos: i32, size: i32, name: &[u8; 8]) -> Vec<u8> {
+        let mut data = vec![];
+        data.write_i32::<LittleEndian>(filepos).unwrap();
+        data.write_i32::<LittleEndian>(size).unwrap();
+        data.extend_from_slice(name);
+        data
+    }
+
+    fn read(data: Vec<u8>) -> Result<(WadInfo, Vec<FileLump>), WadError> {
+        read_directory(&mut Cursor::new(data))
+    }
+
+    #[test]
+    fn reads_a_valid_directory() {
+        let mut rest = b"data".to_vec();
+        rest.extend(filelump(12, 4, b"DEMO1\0xy"));
+        let (header, fileinfo) = read(wad(b"PWAD", 1, 16, &rest)).unwrap();
+        assert_eq!(&header.identification, b"PWAD");
+        assert_eq!(fileinfo.len(), 1);
+        assert_eq!((fileinfo[0].filepos, fileinfo[0].size), (12, 4));
+        assert_eq!(&fileinfo[0].name, b"DEMO1\0xy");
+    }
+
+    #[test]
+    fn truncated_header_is_an_io_error() {
+        let data = wad(b"IWAD", 0, 12, &[]);
+        assert!(matches!(read(data[..6].to_vec()), Err(WadError::Io(_))));
+        assert!(matches!(read(data[..10].to_vec()), Err(WadError::Io(_))));
+    }
+
+    #[test]
+    fn bad_magic() {
+        assert!(matches!(
+            read(wad(b"ZWAD", 0, 12, &[])),
+            Err(WadError::BadMagic(magic)) if &magic == b"ZWAD"
+        ));
+    }
+
+    #[test]
+    fn negative_lump_count() {
+        assert!(matches!(
+            read(wad(b"IWAD", -1, 12, &[])),
+            Err(WadError::NegativeLumpCount(-1))
+        ));
+    }
+
+    #[test]
+    fn directory_past_eof() {
+        // one entry claimed, only half of it present
+        let entry = filelump(12, 0, b"MAP01\0\0\0");
+        assert!(matches!(
+            read(wad(b"PWAD", 1, 12, &entry[..8])),
+            Err(WadError::DirectoryPastEof {
+                infotableofs: 12,
+                numlumps: 1,
+                file_len: 20,
+            })
+        ));
+        assert!(matches!(
+            read(wad(b"PWAD", 0, -4, &[])),
+            Err(WadError::DirectoryPastEof { .. })
+        ));
+    }
+
+    #[test]
+    fn lump_past_eof() {
+        let mut rest = filelump(12, 0, b"MAP01\0\0\0");
+        rest.extend(filelump(20, 100, b"THINGS\0\0"));
+        assert!(matches!(
+            read(wad(b"PWAD", 2, 12, &rest)),
+            Err(WadError::LumpPastEof {
+                index: 1,
+                ref name,
+                filepos: 20,
+                size: 100,
+                file_len: 44,
+            }) if name == "THINGS"
+        ));
+        let rest = filelump(12, -1, b"MAP01\0\0\0");
+        assert!(matches!(
+            read(wad(b"PWAD", 1, 12, &rest)),
+            Err(WadError::LumpPastEof { index: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn non_ascii_name() {
+        let mut rest = filelump(12, 0, b"MAP01\0\xff\xff");
+        rest.extend(filelump(12, 0, b"MAP\xe902\0\0"));
+        assert!(matches!(
+            read(wad(b"PWAD", 2, 12, &rest)),
+            Err(WadError::NonAsciiName { index: 1, name }) if &name == b"MAP\xe902\0\0"
+        ));
+    }
+
+    #[test]
+    fn in_file_names_the_file() {
+        let e = WadError::NegativeLumpCount(-1).in_file("doom.wad");
+        assert!(matches!(&e, WadError::InFile(path, inner)
+            if path == Path::new("doom.wad") && matches!(**inner, WadError::NegativeLumpCount(-1))));
+        assert!(e.to_string().contains("doom.wad"));
+    }
+}
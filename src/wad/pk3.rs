@@ -0,0 +1,250 @@
+use super::{
+    archive::{lump_name, placement, ArchiveLump, ArchivePlacement},
+    normalize_lump_name, read_directory, LumpNamespace, WadError,
+};
+use parking_lot::Mutex;
+use std::{
+    fs::File,
+    io::{Cursor, Read},
+    path::Path,
+};
+use zip::ZipArchive;
+
+/// Where a lump's bytes live inside the zip file. Lumps from WADs embedded
+/// in `maps/` are a slice of one zip entry.
+struct Pk3Entry {
+    zip_index: usize,
+    offset: usize,
+    size: usize,
+}
+
+/// A zip file (usually named `.pk3`) mounted as a source of lumps. See
+/// `archive::placement` for how paths inside it become lumps.
+pub struct Pk3Archive {
+    zip: Mutex<ZipArchive<File>>,
+    entries: Vec<Pk3Entry>,
+}
+
+impl Pk3Archive {
+    /// Open a zip file and list the lumps it provides, in the order they
+    /// should be added to the directory.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<(Self, Vec<ArchiveLump>), WadError> {
+        let mut zip = ZipArchive::new(File::open(path)?)?;
+        let mut entries = vec![];
+        let mut lumps = vec![];
+
+        // sort by path so the lump order does not depend on the zip tool
+        let mut names: Vec<(String, usize)> = (0..zip.len())
+            .filter_map(|i| {
+                let file = zip.by_index(i).ok()?;
+                if file.is_dir() {
+                    None
+                } else {
+                    Some((file.name().to_string(), i))
+                }
+            })
+            .collect();
+        names.sort();
+
+        for (path, zip_index) in names {
+            let namespace = match placement(&path) {
+                ArchivePlacement::Lump(namespace) => namespace,
+                ArchivePlacement::MapWad => {
+                    add_map_wad(&mut zip, zip_index, &mut entries, &mut lumps)
+                        .map_err(|e| e.in_file(&path))?;
+                    continue;
+                }
+                ArchivePlacement::Skip => continue,
+            };
+            let name = match lump_name(&path) {
+                Some(name) => name,
+                None => continue,
+            };
+
+            let size = zip.by_index(zip_index)?.size() as usize;
+            lumps.push(ArchiveLump {
+                name,
+                namespace,
+                entry: entries.len(),
+                size,
+            });
+            entries.push(Pk3Entry {
+                zip_index,
+                offset: 0,
+                size,
+            });
+        }
+        let archive = Self {
+            zip: Mutex::new(zip),
+            entries,
+        };
+        Ok((archive, lumps))
+    }
+
+    /// Read the lump at `entry`, as given by `ArchiveLump::entry`.
+    pub fn read(&self, entry: usize) -> Result<Vec<u8>, WadError> {
+        let entry = &self.entries[entry];
+        let mut zip = self.zip.lock();
+        let file = zip.by_index(entry.zip_index)?;
+        let mut data = Vec::with_capacity(entry.offset + entry.size);
+        file.take((entry.offset + entry.size) as u64)
+            .read_to_end(&mut data)?;
+        data.drain(..entry.offset.min(data.len()));
+        Ok(data)
+    }
+}
+
+/// Add the lumps of a WAD stored in `maps/`.
+fn add_map_wad(
+    zip: &mut ZipArchive<File>,
+    zip_index: usize,
+    entries: &mut Vec<Pk3Entry>,
+    lumps: &mut Vec<ArchiveLump>,
+) -> Result<(), WadError> {
+    let mut data = vec![];
+    zip.by_index(zip_index)?.read_to_end(&mut data)?;
+    let (_, fileinfo) = read_directory(&mut Cursor::new(&data))?;
+    for lump in fileinfo {
+        lumps.push(ArchiveLump {
+            name: normalize_lump_name(&lump.name),
+            namespace: LumpNamespace::Global,
+            entry: entries.len(),
+            size: lump.size as usize,
+        });
+        entries.push(Pk3Entry {
+            zip_index,
+            offset: lump.filepos as usize,
+            size: lump.size as usize,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wad::{test_path, LumpDirectory, WadBuilder, WadFileInfo, WadKind, WadSource};
+    use std::io::Write;
+    use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+    /// Write a zip holding `files`, compressing every other one.
+    fn write_zip(path: &Path, files: &[(&str, Vec<u8>)]) {
+        let mut zip = ZipWriter::new(File::create(path).unwrap());
+        zip.add_directory("sprites/", FileOptions::default())
+            .unwrap();
+        for (i, (name, data)) in files.iter().enumerate() {
+            let method = if i % 2 == 0 {
+                CompressionMethod::Stored
+            } else {
+                CompressionMethod::Deflated
+            };
+            zip.start_file(*name, FileOptions::default().compression_method(method))
+                .unwrap();
+            zip.write_all(data).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    fn map_wad() -> Vec<u8> {
+        let mut data = vec![];
+        WadBuilder::new(WadKind::Pwad)
+            .push("MAP01", vec![])
+            .and_then(|b| b.push("THINGS", b"things".to_vec()))
+            .and_then(|b| b.push("LINEDEFS", b"lines".to_vec()))
+            .and_then(|b| b.write(&mut data))
+            .unwrap();
+        data
+    }
+
+    #[test]
+    fn archive_paths_become_lumps() {
+        let path = test_path("pk3-paths.pk3");
+        write_zip(
+            &path,
+            &[
+                ("sprites/trooa1.png", b"sprite".to_vec()),
+                ("flats/floor0_1.lmp", b"flat".to_vec()),
+                ("colormaps/watermap.lmp", b"colormap".to_vec()),
+                ("maps/map01.wad", map_wad()),
+                ("maps/map01.txt", b"notes".to_vec()),
+                ("sounds/dspistol.lmp", b"sound".to_vec()),
+                ("acs/library.o", b"global".to_vec()),
+                ("mapinfo.txt", b"root".to_vec()),
+                ("graphics/verylongname.png", b"skipped".to_vec()),
+            ],
+        );
+        let (archive, lumps) = Pk3Archive::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let found: Vec<_> = lumps
+            .iter()
+            .map(|l| {
+                let len = l.name.iter().position(|&c| c == 0).unwrap_or(8);
+                (
+                    String::from_utf8_lossy(&l.name[..len]).into_owned(),
+                    l.namespace,
+                    archive.read(l.entry).unwrap(),
+                )
+            })
+            .collect();
+        // sorted by path, with the map WAD's lumps in place
+        let expected = [
+            ("LIBRARY", LumpNamespace::Global, &b"global"[..]),
+            ("WATERMAP", LumpNamespace::Colormaps, b"colormap"),
+            ("FLOOR0_1", LumpNamespace::Flats, b"flat"),
+            ("MAPINFO", LumpNamespace::Global, b"root"),
+            ("MAP01", LumpNamespace::Global, b""),
+            ("THINGS", LumpNamespace::Global, b"things"),
+            ("LINEDEFS", LumpNamespace::Global, b"lines"),
+            ("DSPISTOL", LumpNamespace::Global, b"sound"),
+            ("TROOA1", LumpNamespace::Sprites, b"sprite"),
+        ];
+        assert_eq!(found.len(), expected.len());
+        for ((name, namespace, data), (e_name, e_namespace, e_data)) in found.iter().zip(&expected)
+        {
+            assert_eq!((name.as_str(), *namespace), (*e_name, *e_namespace));
+            assert_eq!(data, e_data, "{}", name);
+        }
+        for lump in &lumps {
+            assert_eq!(lump.size, archive.read(lump.entry).unwrap().len());
+        }
+    }
+
+    #[test]
+    fn pk3_lumps_override_wad_lumps() {
+        let wad = test_path("pk3-override.wad");
+        WadBuilder::new(WadKind::Iwad)
+            .push("PLAYPAL", b"iwad".to_vec())
+            .and_then(|b| b.write_to_file(&wad))
+            .unwrap();
+        let pk3 = test_path("pk3-override.zip");
+        write_zip(&pk3, &[("playpal.pal", b"pk3".to_vec())]);
+
+        let mut wad_files = vec![
+            WadFileInfo {
+                name: wad.clone(),
+                src: WadSource::Iwad,
+                handle: 0,
+            },
+            WadFileInfo {
+                name: pk3.clone(),
+                src: WadSource::Pwad,
+                handle: 0,
+            },
+        ];
+        let directory = LumpDirectory::open(&mut wad_files).unwrap();
+        std::fs::remove_file(wad).unwrap();
+        let lump = directory.check_num_for_name("PLAYPAL").unwrap();
+        assert_eq!(directory.read_lump(lump).unwrap(), b"pk3");
+        std::fs::remove_file(pk3).unwrap();
+    }
+
+    #[test]
+    fn a_broken_zip_is_an_error() {
+        let path = test_path("pk3-broken.pk3");
+        std::fs::write(&path, b"PK\x03\x04 not really").unwrap();
+        let result = Pk3Archive::open(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(WadError::Zip(_))));
+    }
+}
@@ -0,0 +1,344 @@
+use super::{normalize_lump_name, read_directory, FileLump, WadError, WadInfo, FILELUMP_SIZE};
+use byteorder::{LittleEndian, WriteBytesExt};
+use std::{
+    convert::TryFrom,
+    fs::File,
+    io::{self, BufWriter, Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+/// The two kinds of WAD file, told apart by the first 4 bytes.
+#[allow(dead_code)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WadKind {
+    Iwad,
+    Pwad,
+}
+
+impl WadKind {
+    pub fn identification(self) -> [u8; 4] {
+        match self {
+            WadKind::Iwad => *b"IWAD",
+            WadKind::Pwad => *b"PWAD",
+        }
+    }
+}
+
+pub trait WriteWadExt {
+    fn write_wadinfo(&mut self, info: &WadInfo) -> Result<(), io::Error>;
+    fn write_filelump(&mut self, lump: &FileLump) -> Result<(), io::Error>;
+}
+
+impl<T> WriteWadExt for T
+where
+    T: Write,
+{
+    fn write_wadinfo(&mut self, info: &WadInfo) -> Result<(), io::Error> {
+        self.write_all(&info.identification)?;
+        self.write_i32::<LittleEndian>(info.numlumps)?;
+        self.write_i32::<LittleEndian>(info.infotableofs)
+    }
+    fn write_filelump(&mut self, lump: &FileLump) -> Result<(), io::Error> {
+        self.write_i32::<LittleEndian>(lump.filepos)?;
+        self.write_i32::<LittleEndian>(lump.size)?;
+        self.write_all(&lump.name)
+    }
+}
+
+/// A single named lump held in memory by a `WadBuilder`.
+#[derive(Debug, Clone)]
+pub struct BuilderLump {
+    /// The name as it is stored. Lumps loaded from a file keep the bytes
+    /// they had there, lowercase letters and bytes after the NUL included.
+    pub name: [u8; 8],
+    pub data: Vec<u8>,
+}
+
+impl BuilderLump {
+    /// Whether the lump is called `name`, ignoring case like the engine.
+    pub fn is_named(&self, name: &str) -> bool {
+        normalize_lump_name(&self.name) == normalize_lump_name(name.as_bytes())
+    }
+}
+
+/// Builds a WAD file in memory, one lump at a time.
+///
+/// Start from scratch with `new`, or load an existing file with `open` to
+/// replace, insert or remove lumps. `write` lays out the lump data followed
+/// by the directory, so the result is always a well-formed WAD no matter
+/// what the source file looked like.
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct WadBuilder {
+    pub kind: WadKind,
+    lumps: Vec<BuilderLump>,
+}
+
+#[allow(dead_code)]
+impl WadBuilder {
+    pub fn new(kind: WadKind) -> Self {
+        Self {
+            kind,
+            lumps: vec![],
+        }
+    }
+
+    /// Load every lump of an existing WAD file into memory.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, WadError> {
+        let path = path.as_ref();
+        File::open(path)
+            .map_err(WadError::from)
+            .and_then(|mut f| Self::from_reader(&mut f))
+            .map_err(|e| e.in_file(path))
+    }
+
+    /// Load every lump of a WAD from any seekable reader. Names are kept as
+    /// they are in the file, so writing it back doesn't change them.
+    pub fn from_reader<R: Read + Seek>(r: &mut R) -> Result<Self, WadError> {
+        let (header, fileinfo) = read_directory(r)?;
+        let kind = if &header.identification == b"IWAD" {
+            WadKind::Iwad
+        } else {
+            WadKind::Pwad
+        };
+        let mut lumps = Vec::with_capacity(fileinfo.len());
+        for lump in fileinfo {
+            let mut data = vec![0; lump.size as usize];
+            r.seek(SeekFrom::Start(lump.filepos as u64))?;
+            r.read_exact(&mut data)?;
+            lumps.push(BuilderLump {
+                name: lump.name,
+                data,
+            });
+        }
+        Ok(Self { kind, lumps })
+    }
+
+    pub fn lumps(&self) -> &[BuilderLump] {
+        &self.lumps
+    }
+
+    /// The index of the first lump called `name`, ignoring case.
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.lumps.iter().position(|l| l.is_named(name))
+    }
+
+    /// Add a lump to the end of the file.
+    pub fn push(&mut self, name: &str, data: Vec<u8>) -> Result<&mut Self, WadError> {
+        let name = check_lump_name(name)?;
+        self.lumps.push(BuilderLump { name, data });
+        Ok(self)
+    }
+
+    /// Add a lump before the lump at `index`. An `index` equal to the number
+    /// of lumps appends it, and a bigger one is an error.
+    pub fn insert(
+        &mut self,
+        index: usize,
+        name: &str,
+        data: Vec<u8>,
+    ) -> Result<&mut Self, WadError> {
+        let name = check_lump_name(name)?;
+        if index > self.lumps.len() {
+            return Err(WadError::BadLumpIndex {
+                index,
+                numlumps: self.lumps.len(),
+            });
+        }
+        self.lumps.insert(index, BuilderLump { name, data });
+        Ok(self)
+    }
+
+    /// Replace the data of the first lump called `name`, or append a new
+    /// lump if there is none.
+    pub fn replace(&mut self, name: &str, data: Vec<u8>) -> Result<&mut Self, WadError> {
+        match self.position(name) {
+            Some(i) => {
+                self.lumps[i].data = data;
+                Ok(self)
+            }
+            None => self.push(name, data),
+        }
+    }
+
+    /// Remove the lump at `index`, returning it.
+    pub fn remove(&mut self, index: usize) -> BuilderLump {
+        self.lumps.remove(index)
+    }
+
+    /// Write the WAD, lump data first and directory last.
+    pub fn write<W: Write>(&self, w: &mut W) -> Result<(), WadError> {
+        let numlumps = to_i32(self.lumps.len())?;
+        let data_len: usize = self.lumps.iter().map(|l| l.data.len()).sum();
+        let infotableofs = to_i32(12 + data_len)?;
+        // make sure the directory itself still fits in a WAD
+        to_i32(infotableofs as usize + self.lumps.len() * FILELUMP_SIZE as usize)?;
+
+        w.write_wadinfo(&WadInfo {
+            identification: self.kind.identification(),
+            numlumps,
+            infotableofs,
+        })?;
+        for lump in &self.lumps {
+            w.write_all(&lump.data)?;
+        }
+        let mut filepos = 12;
+        for lump in &self.lumps {
+            let size = lump.data.len() as i32;
+            w.write_filelump(&FileLump {
+                filepos,
+                size,
+                name: lump.name,
+            })?;
+            filepos += size;
+        }
+        Ok(())
+    }
+
+    /// Write the WAD to a file, replacing it if it exists.
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> Result<(), WadError> {
+        let path = path.as_ref();
+        File::create(path)
+            .map_err(WadError::from)
+            .and_then(|f| {
+                let mut w = BufWriter::new(f);
+                self.write(&mut w)?;
+                w.flush().map_err(WadError::from)
+            })
+            .map_err(|e| e.in_file(path))
+    }
+}
+
+fn check_lump_name(name: &str) -> Result<[u8; 8], WadError> {
+    if name.is_empty() || name.len() > 8 || !name.is_ascii() || name.contains('\0') {
+        return Err(WadError::BadLumpName(name.to_string()));
+    }
+    Ok(normalize_lump_name(name.as_bytes()))
+}
+
+fn to_i32(n: usize) -> Result<i32, WadError> {
+    i32::try_from(n).map_err(|_| {
+        WadError::Io(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "WAD would be larger than 2 GiB",
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wad::test_path;
+    use std::io::Cursor;
+
+    fn names(builder: &WadBuilder) -> Vec<&[u8]> {
+        builder
+            .lumps()
+            .iter()
+            .map(|l| &l.name[..l.name.iter().position(|&c| c == 0).unwrap_or(8)])
+            .collect()
+    }
+
+    fn round_trip(builder: &WadBuilder) -> WadBuilder {
+        let mut data = vec![];
+        builder.write(&mut data).unwrap();
+        WadBuilder::from_reader(&mut Cursor::new(data)).unwrap()
+    }
+
+    #[test]
+    fn write_then_read_gives_the_same_lumps() {
+        let mut builder = WadBuilder::new(WadKind::Pwad);
+        builder
+            .push("map01", vec![])
+            .and_then(|b| b.push("THINGS", vec![1, 2, 3]))
+            .and_then(|b| b.push("LINEDEFS", vec![4; 14]))
+            .unwrap();
+        let read = round_trip(&builder);
+        assert_eq!(read.kind, WadKind::Pwad);
+        assert_eq!(names(&read), [&b"MAP01"[..], b"THINGS", b"LINEDEFS"]);
+        assert_eq!(read.lumps()[1].data, [1, 2, 3]);
+        assert_eq!(read.lumps()[2].data, [4; 14]);
+
+        // the data comes first, then the directory
+        let mut data = vec![];
+        builder.write(&mut data).unwrap();
+        assert_eq!(data.len(), 12 + 17 + 3 * FILELUMP_SIZE as usize);
+        assert_eq!(&data[8..12], &29i32.to_le_bytes());
+    }
+
+    #[test]
+    fn edits_survive_a_round_trip() {
+        let path = test_path("writer-edit.wad");
+        let mut builder = WadBuilder::new(WadKind::Iwad);
+        builder
+            .push("PLAYPAL", vec![0; 4])
+            .and_then(|b| b.push("COLORMAP", vec![1; 4]))
+            .and_then(|b| b.write_to_file(&path))
+            .unwrap();
+
+        let mut builder = WadBuilder::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(builder.kind, WadKind::Iwad);
+        builder
+            .replace("colormap", vec![2; 2])
+            .and_then(|b| b.replace("ENDOOM", vec![3]))
+            .and_then(|b| b.insert(0, "DEMO1", vec![4]))
+            .unwrap();
+        assert_eq!(builder.remove(1).data, [0; 4]);
+        let read = round_trip(&builder);
+        assert_eq!(names(&read), [&b"DEMO1"[..], b"COLORMAP", b"ENDOOM"]);
+        assert_eq!(read.lumps()[1].data, [2; 2]);
+        assert_eq!(read.position("endoom"), Some(2));
+    }
+
+    #[test]
+    fn insert_at_the_end_appends_and_past_it_fails() {
+        let mut builder = WadBuilder::new(WadKind::Pwad);
+        builder.push("A", vec![]).unwrap();
+        builder.insert(1, "B", vec![]).unwrap();
+        assert_eq!(names(&builder), [&b"A"[..], b"B"]);
+        assert!(matches!(
+            builder.insert(3, "C", vec![]),
+            Err(WadError::BadLumpIndex {
+                index: 3,
+                numlumps: 2
+            })
+        ));
+        assert_eq!(builder.lumps().len(), 2);
+    }
+
+    #[test]
+    fn bad_lump_names_are_rejected() {
+        let mut builder = WadBuilder::new(WadKind::Pwad);
+        for name in ["", "TOOLONGNAME", "MAP\u{e9}1", "A\0B"] {
+            assert!(
+                matches!(builder.push(name, vec![]), Err(WadError::BadLumpName(ref n)) if n == name),
+                "{:?}",
+                name
+            );
+        }
+        assert!(builder.lumps().is_empty());
+    }
+
+    #[test]
+    fn names_loaded_from_a_file_are_kept() {
+        let mut data = vec![];
+        data.write_wadinfo(&WadInfo {
+            identification: *b"PWAD",
+            numlumps: 1,
+            infotableofs: 12,
+        })
+        .unwrap();
+        data.write_filelump(&FileLump {
+            filepos: 12,
+            size: 0,
+            name: *b"map01\0xy",
+        })
+        .unwrap();
+        let builder = WadBuilder::from_reader(&mut Cursor::new(&data)).unwrap();
+        assert!(builder.lumps()[0].is_named("MAP01"));
+        let mut written = vec![];
+        builder.write(&mut written).unwrap();
+        assert_eq!(written, data);
+    }
+}